@@ -0,0 +1,40 @@
+CREATE TABLE IF NOT EXISTS zones (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    normalized_name TEXT NOT NULL UNIQUE,
+    polygon JSONB,
+    service_days TEXT[] NOT NULL DEFAULT ARRAY['LUNES', 'MARTES', 'MIERCOLES', 'JUEVES', 'VIERNES', 'SABADO', 'DOMINGO'],
+    default_slots TEXT[] NOT NULL DEFAULT '{}',
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (service_days <@ ARRAY['LUNES', 'MARTES', 'MIERCOLES', 'JUEVES', 'VIERNES', 'SABADO', 'DOMINGO'])
+);
+
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS zone_id UUID REFERENCES zones(id);
+CREATE INDEX IF NOT EXISTS idx_orders_zone_id ON orders(zone_id);
+
+-- Misma normalización que `normalize_catalog_name`: espacios colapsados,
+-- sin acentos y en mayúsculas.
+CREATE TEMP TABLE order_zone_keys ON COMMIT DROP AS
+SELECT
+    id AS order_id,
+    btrim(regexp_replace(zone, '\s+', ' ', 'g')) AS clean_name,
+    upper(translate(btrim(regexp_replace(zone, '\s+', ' ', 'g')), 'áéíóúüñÁÉÍÓÚÜÑ', 'aeiouunAEIOUUN')) AS normalized_name,
+    created_at
+FROM orders
+WHERE btrim(zone) <> '';
+
+-- Cada variante de escritura se consolida en una zona; el nombre visible es
+-- la primera grafía registrada.
+INSERT INTO zones (id, name, normalized_name)
+SELECT DISTINCT ON (normalized_name) gen_random_uuid(), clean_name, normalized_name
+FROM order_zone_keys
+ORDER BY normalized_name, created_at ASC
+ON CONFLICT (normalized_name) DO NOTHING;
+
+UPDATE orders o
+SET zone_id = z.id, zone = z.name
+FROM order_zone_keys k
+JOIN zones z ON z.normalized_name = k.normalized_name
+WHERE o.id = k.order_id;
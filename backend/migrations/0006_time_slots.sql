@@ -0,0 +1,36 @@
+CREATE TABLE IF NOT EXISTS time_slots (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    normalized_name TEXT NOT NULL UNIQUE,
+    start_time TIME NOT NULL,
+    end_time TIME NOT NULL,
+    max_orders INTEGER CHECK (max_orders > 0),
+    max_cylinders INTEGER CHECK (max_cylinders > 0),
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (start_time < end_time)
+);
+
+CREATE TABLE IF NOT EXISTS slot_zone_capacities (
+    slot_id UUID NOT NULL REFERENCES time_slots(id) ON DELETE CASCADE,
+    zone_id UUID NOT NULL REFERENCES zones(id) ON DELETE CASCADE,
+    max_orders INTEGER CHECK (max_orders > 0),
+    max_cylinders INTEGER CHECK (max_cylinders > 0),
+    PRIMARY KEY (slot_id, zone_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_orders_slot_load ON orders(scheduled_date, zone_id, time_slot);
+
+INSERT INTO time_slots (id, name, normalized_name, start_time, end_time)
+VALUES
+('00000000-0000-0000-0000-000000000101', 'MAÑANA', 'MANANA', '09:00', '13:00'),
+('00000000-0000-0000-0000-000000000102', 'TARDE', 'TARDE', '14:00', '19:00')
+ON CONFLICT (normalized_name) DO NOTHING;
+
+-- Unifica las grafías libres existentes ("manana", "Mañana ") con el catálogo.
+UPDATE orders o
+SET time_slot = s.name
+FROM time_slots s
+WHERE s.normalized_name = upper(translate(btrim(regexp_replace(o.time_slot, '\s+', ' ', 'g')), 'áéíóúüñÁÉÍÓÚÜÑ', 'aeiouunAEIOUUN'))
+  AND o.time_slot <> s.name;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS non_working_dates (
+    date DATE PRIMARY KEY,
+    description TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS closed_weekdays (
+    day TEXT PRIMARY KEY CHECK (day IN ('LUNES', 'MARTES', 'MIERCOLES', 'JUEVES', 'VIERNES', 'SABADO', 'DOMINGO'))
+);
+
+INSERT INTO closed_weekdays (day) VALUES ('DOMINGO') ON CONFLICT (day) DO NOTHING;
@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS recurring_orders (
+    id UUID PRIMARY KEY,
+    address TEXT NOT NULL,
+    zone_id UUID NOT NULL REFERENCES zones(id),
+    slot_id UUID NOT NULL REFERENCES time_slots(id),
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    interval_weeks INTEGER NOT NULL CHECK (interval_weeks BETWEEN 1 AND 12),
+    notes TEXT,
+    next_date DATE NOT NULL,
+    status TEXT NOT NULL DEFAULT 'ACTIVA' CHECK (status IN ('ACTIVA', 'PAUSADA', 'FINALIZADA')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_recurring_orders_due ON recurring_orders(status, next_date);
+
+-- Una fila por fecha: registra el pedido generado, la omisión o el motivo del fallo.
+CREATE TABLE IF NOT EXISTS recurring_order_occurrences (
+    recurring_order_id UUID NOT NULL REFERENCES recurring_orders(id) ON DELETE CASCADE,
+    scheduled_date DATE NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('CREADA', 'OMITIDA', 'FALLIDA')),
+    order_id UUID REFERENCES orders(id),
+    detail TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (recurring_order_id, scheduled_date)
+);
@@ -0,0 +1,26 @@
+ALTER TABLE users DROP CONSTRAINT IF EXISTS users_role_check;
+ALTER TABLE users ADD CONSTRAINT users_role_check
+    CHECK (role IN ('ADMIN', 'SUPERVISOR', 'REPARTIDOR', 'CLIENTE'));
+
+ALTER TABLE orders DROP CONSTRAINT IF EXISTS orders_status_check;
+ALTER TABLE orders ADD CONSTRAINT orders_status_check
+    CHECK (status IN ('PENDIENTE', 'ASIGNADO', 'EN_REPARTO', 'ENTREGADO', 'CANCELADO'));
+
+-- Direcciones guardadas de un cliente. Se desactivan en lugar de borrarse
+-- porque los pedidos ya hechos las referencian.
+CREATE TABLE IF NOT EXISTS customer_addresses (
+    id UUID PRIMARY KEY,
+    customer_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    label TEXT NOT NULL,
+    address TEXT NOT NULL,
+    zone_id UUID NOT NULL REFERENCES zones(id),
+    notes TEXT,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_customer_addresses_customer_id ON customer_addresses(customer_id);
+
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS customer_id UUID REFERENCES users(id);
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS address_id UUID REFERENCES customer_addresses(id);
+CREATE INDEX IF NOT EXISTS idx_orders_customer_id ON orders(customer_id);
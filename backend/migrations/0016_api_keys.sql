@@ -0,0 +1,17 @@
+-- Credenciales para integraciones: sólo se guarda el hash; `prefix` permite
+-- reconocer la key en listados sin exponerla.
+CREATE TABLE IF NOT EXISTS api_keys (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    prefix TEXT NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    permissions TEXT[] NOT NULL,
+    created_by UUID NOT NULL REFERENCES users(id),
+    expires_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS api_key_id UUID REFERENCES api_keys(id);
+CREATE INDEX IF NOT EXISTS idx_audit_events_api_key_id ON audit_events(api_key_id);
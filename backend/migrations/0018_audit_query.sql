@@ -0,0 +1,5 @@
+-- Pedido HTTP que originó cada evento (`X-Request-Id`).
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS request_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_cursor ON audit_events(created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_events_entity_id ON audit_events(entity_id);
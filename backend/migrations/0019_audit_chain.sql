@@ -0,0 +1,51 @@
+-- Cadena de hashes: cada evento guarda el hash del anterior (`prev_hash`) y
+-- el propio, calculado por la aplicación sobre su contenido. Los eventos
+-- previos a esta migración quedan numerados y se sellan al registrar el
+-- siguiente evento.
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS seq BIGINT;
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS prev_hash TEXT;
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS hash TEXT;
+
+UPDATE audit_events a
+SET seq = numbered.seq
+FROM (
+    SELECT id, ROW_NUMBER() OVER (ORDER BY created_at, id) AS seq
+    FROM audit_events
+) numbered
+WHERE a.id = numbered.id AND a.seq IS NULL;
+
+ALTER TABLE audit_events ALTER COLUMN seq SET NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_audit_events_seq ON audit_events(seq);
+CREATE INDEX IF NOT EXISTS idx_audit_events_unsealed ON audit_events(seq) WHERE hash IS NULL;
+
+-- Sólo se agregan filas. La única modificación permitida es sellar una fila
+-- sin hash sin tocar su contenido.
+CREATE OR REPLACE FUNCTION audit_events_append_only() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' THEN
+        IF OLD.hash IS NULL
+            AND NEW.hash IS NOT NULL
+            AND (NEW.id, NEW.seq, NEW.actor_id, NEW.api_key_id, NEW.entity, NEW.entity_id,
+                 NEW.action, NEW.details, NEW.request_id, NEW.created_at)
+                IS NOT DISTINCT FROM
+                (OLD.id, OLD.seq, OLD.actor_id, OLD.api_key_id, OLD.entity, OLD.entity_id,
+                 OLD.action, OLD.details, OLD.request_id, OLD.created_at)
+        THEN
+            RETURN NEW;
+        END IF;
+    END IF;
+    RAISE EXCEPTION 'audit_events es de sólo inserción (% no permitido)', TG_OP;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS audit_events_no_update ON audit_events;
+CREATE TRIGGER audit_events_no_update
+    BEFORE UPDATE OR DELETE ON audit_events
+    FOR EACH ROW EXECUTE FUNCTION audit_events_append_only();
+
+DROP TRIGGER IF EXISTS audit_events_no_truncate ON audit_events;
+CREATE TRIGGER audit_events_no_truncate
+    BEFORE TRUNCATE ON audit_events
+    FOR EACH STATEMENT EXECUTE FUNCTION audit_events_append_only();
+
+REVOKE UPDATE, DELETE, TRUNCATE ON audit_events FROM PUBLIC;
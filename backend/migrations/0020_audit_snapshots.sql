@@ -0,0 +1,28 @@
+-- Estado antes/después de cada cambio y datos del cliente que lo originó.
+-- `hash_version` indica qué campos cubre el hash: los eventos existentes
+-- quedan en la versión 1 y la aplicación escribe los nuevos con la 2.
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS before_state JSONB;
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS after_state JSONB;
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS ip TEXT;
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS user_agent TEXT;
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS hash_version SMALLINT NOT NULL DEFAULT 1;
+
+CREATE OR REPLACE FUNCTION audit_events_append_only() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'UPDATE' THEN
+        IF OLD.hash IS NULL
+            AND NEW.hash IS NOT NULL
+            AND (NEW.id, NEW.seq, NEW.actor_id, NEW.api_key_id, NEW.entity, NEW.entity_id,
+                 NEW.action, NEW.details, NEW.request_id, NEW.created_at, NEW.before_state,
+                 NEW.after_state, NEW.ip, NEW.user_agent, NEW.hash_version)
+                IS NOT DISTINCT FROM
+                (OLD.id, OLD.seq, OLD.actor_id, OLD.api_key_id, OLD.entity, OLD.entity_id,
+                 OLD.action, OLD.details, OLD.request_id, OLD.created_at, OLD.before_state,
+                 OLD.after_state, OLD.ip, OLD.user_agent, OLD.hash_version)
+        THEN
+            RETURN NEW;
+        END IF;
+    END IF;
+    RAISE EXCEPTION 'audit_events es de sólo inserción (% no permitido)', TG_OP;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,18 @@
+-- Outbox transaccional: cada evento se inserta junto con el cambio de estado
+-- que lo origina y un dispatcher lo entrega después. `next_attempt_at` NULL
+-- marca un evento descartado tras agotar los reintentos.
+CREATE TABLE IF NOT EXISTS outbox_events (
+    id UUID PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    aggregate_id UUID NOT NULL,
+    payload JSONB NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ DEFAULT NOW(),
+    published_at TIMESTAMPTZ,
+    last_error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_events_pending
+    ON outbox_events(next_attempt_at)
+    WHERE published_at IS NULL;
@@ -0,0 +1,18 @@
+-- Audiencia del evento para el stream en vivo: repartidor y cliente del
+-- pedido involucrado.
+ALTER TABLE outbox_events ADD COLUMN IF NOT EXISTS assignee_id UUID;
+ALTER TABLE outbox_events ADD COLUMN IF NOT EXISTS customer_id UUID;
+
+-- Cada evento se anuncia a todas las réplicas al confirmarse la transacción
+-- que lo insertó; el payload es sólo el id, NOTIFY limita su tamaño.
+CREATE OR REPLACE FUNCTION outbox_events_notify() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('gasflow_events', NEW.id::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS outbox_events_notify ON outbox_events;
+CREATE TRIGGER outbox_events_notify
+    AFTER INSERT ON outbox_events
+    FOR EACH ROW EXECUTE FUNCTION outbox_events_notify();
@@ -0,0 +1,27 @@
+-- Fixes GPS de los repartidores. Un reenvío del mismo lote no duplica filas:
+-- el par repartidor/instante es único.
+CREATE TABLE IF NOT EXISTS driver_positions (
+    id UUID PRIMARY KEY,
+    driver_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    lat DOUBLE PRECISION NOT NULL,
+    lng DOUBLE PRECISION NOT NULL,
+    accuracy_m DOUBLE PRECISION,
+    recorded_at TIMESTAMPTZ NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (driver_id, recorded_at)
+);
+
+-- Para la depuración por retención.
+CREATE INDEX IF NOT EXISTS idx_driver_positions_recorded_at
+    ON driver_positions (recorded_at);
+
+-- Dónde estaba el repartidor al registrar la entrega o el fallo.
+ALTER TABLE deliveries ADD COLUMN IF NOT EXISTS position_lat DOUBLE PRECISION;
+ALTER TABLE deliveries ADD COLUMN IF NOT EXISTS position_lng DOUBLE PRECISION;
+ALTER TABLE deliveries ADD COLUMN IF NOT EXISTS position_accuracy_m DOUBLE PRECISION;
+ALTER TABLE deliveries ADD COLUMN IF NOT EXISTS position_recorded_at TIMESTAMPTZ;
+
+ALTER TABLE delivery_failures ADD COLUMN IF NOT EXISTS position_lat DOUBLE PRECISION;
+ALTER TABLE delivery_failures ADD COLUMN IF NOT EXISTS position_lng DOUBLE PRECISION;
+ALTER TABLE delivery_failures ADD COLUMN IF NOT EXISTS position_accuracy_m DOUBLE PRECISION;
+ALTER TABLE delivery_failures ADD COLUMN IF NOT EXISTS position_recorded_at TIMESTAMPTZ;
@@ -0,0 +1,22 @@
+-- Fotos y firmas de prueba de entrega. Los archivos viven en el blob store;
+-- acá quedan las claves, el hash y quién los subió.
+CREATE TABLE IF NOT EXISTS delivery_attachments (
+    id UUID PRIMARY KEY,
+    delivery_id UUID REFERENCES deliveries(id),
+    failure_id UUID REFERENCES delivery_failures(id),
+    order_id UUID NOT NULL REFERENCES orders(id),
+    kind TEXT NOT NULL CHECK (kind IN ('FOTO', 'FIRMA')),
+    content_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    sha256 TEXT NOT NULL,
+    blob_key TEXT NOT NULL,
+    thumbnail_key TEXT,
+    uploaded_by UUID REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (num_nonnulls(delivery_id, failure_id) = 1)
+);
+
+CREATE INDEX IF NOT EXISTS idx_delivery_attachments_delivery
+    ON delivery_attachments (delivery_id, created_at) WHERE delivery_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_delivery_attachments_failure
+    ON delivery_attachments (failure_id, created_at) WHERE failure_id IS NOT NULL;
@@ -0,0 +1,27 @@
+-- Correcciones y anulaciones de entregas. La fila original de `deliveries`
+-- no se modifica (salvo `voided_at`); el stock suma las diferencias de cada
+-- corrección en su propia fecha.
+ALTER TABLE deliveries ADD COLUMN IF NOT EXISTS voided_at TIMESTAMPTZ;
+
+-- Una entrega anulada libera el pedido para registrar la correcta.
+ALTER TABLE deliveries DROP CONSTRAINT IF EXISTS deliveries_order_id_key;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_deliveries_order_active
+    ON deliveries (order_id) WHERE voided_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS delivery_corrections (
+    id UUID PRIMARY KEY,
+    delivery_id UUID NOT NULL REFERENCES deliveries(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('CORRECCION', 'ANULACION')),
+    llenas_antes INTEGER NOT NULL,
+    vacias_antes INTEGER NOT NULL,
+    llenas_despues INTEGER NOT NULL CHECK (llenas_despues >= 0),
+    vacias_despues INTEGER NOT NULL CHECK (vacias_despues >= 0),
+    reason TEXT NOT NULL,
+    corrected_by UUID REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_delivery_corrections_delivery
+    ON delivery_corrections (delivery_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_delivery_corrections_created
+    ON delivery_corrections (created_at);
@@ -0,0 +1,7 @@
+-- Entregas parciales: el pedido queda ENTREGADO_PARCIAL o el saldo pasa a
+-- un pedido de seguimiento enlazado desde la entrega.
+ALTER TABLE orders DROP CONSTRAINT IF EXISTS orders_status_check;
+ALTER TABLE orders ADD CONSTRAINT orders_status_check
+    CHECK (status IN ('PENDIENTE', 'ASIGNADO', 'EN_REPARTO', 'ENTREGADO', 'ENTREGADO_PARCIAL', 'CANCELADO'));
+
+ALTER TABLE deliveries ADD COLUMN IF NOT EXISTS follow_up_order_id UUID REFERENCES orders(id);
@@ -1,45 +1,328 @@
 use crate::domain::auth::{Claims, Role};
 use crate::domain::error::DomainError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+    OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+};
+use jsonwebtoken::{
+    decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use ring::signature::{Ed25519KeyPair, KeyPair, RsaKeyPair};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 use uuid::Uuid;
 
-#[derive(Debug, Clone)]
+/// Clave asimétrica identificada por `kid`. Se carga desde la clave privada
+/// en PEM (RSA PKCS#1/PKCS#8 o Ed25519 PKCS#8); la parte pública se publica
+/// en el JWKS.
+#[derive(Clone)]
+pub struct JwtSigningKey {
+    kid: String,
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+    jwk: Jwk,
+}
+
+impl JwtSigningKey {
+    pub fn from_pem(kid: &str, pem: &str) -> Result<Self, DomainError> {
+        let invalid = |detail: &str| {
+            DomainError::Infrastructure(format!("clave JWT {} inválida: {}", kid, detail))
+        };
+        let parsed = pem::parse(pem).map_err(|e| invalid(&e.to_string()))?;
+
+        let (algorithm, encoding, params) = if let Some(key_pair) = rsa_key_pair(&parsed) {
+            let public = ring::rsa::PublicKeyComponents::<Vec<u8>>::from(key_pair.public());
+            (
+                Algorithm::RS256,
+                EncodingKey::from_rsa_pem(pem.as_bytes()).map_err(|e| invalid(&e.to_string()))?,
+                AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: URL_SAFE_NO_PAD.encode(public.n),
+                    e: URL_SAFE_NO_PAD.encode(public.e),
+                }),
+            )
+        } else if parsed.tag() == "PRIVATE KEY" {
+            let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(parsed.contents())
+                .map_err(|_| invalid("se espera una clave RSA o Ed25519"))?;
+            (
+                Algorithm::EdDSA,
+                EncodingKey::from_ed_pem(pem.as_bytes()).map_err(|e| invalid(&e.to_string()))?,
+                AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                    key_type: OctetKeyPairType::OctetKeyPair,
+                    curve: EllipticCurve::Ed25519,
+                    x: URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref()),
+                }),
+            )
+        } else {
+            return Err(invalid(&format!("PEM {} no soportado", parsed.tag())));
+        };
+
+        let jwk = Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_algorithm: Some(match algorithm {
+                    Algorithm::RS256 => KeyAlgorithm::RS256,
+                    _ => KeyAlgorithm::EdDSA,
+                }),
+                key_id: Some(kid.to_string()),
+                ..CommonParameters::default()
+            },
+            algorithm: params,
+        };
+
+        Ok(Self {
+            kid: kid.to_string(),
+            algorithm,
+            encoding,
+            jwk,
+        })
+    }
+}
+
+fn rsa_key_pair(parsed: &pem::Pem) -> Option<RsaKeyPair> {
+    match parsed.tag() {
+        "RSA PRIVATE KEY" => RsaKeyPair::from_der(parsed.contents()).ok(),
+        "PRIVATE KEY" => RsaKeyPair::from_pkcs8(parsed.contents()).ok(),
+        _ => None,
+    }
+}
+
+/// Lee `<kid>.pem` de `dir`: el nombre del archivo es el `kid`.
+pub fn load_signing_keys(dir: &Path) -> Result<Vec<JwtSigningKey>, DomainError> {
+    let entries = std::fs::read_dir(dir).map_err(|e| {
+        DomainError::Infrastructure(format!("no se pudo leer {}: {}", dir.display(), e))
+    })?;
+
+    let mut keys = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| DomainError::Infrastructure(e.to_string()))?
+            .path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("pem") {
+            continue;
+        }
+        let Some(kid) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let pem = std::fs::read_to_string(&path).map_err(|e| {
+            DomainError::Infrastructure(format!("no se pudo leer {}: {}", path.display(), e))
+        })?;
+        keys.push(JwtSigningKey::from_pem(kid, &pem)?);
+    }
+    keys.sort_by(|a, b| a.kid.cmp(&b.kid));
+    Ok(keys)
+}
+
+struct Verifier {
+    algorithm: Algorithm,
+    key: DecodingKey,
+}
+
+struct Keys {
+    /// Firma los tokens nuevos; `None` en modo HS256.
+    active: Option<JwtSigningKey>,
+    verifiers: HashMap<String, Verifier>,
+    /// Secreto HS256: firma si no hay claves asimétricas y, si no, sólo
+    /// valida los tokens sin `kid` emitidos antes de migrar.
+    secret: Option<String>,
+    jwks: JwkSet,
+}
+
+#[derive(Clone)]
 pub struct JwtService {
-    secret: String,
-    expiration_hours: i64,
+    keys: Arc<Keys>,
+    access_token_minutes: i64,
+    refresh_token_days: i64,
+}
+
+impl std::fmt::Debug for JwtService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JwtService")
+            .field("active_kid", &self.keys.active.as_ref().map(|key| &key.kid))
+            .field("access_token_minutes", &self.access_token_minutes)
+            .field("refresh_token_days", &self.refresh_token_days)
+            .finish()
+    }
 }
 
 impl JwtService {
-    pub fn new(secret: String, expiration_hours: i64) -> Self {
+    pub fn new(secret: String, access_token_minutes: i64, refresh_token_days: i64) -> Self {
         Self {
-            secret,
-            expiration_hours,
+            keys: Arc::new(Keys {
+                active: None,
+                verifiers: HashMap::new(),
+                secret: Some(secret),
+                jwks: JwkSet { keys: Vec::new() },
+            }),
+            access_token_minutes,
+            refresh_token_days,
         }
     }
 
-    pub fn issue(&self, user_id: Uuid, role: Role) -> Result<String, DomainError> {
+    /// Firma con `active_kid` y valida con todas las claves: para rotar se
+    /// agrega la clave nueva, se la activa y la anterior se retira cuando
+    /// vencieron sus tokens.
+    pub fn with_keys(
+        keys: Vec<JwtSigningKey>,
+        active_kid: &str,
+        legacy_secret: Option<String>,
+        access_token_minutes: i64,
+        refresh_token_days: i64,
+    ) -> Result<Self, DomainError> {
+        let active = keys
+            .iter()
+            .find(|key| key.kid == active_kid)
+            .cloned()
+            .ok_or_else(|| {
+                DomainError::Infrastructure(format!("no hay clave JWT con kid {}", active_kid))
+            })?;
+
+        let mut verifiers = HashMap::new();
+        for key in &keys {
+            let decoding = DecodingKey::from_jwk(&key.jwk)
+                .map_err(|e| DomainError::Infrastructure(e.to_string()))?;
+            verifiers.insert(
+                key.kid.clone(),
+                Verifier {
+                    algorithm: key.algorithm,
+                    key: decoding,
+                },
+            );
+        }
+
+        Ok(Self {
+            keys: Arc::new(Keys {
+                active: Some(active),
+                verifiers,
+                secret: legacy_secret,
+                jwks: JwkSet {
+                    keys: keys.into_iter().map(|key| key.jwk).collect(),
+                },
+            }),
+            access_token_minutes,
+            refresh_token_days,
+        })
+    }
+
+    pub fn access_token_ttl(&self) -> Duration {
+        Duration::minutes(self.access_token_minutes)
+    }
+
+    pub fn refresh_token_ttl(&self) -> Duration {
+        Duration::days(self.refresh_token_days)
+    }
+
+    /// Claves públicas vigentes; vacío en modo HS256.
+    pub fn jwks(&self) -> &JwkSet {
+        &self.keys.jwks
+    }
+
+    pub fn issue(&self, user_id: Uuid, role: Role, generation: i64) -> Result<String, DomainError> {
         let claims = Claims {
             sub: user_id.to_string(),
             role,
-            exp: (Utc::now() + Duration::hours(self.expiration_hours)).timestamp() as usize,
+            exp: (Utc::now() + self.access_token_ttl()).timestamp() as usize,
+            gen: generation,
         };
 
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.secret.as_bytes()),
-        )
-        .map_err(|e| DomainError::Infrastructure(e.to_string()))
+        let result = match (&self.keys.active, &self.keys.secret) {
+            (Some(active), _) => {
+                let mut header = Header::new(active.algorithm);
+                header.kid = Some(active.kid.clone());
+                encode(&header, &claims, &active.encoding)
+            }
+            (None, Some(secret)) => encode(
+                &Header::default(),
+                &claims,
+                &EncodingKey::from_secret(secret.as_bytes()),
+            ),
+            (None, None) => {
+                return Err(DomainError::Infrastructure(
+                    "sin clave para firmar tokens".to_string(),
+                ))
+            }
+        };
+        result.map_err(|e| DomainError::Infrastructure(e.to_string()))
     }
 
     pub fn verify(&self, token: &str) -> Result<Claims, DomainError> {
-        decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(self.secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map(|data| data.claims)
-        .map_err(|_| DomainError::Unauthorized("token inválido".to_string()))
+        let invalid = || DomainError::Unauthorized("token inválido".to_string());
+        let header = decode_header(token).map_err(|_| invalid())?;
+
+        // El algoritmo sale de la clave, nunca del header: evita que un token
+        // HS256 se valide con una clave pública.
+        let secret_key;
+        let (algorithm, key) = match &header.kid {
+            Some(kid) => {
+                let verifier = self.keys.verifiers.get(kid).ok_or_else(invalid)?;
+                (verifier.algorithm, &verifier.key)
+            }
+            None => {
+                let secret = self.keys.secret.as_ref().ok_or_else(invalid)?;
+                secret_key = DecodingKey::from_secret(secret.as_bytes());
+                (Algorithm::HS256, &secret_key)
+            }
+        };
+
+        decode::<Claims>(token, key, &Validation::new(algorithm))
+            .map(|data| data.claims)
+            .map_err(|_| invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+
+    fn ed25519_key(kid: &str) -> JwtSigningKey {
+        let der = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let pem = pem::encode(&pem::Pem::new("PRIVATE KEY", der.as_ref().to_vec()));
+        JwtSigningKey::from_pem(kid, &pem).unwrap()
+    }
+
+    #[test]
+    fn rotation_keeps_old_tokens_valid() {
+        let old = ed25519_key("2026-01");
+        let new = ed25519_key("2026-02");
+        let user_id = Uuid::new_v4();
+
+        let before = JwtService::with_keys(vec![old.clone()], "2026-01", None, 15, 30).unwrap();
+        let token = before.issue(user_id, Role::Admin, 0).unwrap();
+
+        let after = JwtService::with_keys(vec![old, new.clone()], "2026-02", None, 15, 30).unwrap();
+        assert_eq!(after.verify(&token).unwrap().sub, user_id.to_string());
+        let fresh = after.issue(user_id, Role::Admin, 0).unwrap();
+        assert_eq!(
+            decode_header(&fresh).unwrap().kid.as_deref(),
+            Some("2026-02")
+        );
+        assert_eq!(after.jwks().keys.len(), 2);
+
+        // Retirada la clave vieja, sus tokens dejan de valer.
+        let retired = JwtService::with_keys(vec![new], "2026-02", None, 15, 30).unwrap();
+        assert!(retired.verify(&token).is_err());
+        assert!(retired.verify(&fresh).is_ok());
+    }
+
+    #[test]
+    fn legacy_hs256_tokens_need_the_secret() {
+        let user_id = Uuid::new_v4();
+        let legacy = JwtService::new("secreto".to_string(), 15, 30)
+            .issue(user_id, Role::Repartidor, 0)
+            .unwrap();
+        let key = ed25519_key("k1");
+
+        let migrating =
+            JwtService::with_keys(vec![key.clone()], "k1", Some("secreto".to_string()), 15, 30)
+                .unwrap();
+        assert!(migrating.verify(&legacy).is_ok());
+
+        let strict = JwtService::with_keys(vec![key], "k1", None, 15, 30).unwrap();
+        assert!(strict.verify(&legacy).is_err());
     }
 }
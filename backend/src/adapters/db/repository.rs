@@ -1,16 +1,69 @@
-use crate::domain::audit::NewAuditEvent;
-use crate::domain::auth::{Role, User};
-use crate::domain::delivery::{Delivery, FailedDelivery, NewDelivery, NewFailedDelivery};
+use crate::domain::api_keys::{ApiKey, NewApiKey};
+use crate::domain::attachments::{
+    ensure_attachment_room, Attachment, AttachmentKind, AttachmentTarget, NewAttachment,
+    StoredAttachment,
+};
+use crate::domain::audit::{
+    AuditEvent, AuditFilter, ChainedAuditEvent, NewAuditEvent, AUDIT_GENESIS_HASH,
+    AUDIT_HASH_VERSION,
+};
+use crate::domain::auth::{
+    LoginFailures, LoginScope, NewPasswordReset, NewRefreshToken, NewUser, PasswordReset,
+    Permission, RefreshToken, Role, User, UserFilter,
+};
+use crate::domain::calendar::{Holiday, NewHoliday};
+use crate::domain::customers::{CustomerAddress, NewCustomerAddress};
+use crate::domain::delivery::{
+    ensure_follow_up_open, Delivery, DeliveryCorrection, DeliveryCorrectionKind, FailedDelivery,
+    FollowUpChange, NewDelivery, NewDeliveryCorrection, NewFailedDelivery, RegisteredDelivery,
+    RegisteredFailure,
+};
 use crate::domain::error::DomainError;
+use crate::domain::events::{
+    DomainEvent, DomainEventType, OutboxEvent, OUTBOX_CLAIM_LEASE_SECONDS,
+};
+use crate::domain::jobs::{JobRun, JobRunStatus, JobTrigger, ABANDONED_JOB_RUN_ERROR};
 use crate::domain::orders::{NewOrder, Order, OrderFilter, OrderStatus, PaginatedOrders};
+use crate::domain::positions::{DriverPosition, LatestDriverPosition, PositionFix};
+use crate::domain::recurring::{
+    NewOccurrence, NewRecurringOrder, OccurrenceStatus, RecurringOccurrence, RecurringOrder,
+    RecurringStatus,
+};
+use crate::domain::slots::{
+    NewTimeSlot, SlotLoad, SlotReservation, SlotZoneCapacity, TimeSlot, TimeSlotUpdate,
+};
 use crate::domain::stock::Inbound;
+use crate::domain::two_factor::{ChallengePurpose, LoginChallenge, NewLoginChallenge};
+use crate::domain::webhooks::{
+    NewWebhookSubscription, PendingWebhookDelivery, WebhookAttempt, WebhookDelivery,
+    WebhookDeliveryFilter, WebhookDeliveryStatus, WebhookSubscription, WebhookSubscriptionUpdate,
+    WEBHOOK_CLAIM_LEASE_SECONDS,
+};
+use crate::domain::zones::{
+    normalize_catalog_name, DayOfWeek, GeoPoint, NewZone, Zone, ZoneUpdate,
+};
+use crate::ports::api_keys_port::ApiKeysPort;
+use crate::ports::attachments_port::AttachmentsPort;
 use crate::ports::audit_port::AuditPort;
 use crate::ports::auth_port::AuthPort;
+use crate::ports::calendar_port::CalendarPort;
+use crate::ports::customers_port::CustomersPort;
 use crate::ports::deliveries_port::DeliveriesPort;
+use crate::ports::jobs_port::{ClaimedJobRun, JobLease, JobsPort};
 use crate::ports::orders_port::OrdersPort;
+use crate::ports::outbox_port::OutboxPort;
+use crate::ports::positions_port::PositionsPort;
+use crate::ports::recurring_port::RecurringOrdersPort;
+use crate::ports::slots_port::SlotsPort;
 use crate::ports::stock_port::{DailyReportTotals, StockPort, StockTotals};
+use crate::ports::two_factor_port::TwoFactorPort;
+use crate::ports::users_port::UsersPort;
+use crate::ports::webhooks_port::WebhooksPort;
+use crate::ports::zones_port::ZonesPort;
 use async_trait::async_trait;
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+use sqlx::pool::PoolConnection;
 use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
@@ -40,8 +93,20 @@ struct UserRow {
     username: String,
     password: String,
     role: String,
+    active: bool,
+    token_generation: i64,
+    locked_at: Option<DateTime<Utc>>,
+    must_change_password: bool,
+    totp_secret: Option<String>,
+    totp_enabled_at: Option<DateTime<Utc>>,
+    totp_last_step: Option<i64>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
 }
 
+const USER_COLUMNS: &str = "id, username, password, role, active, token_generation, locked_at, \
+     must_change_password, totp_secret, totp_enabled_at, totp_last_step, created_at, updated_at";
+
 impl TryFrom<UserRow> for User {
     type Error = DomainError;
 
@@ -54,21 +119,242 @@ impl TryFrom<UserRow> for User {
             username: value.username,
             password: value.password,
             role,
+            active: value.active,
+            token_generation: value.token_generation,
+            locked_at: value.locked_at,
+            must_change_password: value.must_change_password,
+            totp_secret: value.totp_secret,
+            totp_enabled_at: value.totp_enabled_at,
+            totp_last_step: value.totp_last_step,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct RefreshTokenRow {
+    id: Uuid,
+    user_id: Uuid,
+    family_id: Uuid,
+    token_generation: i64,
+    expires_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<RefreshTokenRow> for RefreshToken {
+    fn from(value: RefreshTokenRow) -> Self {
+        RefreshToken {
+            id: value.id,
+            user_id: value.user_id,
+            family_id: value.family_id,
+            token_generation: value.token_generation,
+            expires_at: value.expires_at,
+            revoked_at: value.revoked_at,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct PasswordResetRow {
+    id: Uuid,
+    user_id: Uuid,
+    expires_at: DateTime<Utc>,
+}
+
+impl From<PasswordResetRow> for PasswordReset {
+    fn from(value: PasswordResetRow) -> Self {
+        PasswordReset {
+            id: value.id,
+            user_id: value.user_id,
+            expires_at: value.expires_at,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct LoginChallengeRow {
+    id: Uuid,
+    user_id: Uuid,
+    purpose: String,
+    attempts: i32,
+    expires_at: DateTime<Utc>,
+}
+
+impl TryFrom<LoginChallengeRow> for LoginChallenge {
+    type Error = DomainError;
+
+    fn try_from(value: LoginChallengeRow) -> Result<Self, Self::Error> {
+        let purpose = ChallengePurpose::from_str(&value.purpose).ok_or_else(|| {
+            DomainError::Infrastructure("propósito de desafío inválido en DB".to_string())
+        })?;
+
+        Ok(LoginChallenge {
+            id: value.id,
+            user_id: value.user_id,
+            purpose,
+            attempts: value.attempts,
+            expires_at: value.expires_at,
         })
     }
 }
 
+#[derive(Debug, FromRow)]
+struct AuditEventRow {
+    id: Uuid,
+    actor_id: Option<Uuid>,
+    actor_username: Option<String>,
+    api_key_id: Option<Uuid>,
+    entity: String,
+    entity_id: Option<Uuid>,
+    action: String,
+    before_state: Option<serde_json::Value>,
+    after_state: Option<serde_json::Value>,
+    details: serde_json::Value,
+    request_id: Option<String>,
+    ip: Option<String>,
+    user_agent: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<AuditEventRow> for AuditEvent {
+    fn from(value: AuditEventRow) -> Self {
+        AuditEvent {
+            id: value.id,
+            actor_id: value.actor_id,
+            actor_username: value.actor_username,
+            api_key_id: value.api_key_id,
+            entity: value.entity,
+            entity_id: value.entity_id,
+            action: value.action,
+            before: value.before_state,
+            after: value.after_state,
+            details: value.details,
+            request_id: value.request_id,
+            ip: value.ip,
+            user_agent: value.user_agent,
+            created_at: value.created_at,
+        }
+    }
+}
+
+/// Clave del advisory lock que serializa las bajas de administradores.
+const ACTIVE_ADMINS_LOCK: i64 = 0x6761_735f_6164_6d6e;
+
+/// Rechaza con `Conflict` si `user_id` es el último ADMIN activo. El lock
+/// dura hasta el fin de la transacción: dos admins que se quitan el rol o se
+/// desactivan mutuamente a la vez no pueden pasar los dos.
+async fn ensure_other_admin_in_tx(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    user_id: Uuid,
+) -> Result<(), DomainError> {
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(ACTIVE_ADMINS_LOCK)
+        .execute(&mut **tx)
+        .await
+        .map_err(PgRepository::map_sqlx_error)?;
+
+    let (is_admin, others) = sqlx::query_as::<_, (bool, i64)>(
+        r#"
+        SELECT COALESCE(BOOL_OR(id = $1), FALSE), COUNT(*) FILTER (WHERE id <> $1)
+        FROM users
+        WHERE role = 'ADMIN' AND active
+        "#,
+    )
+    .bind(user_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(PgRepository::map_sqlx_error)?;
+
+    if is_admin && others == 0 {
+        return Err(DomainError::Conflict(
+            "no se puede quitar el último ADMIN activo".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Clave del advisory lock que serializa las escrituras de auditoría.
+const AUDIT_CHAIN_LOCK: i64 = 0x6761_735f_6175_6474;
+
+const CHAINED_AUDIT_EVENT_COLUMNS: &str = "seq, id, actor_id, api_key_id, entity, entity_id, \
+     action, before_state, after_state, details, request_id, ip, user_agent, created_at, \
+     hash_version, prev_hash, hash";
+
+#[derive(Debug, FromRow)]
+struct ChainedAuditEventRow {
+    seq: i64,
+    id: Uuid,
+    actor_id: Option<Uuid>,
+    api_key_id: Option<Uuid>,
+    entity: String,
+    entity_id: Option<Uuid>,
+    action: String,
+    before_state: Option<serde_json::Value>,
+    after_state: Option<serde_json::Value>,
+    details: serde_json::Value,
+    request_id: Option<String>,
+    ip: Option<String>,
+    user_agent: Option<String>,
+    created_at: DateTime<Utc>,
+    hash_version: i16,
+    prev_hash: Option<String>,
+    hash: Option<String>,
+}
+
+impl From<ChainedAuditEventRow> for ChainedAuditEvent {
+    fn from(value: ChainedAuditEventRow) -> Self {
+        ChainedAuditEvent {
+            seq: value.seq,
+            id: value.id,
+            actor_id: value.actor_id,
+            api_key_id: value.api_key_id,
+            entity: value.entity,
+            entity_id: value.entity_id,
+            action: value.action,
+            before: value.before_state,
+            after: value.after_state,
+            details: value.details,
+            request_id: value.request_id,
+            ip: value.ip,
+            user_agent: value.user_agent,
+            created_at: value.created_at,
+            hash_version: value.hash_version,
+            prev_hash: value.prev_hash,
+            hash: value.hash,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct LoginFailuresRow {
+    failures: i32,
+    last_failure_at: DateTime<Utc>,
+}
+
+impl From<LoginFailuresRow> for LoginFailures {
+    fn from(value: LoginFailuresRow) -> Self {
+        LoginFailures {
+            failures: value.failures,
+            last_failure_at: value.last_failure_at,
+        }
+    }
+}
+
 #[derive(Debug, FromRow)]
 struct OrderRow {
     id: Uuid,
     address: String,
     zone: String,
+    zone_id: Option<Uuid>,
     scheduled_date: NaiveDate,
     time_slot: String,
     quantity: i32,
     notes: Option<String>,
     status: String,
     assignee_id: Option<Uuid>,
+    customer_id: Option<Uuid>,
+    address_id: Option<Uuid>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
@@ -84,12 +370,15 @@ impl TryFrom<OrderRow> for Order {
             id: value.id,
             address: value.address,
             zone: value.zone,
+            zone_id: value.zone_id,
             scheduled_date: value.scheduled_date,
             time_slot: value.time_slot,
             quantity: value.quantity,
             notes: value.notes,
             status,
             assignee_id: value.assignee_id,
+            customer_id: value.customer_id,
+            address_id: value.address_id,
             created_at: value.created_at,
             updated_at: value.updated_at,
         })
@@ -103,9 +392,30 @@ struct DeliveryRow {
     llenas_entregadas: i32,
     vacias_recibidas: i32,
     notes: Option<String>,
+    position_lat: Option<f64>,
+    position_lng: Option<f64>,
+    position_accuracy_m: Option<f64>,
+    position_recorded_at: Option<DateTime<Utc>>,
+    voided_at: Option<DateTime<Utc>>,
+    follow_up_order_id: Option<Uuid>,
     created_at: DateTime<Utc>,
 }
 
+/// Las cuatro columnas `position_*` de entregas y fallos.
+fn position_from_columns(
+    lat: Option<f64>,
+    lng: Option<f64>,
+    accuracy_m: Option<f64>,
+    recorded_at: Option<DateTime<Utc>>,
+) -> Option<PositionFix> {
+    Some(PositionFix {
+        lat: lat?,
+        lng: lng?,
+        accuracy_m,
+        recorded_at: recorded_at?,
+    })
+}
+
 impl From<DeliveryRow> for Delivery {
     fn from(value: DeliveryRow) -> Self {
         Delivery {
@@ -114,11 +424,62 @@ impl From<DeliveryRow> for Delivery {
             llenas_entregadas: value.llenas_entregadas,
             vacias_recibidas: value.vacias_recibidas,
             notes: value.notes,
+            position: position_from_columns(
+                value.position_lat,
+                value.position_lng,
+                value.position_accuracy_m,
+                value.position_recorded_at,
+            ),
+            voided_at: value.voided_at,
+            follow_up_order_id: value.follow_up_order_id,
             created_at: value.created_at,
         }
     }
 }
 
+const DELIVERY_COLUMNS: &str = "id, order_id, llenas_entregadas, vacias_recibidas, notes, position_lat, position_lng, position_accuracy_m, position_recorded_at, voided_at, follow_up_order_id, created_at";
+
+#[derive(Debug, FromRow)]
+struct DeliveryCorrectionRow {
+    id: Uuid,
+    delivery_id: Uuid,
+    order_id: Uuid,
+    kind: String,
+    llenas_antes: i32,
+    vacias_antes: i32,
+    llenas_despues: i32,
+    vacias_despues: i32,
+    reason: String,
+    corrected_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<DeliveryCorrectionRow> for DeliveryCorrection {
+    type Error = DomainError;
+
+    fn try_from(row: DeliveryCorrectionRow) -> Result<Self, Self::Error> {
+        let kind = DeliveryCorrectionKind::from_str(&row.kind).ok_or_else(|| {
+            DomainError::Infrastructure(format!("tipo de corrección inválido: {}", row.kind))
+        })?;
+        Ok(Self {
+            id: row.id,
+            delivery_id: row.delivery_id,
+            order_id: row.order_id,
+            kind,
+            llenas_antes: row.llenas_antes,
+            vacias_antes: row.vacias_antes,
+            llenas_despues: row.llenas_despues,
+            vacias_despues: row.vacias_despues,
+            reason: row.reason,
+            corrected_by: row.corrected_by,
+            created_at: row.created_at,
+        })
+    }
+}
+
+/// `order_id` sale de la entrega; las columnas van calificadas con `c.`.
+const DELIVERY_CORRECTION_COLUMNS: &str = "c.id, c.delivery_id, d.order_id, c.kind, c.llenas_antes, c.vacias_antes, c.llenas_despues, c.vacias_despues, c.reason, c.corrected_by, c.created_at";
+
 #[derive(Debug, FromRow)]
 struct FailedDeliveryRow {
     id: Uuid,
@@ -126,6 +487,10 @@ struct FailedDeliveryRow {
     reason: String,
     reprogram_date: Option<NaiveDate>,
     reprogram_time_slot: Option<String>,
+    position_lat: Option<f64>,
+    position_lng: Option<f64>,
+    position_accuracy_m: Option<f64>,
+    position_recorded_at: Option<DateTime<Utc>>,
     created_at: DateTime<Utc>,
 }
 
@@ -137,384 +502,3887 @@ impl From<FailedDeliveryRow> for FailedDelivery {
             reason: value.reason,
             reprogram_date: value.reprogram_date,
             reprogram_time_slot: value.reprogram_time_slot,
+            position: position_from_columns(
+                value.position_lat,
+                value.position_lng,
+                value.position_accuracy_m,
+                value.position_recorded_at,
+            ),
             created_at: value.created_at,
         }
     }
 }
 
-#[async_trait]
-impl AuthPort for PgRepository {
-    async fn find_user_by_username(&self, username: &str) -> Result<Option<User>, DomainError> {
-        let row = sqlx::query_as::<_, UserRow>(
-            "SELECT id, username, password, role FROM users WHERE username = $1",
-        )
-        .bind(username)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(Self::map_sqlx_error)?;
+#[derive(Debug, FromRow)]
+struct ZoneRow {
+    id: Uuid,
+    name: String,
+    polygon: Option<serde_json::Value>,
+    service_days: Vec<String>,
+    default_slots: Vec<String>,
+    active: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
 
-        row.map(User::try_from).transpose()
-    }
+impl TryFrom<ZoneRow> for Zone {
+    type Error = DomainError;
 
-    async fn find_user_by_id(&self, user_id: Uuid) -> Result<Option<User>, DomainError> {
-        let row = sqlx::query_as::<_, UserRow>(
-            "SELECT id, username, password, role FROM users WHERE id = $1",
-        )
-        .bind(user_id)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(Self::map_sqlx_error)?;
+    fn try_from(value: ZoneRow) -> Result<Self, Self::Error> {
+        let polygon = value
+            .polygon
+            .map(serde_json::from_value::<Vec<GeoPoint>>)
+            .transpose()
+            .map_err(|_| DomainError::Infrastructure("polígono inválido en DB".to_string()))?;
+        let service_days = value
+            .service_days
+            .iter()
+            .map(|day| DayOfWeek::from_str(day))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| DomainError::Infrastructure("día inválido en DB".to_string()))?;
 
-        row.map(User::try_from).transpose()
+        Ok(Zone {
+            id: value.id,
+            name: value.name,
+            polygon,
+            service_days,
+            default_slots: value.default_slots,
+            active: value.active,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        })
     }
 }
 
-#[async_trait]
-impl OrdersPort for PgRepository {
-    async fn create_order(&self, input: NewOrder) -> Result<Order, DomainError> {
-        let row = sqlx::query_as::<_, OrderRow>(
-            r#"
-            INSERT INTO orders (
-                id, address, zone, scheduled_date, time_slot, quantity, notes, status, assignee_id
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, 'PENDIENTE', NULL)
-            RETURNING id, address, zone, scheduled_date, time_slot, quantity, notes, status, assignee_id, created_at, updated_at
-            "#,
-        )
-        .bind(Uuid::new_v4())
-        .bind(input.address)
-        .bind(input.zone)
-        .bind(input.scheduled_date)
-        .bind(input.time_slot)
-        .bind(input.quantity)
-        .bind(input.notes)
-        .fetch_one(&self.pool)
-        .await
-        .map_err(Self::map_sqlx_error)?;
-
-        row.try_into()
-    }
-
-    async fn list_orders(&self, filter: OrderFilter) -> Result<PaginatedOrders, DomainError> {
-        let page = filter.page;
-        let page_size = filter.page_size;
-        let offset = (page - 1) * page_size;
-
-        let mut count_builder =
-            QueryBuilder::<Postgres>::new("SELECT COUNT(*)::BIGINT FROM orders WHERE 1=1");
+fn day_names(days: &[DayOfWeek]) -> Vec<String> {
+    days.iter().map(|day| day.as_str().to_string()).collect()
+}
 
-        if let Some(date) = filter.date {
-            count_builder.push(" AND scheduled_date = ").push_bind(date);
-        }
+fn polygon_json(polygon: Option<Vec<GeoPoint>>) -> Result<Option<serde_json::Value>, DomainError> {
+    polygon
+        .map(serde_json::to_value)
+        .transpose()
+        .map_err(|e| DomainError::Infrastructure(e.to_string()))
+}
 
-        if let Some(status) = &filter.status {
-            count_builder
-                .push(" AND status = ")
-                .push_bind(status.as_str());
-        }
+#[derive(Debug, FromRow)]
+struct TimeSlotRow {
+    id: Uuid,
+    name: String,
+    start_time: NaiveTime,
+    end_time: NaiveTime,
+    max_orders: Option<i32>,
+    max_cylinders: Option<i32>,
+    active: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
 
-        if let Some(assignee) = filter.assignee {
-            count_builder
-                .push(" AND assignee_id = ")
-                .push_bind(assignee);
+impl From<TimeSlotRow> for TimeSlot {
+    fn from(value: TimeSlotRow) -> Self {
+        TimeSlot {
+            id: value.id,
+            name: value.name,
+            start_time: value.start_time,
+            end_time: value.end_time,
+            max_orders: value.max_orders,
+            max_cylinders: value.max_cylinders,
+            active: value.active,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
         }
+    }
+}
 
-        let total: i64 = count_builder
-            .build_query_scalar()
-            .fetch_one(&self.pool)
-            .await
-            .map_err(Self::map_sqlx_error)?;
-
-        let mut builder = QueryBuilder::<Postgres>::new(
-            "SELECT id, address, zone, scheduled_date, time_slot, quantity, notes, status, assignee_id, created_at, updated_at FROM orders WHERE 1=1",
-        );
+#[derive(Debug, FromRow)]
+struct SlotZoneCapacityRow {
+    slot_id: Uuid,
+    zone_id: Uuid,
+    max_orders: Option<i32>,
+    max_cylinders: Option<i32>,
+}
 
-        if let Some(date) = filter.date {
-            builder.push(" AND scheduled_date = ").push_bind(date);
+impl From<SlotZoneCapacityRow> for SlotZoneCapacity {
+    fn from(value: SlotZoneCapacityRow) -> Self {
+        SlotZoneCapacity {
+            slot_id: value.slot_id,
+            zone_id: value.zone_id,
+            max_orders: value.max_orders,
+            max_cylinders: value.max_cylinders,
         }
+    }
+}
 
-        if let Some(status) = filter.status {
-            builder.push(" AND status = ").push_bind(status.as_str());
-        }
+#[derive(Debug, FromRow)]
+struct HolidayRow {
+    date: NaiveDate,
+    description: String,
+    created_at: DateTime<Utc>,
+}
 
-        if let Some(assignee) = filter.assignee {
-            builder.push(" AND assignee_id = ").push_bind(assignee);
+impl From<HolidayRow> for Holiday {
+    fn from(value: HolidayRow) -> Self {
+        Holiday {
+            date: value.date,
+            description: value.description,
+            created_at: value.created_at,
         }
+    }
+}
 
-        builder
-            .push(" ORDER BY scheduled_date ASC, created_at ASC")
-            .push(" LIMIT ")
-            .push_bind(page_size)
-            .push(" OFFSET ")
-            .push_bind(offset);
+#[derive(Debug, FromRow)]
+struct RecurringOrderRow {
+    id: Uuid,
+    address: String,
+    zone_id: Uuid,
+    zone: String,
+    slot_id: Uuid,
+    time_slot: String,
+    quantity: i32,
+    interval_weeks: i32,
+    notes: Option<String>,
+    next_date: NaiveDate,
+    status: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
 
-        let rows = builder
-            .build_query_as::<OrderRow>()
-            .fetch_all(&self.pool)
-            .await
-            .map_err(Self::map_sqlx_error)?;
+impl TryFrom<RecurringOrderRow> for RecurringOrder {
+    type Error = DomainError;
 
-        let items: Vec<Order> = rows
-            .into_iter()
-            .map(TryInto::try_into)
-            .collect::<Result<_, _>>()?;
-        let total_pages = if total == 0 {
-            0
-        } else {
-            (total + page_size - 1) / page_size
-        };
+    fn try_from(value: RecurringOrderRow) -> Result<Self, Self::Error> {
+        let status = RecurringStatus::from_str(&value.status)
+            .ok_or_else(|| DomainError::Infrastructure("estado inválido en DB".to_string()))?;
 
-        Ok(PaginatedOrders {
-            items,
-            page,
-            page_size,
-            total,
-            total_pages,
+        Ok(RecurringOrder {
+            id: value.id,
+            address: value.address,
+            zone_id: value.zone_id,
+            zone: value.zone,
+            slot_id: value.slot_id,
+            time_slot: value.time_slot,
+            quantity: value.quantity,
+            interval_weeks: value.interval_weeks,
+            notes: value.notes,
+            next_date: value.next_date,
+            status,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
         })
     }
+}
 
-    async fn get_order_by_id(&self, order_id: Uuid) -> Result<Option<Order>, DomainError> {
-        let row = sqlx::query_as::<_, OrderRow>(
-            "SELECT id, address, zone, scheduled_date, time_slot, quantity, notes, status, assignee_id, created_at, updated_at FROM orders WHERE id = $1",
-        )
-        .bind(order_id)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(Self::map_sqlx_error)?;
+#[derive(Debug, FromRow)]
+struct RecurringOccurrenceRow {
+    recurring_order_id: Uuid,
+    scheduled_date: NaiveDate,
+    status: String,
+    order_id: Option<Uuid>,
+    detail: Option<String>,
+    created_at: DateTime<Utc>,
+}
 
-        row.map(TryInto::try_into).transpose()
-    }
+impl TryFrom<RecurringOccurrenceRow> for RecurringOccurrence {
+    type Error = DomainError;
 
-    async fn update_order_status(
-        &self,
+    fn try_from(value: RecurringOccurrenceRow) -> Result<Self, Self::Error> {
+        let status = OccurrenceStatus::from_str(&value.status)
+            .ok_or_else(|| DomainError::Infrastructure("estado inválido en DB".to_string()))?;
+
+        Ok(RecurringOccurrence {
+            recurring_order_id: value.recurring_order_id,
+            scheduled_date: value.scheduled_date,
+            status,
+            order_id: value.order_id,
+            detail: value.detail,
+            created_at: value.created_at,
+        })
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct JobRunRow {
+    id: Uuid,
+    job_name: String,
+    trigger: String,
+    status: String,
+    started_at: DateTime<Utc>,
+    finished_at: Option<DateTime<Utc>>,
+    output: Option<serde_json::Value>,
+    error: Option<String>,
+}
+
+impl TryFrom<JobRunRow> for JobRun {
+    type Error = DomainError;
+
+    fn try_from(value: JobRunRow) -> Result<Self, Self::Error> {
+        let trigger = JobTrigger::from_str(&value.trigger)
+            .ok_or_else(|| DomainError::Infrastructure("trigger inválido en DB".to_string()))?;
+        let status = JobRunStatus::from_str(&value.status)
+            .ok_or_else(|| DomainError::Infrastructure("estado inválido en DB".to_string()))?;
+
+        Ok(JobRun {
+            id: value.id,
+            job_name: value.job_name,
+            trigger,
+            status,
+            started_at: value.started_at,
+            finished_at: value.finished_at,
+            output: value.output,
+            error: value.error,
+        })
+    }
+}
+
+// Zona y franja se leen del catálogo para seguir los renombres.
+const RECURRING_ORDER_SELECT: &str = r#"
+    SELECT r.id, r.address, r.zone_id, z.name AS zone, r.slot_id, s.name AS time_slot,
+           r.quantity, r.interval_weeks, r.notes, r.next_date, r.status, r.created_at, r.updated_at
+    FROM recurring_orders r
+    JOIN zones z ON z.id = r.zone_id
+    JOIN time_slots s ON s.id = r.slot_id
+"#;
+
+#[derive(Debug, FromRow)]
+struct ApiKeyRow {
+    id: Uuid,
+    name: String,
+    prefix: String,
+    permissions: Vec<String>,
+    created_by: Uuid,
+    expires_at: Option<DateTime<Utc>>,
+    last_used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<ApiKeyRow> for ApiKey {
+    type Error = DomainError;
+
+    fn try_from(value: ApiKeyRow) -> Result<Self, Self::Error> {
+        let permissions = value
+            .permissions
+            .iter()
+            .map(|p| {
+                Permission::from_str(p).ok_or_else(|| {
+                    DomainError::Infrastructure("permiso inválido en DB".to_string())
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(ApiKey {
+            id: value.id,
+            name: value.name,
+            prefix: value.prefix,
+            permissions,
+            created_by: value.created_by,
+            expires_at: value.expires_at,
+            last_used_at: value.last_used_at,
+            revoked_at: value.revoked_at,
+            created_at: value.created_at,
+        })
+    }
+}
+
+const API_KEY_COLUMNS: &str =
+    "id, name, prefix, permissions, created_by, expires_at, last_used_at, revoked_at, created_at";
+
+#[derive(Debug, FromRow)]
+struct CustomerAddressRow {
+    id: Uuid,
+    customer_id: Uuid,
+    label: String,
+    address: String,
+    zone_id: Uuid,
+    zone: String,
+    notes: Option<String>,
+    active: bool,
+    created_at: DateTime<Utc>,
+}
+
+impl From<CustomerAddressRow> for CustomerAddress {
+    fn from(value: CustomerAddressRow) -> Self {
+        CustomerAddress {
+            id: value.id,
+            customer_id: value.customer_id,
+            label: value.label,
+            address: value.address,
+            zone_id: value.zone_id,
+            zone: value.zone,
+            notes: value.notes,
+            active: value.active,
+            created_at: value.created_at,
+        }
+    }
+}
+
+const CUSTOMER_ADDRESS_SELECT: &str = r#"
+    SELECT a.id, a.customer_id, a.label, a.address, a.zone_id, z.name AS zone, a.notes,
+           a.active, a.created_at
+    FROM customer_addresses a
+    JOIN zones z ON z.id = a.zone_id
+"#;
+
+#[derive(Debug, FromRow)]
+struct OutboxEventRow {
+    id: Uuid,
+    event_type: String,
+    aggregate_id: Uuid,
+    payload: serde_json::Value,
+    occurred_at: DateTime<Utc>,
+    attempts: i32,
+    assignee_id: Option<Uuid>,
+    customer_id: Option<Uuid>,
+}
+
+impl TryFrom<OutboxEventRow> for OutboxEvent {
+    type Error = DomainError;
+
+    fn try_from(row: OutboxEventRow) -> Result<Self, Self::Error> {
+        let event_type = DomainEventType::from_str(&row.event_type).ok_or_else(|| {
+            DomainError::Infrastructure(format!("tipo de evento inválido: {}", row.event_type))
+        })?;
+        Ok(Self {
+            id: row.id,
+            event_type,
+            aggregate_id: row.aggregate_id,
+            payload: row.payload,
+            occurred_at: row.occurred_at,
+            attempts: row.attempts,
+            assignee_id: row.assignee_id,
+            customer_id: row.customer_id,
+        })
+    }
+}
+
+const OUTBOX_EVENT_COLUMNS: &str =
+    "id, event_type, aggregate_id, payload, occurred_at, attempts, assignee_id, customer_id";
+
+/// Encola el evento en la transacción del cambio que lo produjo: si ésta se
+/// revierte, el evento no existe.
+async fn insert_outbox_event(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    event: DomainEvent,
+) -> Result<(), DomainError> {
+    sqlx::query(
+        r#"
+        INSERT INTO outbox_events (id, event_type, aggregate_id, payload, assignee_id, customer_id)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(event.event_type.as_str())
+    .bind(event.aggregate_id)
+    .bind(event.payload)
+    .bind(event.assignee_id)
+    .bind(event.customer_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(PgRepository::map_sqlx_error)?;
+    Ok(())
+}
+
+const SLOT_LOAD_QUERY: &str = r#"
+    SELECT COUNT(*)::BIGINT, COALESCE(SUM(quantity), 0)::BIGINT
+    FROM orders
+    WHERE scheduled_date = $1 AND zone_id = $2 AND time_slot = $3
+      AND status <> 'CANCELADO'
+      AND ($4::UUID IS NULL OR id <> $4)
+"#;
+
+/// Toma el lock de la franja (fecha, zona, franja) hasta el fin de la
+/// transacción y verifica el cupo: las reservas concurrentes de la misma
+/// franja se serializan y la carga medida no cambia antes de escribir.
+async fn reserve_slot_in_tx(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    reservation: &SlotReservation,
+    date: NaiveDate,
+    quantity: i32,
+    exclude_order_id: Option<Uuid>,
+) -> Result<(), DomainError> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(format!(
+            "slot:{}:{}:{}",
+            date, reservation.zone_id, reservation.slot_name
+        ))
+        .execute(&mut **tx)
+        .await
+        .map_err(PgRepository::map_sqlx_error)?;
+
+    let (orders, cylinders) = sqlx::query_as::<_, (i64, i64)>(SLOT_LOAD_QUERY)
+        .bind(date)
+        .bind(reservation.zone_id)
+        .bind(&reservation.slot_name)
+        .bind(exclude_order_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(PgRepository::map_sqlx_error)?;
+
+    reservation.ensure_fits(date, SlotLoad { orders, cylinders }, quantity)
+}
+
+/// Reserva la franja y escribe el pedido con su `order.created` en el outbox.
+async fn insert_order_in_tx(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    input: NewOrder,
+    reservation: &SlotReservation,
+) -> Result<Order, DomainError> {
+    reserve_slot_in_tx(tx, reservation, input.scheduled_date, input.quantity, None).await?;
+
+    let row = sqlx::query_as::<_, OrderRow>(
+        r#"
+        INSERT INTO orders (
+            id, address, zone, zone_id, scheduled_date, time_slot, quantity, notes, status, assignee_id,
+            customer_id, address_id
+        ) VALUES (
+            $1, $2, $3, $8, $4, $5, $6, $7, 'PENDIENTE', NULL,
+            $9, $10
+        )
+        RETURNING id, address, zone, zone_id, scheduled_date, time_slot, quantity, notes, status, assignee_id, customer_id, address_id, created_at, updated_at
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(input.address)
+    .bind(&input.zone)
+    .bind(input.scheduled_date)
+    .bind(input.time_slot)
+    .bind(input.quantity)
+    .bind(input.notes)
+    .bind(reservation.zone_id)
+    .bind(input.customer_id)
+    .bind(input.address_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(PgRepository::map_sqlx_error)?;
+
+    let order: Order = row.try_into()?;
+    insert_outbox_event(tx, DomainEvent::order_created(&order)).await?;
+    Ok(order)
+}
+
+/// Cambia el estado del pedido y encola `order.status_changed`.
+async fn update_order_status_in_tx(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    order_id: Uuid,
+    status: OrderStatus,
+) -> Result<Order, DomainError> {
+    let row = sqlx::query_as::<_, OrderRow>(
+        r#"
+        UPDATE orders
+        SET status = $2, updated_at = NOW()
+        WHERE id = $1
+        RETURNING id, address, zone, zone_id, scheduled_date, time_slot, quantity, notes, status, assignee_id, customer_id, address_id, created_at, updated_at
+        "#,
+    )
+    .bind(order_id)
+    .bind(status.as_str())
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(PgRepository::map_sqlx_error)?;
+
+    let row = row.ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
+    let order: Order = row.try_into()?;
+    insert_outbox_event(tx, DomainEvent::order_status_changed(&order)).await?;
+    Ok(order)
+}
+
+/// Cancela o ajusta el seguimiento de una entrega corregida. Se vuelve a
+/// mirar su estado con la fila bloqueada: si salió a reparto entre la lectura
+/// y la escritura, la corrección no se asienta.
+async fn change_follow_up_in_tx(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    follow_up_id: Uuid,
+    change: FollowUpChange,
+    reservation: Option<&SlotReservation>,
+) -> Result<(), DomainError> {
+    sqlx::query("SELECT 1 FROM orders WHERE id = $1 FOR UPDATE")
+        .bind(follow_up_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(PgRepository::map_sqlx_error)?;
+    let follow_up = fetch_order_in_tx(tx, follow_up_id).await?;
+    ensure_follow_up_open(&follow_up)?;
+
+    match change {
+        FollowUpChange::Keep => {}
+        FollowUpChange::Cancel => {
+            update_order_status_in_tx(tx, follow_up_id, OrderStatus::Cancelado).await?;
+        }
+        FollowUpChange::Resize(quantity) => {
+            if let Some(reservation) = reservation {
+                reserve_slot_in_tx(
+                    tx,
+                    reservation,
+                    follow_up.scheduled_date,
+                    quantity,
+                    Some(follow_up_id),
+                )
+                .await?;
+            }
+            sqlx::query("UPDATE orders SET quantity = $2, updated_at = NOW() WHERE id = $1")
+                .bind(follow_up_id)
+                .bind(quantity)
+                .execute(&mut **tx)
+                .await
+                .map_err(PgRepository::map_sqlx_error)?;
+        }
+    }
+    Ok(())
+}
+
+/// Devuelve el pedido a `ASIGNADO` en otra fecha y franja. Con `reservation`
+/// mide el cupo bajo el lock de la franja, sin contarse a sí mismo.
+async fn reprogram_order_in_tx(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    order_id: Uuid,
+    scheduled_date: NaiveDate,
+    time_slot: String,
+    reservation: Option<&SlotReservation>,
+) -> Result<Order, DomainError> {
+    if let Some(reservation) = reservation {
+        let quantity: Option<i32> =
+            sqlx::query_scalar("SELECT quantity FROM orders WHERE id = $1 FOR UPDATE")
+                .bind(order_id)
+                .fetch_optional(&mut **tx)
+                .await
+                .map_err(PgRepository::map_sqlx_error)?;
+        let quantity =
+            quantity.ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
+        reserve_slot_in_tx(tx, reservation, scheduled_date, quantity, Some(order_id)).await?;
+    }
+
+    let row = sqlx::query_as::<_, OrderRow>(
+        r#"
+        UPDATE orders
+        SET scheduled_date = $2, time_slot = $3, status = 'ASIGNADO', updated_at = NOW()
+        WHERE id = $1
+        RETURNING id, address, zone, zone_id, scheduled_date, time_slot, quantity, notes, status, assignee_id, customer_id, address_id, created_at, updated_at
+        "#,
+    )
+    .bind(order_id)
+    .bind(scheduled_date)
+    .bind(time_slot)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(PgRepository::map_sqlx_error)?;
+
+    let row = row.ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
+    let order: Order = row.try_into()?;
+    insert_outbox_event(tx, DomainEvent::order_status_changed(&order)).await?;
+    Ok(order)
+}
+
+/// Pedido al que se refiere un evento de entrega, leído dentro de la misma
+/// transacción.
+async fn fetch_order_in_tx(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    order_id: Uuid,
+) -> Result<Order, DomainError> {
+    let row = sqlx::query_as::<_, OrderRow>(
+        "SELECT id, address, zone, zone_id, scheduled_date, time_slot, quantity, notes, status, assignee_id, customer_id, address_id, created_at, updated_at FROM orders WHERE id = $1",
+    )
+    .bind(order_id)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(PgRepository::map_sqlx_error)?;
+
+    let row = row.ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
+    row.try_into()
+}
+
+#[async_trait]
+impl AuthPort for PgRepository {
+    async fn find_user_by_username(&self, username: &str) -> Result<Option<User>, DomainError> {
+        let row = sqlx::query_as::<_, UserRow>(&format!(
+            "SELECT {} FROM users WHERE username = $1",
+            USER_COLUMNS
+        ))
+        .bind(username)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.map(User::try_from).transpose()
+    }
+
+    async fn find_user_by_id(&self, user_id: Uuid) -> Result<Option<User>, DomainError> {
+        let row = sqlx::query_as::<_, UserRow>(&format!(
+            "SELECT {} FROM users WHERE id = $1",
+            USER_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.map(User::try_from).transpose()
+    }
+
+    async fn store_refresh_token(&self, input: NewRefreshToken) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO refresh_tokens (id, user_id, token_hash, family_id, token_generation, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(input.user_id)
+        .bind(input.token_hash)
+        .bind(input.family_id)
+        .bind(input.token_generation)
+        .bind(input.expires_at)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn find_refresh_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<RefreshToken>, DomainError> {
+        let row = sqlx::query_as::<_, RefreshTokenRow>(
+            r#"
+            SELECT id, user_id, family_id, token_generation, expires_at, revoked_at
+            FROM refresh_tokens
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.map(Into::into))
+    }
+
+    async fn consume_refresh_token(&self, token_id: Uuid) -> Result<bool, DomainError> {
+        let result = sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL",
+        )
+        .bind(token_id)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn revoke_refresh_family(&self, family_id: Uuid) -> Result<(), DomainError> {
+        sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = NOW() WHERE family_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(family_id)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn revoke_user_tokens(&self, user_id: Uuid) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        sqlx::query(
+            "UPDATE users SET token_generation = token_generation + 1, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(())
+    }
+
+    async fn purge_refresh_tokens(&self, before: DateTime<Utc>) -> Result<u64, DomainError> {
+        let result = sqlx::query("DELETE FROM refresh_tokens WHERE expires_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn find_login_failures(
+        &self,
+        scope: LoginScope,
+        key: &str,
+    ) -> Result<Option<LoginFailures>, DomainError> {
+        let row = sqlx::query_as::<_, LoginFailuresRow>(
+            "SELECT failures, last_failure_at FROM login_failures WHERE scope = $1 AND key = $2",
+        )
+        .bind(scope.as_str())
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.map(Into::into))
+    }
+
+    async fn record_login_failure(
+        &self,
+        scope: LoginScope,
+        key: &str,
+        now: DateTime<Utc>,
+        window_start: DateTime<Utc>,
+    ) -> Result<LoginFailures, DomainError> {
+        let row = sqlx::query_as::<_, LoginFailuresRow>(
+            r#"
+            INSERT INTO login_failures (scope, key, failures, last_failure_at)
+            VALUES ($1, $2, 1, $3)
+            ON CONFLICT (scope, key) DO UPDATE SET
+                failures = CASE
+                    WHEN login_failures.last_failure_at < $4 THEN 1
+                    ELSE login_failures.failures + 1
+                END,
+                last_failure_at = EXCLUDED.last_failure_at
+            RETURNING failures, last_failure_at
+            "#,
+        )
+        .bind(scope.as_str())
+        .bind(key)
+        .bind(now)
+        .bind(window_start)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.into())
+    }
+
+    async fn clear_login_failures(&self, scope: LoginScope, key: &str) -> Result<(), DomainError> {
+        sqlx::query("DELETE FROM login_failures WHERE scope = $1 AND key = $2")
+            .bind(scope.as_str())
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn lock_user(&self, user_id: Uuid) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET locked_at = NOW(),
+                token_generation = token_generation + 1,
+                updated_at = NOW()
+            WHERE id = $1 AND locked_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn update_password_hash(
+        &self,
+        user_id: Uuid,
+        current: &str,
+        password_hash: &str,
+    ) -> Result<(), DomainError> {
+        sqlx::query(
+            "UPDATE users SET password = $3, updated_at = NOW() WHERE id = $1 AND password = $2",
+        )
+        .bind(user_id)
+        .bind(current)
+        .bind(password_hash)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn change_password(
+        &self,
+        user_id: Uuid,
+        password_hash: &str,
+    ) -> Result<User, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        let row = sqlx::query_as::<_, UserRow>(&format!(
+            r#"
+            UPDATE users
+            SET password = $2,
+                must_change_password = FALSE,
+                token_generation = token_generation + 1,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            USER_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(password_hash)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?
+        .ok_or_else(|| DomainError::NotFound("usuario no encontrado".to_string()))?;
+
+        sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        row.try_into()
+    }
+
+    async fn create_password_reset(
+        &self,
+        input: NewPasswordReset,
+    ) -> Result<PasswordReset, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        sqlx::query("DELETE FROM password_resets WHERE user_id = $1 AND used_at IS NULL")
+            .bind(input.user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        let row = sqlx::query_as::<_, PasswordResetRow>(
+            r#"
+            INSERT INTO password_resets (id, user_id, code_hash, expires_at, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, user_id, expires_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(input.user_id)
+        .bind(input.code_hash)
+        .bind(input.expires_at)
+        .bind(input.created_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        sqlx::query(
+            "UPDATE users SET must_change_password = TRUE, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(input.user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(row.into())
+    }
+
+    async fn find_password_reset(
+        &self,
+        user_id: Uuid,
+        code_hash: &str,
+    ) -> Result<Option<PasswordReset>, DomainError> {
+        let row = sqlx::query_as::<_, PasswordResetRow>(
+            r#"
+            SELECT id, user_id, expires_at
+            FROM password_resets
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.map(Into::into))
+    }
+
+    async fn consume_password_reset(&self, reset_id: Uuid) -> Result<bool, DomainError> {
+        let result = sqlx::query(
+            "UPDATE password_resets SET used_at = NOW() WHERE id = $1 AND used_at IS NULL",
+        )
+        .bind(reset_id)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
+
+#[async_trait]
+impl OrdersPort for PgRepository {
+    async fn create_order(
+        &self,
+        input: NewOrder,
+        reservation: SlotReservation,
+    ) -> Result<Order, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+        let order = insert_order_in_tx(&mut tx, input, &reservation).await?;
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(order)
+    }
+
+    async fn list_orders(&self, filter: OrderFilter) -> Result<PaginatedOrders, DomainError> {
+        let page = filter.page;
+        let page_size = filter.page_size;
+        let offset = (page - 1) * page_size;
+
+        let mut count_builder =
+            QueryBuilder::<Postgres>::new("SELECT COUNT(*)::BIGINT FROM orders WHERE 1=1");
+
+        if let Some(date) = filter.date {
+            count_builder.push(" AND scheduled_date = ").push_bind(date);
+        }
+
+        if let Some(from_date) = filter.from_date {
+            count_builder
+                .push(" AND scheduled_date >= ")
+                .push_bind(from_date);
+        }
+
+        if let Some(status) = &filter.status {
+            count_builder
+                .push(" AND status = ")
+                .push_bind(status.as_str());
+        }
+
+        if let Some(assignee) = filter.assignee {
+            count_builder
+                .push(" AND assignee_id = ")
+                .push_bind(assignee);
+        }
+
+        if let Some(customer) = filter.customer {
+            count_builder
+                .push(" AND customer_id = ")
+                .push_bind(customer);
+        }
+
+        let total: i64 = count_builder
+            .build_query_scalar()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        let mut builder = QueryBuilder::<Postgres>::new(
+            "SELECT id, address, zone, zone_id, scheduled_date, time_slot, quantity, notes, status, assignee_id, customer_id, address_id, created_at, updated_at FROM orders WHERE 1=1",
+        );
+
+        if let Some(date) = filter.date {
+            builder.push(" AND scheduled_date = ").push_bind(date);
+        }
+
+        if let Some(from_date) = filter.from_date {
+            builder.push(" AND scheduled_date >= ").push_bind(from_date);
+        }
+
+        if let Some(status) = filter.status {
+            builder.push(" AND status = ").push_bind(status.as_str());
+        }
+
+        if let Some(assignee) = filter.assignee {
+            builder.push(" AND assignee_id = ").push_bind(assignee);
+        }
+
+        if let Some(customer) = filter.customer {
+            builder.push(" AND customer_id = ").push_bind(customer);
+        }
+
+        builder
+            .push(" ORDER BY scheduled_date ASC, created_at ASC")
+            .push(" LIMIT ")
+            .push_bind(page_size)
+            .push(" OFFSET ")
+            .push_bind(offset);
+
+        let rows = builder
+            .build_query_as::<OrderRow>()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        let items: Vec<Order> = rows
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?;
+        let total_pages = if total == 0 {
+            0
+        } else {
+            (total + page_size - 1) / page_size
+        };
+
+        Ok(PaginatedOrders {
+            items,
+            page,
+            page_size,
+            total,
+            total_pages,
+        })
+    }
+
+    async fn get_order_by_id(&self, order_id: Uuid) -> Result<Option<Order>, DomainError> {
+        let row = sqlx::query_as::<_, OrderRow>(
+            "SELECT id, address, zone, zone_id, scheduled_date, time_slot, quantity, notes, status, assignee_id, customer_id, address_id, created_at, updated_at FROM orders WHERE id = $1",
+        )
+        .bind(order_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+
+    async fn update_order_status(
+        &self,
         order_id: Uuid,
         status: OrderStatus,
-    ) -> Result<Order, DomainError> {
-        let row = sqlx::query_as::<_, OrderRow>(
+    ) -> Result<Order, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+        let order = update_order_status_in_tx(&mut tx, order_id, status).await?;
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(order)
+    }
+
+    async fn assign_orders(&self, order_ids: &[Uuid], driver_id: Uuid) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        for order_id in order_ids {
+            let row = sqlx::query_as::<_, OrderRow>(
+                r#"
+                UPDATE orders SET assignee_id = $1, status = 'ASIGNADO', updated_at = NOW()
+                WHERE id = $2 AND status <> 'CANCELADO'
+                RETURNING id, address, zone, zone_id, scheduled_date, time_slot, quantity, notes, status, assignee_id, customer_id, address_id, created_at, updated_at
+                "#,
+            )
+            .bind(driver_id)
+            .bind(*order_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+            let Some(row) = row else {
+                return Err(DomainError::NotFound(format!(
+                    "pedido {} no encontrado o cancelado",
+                    order_id
+                )));
+            };
+            let order: Order = row.try_into()?;
+            insert_outbox_event(&mut tx, DomainEvent::order_assigned(&order)).await?;
+
+            sqlx::query("INSERT INTO assignments (id, order_id, driver_id) VALUES ($1, $2, $3)")
+                .bind(Uuid::new_v4())
+                .bind(*order_id)
+                .bind(driver_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(Self::map_sqlx_error)?;
+        }
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DeliveriesPort for PgRepository {
+    async fn create_delivery(
+        &self,
+        input: NewDelivery,
+        status: OrderStatus,
+        follow_up: Option<(NewOrder, SlotReservation)>,
+    ) -> Result<RegisteredDelivery, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        let follow_up = match follow_up {
+            Some((order, reservation)) => {
+                Some(insert_order_in_tx(&mut tx, order, &reservation).await?)
+            }
+            None => None,
+        };
+
+        let row = sqlx::query_as::<_, DeliveryRow>(&format!(
+            r#"
+            INSERT INTO deliveries (
+                id, order_id, llenas_entregadas, vacias_recibidas, notes,
+                position_lat, position_lng, position_accuracy_m, position_recorded_at,
+                follow_up_order_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING {}
+            "#,
+            DELIVERY_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(input.order_id)
+        .bind(input.llenas_entregadas)
+        .bind(input.vacias_recibidas)
+        .bind(input.notes)
+        .bind(input.position.map(|fix| fix.lat))
+        .bind(input.position.map(|fix| fix.lng))
+        .bind(input.position.and_then(|fix| fix.accuracy_m))
+        .bind(input.position.map(|fix| fix.recorded_at))
+        .bind(follow_up.as_ref().map(|order| order.id))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let delivery: Delivery = row.into();
+        let order = fetch_order_in_tx(&mut tx, delivery.order_id).await?;
+        insert_outbox_event(&mut tx, DomainEvent::delivery_registered(&delivery, &order)).await?;
+        let order = update_order_status_in_tx(&mut tx, delivery.order_id, status).await?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(RegisteredDelivery {
+            delivery,
+            order,
+            follow_up,
+        })
+    }
+
+    async fn create_failed_delivery(
+        &self,
+        input: NewFailedDelivery,
+        scheduled_date: NaiveDate,
+        time_slot: String,
+        reservation: Option<SlotReservation>,
+    ) -> Result<RegisteredFailure, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        let row = sqlx::query_as::<_, FailedDeliveryRow>(
+            r#"
+            INSERT INTO delivery_failures (
+                id, order_id, reason, reprogram_date, reprogram_time_slot,
+                position_lat, position_lng, position_accuracy_m, position_recorded_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, order_id, reason, reprogram_date, reprogram_time_slot,
+                      position_lat, position_lng, position_accuracy_m, position_recorded_at,
+                      created_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(input.order_id)
+        .bind(input.reason)
+        .bind(input.reprogram_date)
+        .bind(input.reprogram_time_slot)
+        .bind(input.position.map(|fix| fix.lat))
+        .bind(input.position.map(|fix| fix.lng))
+        .bind(input.position.and_then(|fix| fix.accuracy_m))
+        .bind(input.position.map(|fix| fix.recorded_at))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let failed: FailedDelivery = row.into();
+        let order = fetch_order_in_tx(&mut tx, failed.order_id).await?;
+        insert_outbox_event(&mut tx, DomainEvent::delivery_failed(&failed, &order)).await?;
+        let order = reprogram_order_in_tx(
+            &mut tx,
+            order.id,
+            scheduled_date,
+            time_slot,
+            reservation.as_ref(),
+        )
+        .await?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(RegisteredFailure { failed, order })
+    }
+
+    async fn get_delivery(&self, delivery_id: Uuid) -> Result<Option<Delivery>, DomainError> {
+        let row = sqlx::query_as::<_, DeliveryRow>(&format!(
+            "SELECT {} FROM deliveries WHERE id = $1",
+            DELIVERY_COLUMNS
+        ))
+        .bind(delivery_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.map(Into::into))
+    }
+
+    async fn get_failed_delivery(
+        &self,
+        failure_id: Uuid,
+    ) -> Result<Option<FailedDelivery>, DomainError> {
+        let row = sqlx::query_as::<_, FailedDeliveryRow>(
+            r#"
+            SELECT id, order_id, reason, reprogram_date, reprogram_time_slot,
+                   position_lat, position_lng, position_accuracy_m, position_recorded_at,
+                   created_at
+            FROM delivery_failures WHERE id = $1
+            "#,
+        )
+        .bind(failure_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.map(Into::into))
+    }
+
+    async fn correct_delivery(
+        &self,
+        input: NewDeliveryCorrection,
+    ) -> Result<DeliveryCorrection, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        let delivery: Delivery = sqlx::query_as::<_, DeliveryRow>(&format!(
+            "SELECT {} FROM deliveries WHERE id = $1 FOR UPDATE",
+            DELIVERY_COLUMNS
+        ))
+        .bind(input.delivery_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?
+        .ok_or_else(|| DomainError::NotFound("entrega no encontrada".to_string()))?
+        .into();
+        if delivery.voided_at.is_some() {
+            return Err(DomainError::Conflict("la entrega está anulada".to_string()));
+        }
+
+        // Las cantidades vigentes son las de la última corrección.
+        let (llenas_antes, vacias_antes) = sqlx::query_as::<_, (i32, i32)>(
+            r#"
+            SELECT llenas_despues, vacias_despues FROM delivery_corrections
+            WHERE delivery_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(delivery.id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?
+        .unwrap_or((delivery.llenas_entregadas, delivery.vacias_recibidas));
+
+        if input.kind == DeliveryCorrectionKind::Correccion
+            && (input.llenas_entregadas, input.vacias_recibidas) == (llenas_antes, vacias_antes)
+        {
+            return Err(DomainError::Validation(
+                "la corrección no cambia las cantidades vigentes".to_string(),
+            ));
+        }
+
+        let row = sqlx::query_as::<_, DeliveryCorrectionRow>(&format!(
+            r#"
+            WITH c AS (
+                INSERT INTO delivery_corrections (
+                    id, delivery_id, kind, llenas_antes, vacias_antes,
+                    llenas_despues, vacias_despues, reason, corrected_by
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                RETURNING *
+            )
+            SELECT {} FROM c JOIN deliveries d ON d.id = c.delivery_id
+            "#,
+            DELIVERY_CORRECTION_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(delivery.id)
+        .bind(input.kind.as_str())
+        .bind(llenas_antes)
+        .bind(vacias_antes)
+        .bind(input.llenas_entregadas)
+        .bind(input.vacias_recibidas)
+        .bind(input.reason)
+        .bind(input.corrected_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+        let correction: DeliveryCorrection = row.try_into()?;
+
+        if input.kind == DeliveryCorrectionKind::Anulacion {
+            sqlx::query("UPDATE deliveries SET voided_at = $2 WHERE id = $1")
+                .bind(delivery.id)
+                .bind(correction.created_at)
+                .execute(&mut *tx)
+                .await
+                .map_err(Self::map_sqlx_error)?;
+        }
+        if let Some(follow_up_id) = delivery.follow_up_order_id {
+            if input.follow_up != FollowUpChange::Keep {
+                change_follow_up_in_tx(
+                    &mut tx,
+                    follow_up_id,
+                    input.follow_up,
+                    input.follow_up_reservation.as_ref(),
+                )
+                .await?;
+            }
+        }
+        let status_changed = sqlx::query(
+            "UPDATE orders SET status = $2, updated_at = NOW() WHERE id = $1 AND status <> $2",
+        )
+        .bind(delivery.order_id)
+        .bind(input.order_status.as_str())
+        .execute(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?
+        .rows_affected()
+            > 0;
+
+        let order = fetch_order_in_tx(&mut tx, delivery.order_id).await?;
+        insert_outbox_event(
+            &mut tx,
+            DomainEvent::delivery_corrected(&correction, &order),
+        )
+        .await?;
+        if status_changed {
+            insert_outbox_event(&mut tx, DomainEvent::order_status_changed(&order)).await?;
+        }
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(correction)
+    }
+
+    async fn list_delivery_corrections(
+        &self,
+        delivery_id: Uuid,
+    ) -> Result<Vec<DeliveryCorrection>, DomainError> {
+        let rows = sqlx::query_as::<_, DeliveryCorrectionRow>(&format!(
+            r#"
+            SELECT {} FROM delivery_corrections c
+            JOIN deliveries d ON d.id = c.delivery_id
+            WHERE c.delivery_id = $1
+            ORDER BY c.created_at, c.id
+            "#,
+            DELIVERY_CORRECTION_COLUMNS
+        ))
+        .bind(delivery_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+}
+
+#[async_trait]
+impl StockPort for PgRepository {
+    async fn register_inbound(&self, input: Inbound) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+        let inbound_id = Uuid::new_v4();
+
+        sqlx::query(
+            "INSERT INTO stock_inbounds (id, inbound_date, cantidad_llenas, notes) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(inbound_id)
+        .bind(input.date)
+        .bind(input.cantidad_llenas)
+        .bind(&input.notes)
+        .execute(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        insert_outbox_event(
+            &mut tx,
+            DomainEvent::stock_inbound_registered(inbound_id, &input),
+        )
+        .await?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(())
+    }
+
+    async fn stock_totals(
+        &self,
+        date: Option<NaiveDate>,
+        tz: Tz,
+    ) -> Result<StockTotals, DomainError> {
+        let inbound_full: i64;
+        let delivered_full: i64;
+        let recovered_empty: i64;
+
+        if let Some(d) = date {
+            inbound_full = sqlx::query_scalar(
+                "SELECT COALESCE(SUM(cantidad_llenas), 0)::BIGINT FROM stock_inbounds WHERE inbound_date <= $1",
+            )
+            .bind(d)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+            let row = sqlx::query_as::<_, (i64, i64)>(
+                r#"
+                SELECT COALESCE(SUM(llenas), 0)::BIGINT, COALESCE(SUM(vacias), 0)::BIGINT
+                FROM (
+                    SELECT llenas_entregadas AS llenas, vacias_recibidas AS vacias, created_at
+                    FROM deliveries
+                    UNION ALL
+                    SELECT llenas_despues - llenas_antes, vacias_despues - vacias_antes, created_at
+                    FROM delivery_corrections
+                ) movements
+                WHERE (created_at AT TIME ZONE $2)::date <= $1
+                "#,
+            )
+            .bind(d)
+            .bind(tz.name())
+            .fetch_one(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+            delivered_full = row.0;
+            recovered_empty = row.1;
+        } else {
+            inbound_full = sqlx::query_scalar(
+                "SELECT COALESCE(SUM(cantidad_llenas), 0)::BIGINT FROM stock_inbounds",
+            )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+            let row = sqlx::query_as::<_, (i64, i64)>(
+                r#"
+                SELECT
+                    (SELECT COALESCE(SUM(llenas_entregadas), 0) FROM deliveries)
+                        + (SELECT COALESCE(SUM(llenas_despues - llenas_antes), 0) FROM delivery_corrections),
+                    (SELECT COALESCE(SUM(vacias_recibidas), 0) FROM deliveries)
+                        + (SELECT COALESCE(SUM(vacias_despues - vacias_antes), 0) FROM delivery_corrections)
+                "#,
+            )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+            delivered_full = row.0;
+            recovered_empty = row.1;
+        }
+
+        Ok(StockTotals {
+            inbound_full,
+            delivered_full,
+            recovered_empty,
+        })
+    }
+
+    async fn daily_report_totals(
+        &self,
+        date: NaiveDate,
+        tz: Tz,
+    ) -> Result<DailyReportTotals, DomainError> {
+        let row = sqlx::query_as::<_, (i64, i64, i64)>(
+            r#"
+            SELECT COALESCE(SUM(entregas), 0)::BIGINT, COALESCE(SUM(llenas), 0)::BIGINT,
+                   COALESCE(SUM(vacias), 0)::BIGINT
+            FROM (
+                SELECT 1 AS entregas, llenas_entregadas AS llenas, vacias_recibidas AS vacias
+                FROM deliveries WHERE (created_at AT TIME ZONE $2)::date = $1
+                UNION ALL
+                SELECT CASE kind WHEN 'ANULACION' THEN -1 ELSE 0 END,
+                       llenas_despues - llenas_antes, vacias_despues - vacias_antes
+                FROM delivery_corrections WHERE (created_at AT TIME ZONE $2)::date = $1
+            ) movements
+            "#,
+        )
+        .bind(date)
+        .bind(tz.name())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(DailyReportTotals {
+            deliveries_count: row.0,
+            delivered_full: row.1,
+            recovered_empty: row.2,
+        })
+    }
+}
+
+#[async_trait]
+impl OutboxPort for PgRepository {
+    async fn claim_outbox_events(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<OutboxEvent>, DomainError> {
+        // SKIP LOCKED evita que dos réplicas esperen por las mismas filas; la
+        // reserva de `next_attempt_at` cubre el tiempo que dura la entrega.
+        let rows = sqlx::query_as::<_, OutboxEventRow>(
+            r#"
+            UPDATE outbox_events
+            SET attempts = attempts + 1, next_attempt_at = $2
+            WHERE id IN (
+                SELECT id FROM outbox_events
+                WHERE published_at IS NULL AND next_attempt_at <= $1
+                ORDER BY occurred_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, event_type, aggregate_id, payload, occurred_at, attempts,
+                      assignee_id, customer_id
+            "#,
+        )
+        .bind(now)
+        .bind(now + chrono::Duration::seconds(OUTBOX_CLAIM_LEASE_SECONDS))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let mut events: Vec<OutboxEvent> = rows
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?;
+        events.sort_by_key(|event| event.occurred_at);
+        Ok(events)
+    }
+
+    async fn extend_outbox_claims(
+        &self,
+        event_ids: &[Uuid],
+        until: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        sqlx::query(
+            "UPDATE outbox_events SET next_attempt_at = $2 WHERE id = ANY($1) AND published_at IS NULL",
+        )
+        .bind(event_ids)
+        .bind(until)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn find_outbox_event(&self, event_id: Uuid) -> Result<Option<OutboxEvent>, DomainError> {
+        let row = sqlx::query_as::<_, OutboxEventRow>(&format!(
+            "SELECT {} FROM outbox_events WHERE id = $1",
+            OUTBOX_EVENT_COLUMNS
+        ))
+        .bind(event_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+
+    async fn mark_event_published(
+        &self,
+        event_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        sqlx::query("UPDATE outbox_events SET published_at = $2, last_error = NULL WHERE id = $1")
+            .bind(event_id)
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn mark_event_failed(
+        &self,
+        event_id: Uuid,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), DomainError> {
+        sqlx::query("UPDATE outbox_events SET last_error = $2, next_attempt_at = $3 WHERE id = $1")
+            .bind(event_id)
+            .bind(error)
+            .bind(retry_at)
+            .execute(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+}
+
+const ATTACHMENT_COLUMNS: &str = "id, delivery_id, failure_id, order_id, kind, content_type, size_bytes, sha256, blob_key, thumbnail_key, uploaded_by, created_at";
+
+#[derive(Debug, FromRow)]
+struct AttachmentRow {
+    id: Uuid,
+    delivery_id: Option<Uuid>,
+    failure_id: Option<Uuid>,
+    order_id: Uuid,
+    kind: String,
+    content_type: String,
+    size_bytes: i64,
+    sha256: String,
+    blob_key: String,
+    thumbnail_key: Option<String>,
+    uploaded_by: Option<Uuid>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<AttachmentRow> for StoredAttachment {
+    type Error = DomainError;
+
+    fn try_from(row: AttachmentRow) -> Result<Self, Self::Error> {
+        let kind = AttachmentKind::from_str(&row.kind).ok_or_else(|| {
+            DomainError::Infrastructure(format!("tipo de adjunto inválido: {}", row.kind))
+        })?;
+        let (target, record_id) = match (row.delivery_id, row.failure_id) {
+            (Some(delivery_id), _) => (AttachmentTarget::Entrega, delivery_id),
+            (None, Some(failure_id)) => (AttachmentTarget::EntregaFallida, failure_id),
+            (None, None) => {
+                return Err(DomainError::Infrastructure(format!(
+                    "adjunto {} sin registro",
+                    row.id
+                )))
+            }
+        };
+        Ok(Self {
+            attachment: Attachment {
+                id: row.id,
+                target,
+                record_id,
+                order_id: row.order_id,
+                kind,
+                content_type: row.content_type,
+                size_bytes: row.size_bytes,
+                sha256: row.sha256,
+                has_thumbnail: row.thumbnail_key.is_some(),
+                uploaded_by: row.uploaded_by,
+                created_at: row.created_at,
+            },
+            blob_key: row.blob_key,
+            thumbnail_key: row.thumbnail_key,
+        })
+    }
+}
+
+fn attachment_target_column(target: AttachmentTarget) -> &'static str {
+    match target {
+        AttachmentTarget::Entrega => "delivery_id",
+        AttachmentTarget::EntregaFallida => "failure_id",
+    }
+}
+
+#[async_trait]
+impl AttachmentsPort for PgRepository {
+    async fn create_attachment(&self, input: NewAttachment) -> Result<Attachment, DomainError> {
+        let (delivery_id, failure_id) = match input.target {
+            AttachmentTarget::Entrega => (Some(input.record_id), None),
+            AttachmentTarget::EntregaFallida => (None, Some(input.record_id)),
+        };
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        // Dos cargas simultáneas no pueden pasarse del tope.
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind(format!("attachments:{}", input.record_id))
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+        let existing: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*)::BIGINT FROM delivery_attachments WHERE {} = $1",
+            attachment_target_column(input.target)
+        ))
+        .bind(input.record_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+        ensure_attachment_room(existing)?;
+
+        let row = sqlx::query_as::<_, AttachmentRow>(&format!(
+            r#"
+            INSERT INTO delivery_attachments (
+                id, delivery_id, failure_id, order_id, kind, content_type, size_bytes,
+                sha256, blob_key, thumbnail_key, uploaded_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING {}
+            "#,
+            ATTACHMENT_COLUMNS
+        ))
+        .bind(input.id)
+        .bind(delivery_id)
+        .bind(failure_id)
+        .bind(input.order_id)
+        .bind(input.kind.as_str())
+        .bind(input.content_type)
+        .bind(input.size_bytes)
+        .bind(input.sha256)
+        .bind(input.blob_key)
+        .bind(input.thumbnail_key)
+        .bind(input.uploaded_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        StoredAttachment::try_from(row).map(|stored| stored.attachment)
+    }
+
+    async fn list_attachments(
+        &self,
+        target: AttachmentTarget,
+        record_id: Uuid,
+    ) -> Result<Vec<Attachment>, DomainError> {
+        let rows = sqlx::query_as::<_, AttachmentRow>(&format!(
+            "SELECT {} FROM delivery_attachments WHERE {} = $1 ORDER BY created_at, id",
+            ATTACHMENT_COLUMNS,
+            attachment_target_column(target)
+        ))
+        .bind(record_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.into_iter()
+            .map(|row| StoredAttachment::try_from(row).map(|stored| stored.attachment))
+            .collect()
+    }
+
+    async fn count_attachments(
+        &self,
+        target: AttachmentTarget,
+        record_id: Uuid,
+    ) -> Result<i64, DomainError> {
+        let count: (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*)::BIGINT FROM delivery_attachments WHERE {} = $1",
+            attachment_target_column(target)
+        ))
+        .bind(record_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(count.0)
+    }
+
+    async fn find_attachment(
+        &self,
+        attachment_id: Uuid,
+    ) -> Result<Option<StoredAttachment>, DomainError> {
+        let row = sqlx::query_as::<_, AttachmentRow>(&format!(
+            "SELECT {} FROM delivery_attachments WHERE id = $1",
+            ATTACHMENT_COLUMNS
+        ))
+        .bind(attachment_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct DriverPositionRow {
+    driver_id: Uuid,
+    lat: f64,
+    lng: f64,
+    accuracy_m: Option<f64>,
+    recorded_at: DateTime<Utc>,
+    received_at: DateTime<Utc>,
+}
+
+impl From<DriverPositionRow> for DriverPosition {
+    fn from(value: DriverPositionRow) -> Self {
+        DriverPosition {
+            driver_id: value.driver_id,
+            lat: value.lat,
+            lng: value.lng,
+            accuracy_m: value.accuracy_m,
+            recorded_at: value.recorded_at,
+            received_at: value.received_at,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct LatestDriverPositionRow {
+    driver_id: Uuid,
+    username: String,
+    lat: f64,
+    lng: f64,
+    accuracy_m: Option<f64>,
+    recorded_at: DateTime<Utc>,
+}
+
+impl From<LatestDriverPositionRow> for LatestDriverPosition {
+    fn from(value: LatestDriverPositionRow) -> Self {
+        LatestDriverPosition {
+            driver_id: value.driver_id,
+            username: value.username,
+            lat: value.lat,
+            lng: value.lng,
+            accuracy_m: value.accuracy_m,
+            recorded_at: value.recorded_at,
+        }
+    }
+}
+
+#[async_trait]
+impl PositionsPort for PgRepository {
+    async fn record_positions(
+        &self,
+        driver_id: Uuid,
+        fixes: &[PositionFix],
+    ) -> Result<usize, DomainError> {
+        let ids: Vec<Uuid> = fixes.iter().map(|_| Uuid::new_v4()).collect();
+        let lats: Vec<f64> = fixes.iter().map(|fix| fix.lat).collect();
+        let lngs: Vec<f64> = fixes.iter().map(|fix| fix.lng).collect();
+        let accuracies: Vec<Option<f64>> = fixes.iter().map(|fix| fix.accuracy_m).collect();
+        let recorded: Vec<DateTime<Utc>> = fixes.iter().map(|fix| fix.recorded_at).collect();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO driver_positions (id, driver_id, lat, lng, accuracy_m, recorded_at)
+            SELECT fix.id, $1, fix.lat, fix.lng, fix.accuracy_m, fix.recorded_at
+            FROM UNNEST($2::uuid[], $3::float8[], $4::float8[], $5::float8[], $6::timestamptz[])
+                AS fix(id, lat, lng, accuracy_m, recorded_at)
+            ON CONFLICT (driver_id, recorded_at) DO NOTHING
+            "#,
+        )
+        .bind(driver_id)
+        .bind(ids)
+        .bind(lats)
+        .bind(lngs)
+        .bind(accuracies)
+        .bind(recorded)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() as usize)
+    }
+
+    async fn latest_driver_position(
+        &self,
+        driver_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Option<PositionFix>, DomainError> {
+        let row = sqlx::query_as::<_, DriverPositionRow>(
+            r#"
+            SELECT driver_id, lat, lng, accuracy_m, recorded_at, received_at
+            FROM driver_positions
+            WHERE driver_id = $1 AND recorded_at >= $2
+            ORDER BY recorded_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(driver_id)
+        .bind(since)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.map(|row| PositionFix {
+            lat: row.lat,
+            lng: row.lng,
+            accuracy_m: row.accuracy_m,
+            recorded_at: row.recorded_at,
+        }))
+    }
+
+    async fn latest_driver_positions(&self) -> Result<Vec<LatestDriverPosition>, DomainError> {
+        let rows = sqlx::query_as::<_, LatestDriverPositionRow>(
+            r#"
+            SELECT DISTINCT ON (p.driver_id)
+                   p.driver_id, u.username, p.lat, p.lng, p.accuracy_m, p.recorded_at
+            FROM driver_positions p
+            JOIN users u ON u.id = p.driver_id
+            WHERE u.active
+            ORDER BY p.driver_id, p.recorded_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let mut positions: Vec<LatestDriverPosition> = rows.into_iter().map(Into::into).collect();
+        positions.sort_by(|a, b| a.username.cmp(&b.username));
+        Ok(positions)
+    }
+
+    async fn driver_positions_on(
+        &self,
+        driver_id: Uuid,
+        date: NaiveDate,
+        tz: Tz,
+    ) -> Result<Vec<DriverPosition>, DomainError> {
+        // Los límites se calculan una vez, así el filtro sigue usando el
+        // índice por `recorded_at`; Postgres resuelve los cambios de horario.
+        let rows = sqlx::query_as::<_, DriverPositionRow>(
+            r#"
+            SELECT driver_id, lat, lng, accuracy_m, recorded_at, received_at
+            FROM driver_positions
+            WHERE driver_id = $1
+              AND recorded_at >= ($2::date)::timestamp AT TIME ZONE $3
+              AND recorded_at < ($2::date + 1)::timestamp AT TIME ZONE $3
+            ORDER BY recorded_at
+            "#,
+        )
+        .bind(driver_id)
+        .bind(date)
+        .bind(tz.name())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn purge_driver_positions(&self, before: DateTime<Utc>) -> Result<u64, DomainError> {
+        let result = sqlx::query("DELETE FROM driver_positions WHERE recorded_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl AuditPort for PgRepository {
+    async fn record_audit_event(&self, event: NewAuditEvent) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        // Un solo escritor a la vez: cada evento necesita el hash del anterior.
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(AUDIT_CHAIN_LOCK)
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        let head = sqlx::query_as::<_, (i64, Option<String>)>(
+            "SELECT seq, hash FROM audit_events WHERE hash IS NOT NULL ORDER BY seq DESC LIMIT 1",
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+        let (mut last_seq, mut last_hash) = match head {
+            Some((seq, Some(hash))) => (seq, hash),
+            _ => (0, AUDIT_GENESIS_HASH.to_string()),
+        };
+
+        // Eventos anteriores a la cadena: se sellan en orden antes del nuevo.
+        let unsealed = sqlx::query_as::<_, ChainedAuditEventRow>(&format!(
+            "SELECT {} FROM audit_events WHERE hash IS NULL ORDER BY seq",
+            CHAINED_AUDIT_EVENT_COLUMNS
+        ))
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+        for row in unsealed {
+            let legacy = ChainedAuditEvent::from(row);
+            let hash = legacy.compute_hash(&last_hash);
+            sqlx::query("UPDATE audit_events SET prev_hash = $2, hash = $3 WHERE id = $1")
+                .bind(legacy.id)
+                .bind(&last_hash)
+                .bind(&hash)
+                .execute(&mut *tx)
+                .await
+                .map_err(Self::map_sqlx_error)?;
+            last_seq = legacy.seq;
+            last_hash = hash;
+        }
+
+        let now = Utc::now();
+        let mut chained = ChainedAuditEvent {
+            seq: last_seq + 1,
+            id: Uuid::new_v4(),
+            actor_id: event.actor_id,
+            api_key_id: event.api_key_id,
+            entity: event.entity,
+            entity_id: event.entity_id,
+            action: event.action,
+            before: event.before,
+            after: event.after,
+            details: event.details,
+            request_id: event.request_id,
+            ip: event.ip,
+            user_agent: event.user_agent,
+            // Postgres guarda microsegundos: se trunca antes de calcular el hash.
+            created_at: DateTime::from_timestamp_micros(now.timestamp_micros()).unwrap_or(now),
+            hash_version: AUDIT_HASH_VERSION,
+            prev_hash: Some(last_hash.clone()),
+            hash: None,
+        };
+        chained.hash = Some(chained.compute_hash(&last_hash));
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_events
+                (id, seq, actor_id, api_key_id, entity, entity_id, action, before_state,
+                 after_state, details, request_id, ip, user_agent, created_at, hash_version,
+                 prev_hash, hash)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            "#,
+        )
+        .bind(chained.id)
+        .bind(chained.seq)
+        .bind(chained.actor_id)
+        .bind(chained.api_key_id)
+        .bind(chained.entity)
+        .bind(chained.entity_id)
+        .bind(chained.action)
+        .bind(chained.before)
+        .bind(chained.after)
+        .bind(chained.details)
+        .bind(chained.request_id)
+        .bind(chained.ip)
+        .bind(chained.user_agent)
+        .bind(chained.created_at)
+        .bind(chained.hash_version)
+        .bind(chained.prev_hash)
+        .bind(chained.hash)
+        .execute(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)
+    }
+
+    async fn list_audit_chain(
+        &self,
+        after_seq: i64,
+        limit: i64,
+    ) -> Result<Vec<ChainedAuditEvent>, DomainError> {
+        let rows = sqlx::query_as::<_, ChainedAuditEventRow>(&format!(
+            "SELECT {} FROM audit_events WHERE seq > $1 ORDER BY seq LIMIT $2",
+            CHAINED_AUDIT_EVENT_COLUMNS
+        ))
+        .bind(after_seq)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn list_audit_events(
+        &self,
+        filter: &AuditFilter,
+        limit: i64,
+    ) -> Result<Vec<AuditEvent>, DomainError> {
+        let mut builder = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT a.id, a.actor_id, u.username AS actor_username, a.api_key_id, a.entity,
+                   a.entity_id, a.action, a.before_state, a.after_state, a.details, a.request_id,
+                   a.ip, a.user_agent, a.created_at
+            FROM audit_events a
+            LEFT JOIN users u ON u.id = a.actor_id
+            WHERE 1=1
+            "#,
+        );
+
+        if let Some(entity) = &filter.entity {
+            builder.push(" AND a.entity = ").push_bind(entity.clone());
+        }
+
+        if let Some(entity_id) = filter.entity_id {
+            builder.push(" AND a.entity_id = ").push_bind(entity_id);
+        }
+
+        if let Some(actor) = &filter.actor {
+            builder.push(" AND u.username = ").push_bind(actor.clone());
+        }
+
+        if let Some(action) = &filter.action {
+            builder.push(" AND a.action = ").push_bind(action.clone());
+        }
+
+        if let Some(from) = filter.from {
+            builder.push(" AND a.created_at >= ").push_bind(from);
+        }
+
+        if let Some(to) = filter.to {
+            builder.push(" AND a.created_at < ").push_bind(to);
+        }
+
+        if let Some(cursor) = filter.cursor {
+            builder
+                .push(" AND (a.created_at, a.id) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+
+        builder
+            .push(" ORDER BY a.created_at DESC, a.id DESC LIMIT ")
+            .push_bind(limit);
+
+        let rows = builder
+            .build_query_as::<AuditEventRow>()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
+
+#[async_trait]
+impl ZonesPort for PgRepository {
+    async fn create_zone(&self, input: NewZone) -> Result<Zone, DomainError> {
+        let row = sqlx::query_as::<_, ZoneRow>(
+            r#"
+            INSERT INTO zones (id, name, normalized_name, polygon, service_days, default_slots)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, name, polygon, service_days, default_slots, active, created_at, updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(&input.name)
+        .bind(normalize_catalog_name(&input.name))
+        .bind(polygon_json(input.polygon)?)
+        .bind(day_names(&input.service_days))
+        .bind(input.default_slots)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.try_into()
+    }
+
+    async fn list_zones(&self, include_inactive: bool) -> Result<Vec<Zone>, DomainError> {
+        let rows = sqlx::query_as::<_, ZoneRow>(
+            "SELECT id, name, polygon, service_days, default_slots, active, created_at, updated_at FROM zones WHERE active OR $1 ORDER BY name ASC",
+        )
+        .bind(include_inactive)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    async fn get_zone_by_id(&self, zone_id: Uuid) -> Result<Option<Zone>, DomainError> {
+        let row = sqlx::query_as::<_, ZoneRow>(
+            "SELECT id, name, polygon, service_days, default_slots, active, created_at, updated_at FROM zones WHERE id = $1",
+        )
+        .bind(zone_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+
+    async fn find_zone_by_name(&self, normalized_name: &str) -> Result<Option<Zone>, DomainError> {
+        let row = sqlx::query_as::<_, ZoneRow>(
+            "SELECT id, name, polygon, service_days, default_slots, active, created_at, updated_at FROM zones WHERE normalized_name = $1",
+        )
+        .bind(normalized_name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+
+    async fn update_zone(&self, zone_id: Uuid, input: ZoneUpdate) -> Result<Zone, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        let row = sqlx::query_as::<_, ZoneRow>(
+            r#"
+            UPDATE zones
+            SET name = $2, normalized_name = $3, polygon = $4, service_days = $5,
+                default_slots = $6, active = $7, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, polygon, service_days, default_slots, active, created_at, updated_at
+            "#,
+        )
+        .bind(zone_id)
+        .bind(&input.name)
+        .bind(normalize_catalog_name(&input.name))
+        .bind(polygon_json(input.polygon)?)
+        .bind(day_names(&input.service_days))
+        .bind(input.default_slots)
+        .bind(input.active)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let row = row.ok_or_else(|| DomainError::NotFound("zona no encontrada".to_string()))?;
+
+        // Renombrar la zona mantiene coherente el texto desnormalizado de los pedidos.
+        sqlx::query("UPDATE orders SET zone = $2 WHERE zone_id = $1 AND zone <> $2")
+            .bind(zone_id)
+            .bind(&row.name)
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        row.try_into()
+    }
+}
+
+#[async_trait]
+impl SlotsPort for PgRepository {
+    async fn create_slot(&self, input: NewTimeSlot) -> Result<TimeSlot, DomainError> {
+        let row = sqlx::query_as::<_, TimeSlotRow>(
+            r#"
+            INSERT INTO time_slots (id, name, normalized_name, start_time, end_time, max_orders, max_cylinders)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, name, start_time, end_time, max_orders, max_cylinders, active, created_at, updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(&input.name)
+        .bind(normalize_catalog_name(&input.name))
+        .bind(input.start_time)
+        .bind(input.end_time)
+        .bind(input.max_orders)
+        .bind(input.max_cylinders)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.into())
+    }
+
+    async fn list_slots(&self, include_inactive: bool) -> Result<Vec<TimeSlot>, DomainError> {
+        let rows = sqlx::query_as::<_, TimeSlotRow>(
+            "SELECT id, name, start_time, end_time, max_orders, max_cylinders, active, created_at, updated_at FROM time_slots WHERE active OR $1 ORDER BY start_time ASC, name ASC",
+        )
+        .bind(include_inactive)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn find_slot_by_name(
+        &self,
+        normalized_name: &str,
+    ) -> Result<Option<TimeSlot>, DomainError> {
+        let row = sqlx::query_as::<_, TimeSlotRow>(
+            "SELECT id, name, start_time, end_time, max_orders, max_cylinders, active, created_at, updated_at FROM time_slots WHERE normalized_name = $1",
+        )
+        .bind(normalized_name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.map(Into::into))
+    }
+
+    async fn update_slot(
+        &self,
+        slot_id: Uuid,
+        input: TimeSlotUpdate,
+    ) -> Result<TimeSlot, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        let previous_name: Option<String> =
+            sqlx::query_scalar("SELECT name FROM time_slots WHERE id = $1 FOR UPDATE")
+                .bind(slot_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(Self::map_sqlx_error)?;
+        let previous_name = previous_name
+            .ok_or_else(|| DomainError::NotFound("franja no encontrada".to_string()))?;
+
+        let row = sqlx::query_as::<_, TimeSlotRow>(
+            r#"
+            UPDATE time_slots
+            SET name = $2, normalized_name = $3, start_time = $4, end_time = $5,
+                max_orders = $6, max_cylinders = $7, active = $8, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, start_time, end_time, max_orders, max_cylinders, active, created_at, updated_at
+            "#,
+        )
+        .bind(slot_id)
+        .bind(&input.name)
+        .bind(normalize_catalog_name(&input.name))
+        .bind(input.start_time)
+        .bind(input.end_time)
+        .bind(input.max_orders)
+        .bind(input.max_cylinders)
+        .bind(input.active)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        // Los pedidos referencian la franja por nombre: un renombre se propaga.
+        if previous_name != row.name {
+            sqlx::query("UPDATE orders SET time_slot = $2 WHERE time_slot = $1")
+                .bind(&previous_name)
+                .bind(&row.name)
+                .execute(&mut *tx)
+                .await
+                .map_err(Self::map_sqlx_error)?;
+        }
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(row.into())
+    }
+
+    async fn set_zone_capacity(
+        &self,
+        capacity: SlotZoneCapacity,
+    ) -> Result<SlotZoneCapacity, DomainError> {
+        let row = sqlx::query_as::<_, SlotZoneCapacityRow>(
+            r#"
+            INSERT INTO slot_zone_capacities (slot_id, zone_id, max_orders, max_cylinders)
+            SELECT $1, $2, $3, $4
+            WHERE EXISTS (SELECT 1 FROM time_slots WHERE id = $1)
+            ON CONFLICT (slot_id, zone_id)
+            DO UPDATE SET max_orders = EXCLUDED.max_orders, max_cylinders = EXCLUDED.max_cylinders
+            RETURNING slot_id, zone_id, max_orders, max_cylinders
+            "#,
+        )
+        .bind(capacity.slot_id)
+        .bind(capacity.zone_id)
+        .bind(capacity.max_orders)
+        .bind(capacity.max_cylinders)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let row = row.ok_or_else(|| DomainError::NotFound("franja no encontrada".to_string()))?;
+        Ok(row.into())
+    }
+
+    async fn remove_zone_capacity(&self, slot_id: Uuid, zone_id: Uuid) -> Result<(), DomainError> {
+        let result =
+            sqlx::query("DELETE FROM slot_zone_capacities WHERE slot_id = $1 AND zone_id = $2")
+                .bind(slot_id)
+                .bind(zone_id)
+                .execute(&self.pool)
+                .await
+                .map_err(Self::map_sqlx_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::NotFound(
+                "capacidad por zona no encontrada".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn zone_capacities(&self, zone_id: Uuid) -> Result<Vec<SlotZoneCapacity>, DomainError> {
+        let rows = sqlx::query_as::<_, SlotZoneCapacityRow>(
+            "SELECT slot_id, zone_id, max_orders, max_cylinders FROM slot_zone_capacities WHERE zone_id = $1",
+        )
+        .bind(zone_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn slot_load(
+        &self,
+        date: NaiveDate,
+        zone_id: Uuid,
+        slot_name: &str,
+        exclude_order_id: Option<Uuid>,
+    ) -> Result<SlotLoad, DomainError> {
+        let row = sqlx::query_as::<_, (i64, i64)>(SLOT_LOAD_QUERY)
+            .bind(date)
+            .bind(zone_id)
+            .bind(slot_name)
+            .bind(exclude_order_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(SlotLoad {
+            orders: row.0,
+            cylinders: row.1,
+        })
+    }
+}
+
+#[async_trait]
+impl CalendarPort for PgRepository {
+    async fn list_holidays(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<Holiday>, DomainError> {
+        let rows = sqlx::query_as::<_, HolidayRow>(
+            r#"
+            SELECT date, description, created_at
+            FROM non_working_dates
+            WHERE ($1::DATE IS NULL OR date >= $1) AND ($2::DATE IS NULL OR date <= $2)
+            ORDER BY date ASC
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn add_holiday(&self, input: NewHoliday) -> Result<Holiday, DomainError> {
+        let row = sqlx::query_as::<_, HolidayRow>(
+            r#"
+            INSERT INTO non_working_dates (date, description)
+            VALUES ($1, $2)
+            RETURNING date, description, created_at
+            "#,
+        )
+        .bind(input.date)
+        .bind(input.description)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.into())
+    }
+
+    async fn remove_holiday(&self, date: NaiveDate) -> Result<(), DomainError> {
+        let result = sqlx::query("DELETE FROM non_working_dates WHERE date = $1")
+            .bind(date)
+            .execute(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::NotFound("feriado no encontrado".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn closed_weekdays(&self) -> Result<Vec<DayOfWeek>, DomainError> {
+        let days: Vec<String> = sqlx::query_scalar("SELECT day FROM closed_weekdays")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        let mut days = days
+            .iter()
+            .map(|day| DayOfWeek::from_str(day))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| DomainError::Infrastructure("día inválido en DB".to_string()))?;
+        days.sort();
+        Ok(days)
+    }
+
+    async fn set_closed_weekdays(&self, days: &[DayOfWeek]) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        sqlx::query("DELETE FROM closed_weekdays")
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        sqlx::query("INSERT INTO closed_weekdays (day) SELECT UNNEST($1::TEXT[])")
+            .bind(day_names(days))
+            .execute(&mut *tx)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(())
+    }
+}
+
+impl PgRepository {
+    async fn fetch_recurring_order(
+        &self,
+        recurring_order_id: Uuid,
+    ) -> Result<Option<RecurringOrder>, DomainError> {
+        let row = sqlx::query_as::<_, RecurringOrderRow>(&format!(
+            "{} WHERE r.id = $1",
+            RECURRING_ORDER_SELECT
+        ))
+        .bind(recurring_order_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+}
+
+/// Mueve `next_date` sólo si sigue valiendo `current` y la plantilla está
+/// activa; `false` si otra ejecución ya tomó esa fecha.
+async fn advance_recurring_in_tx(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    recurring_order_id: Uuid,
+    current: NaiveDate,
+    next: NaiveDate,
+) -> Result<bool, DomainError> {
+    let result = sqlx::query(
+        r#"
+        UPDATE recurring_orders
+        SET next_date = $3, updated_at = NOW()
+        WHERE id = $1 AND next_date = $2 AND status = 'ACTIVA'
+        "#,
+    )
+    .bind(recurring_order_id)
+    .bind(current)
+    .bind(next)
+    .execute(&mut **tx)
+    .await
+    .map_err(PgRepository::map_sqlx_error)?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+async fn insert_occurrence_in_tx(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    input: NewOccurrence,
+) -> Result<RecurringOccurrence, DomainError> {
+    let row = sqlx::query_as::<_, RecurringOccurrenceRow>(
+        r#"
+        INSERT INTO recurring_order_occurrences (recurring_order_id, scheduled_date, status, order_id, detail)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING recurring_order_id, scheduled_date, status, order_id, detail, created_at
+        "#,
+    )
+    .bind(input.recurring_order_id)
+    .bind(input.scheduled_date)
+    .bind(input.status.as_str())
+    .bind(input.order_id)
+    .bind(input.detail)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(PgRepository::map_sqlx_error)?;
+
+    row.try_into()
+}
+
+#[async_trait]
+impl RecurringOrdersPort for PgRepository {
+    async fn create_recurring_order(
+        &self,
+        input: NewRecurringOrder,
+    ) -> Result<RecurringOrder, DomainError> {
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO recurring_orders (id, address, zone_id, slot_id, quantity, interval_weeks, notes, next_date)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(id)
+        .bind(input.address)
+        .bind(input.zone_id)
+        .bind(input.slot_id)
+        .bind(input.quantity)
+        .bind(input.interval_weeks)
+        .bind(input.notes)
+        .bind(input.start_date)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        self.fetch_recurring_order(id).await?.ok_or_else(|| {
+            DomainError::Infrastructure("pedido recurrente no encontrado tras crear".to_string())
+        })
+    }
+
+    async fn list_recurring_orders(
+        &self,
+        status: Option<RecurringStatus>,
+    ) -> Result<Vec<RecurringOrder>, DomainError> {
+        let rows = sqlx::query_as::<_, RecurringOrderRow>(&format!(
+            "{} WHERE $1::TEXT IS NULL OR r.status = $1 ORDER BY r.next_date ASC, r.created_at ASC",
+            RECURRING_ORDER_SELECT
+        ))
+        .bind(status.map(|s| s.as_str()))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    async fn get_recurring_order_by_id(
+        &self,
+        recurring_order_id: Uuid,
+    ) -> Result<Option<RecurringOrder>, DomainError> {
+        self.fetch_recurring_order(recurring_order_id).await
+    }
+
+    async fn due_recurring_orders(
+        &self,
+        until: NaiveDate,
+    ) -> Result<Vec<RecurringOrder>, DomainError> {
+        let rows = sqlx::query_as::<_, RecurringOrderRow>(&format!(
+            "{} WHERE r.status = 'ACTIVA' AND r.next_date <= $1 ORDER BY r.next_date ASC",
+            RECURRING_ORDER_SELECT
+        ))
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    async fn update_recurring_status(
+        &self,
+        recurring_order_id: Uuid,
+        status: RecurringStatus,
+        next_date: NaiveDate,
+    ) -> Result<RecurringOrder, DomainError> {
+        let result = sqlx::query(
+            "UPDATE recurring_orders SET status = $2, next_date = $3, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(recurring_order_id)
+        .bind(status.as_str())
+        .bind(next_date)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::NotFound(
+                "pedido recurrente no encontrado".to_string(),
+            ));
+        }
+
+        self.fetch_recurring_order(recurring_order_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("pedido recurrente no encontrado".to_string()))
+    }
+
+    async fn advance_recurring_order(
+        &self,
+        recurring_order_id: Uuid,
+        current: NaiveDate,
+        next: NaiveDate,
+    ) -> Result<bool, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+        let advanced = advance_recurring_in_tx(&mut tx, recurring_order_id, current, next).await?;
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(advanced)
+    }
+
+    async fn advance_with_occurrence(
+        &self,
+        current: NaiveDate,
+        next: NaiveDate,
+        input: NewOccurrence,
+    ) -> Result<Option<RecurringOccurrence>, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+        if !advance_recurring_in_tx(&mut tx, input.recurring_order_id, current, next).await? {
+            return Ok(None);
+        }
+        let occurrence = insert_occurrence_in_tx(&mut tx, input).await?;
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(Some(occurrence))
+    }
+
+    async fn materialize_occurrence(
+        &self,
+        recurring_order_id: Uuid,
+        next: NaiveDate,
+        input: NewOrder,
+        reservation: SlotReservation,
+    ) -> Result<Option<Order>, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+        let date = input.scheduled_date;
+        if !advance_recurring_in_tx(&mut tx, recurring_order_id, date, next).await? {
+            return Ok(None);
+        }
+        let order = insert_order_in_tx(&mut tx, input, &reservation).await?;
+        insert_occurrence_in_tx(
+            &mut tx,
+            NewOccurrence {
+                recurring_order_id,
+                scheduled_date: date,
+                status: OccurrenceStatus::Creada,
+                order_id: Some(order.id),
+                detail: None,
+            },
+        )
+        .await?;
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(Some(order))
+    }
+
+    async fn find_occurrence(
+        &self,
+        recurring_order_id: Uuid,
+        date: NaiveDate,
+    ) -> Result<Option<RecurringOccurrence>, DomainError> {
+        let row = sqlx::query_as::<_, RecurringOccurrenceRow>(
+            r#"
+            SELECT recurring_order_id, scheduled_date, status, order_id, detail, created_at
+            FROM recurring_order_occurrences
+            WHERE recurring_order_id = $1 AND scheduled_date = $2
+            "#,
+        )
+        .bind(recurring_order_id)
+        .bind(date)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+
+    async fn record_occurrence(
+        &self,
+        input: NewOccurrence,
+    ) -> Result<RecurringOccurrence, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+        let occurrence = insert_occurrence_in_tx(&mut tx, input).await?;
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(occurrence)
+    }
+
+    async fn list_occurrences(
+        &self,
+        recurring_order_id: Uuid,
+    ) -> Result<Vec<RecurringOccurrence>, DomainError> {
+        let rows = sqlx::query_as::<_, RecurringOccurrenceRow>(
+            r#"
+            SELECT recurring_order_id, scheduled_date, status, order_id, detail, created_at
+            FROM recurring_order_occurrences
+            WHERE recurring_order_id = $1
+            ORDER BY scheduled_date ASC
+            "#,
+        )
+        .bind(recurring_order_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+}
+
+/// Conexión que sostiene el advisory lock de sesión de una corrida.
+struct PgJobLease {
+    conn: Option<PoolConnection<Postgres>>,
+    key: String,
+}
+
+#[async_trait]
+impl JobLease for PgJobLease {
+    async fn release(mut self: Box<Self>) -> Result<(), DomainError> {
+        let Some(mut conn) = self.conn.take() else {
+            return Ok(());
+        };
+        let result = sqlx::query("SELECT pg_advisory_unlock(hashtext($1))")
+            .bind(&self.key)
+            .execute(&mut *conn)
+            .await;
+        if result.is_err() {
+            // No se sabe si el lock sigue tomado: la conexión no vuelve al pool.
+            drop(conn.detach());
+        }
+        result.map(|_| ()).map_err(PgRepository::map_sqlx_error)
+    }
+}
+
+impl Drop for PgJobLease {
+    fn drop(&mut self) {
+        // Sin `release` explícito se cierra la conexión y Postgres suelta el lock.
+        if let Some(conn) = self.conn.take() {
+            drop(conn.detach());
+        }
+    }
+}
+
+impl PgRepository {
+    /// Registra el inicio de una corrida; se llama con el lock del job tomado,
+    /// así que cualquier `EN_CURSO` que quede es de una réplica que lo perdió.
+    async fn start_job_run(
+        &self,
+        name: &str,
+        trigger: JobTrigger,
+        now: DateTime<Utc>,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<Option<JobRun>, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        if trigger == JobTrigger::Programada {
+            let due: Option<DateTime<Utc>> =
+                sqlx::query_scalar("SELECT next_run_at FROM jobs WHERE name = $1")
+                    .bind(name)
+                    .fetch_optional(&mut *tx)
+                    .await
+                    .map_err(Self::map_sqlx_error)?;
+            if due.is_none_or(|at| at > now) {
+                return Ok(None);
+            }
+
+            sqlx::query("UPDATE jobs SET next_run_at = $2, updated_at = NOW() WHERE name = $1")
+                .bind(name)
+                .bind(next_run_at)
+                .execute(&mut *tx)
+                .await
+                .map_err(Self::map_sqlx_error)?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE job_runs
+            SET status = 'FALLIDA', finished_at = $2, error = $3
+            WHERE job_name = $1 AND status = 'EN_CURSO'
+            "#,
+        )
+        .bind(name)
+        .bind(now)
+        .bind(ABANDONED_JOB_RUN_ERROR)
+        .execute(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        let row = sqlx::query_as::<_, JobRunRow>(
+            r#"
+            INSERT INTO job_runs (id, job_name, trigger, status, started_at)
+            VALUES ($1, $2, $3, 'EN_CURSO', $4)
+            RETURNING id, job_name, trigger, status, started_at, finished_at, output, error
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(name)
+        .bind(trigger.as_str())
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        row.try_into().map(Some)
+    }
+}
+
+#[async_trait]
+impl JobsPort for PgRepository {
+    async fn register_job(
+        &self,
+        name: &str,
+        schedule: &str,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO jobs (name, schedule, next_run_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (name) DO UPDATE
+            SET schedule = EXCLUDED.schedule, next_run_at = EXCLUDED.next_run_at, updated_at = NOW()
+            WHERE jobs.schedule <> EXCLUDED.schedule
+            "#,
+        )
+        .bind(name)
+        .bind(schedule)
+        .bind(next_run_at)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn job_next_run_at(&self, name: &str) -> Result<Option<DateTime<Utc>>, DomainError> {
+        sqlx::query_scalar("SELECT next_run_at FROM jobs WHERE name = $1")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)
+    }
+
+    async fn claim_job_run(
+        &self,
+        name: &str,
+        trigger: JobTrigger,
+        now: DateTime<Utc>,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<Option<ClaimedJobRun>, DomainError> {
+        // Lock de sesión en una conexión propia que se retiene toda la corrida:
+        // a diferencia de uno transaccional no se libera al registrar el inicio.
+        let key = format!("gasflow.job.{}", name);
+        let mut conn = self.pool.acquire().await.map_err(Self::map_sqlx_error)?;
+        let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock(hashtext($1))")
+            .bind(&key)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+        if !locked {
+            return Ok(None);
+        }
+        let lease = Box::new(PgJobLease {
+            conn: Some(conn),
+            key,
+        });
+
+        match self.start_job_run(name, trigger, now, next_run_at).await {
+            Ok(Some(run)) => Ok(Some(ClaimedJobRun { run, lease })),
+            Ok(None) => {
+                lease.release().await?;
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn finish_job_run(
+        &self,
+        run_id: Uuid,
+        status: JobRunStatus,
+        output: Option<serde_json::Value>,
+        error: Option<String>,
+    ) -> Result<JobRun, DomainError> {
+        let row = sqlx::query_as::<_, JobRunRow>(
+            r#"
+            UPDATE job_runs
+            SET status = $2, output = $3, error = $4, finished_at = NOW()
+            WHERE id = $1
+            RETURNING id, job_name, trigger, status, started_at, finished_at, output, error
+            "#,
+        )
+        .bind(run_id)
+        .bind(status.as_str())
+        .bind(output)
+        .bind(error)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.ok_or_else(|| DomainError::NotFound("ejecución no encontrada".to_string()))?
+            .try_into()
+    }
+
+    async fn list_job_runs(&self, name: &str, limit: i64) -> Result<Vec<JobRun>, DomainError> {
+        let rows = sqlx::query_as::<_, JobRunRow>(
+            r#"
+            SELECT id, job_name, trigger, status, started_at, finished_at, output, error
+            FROM job_runs
+            WHERE job_name = $1
+            ORDER BY started_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(name)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    async fn purge_job_runs(&self, before: DateTime<Utc>) -> Result<u64, DomainError> {
+        let result =
+            sqlx::query("DELETE FROM job_runs WHERE started_at < $1 AND status <> 'EN_CURSO'")
+                .bind(before)
+                .execute(&self.pool)
+                .await
+                .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[async_trait]
+impl UsersPort for PgRepository {
+    async fn create_user(&self, input: NewUser) -> Result<User, DomainError> {
+        let row = sqlx::query_as::<_, UserRow>(&format!(
+            "INSERT INTO users (id, username, password, role) VALUES ($1, $2, $3, $4) RETURNING {}",
+            USER_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(input.username)
+        .bind(input.password_hash)
+        .bind(input.role.as_str())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.try_into()
+    }
+
+    async fn list_users(&self, filter: UserFilter) -> Result<Vec<User>, DomainError> {
+        let mut query =
+            QueryBuilder::<Postgres>::new(format!("SELECT {} FROM users WHERE 1=1", USER_COLUMNS));
+        if let Some(role) = filter.role {
+            query.push(" AND role = ").push_bind(role.as_str());
+        }
+        if !filter.include_inactive {
+            query.push(" AND active");
+        }
+        query.push(" ORDER BY username ASC");
+
+        let rows = query
+            .build_query_as::<UserRow>()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    async fn update_username(&self, user_id: Uuid, username: &str) -> Result<User, DomainError> {
+        let row = sqlx::query_as::<_, UserRow>(&format!(
+            "UPDATE users SET username = $2, updated_at = NOW() WHERE id = $1 RETURNING {}",
+            USER_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(username)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.ok_or_else(|| DomainError::NotFound("usuario no encontrado".to_string()))?
+            .try_into()
+    }
+
+    async fn set_user_role(&self, user_id: Uuid, role: Role) -> Result<User, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+        if role != Role::Admin {
+            ensure_other_admin_in_tx(&mut tx, user_id).await?;
+        }
+        let row = sqlx::query_as::<_, UserRow>(&format!(
+            "UPDATE users SET role = $2, updated_at = NOW() WHERE id = $1 RETURNING {}",
+            USER_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(role.as_str())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+
+        row.ok_or_else(|| DomainError::NotFound("usuario no encontrado".to_string()))?
+            .try_into()
+    }
+
+    async fn set_user_active(&self, user_id: Uuid, active: bool) -> Result<User, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+        if !active {
+            ensure_other_admin_in_tx(&mut tx, user_id).await?;
+        }
+        let row = sqlx::query_as::<_, UserRow>(&format!(
+            "UPDATE users SET active = $2, updated_at = NOW() WHERE id = $1 RETURNING {}",
+            USER_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(active)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+
+        row.ok_or_else(|| DomainError::NotFound("usuario no encontrado".to_string()))?
+            .try_into()
+    }
+
+    async fn unlock_user(&self, user_id: Uuid) -> Result<User, DomainError> {
+        let row = sqlx::query_as::<_, UserRow>(&format!(
+            "UPDATE users SET locked_at = NULL, updated_at = NOW() WHERE id = $1 RETURNING {}",
+            USER_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.ok_or_else(|| DomainError::NotFound("usuario no encontrado".to_string()))?
+            .try_into()
+    }
+}
+
+#[async_trait]
+impl CustomersPort for PgRepository {
+    async fn create_customer_address(
+        &self,
+        input: NewCustomerAddress,
+    ) -> Result<CustomerAddress, DomainError> {
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO customer_addresses (id, customer_id, label, address, zone_id, notes)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(id)
+        .bind(input.customer_id)
+        .bind(input.label)
+        .bind(input.address)
+        .bind(input.zone_id)
+        .bind(input.notes)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        self.find_customer_address(input.customer_id, id)
+            .await?
+            .ok_or_else(|| {
+                DomainError::Infrastructure("dirección no encontrada tras crear".to_string())
+            })
+    }
+
+    async fn list_customer_addresses(
+        &self,
+        customer_id: Uuid,
+    ) -> Result<Vec<CustomerAddress>, DomainError> {
+        let rows = sqlx::query_as::<_, CustomerAddressRow>(&format!(
+            "{} WHERE a.customer_id = $1 AND a.active ORDER BY a.created_at ASC",
+            CUSTOMER_ADDRESS_SELECT
+        ))
+        .bind(customer_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn find_customer_address(
+        &self,
+        customer_id: Uuid,
+        address_id: Uuid,
+    ) -> Result<Option<CustomerAddress>, DomainError> {
+        let row = sqlx::query_as::<_, CustomerAddressRow>(&format!(
+            "{} WHERE a.id = $1 AND a.customer_id = $2",
+            CUSTOMER_ADDRESS_SELECT
+        ))
+        .bind(address_id)
+        .bind(customer_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(row.map(Into::into))
+    }
+
+    async fn deactivate_customer_address(
+        &self,
+        customer_id: Uuid,
+        address_id: Uuid,
+    ) -> Result<bool, DomainError> {
+        let result = sqlx::query(
+            "UPDATE customer_addresses SET active = FALSE WHERE id = $1 AND customer_id = $2 AND active",
+        )
+        .bind(address_id)
+        .bind(customer_id)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
+
+#[async_trait]
+impl ApiKeysPort for PgRepository {
+    async fn create_api_key(&self, input: NewApiKey) -> Result<ApiKey, DomainError> {
+        let permissions: Vec<&str> = input.permissions.iter().map(|p| p.as_str()).collect();
+        let row = sqlx::query_as::<_, ApiKeyRow>(&format!(
+            r#"
+            INSERT INTO api_keys (id, name, prefix, key_hash, permissions, created_by, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            API_KEY_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(input.name)
+        .bind(input.prefix)
+        .bind(input.key_hash)
+        .bind(permissions)
+        .bind(input.created_by)
+        .bind(input.expires_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.try_into()
+    }
+
+    async fn list_api_keys(&self) -> Result<Vec<ApiKey>, DomainError> {
+        let rows = sqlx::query_as::<_, ApiKeyRow>(&format!(
+            "SELECT {} FROM api_keys ORDER BY created_at DESC",
+            API_KEY_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    async fn find_api_key_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, DomainError> {
+        let row = sqlx::query_as::<_, ApiKeyRow>(&format!(
+            "SELECT {} FROM api_keys WHERE key_hash = $1",
+            API_KEY_COLUMNS
+        ))
+        .bind(key_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+
+    async fn get_api_key_by_id(&self, api_key_id: Uuid) -> Result<Option<ApiKey>, DomainError> {
+        let row = sqlx::query_as::<_, ApiKeyRow>(&format!(
+            "SELECT {} FROM api_keys WHERE id = $1",
+            API_KEY_COLUMNS
+        ))
+        .bind(api_key_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+
+    async fn revoke_api_key(&self, api_key_id: Uuid) -> Result<Option<ApiKey>, DomainError> {
+        let row = sqlx::query_as::<_, ApiKeyRow>(&format!(
+            "UPDATE api_keys SET revoked_at = COALESCE(revoked_at, NOW()) WHERE id = $1 RETURNING {}",
+            API_KEY_COLUMNS
+        ))
+        .bind(api_key_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        row.map(TryInto::try_into).transpose()
+    }
+
+    async fn touch_api_key(&self, api_key_id: Uuid, now: DateTime<Utc>) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            UPDATE api_keys SET last_used_at = $2
+            WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < $2 - INTERVAL '1 minute')
+            "#,
+        )
+        .bind(api_key_id)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(())
+    }
+}
+
+async fn insert_recovery_codes(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    user_id: Uuid,
+    code_hashes: Vec<String>,
+) -> Result<(), DomainError> {
+    sqlx::query("DELETE FROM recovery_codes WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(PgRepository::map_sqlx_error)?;
+
+    for code_hash in code_hashes {
+        sqlx::query("INSERT INTO recovery_codes (id, user_id, code_hash) VALUES ($1, $2, $3)")
+            .bind(Uuid::new_v4())
+            .bind(user_id)
+            .bind(code_hash)
+            .execute(&mut **tx)
+            .await
+            .map_err(PgRepository::map_sqlx_error)?;
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl TwoFactorPort for PgRepository {
+    async fn set_pending_totp_secret(
+        &self,
+        user_id: Uuid,
+        secret: &str,
+    ) -> Result<bool, DomainError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users SET totp_secret = $2, totp_last_step = NULL, updated_at = NOW()
+            WHERE id = $1 AND totp_enabled_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(secret)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn enable_totp(
+        &self,
+        user_id: Uuid,
+        step: i64,
+        recovery_code_hashes: Vec<String>,
+    ) -> Result<bool, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE users SET totp_enabled_at = NOW(), totp_last_step = $2, updated_at = NOW()
+            WHERE id = $1 AND totp_secret IS NOT NULL AND totp_enabled_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        insert_recovery_codes(&mut tx, user_id, recovery_code_hashes).await?;
+        tx.commit().await.map_err(Self::map_sqlx_error)?;
+        Ok(true)
+    }
+
+    async fn record_totp_step(&self, user_id: Uuid, step: i64) -> Result<bool, DomainError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users SET totp_last_step = $2
+            WHERE id = $1 AND (totp_last_step IS NULL OR totp_last_step < $2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn disable_totp(&self, user_id: Uuid) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET totp_secret = NULL, totp_enabled_at = NULL, totp_last_step = NULL, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+        insert_recovery_codes(&mut tx, user_id, Vec::new()).await?;
+
+        tx.commit().await.map_err(Self::map_sqlx_error)
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        user_id: Uuid,
+        code_hashes: Vec<String>,
+    ) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+        insert_recovery_codes(&mut tx, user_id, code_hashes).await?;
+        tx.commit().await.map_err(Self::map_sqlx_error)
+    }
+
+    async fn consume_recovery_code(
+        &self,
+        user_id: Uuid,
+        code_hash: &str,
+    ) -> Result<bool, DomainError> {
+        let result = sqlx::query(
             r#"
-            UPDATE orders
-            SET status = $2, updated_at = NOW()
-            WHERE id = $1
-            RETURNING id, address, zone, scheduled_date, time_slot, quantity, notes, status, assignee_id, created_at, updated_at
+            UPDATE recovery_codes SET used_at = NOW()
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
             "#,
         )
-        .bind(order_id)
-        .bind(status.as_str())
-        .fetch_optional(&self.pool)
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(&self.pool)
         .await
         .map_err(Self::map_sqlx_error)?;
 
-        let row = row.ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
-        row.try_into()
+        Ok(result.rows_affected() >= 1)
     }
 
-    async fn reprogram_order(
+    async fn create_login_challenge(
         &self,
-        order_id: Uuid,
-        scheduled_date: NaiveDate,
-        time_slot: String,
-    ) -> Result<Order, DomainError> {
-        let row = sqlx::query_as::<_, OrderRow>(
+        input: NewLoginChallenge,
+    ) -> Result<LoginChallenge, DomainError> {
+        // Los desafíos vencidos no sirven para nada: se limpian al crear otro.
+        sqlx::query("DELETE FROM login_challenges WHERE expires_at < NOW()")
+            .execute(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        let row = sqlx::query_as::<_, LoginChallengeRow>(
             r#"
-            UPDATE orders
-            SET scheduled_date = $2, time_slot = $3, status = 'ASIGNADO', updated_at = NOW()
-            WHERE id = $1
-            RETURNING id, address, zone, scheduled_date, time_slot, quantity, notes, status, assignee_id, created_at, updated_at
+            INSERT INTO login_challenges (id, user_id, token_hash, purpose, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, user_id, purpose, attempts, expires_at
             "#,
         )
-        .bind(order_id)
-        .bind(scheduled_date)
-        .bind(time_slot)
-        .fetch_optional(&self.pool)
+        .bind(Uuid::new_v4())
+        .bind(input.user_id)
+        .bind(input.token_hash)
+        .bind(input.purpose.as_str())
+        .bind(input.expires_at)
+        .fetch_one(&self.pool)
         .await
         .map_err(Self::map_sqlx_error)?;
 
-        let row = row.ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
         row.try_into()
     }
 
-    async fn assign_orders(&self, order_ids: &[Uuid], driver_id: Uuid) -> Result<(), DomainError> {
-        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
+    async fn find_login_challenge(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<LoginChallenge>, DomainError> {
+        let row = sqlx::query_as::<_, LoginChallengeRow>(
+            r#"
+            SELECT id, user_id, purpose, attempts, expires_at
+            FROM login_challenges
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
 
-        for order_id in order_ids {
-            let result = sqlx::query(
-                "UPDATE orders SET assignee_id = $1, status = 'ASIGNADO', updated_at = NOW() WHERE id = $2",
-            )
-            .bind(driver_id)
-            .bind(*order_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(Self::map_sqlx_error)?;
+        row.map(TryInto::try_into).transpose()
+    }
 
-            if result.rows_affected() == 0 {
-                return Err(DomainError::NotFound(format!(
-                    "pedido {} no encontrado",
-                    order_id
-                )));
-            }
+    async fn record_challenge_failure(
+        &self,
+        challenge_id: Uuid,
+        max_attempts: i32,
+    ) -> Result<(), DomainError> {
+        let mut tx = self.pool.begin().await.map_err(Self::map_sqlx_error)?;
 
-            sqlx::query("INSERT INTO assignments (id, order_id, driver_id) VALUES ($1, $2, $3)")
-                .bind(Uuid::new_v4())
-                .bind(*order_id)
-                .bind(driver_id)
+        let attempts: Option<i32> = sqlx::query_scalar(
+            "UPDATE login_challenges SET attempts = attempts + 1 WHERE id = $1 RETURNING attempts",
+        )
+        .bind(challenge_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+        if attempts.is_some_and(|attempts| attempts >= max_attempts) {
+            sqlx::query("DELETE FROM login_challenges WHERE id = $1")
+                .bind(challenge_id)
                 .execute(&mut *tx)
                 .await
                 .map_err(Self::map_sqlx_error)?;
         }
 
-        tx.commit().await.map_err(Self::map_sqlx_error)?;
-        Ok(())
+        tx.commit().await.map_err(Self::map_sqlx_error)
+    }
+
+    async fn consume_login_challenge(&self, challenge_id: Uuid) -> Result<bool, DomainError> {
+        let result = sqlx::query("DELETE FROM login_challenges WHERE id = $1")
+            .bind(challenge_id)
+            .execute(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected() == 1)
     }
 }
 
-#[async_trait]
-impl DeliveriesPort for PgRepository {
-    async fn create_delivery(&self, input: NewDelivery) -> Result<Delivery, DomainError> {
-        let row = sqlx::query_as::<_, DeliveryRow>(
-            r#"
-            INSERT INTO deliveries (id, order_id, llenas_entregadas, vacias_recibidas, notes)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, order_id, llenas_entregadas, vacias_recibidas, notes, created_at
-            "#,
-        )
-        .bind(Uuid::new_v4())
-        .bind(input.order_id)
-        .bind(input.llenas_entregadas)
-        .bind(input.vacias_recibidas)
-        .bind(input.notes)
-        .fetch_one(&self.pool)
+#[derive(Debug, FromRow)]
+struct WebhookSubscriptionRow {
+    id: Uuid,
+    url: String,
+    event_types: Vec<String>,
+    active: bool,
+    created_by: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<WebhookSubscriptionRow> for WebhookSubscription {
+    type Error = DomainError;
+
+    fn try_from(row: WebhookSubscriptionRow) -> Result<Self, Self::Error> {
+        let event_types = row
+            .event_types
+            .iter()
+            .map(|value| {
+                DomainEventType::from_str(value).ok_or_else(|| {
+                    DomainError::Infrastructure(format!("tipo de evento inválido: {}", value))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            id: row.id,
+            url: row.url,
+            event_types,
+            active: row.active,
+            created_by: row.created_by,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+const WEBHOOK_SUBSCRIPTION_COLUMNS: &str =
+    "id, url, event_types, active, created_by, created_at, updated_at";
+
+#[derive(Debug, FromRow)]
+struct WebhookDeliveryRow {
+    id: Uuid,
+    subscription_id: Uuid,
+    event_id: Uuid,
+    event_type: String,
+    status: String,
+    attempts: i32,
+    next_attempt_at: Option<DateTime<Utc>>,
+    last_status_code: Option<i32>,
+    last_error: Option<String>,
+    delivered_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<WebhookDeliveryRow> for WebhookDelivery {
+    type Error = DomainError;
+
+    fn try_from(row: WebhookDeliveryRow) -> Result<Self, Self::Error> {
+        let event_type = DomainEventType::from_str(&row.event_type).ok_or_else(|| {
+            DomainError::Infrastructure(format!("tipo de evento inválido: {}", row.event_type))
+        })?;
+        let status = WebhookDeliveryStatus::from_str(&row.status).ok_or_else(|| {
+            DomainError::Infrastructure(format!("estado de entrega inválido: {}", row.status))
+        })?;
+        Ok(Self {
+            id: row.id,
+            subscription_id: row.subscription_id,
+            event_id: row.event_id,
+            event_type,
+            status,
+            attempts: row.attempts,
+            next_attempt_at: row.next_attempt_at,
+            last_status_code: row.last_status_code,
+            last_error: row.last_error,
+            delivered_at: row.delivered_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+const WEBHOOK_DELIVERY_SELECT: &str = r#"
+    SELECT d.id, d.subscription_id, d.event_id, e.event_type, d.status, d.attempts,
+           d.next_attempt_at, d.last_status_code, d.last_error, d.delivered_at,
+           d.created_at, d.updated_at
+    FROM webhook_deliveries d
+    JOIN outbox_events e ON e.id = d.event_id
+"#;
+
+#[derive(Debug, FromRow)]
+struct PendingWebhookDeliveryRow {
+    delivery_id: Uuid,
+    url: String,
+    secret: String,
+    attempts: i32,
+    event_id: Uuid,
+    event_type: String,
+    aggregate_id: Uuid,
+    payload: serde_json::Value,
+    occurred_at: DateTime<Utc>,
+    event_attempts: i32,
+    assignee_id: Option<Uuid>,
+    customer_id: Option<Uuid>,
+}
+
+impl TryFrom<PendingWebhookDeliveryRow> for PendingWebhookDelivery {
+    type Error = DomainError;
+
+    fn try_from(row: PendingWebhookDeliveryRow) -> Result<Self, Self::Error> {
+        let event = OutboxEventRow {
+            id: row.event_id,
+            event_type: row.event_type,
+            aggregate_id: row.aggregate_id,
+            payload: row.payload,
+            occurred_at: row.occurred_at,
+            attempts: row.event_attempts,
+            assignee_id: row.assignee_id,
+            customer_id: row.customer_id,
+        }
+        .try_into()?;
+        Ok(Self {
+            delivery_id: row.delivery_id,
+            url: row.url,
+            secret: row.secret,
+            attempts: row.attempts,
+            event,
+        })
+    }
+}
+
+impl PgRepository {
+    async fn fetch_webhook_delivery(
+        &self,
+        delivery_id: Uuid,
+    ) -> Result<Option<WebhookDelivery>, DomainError> {
+        let row = sqlx::query_as::<_, WebhookDeliveryRow>(&format!(
+            "{} WHERE d.id = $1",
+            WEBHOOK_DELIVERY_SELECT
+        ))
+        .bind(delivery_id)
+        .fetch_optional(&self.pool)
         .await
         .map_err(Self::map_sqlx_error)?;
 
-        Ok(row.into())
+        row.map(TryInto::try_into).transpose()
     }
+}
 
-    async fn create_failed_delivery(
+fn event_type_names(event_types: &[DomainEventType]) -> Vec<String> {
+    event_types
+        .iter()
+        .map(|kind| kind.as_str().to_string())
+        .collect()
+}
+
+#[async_trait]
+impl WebhooksPort for PgRepository {
+    async fn create_webhook_subscription(
         &self,
-        input: NewFailedDelivery,
-    ) -> Result<FailedDelivery, DomainError> {
-        let row = sqlx::query_as::<_, FailedDeliveryRow>(
+        input: NewWebhookSubscription,
+    ) -> Result<WebhookSubscription, DomainError> {
+        let row = sqlx::query_as::<_, WebhookSubscriptionRow>(&format!(
             r#"
-            INSERT INTO delivery_failures (id, order_id, reason, reprogram_date, reprogram_time_slot)
+            INSERT INTO webhook_subscriptions (id, url, event_types, secret, created_by)
             VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, order_id, reason, reprogram_date, reprogram_time_slot, created_at
+            RETURNING {}
             "#,
-        )
+            WEBHOOK_SUBSCRIPTION_COLUMNS
+        ))
         .bind(Uuid::new_v4())
-        .bind(input.order_id)
-        .bind(input.reason)
-        .bind(input.reprogram_date)
-        .bind(input.reprogram_time_slot)
+        .bind(input.url)
+        .bind(event_type_names(&input.event_types))
+        .bind(input.secret)
+        .bind(input.created_by)
         .fetch_one(&self.pool)
         .await
         .map_err(Self::map_sqlx_error)?;
 
-        Ok(row.into())
+        row.try_into()
     }
-}
 
-#[async_trait]
-impl StockPort for PgRepository {
-    async fn register_inbound(&self, input: Inbound) -> Result<(), DomainError> {
-        sqlx::query(
-            "INSERT INTO stock_inbounds (id, inbound_date, cantidad_llenas, notes) VALUES ($1, $2, $3, $4)",
-        )
-        .bind(Uuid::new_v4())
-        .bind(input.date)
-        .bind(input.cantidad_llenas)
-        .bind(input.notes)
-        .execute(&self.pool)
+    async fn list_webhook_subscriptions(&self) -> Result<Vec<WebhookSubscription>, DomainError> {
+        let rows = sqlx::query_as::<_, WebhookSubscriptionRow>(&format!(
+            "SELECT {} FROM webhook_subscriptions ORDER BY created_at",
+            WEBHOOK_SUBSCRIPTION_COLUMNS
+        ))
+        .fetch_all(&self.pool)
         .await
         .map_err(Self::map_sqlx_error)?;
 
-        Ok(())
+        rows.into_iter().map(TryInto::try_into).collect()
     }
 
-    async fn stock_totals(&self, date: Option<NaiveDate>) -> Result<StockTotals, DomainError> {
-        let inbound_full: i64;
-        let delivered_full: i64;
-        let recovered_empty: i64;
+    async fn get_webhook_subscription(
+        &self,
+        subscription_id: Uuid,
+    ) -> Result<Option<WebhookSubscription>, DomainError> {
+        let row = sqlx::query_as::<_, WebhookSubscriptionRow>(&format!(
+            "SELECT {} FROM webhook_subscriptions WHERE id = $1",
+            WEBHOOK_SUBSCRIPTION_COLUMNS
+        ))
+        .bind(subscription_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
 
-        if let Some(d) = date {
-            inbound_full = sqlx::query_scalar(
-                "SELECT COALESCE(SUM(cantidad_llenas), 0)::BIGINT FROM stock_inbounds WHERE inbound_date <= $1",
-            )
-            .bind(d)
-            .fetch_one(&self.pool)
-            .await
-            .map_err(Self::map_sqlx_error)?;
+        row.map(TryInto::try_into).transpose()
+    }
 
-            let row = sqlx::query_as::<_, (i64, i64)>(
-                "SELECT COALESCE(SUM(llenas_entregadas), 0)::BIGINT, COALESCE(SUM(vacias_recibidas), 0)::BIGINT FROM deliveries WHERE created_at::date <= $1",
-            )
-            .bind(d)
-            .fetch_one(&self.pool)
-            .await
-            .map_err(Self::map_sqlx_error)?;
+    async fn update_webhook_subscription(
+        &self,
+        subscription_id: Uuid,
+        update: WebhookSubscriptionUpdate,
+    ) -> Result<WebhookSubscription, DomainError> {
+        let row = sqlx::query_as::<_, WebhookSubscriptionRow>(&format!(
+            r#"
+            UPDATE webhook_subscriptions
+            SET url = COALESCE($2, url),
+                event_types = COALESCE($3, event_types),
+                secret = COALESCE($4, secret),
+                active = COALESCE($5, active),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            WEBHOOK_SUBSCRIPTION_COLUMNS
+        ))
+        .bind(subscription_id)
+        .bind(update.url)
+        .bind(update.event_types.as_deref().map(event_type_names))
+        .bind(update.secret)
+        .bind(update.active)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
 
-            delivered_full = row.0;
-            recovered_empty = row.1;
-        } else {
-            inbound_full = sqlx::query_scalar(
-                "SELECT COALESCE(SUM(cantidad_llenas), 0)::BIGINT FROM stock_inbounds",
-            )
-            .fetch_one(&self.pool)
-            .await
-            .map_err(Self::map_sqlx_error)?;
+        row.ok_or_else(|| DomainError::NotFound("suscripción no encontrada".to_string()))?
+            .try_into()
+    }
 
-            let row = sqlx::query_as::<_, (i64, i64)>(
-                "SELECT COALESCE(SUM(llenas_entregadas), 0)::BIGINT, COALESCE(SUM(vacias_recibidas), 0)::BIGINT FROM deliveries",
-            )
-            .fetch_one(&self.pool)
+    async fn delete_webhook_subscription(&self, subscription_id: Uuid) -> Result<(), DomainError> {
+        let result = sqlx::query("DELETE FROM webhook_subscriptions WHERE id = $1")
+            .bind(subscription_id)
+            .execute(&self.pool)
             .await
             .map_err(Self::map_sqlx_error)?;
 
-            delivered_full = row.0;
-            recovered_empty = row.1;
+        if result.rows_affected() == 0 {
+            return Err(DomainError::NotFound(
+                "suscripción no encontrada".to_string(),
+            ));
         }
+        Ok(())
+    }
 
-        Ok(StockTotals {
-            inbound_full,
-            delivered_full,
-            recovered_empty,
-        })
+    async fn enqueue_webhook_deliveries(&self, event: &OutboxEvent) -> Result<u64, DomainError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries (id, subscription_id, event_id, status, next_attempt_at)
+            SELECT gen_random_uuid(), id, $1, 'PENDIENTE', NOW()
+            FROM webhook_subscriptions
+            WHERE active AND $2 = ANY(event_types)
+            ON CONFLICT (subscription_id, event_id) DO NOTHING
+            "#,
+        )
+        .bind(event.id)
+        .bind(event.event_type.as_str())
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        Ok(result.rows_affected())
     }
 
-    async fn daily_report_totals(&self, date: NaiveDate) -> Result<DailyReportTotals, DomainError> {
-        let row = sqlx::query_as::<_, (i64, i64, i64)>(
-            "SELECT COUNT(*)::BIGINT, COALESCE(SUM(llenas_entregadas), 0)::BIGINT, COALESCE(SUM(vacias_recibidas), 0)::BIGINT FROM deliveries WHERE created_at::date = $1",
+    async fn claim_webhook_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<PendingWebhookDelivery>, DomainError> {
+        let rows = sqlx::query_as::<_, PendingWebhookDeliveryRow>(
+            r#"
+            UPDATE webhook_deliveries d
+            SET attempts = d.attempts + 1, next_attempt_at = $2, updated_at = NOW()
+            FROM webhook_subscriptions s, outbox_events e
+            WHERE d.id IN (
+                SELECT pending.id FROM webhook_deliveries pending
+                JOIN webhook_subscriptions owner ON owner.id = pending.subscription_id
+                WHERE pending.status = 'PENDIENTE' AND pending.next_attempt_at <= $1 AND owner.active
+                ORDER BY pending.next_attempt_at
+                LIMIT $3
+                FOR UPDATE OF pending SKIP LOCKED
+            )
+            AND s.id = d.subscription_id AND e.id = d.event_id
+            RETURNING d.id AS delivery_id, s.url, s.secret, d.attempts, e.id AS event_id,
+                      e.event_type, e.aggregate_id, e.payload, e.occurred_at,
+                      e.attempts AS event_attempts, e.assignee_id, e.customer_id
+            "#,
         )
-        .bind(date)
-        .fetch_one(&self.pool)
+        .bind(now)
+        .bind(now + chrono::Duration::seconds(WEBHOOK_CLAIM_LEASE_SECONDS))
+        .bind(limit)
+        .fetch_all(&self.pool)
         .await
         .map_err(Self::map_sqlx_error)?;
 
-        Ok(DailyReportTotals {
-            deliveries_count: row.0,
-            delivered_full: row.1,
-            recovered_empty: row.2,
-        })
+        rows.into_iter().map(TryInto::try_into).collect()
     }
-}
 
-#[async_trait]
-impl AuditPort for PgRepository {
-    async fn record_audit_event(&self, event: NewAuditEvent) -> Result<(), DomainError> {
+    async fn record_webhook_attempt(
+        &self,
+        delivery_id: Uuid,
+        attempt: WebhookAttempt,
+    ) -> Result<(), DomainError> {
+        let delivered_at =
+            (attempt.status == WebhookDeliveryStatus::Entregada).then_some(attempt.attempted_at);
         sqlx::query(
             r#"
-            INSERT INTO audit_events (id, actor_id, entity, entity_id, action, details)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            UPDATE webhook_deliveries
+            SET status = $2, last_status_code = $3, last_error = $4, next_attempt_at = $5,
+                delivered_at = COALESCE($6, delivered_at), updated_at = NOW()
+            WHERE id = $1
             "#,
         )
-        .bind(Uuid::new_v4())
-        .bind(event.actor_id)
-        .bind(event.entity)
-        .bind(event.entity_id)
-        .bind(event.action)
-        .bind(event.details)
+        .bind(delivery_id)
+        .bind(attempt.status.as_str())
+        .bind(attempt.status_code)
+        .bind(attempt.error)
+        .bind(attempt.next_attempt_at)
+        .bind(delivered_at)
         .execute(&self.pool)
         .await
         .map_err(Self::map_sqlx_error)?;
 
         Ok(())
     }
+
+    async fn list_webhook_deliveries(
+        &self,
+        subscription_id: Uuid,
+        filter: WebhookDeliveryFilter,
+    ) -> Result<Vec<WebhookDelivery>, DomainError> {
+        let mut builder = QueryBuilder::<Postgres>::new(WEBHOOK_DELIVERY_SELECT);
+        builder
+            .push(" WHERE d.subscription_id = ")
+            .push_bind(subscription_id);
+        if let Some(status) = filter.status {
+            builder.push(" AND d.status = ").push_bind(status.as_str());
+        }
+        builder
+            .push(" ORDER BY d.created_at DESC, d.id DESC LIMIT ")
+            .push_bind(filter.limit);
+
+        let rows = builder
+            .build_query_as::<WebhookDeliveryRow>()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(Self::map_sqlx_error)?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    async fn get_webhook_delivery(
+        &self,
+        delivery_id: Uuid,
+    ) -> Result<Option<WebhookDelivery>, DomainError> {
+        self.fetch_webhook_delivery(delivery_id).await
+    }
+
+    async fn requeue_webhook_delivery(
+        &self,
+        delivery_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<WebhookDelivery, DomainError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = 'PENDIENTE', attempts = 0, next_attempt_at = $2, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(Self::map_sqlx_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::NotFound("entrega no encontrada".to_string()));
+        }
+        self.fetch_webhook_delivery(delivery_id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("entrega no encontrada".to_string()))
+    }
 }
@@ -0,0 +1,2 @@
+pub mod pg_listener;
+pub mod webhook;
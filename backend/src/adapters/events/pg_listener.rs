@@ -0,0 +1,46 @@
+use crate::application::events::live::LiveEvents;
+use crate::ports::outbox_port::OutboxPort;
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::time::Duration;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+/// Canal al que el trigger de `outbox_events` avisa cada inserción.
+pub const EVENTS_CHANNEL: &str = "gasflow_events";
+const RECONNECT_DELAY_SECONDS: u64 = 5;
+
+/// Escucha `NOTIFY` y reenvía cada evento a los streams de esta réplica.
+/// No vuelve nunca: ante un error de conexión espera y se reconecta.
+pub async fn forward_notifications<P: OutboxPort>(pool: PgPool, port: P, live: LiveEvents) {
+    loop {
+        if let Err(err) = listen(&pool, &port, &live).await {
+            error!(error = %err, "live events listener disconnected");
+        }
+        tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY_SECONDS)).await;
+    }
+}
+
+async fn listen<P: OutboxPort>(
+    pool: &PgPool,
+    port: &P,
+    live: &LiveEvents,
+) -> Result<(), sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(EVENTS_CHANNEL).await?;
+    loop {
+        let notification = listener.recv().await?;
+        let Ok(event_id) = Uuid::parse_str(notification.payload()) else {
+            warn!(
+                payload = notification.payload(),
+                "invalid live event notification"
+            );
+            continue;
+        };
+        match port.find_outbox_event(event_id).await {
+            Ok(Some(event)) => live.publish(event),
+            Ok(None) => {}
+            Err(err) => error!(event_id = %event_id, error = %err, "live event lookup failed"),
+        }
+    }
+}
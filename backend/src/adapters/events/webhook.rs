@@ -0,0 +1,150 @@
+use crate::domain::error::DomainError;
+use crate::domain::events::OutboxEvent;
+use crate::domain::webhooks::WebhookTargetPolicy;
+use crate::ports::event_sink_port::EventSink;
+use crate::ports::webhooks_port::WebhookClient;
+use async_trait::async_trait;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use url::{Host, Url};
+
+const WEBHOOK_TIMEOUT_SECONDS: u64 = 10;
+
+/// Cliente HTTP compartido por los webhooks salientes. No sigue redirecciones
+/// y verifica las direcciones resueltas en cada envío, así un nombre que hoy
+/// apunta a una IP pública no puede redirigirse después a la red interna.
+#[derive(Debug, Clone)]
+pub struct HttpWebhookClient {
+    client: reqwest::Client,
+    policy: WebhookTargetPolicy,
+}
+
+impl HttpWebhookClient {
+    pub fn new(policy: WebhookTargetPolicy) -> Result<Self, DomainError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECONDS))
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(Arc::new(TargetResolver { policy }))
+            .build()
+            .map_err(|err| DomainError::Infrastructure(err.to_string()))?;
+        Ok(Self { client, policy })
+    }
+
+    /// Las IP literales no pasan por el resolver: se verifican acá.
+    fn ensure_target(&self, url: &str) -> Result<(), DomainError> {
+        let parsed = Url::parse(url).map_err(|err| {
+            DomainError::Infrastructure(format!("url de webhook inválida: {}", err))
+        })?;
+        if !self.policy.allow_insecure && parsed.scheme() != "https" {
+            return Err(DomainError::Infrastructure(
+                "webhook rechazado: url sin https".to_string(),
+            ));
+        }
+        let ip = match parsed.host() {
+            Some(Host::Ipv4(ip)) => Some(IpAddr::V4(ip)),
+            Some(Host::Ipv6(ip)) => Some(IpAddr::V6(ip)),
+            _ => None,
+        };
+        if ip.is_some_and(|ip| !self.policy.allows_ip(ip)) {
+            return Err(DomainError::Infrastructure(
+                "webhook rechazado: destino en la red local o privada".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Resolución DNS que descarta direcciones no permitidas por la política.
+struct TargetResolver {
+    policy: WebhookTargetPolicy,
+}
+
+impl Resolve for TargetResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let policy = self.policy;
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| policy.allows_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(
+                    format!("{} no resuelve a ninguna dirección pública permitida", host).into(),
+                );
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[async_trait]
+impl WebhookClient for HttpWebhookClient {
+    async fn post_json(
+        &self,
+        url: &str,
+        headers: &[(&'static str, String)],
+        body: String,
+    ) -> Result<u16, DomainError> {
+        self.ensure_target(url)?;
+        let mut request = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|err| DomainError::Infrastructure(format!("webhook inalcanzable: {}", err)))?;
+        Ok(response.status().as_u16())
+    }
+}
+
+/// Publica cada evento con un POST JSON a una URL fija. Cualquier respuesta
+/// fuera de 2xx cuenta como fallo y el dispatcher la reintenta.
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    client: HttpWebhookClient,
+    url: String,
+}
+
+impl WebhookSink {
+    pub fn new(url: String, policy: WebhookTargetPolicy) -> Result<Self, DomainError> {
+        policy.validate_url(&url)?;
+        Ok(Self {
+            client: HttpWebhookClient::new(policy)?,
+            url,
+        })
+    }
+}
+
+#[async_trait]
+impl EventSink for WebhookSink {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), DomainError> {
+        let headers = [
+            ("X-GasFlow-Event", event.event_type.as_str().to_string()),
+            ("X-GasFlow-Event-Id", event.id.to_string()),
+        ];
+        let status = self
+            .client
+            .post_json(&self.url, &headers, event.envelope().to_string())
+            .await?;
+
+        if !(200..300).contains(&status) {
+            return Err(DomainError::Infrastructure(format!(
+                "webhook respondió {}",
+                status
+            )));
+        }
+        Ok(())
+    }
+}
@@ -1,38 +1,117 @@
 use crate::application;
-use crate::domain::audit::NewAuditEvent;
-use crate::domain::auth::Role;
-use crate::domain::delivery::{Delivery, FailedDelivery, NewDelivery, NewFailedDelivery};
+use crate::domain::api_keys::{ApiKey, ApiKeyIssued};
+use crate::domain::attachments::{Attachment, AttachmentKind, AttachmentTarget};
+use crate::domain::audit::{
+    AuditChainReport, AuditContext, AuditCursor, AuditEvent, AuditFilter, AuditPage,
+    BrokenAuditLink, DEFAULT_AUDIT_PAGE_SIZE, MAX_AUDIT_PAGE_SIZE,
+};
+use crate::domain::auth::{
+    LegacyPasswordUser, PasswordResetIssued, PasswordScheme, Permission, Role, TokenPair,
+    UserAccount, UserFilter,
+};
+use crate::domain::calendar::{Holiday, NewHoliday, WorkingCalendar, NEXT_AVAILABLE_HORIZON_DAYS};
+use crate::domain::customers::CustomerAddress;
+use crate::domain::delivery::{
+    Delivery, DeliveryCorrection, DeliveryCorrectionKind, DeliveryRemainder, FailedDelivery,
+    NewDelivery, NewFailedDelivery,
+};
 use crate::domain::error::DomainError;
+use crate::domain::events::{DomainEventType, EventViewer, OutboxEvent};
+use crate::domain::jobs::{JobRun, JobRunStatus, JobSummary, JobTrigger, DEFAULT_JOB_RUNS_LIMIT};
 use crate::domain::orders::{
-    NewOrder, Order, OrderFilter, OrderStatus, PaginatedOrders, DEFAULT_ORDERS_PAGE,
+    NewOrder, Order, OrderFilter, OrderStatus, OrderViewer, PaginatedOrders, DEFAULT_ORDERS_PAGE,
     DEFAULT_ORDERS_PAGE_SIZE, MAX_ORDERS_PAGE_SIZE,
 };
+use crate::domain::positions::{
+    DriverPosition, LatestDriverPosition, PositionBatchReport, PositionFix,
+};
+use crate::domain::recurring::{
+    MaterializeReport, OccurrenceStatus, RecurringOccurrence, RecurringOrder, RecurringStatus,
+    DEFAULT_RECURRING_DAYS_AHEAD,
+};
+use crate::domain::slots::{
+    NewTimeSlot, SlotAvailability, SlotZoneCapacity, TimeSlot, TimeSlotUpdate,
+};
 use crate::domain::stock::{DailyOperationalReport, Inbound, StockSummary};
-use crate::ports::audit_port::AuditPort;
+use crate::domain::two_factor::{
+    ChallengePurpose, LoginOutcome, RecoveryCodes, TwoFactorEnrollment, TwoFactorLogin,
+};
+use crate::domain::webhooks::{
+    WebhookDelivery, WebhookDeliveryFilter, WebhookDeliveryStatus, WebhookSubscription,
+    WebhookSubscriptionCreated, WebhookSubscriptionUpdate, DEFAULT_WEBHOOK_DELIVERIES_LIMIT,
+};
+use crate::domain::zones::{DayOfWeek, GeoPoint, NewZone, Zone, ZoneUpdate};
 use crate::ports::orders_port::OrdersPort;
 use crate::AppState;
-use axum::extract::{Path, Query, Request, State};
+use axum::async_trait;
+use axum::body::Bytes;
+use axum::extract::{ConnectInfo, FromRequestParts, Path, Query, Request, State};
 use axum::http::{
-    header::{HeaderName, AUTHORIZATION},
-    HeaderValue, Method, StatusCode,
+    header::{
+        HeaderName, AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE, RETRY_AFTER, USER_AGENT,
+        X_CONTENT_TYPE_OPTIONS,
+    },
+    request::Parts,
+    HeaderMap, HeaderValue, Method, StatusCode,
 };
 use axum::middleware::Next;
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json};
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Instant;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::{BroadcastStream, IntervalStream};
+use tokio_stream::{Stream, StreamExt};
 use tracing::info;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+const FORWARDED_FOR_HEADER: HeaderName = HeaderName::from_static("x-forwarded-for");
+const API_KEY_HEADER: HeaderName = HeaderName::from_static("x-api-key");
+/// Cada cuánto `GET /events/stream` vuelve a validar las credenciales.
+const EVENT_STREAM_AUTH_RECHECK_SECONDS: u64 = 15;
 
 #[derive(Debug, Clone)]
 pub struct AuthContext {
-    pub user_id: Uuid,
-    pub role: Role,
+    /// Usuario de la sesión; `None` con API key, que no actúa en nombre de
+    /// ningún usuario (ni siquiera del administrador que la emitió).
+    pub user_id: Option<Uuid>,
+    pub permissions: Vec<Permission>,
+    /// Presente si la petición llegó con `X-Api-Key`.
+    pub api_key_id: Option<Uuid>,
+}
+
+impl AuthContext {
+    pub fn for_user(user_id: Uuid, role: &Role) -> Self {
+        Self {
+            user_id: Some(user_id),
+            permissions: role.permissions().to_vec(),
+            api_key_id: None,
+        }
+    }
+
+    pub fn has(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+
+    /// Usuario a registrar como actor. Con API key no hay usuario: la key
+    /// queda en `api_key_id` del `AuditContext`.
+    pub fn actor_id(&self) -> Option<Uuid> {
+        self.user_id
+    }
+
+    /// Usuario de la sesión para las operaciones sobre lo propio.
+    pub fn session_user(&self) -> Result<Uuid, DomainError> {
+        self.user_id
+            .ok_or_else(|| DomainError::Unauthorized("requiere una sesión de usuario".to_string()))
+    }
 }
 
 fn map_error(err: DomainError) -> (StatusCode, Json<serde_json::Value>) {
@@ -41,6 +120,13 @@ fn map_error(err: DomainError) -> (StatusCode, Json<serde_json::Value>) {
         DomainError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, Json(json!({ "error": msg }))),
         DomainError::NotFound(msg) => (StatusCode::NOT_FOUND, Json(json!({ "error": msg }))),
         DomainError::Conflict(msg) => (StatusCode::CONFLICT, Json(json!({ "error": msg }))),
+        DomainError::TooManyRequests {
+            message,
+            retry_after_seconds,
+        } => (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({ "error": message, "retry_after": retry_after_seconds })),
+        ),
         DomainError::Infrastructure(msg) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(json!({ "error": msg })),
@@ -48,6 +134,79 @@ fn map_error(err: DomainError) -> (StatusCode, Json<serde_json::Value>) {
     }
 }
 
+/// Como `map_error`, pero agrega `Retry-After` cuando la respuesta es 429.
+fn error_response(err: DomainError) -> Response {
+    let retry_after = match &err {
+        DomainError::TooManyRequests {
+            retry_after_seconds,
+            ..
+        } => Some(*retry_after_seconds),
+        _ => None,
+    };
+    let mut response = map_error(err).into_response();
+    if let Some(seconds) = retry_after {
+        response
+            .headers_mut()
+            .insert(RETRY_AFTER, HeaderValue::from(seconds));
+    }
+    response
+}
+
+/// IP del cliente: el primer salto de `X-Forwarded-For` si el proxy es de
+/// confianza, si no la dirección de la conexión.
+fn client_ip(
+    headers: &HeaderMap,
+    connect_info: Option<&ConnectInfo<SocketAddr>>,
+    trust_proxy_headers: bool,
+) -> Option<String> {
+    let forwarded = trust_proxy_headers
+        .then(|| headers.get(&FORWARDED_FOR_HEADER))
+        .flatten()
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string);
+    forwarded.or_else(|| connect_info.map(|ConnectInfo(addr)| addr.ip().to_string()))
+}
+
+/// `X-Request-Id` del pedido en curso, recibido o generado por
+/// `request_id_middleware`.
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Arma el origen de auditoría del pedido: la sesión o API key si la ruta
+/// está autenticada, el request id, la IP y el user agent.
+#[async_trait]
+impl FromRequestParts<AppState> for AuditContext {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let auth = parts.extensions.get::<AuthContext>();
+        Ok(AuditContext {
+            actor_id: auth.and_then(AuthContext::actor_id),
+            api_key_id: auth.and_then(|ctx| ctx.api_key_id),
+            request_id: parts
+                .extensions
+                .get::<RequestId>()
+                .map(|RequestId(id)| id.clone()),
+            ip: client_ip(
+                &parts.headers,
+                parts.extensions.get::<ConnectInfo<SocketAddr>>(),
+                state.trust_proxy_headers,
+            ),
+            user_agent: parts
+                .headers
+                .get(USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(ToOwned::to_owned),
+        })
+    }
+}
+
 fn parse_bearer_token(header_value: &str) -> Option<&str> {
     header_value.strip_prefix("Bearer ")
 }
@@ -61,18 +220,16 @@ fn parse_date(date: &str) -> Result<NaiveDate, DomainError> {
         .map_err(|_| DomainError::Validation("fecha inválida (usar YYYY-MM-DD)".to_string()))
 }
 
+fn parse_time(time: &str) -> Result<NaiveTime, DomainError> {
+    NaiveTime::parse_from_str(time, "%H:%M")
+        .map_err(|_| DomainError::Validation("hora inválida (usar HH:MM)".to_string()))
+}
+
 fn parse_status(status: &str) -> Result<OrderStatus, DomainError> {
     OrderStatus::from_str(status)
         .ok_or_else(|| DomainError::Validation("status inválido".to_string()))
 }
 
-fn require_admin(ctx: &AuthContext) -> Result<(), DomainError> {
-    if ctx.role != Role::Admin {
-        return Err(DomainError::Unauthorized("requiere rol ADMIN".to_string()));
-    }
-    Ok(())
-}
-
 fn parse_page(page: Option<i64>) -> Result<i64, DomainError> {
     let page = page.unwrap_or(DEFAULT_ORDERS_PAGE);
     if page < 1 {
@@ -85,7 +242,7 @@ fn parse_page(page: Option<i64>) -> Result<i64, DomainError> {
 
 fn parse_page_size(page_size: Option<i64>) -> Result<i64, DomainError> {
     let page_size = page_size.unwrap_or(DEFAULT_ORDERS_PAGE_SIZE);
-    if page_size < 1 || page_size > MAX_ORDERS_PAGE_SIZE {
+    if !(1..=MAX_ORDERS_PAGE_SIZE).contains(&page_size) {
         return Err(DomainError::Validation(format!(
             "page_size debe estar entre 1 y {}",
             MAX_ORDERS_PAGE_SIZE
@@ -94,8 +251,18 @@ fn parse_page_size(page_size: Option<i64>) -> Result<i64, DomainError> {
     Ok(page_size)
 }
 
+fn order_viewer(ctx: &AuthContext) -> OrderViewer {
+    OrderViewer {
+        user_id: ctx.user_id,
+        read_all: ctx.has(Permission::OrdersReadAll),
+        self_service: ctx.has(Permission::OrdersSelfService),
+    }
+}
+
 fn ensure_delivery_access(ctx: &AuthContext, order: &Order) -> Result<(), DomainError> {
-    if ctx.role == Role::Repartidor && order.assignee_id != Some(ctx.user_id) {
+    if !ctx.has(Permission::DeliveriesAnyOrder)
+        && (order.assignee_id.is_none() || order.assignee_id != ctx.user_id)
+    {
         return Err(DomainError::Unauthorized(
             "no podés registrar entregas de pedidos no asignados".to_string(),
         ));
@@ -116,7 +283,7 @@ pub async fn request_id_middleware(mut req: Request, next: Next) -> Response {
     let method: Method = req.method().clone();
     let path = req.uri().path().to_string();
 
-    req.extensions_mut().insert(request_id.clone());
+    req.extensions_mut().insert(RequestId(request_id.clone()));
 
     let mut response = next.run(req).await;
 
@@ -157,25 +324,103 @@ pub async fn auth_middleware(
     mut req: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
-    let auth = req
-        .headers()
+    let ctx = resolve_auth(&state, req.headers())
+        .await
+        .map_err(|err| match err {
+            DomainError::Infrastructure(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNAUTHORIZED,
+        })?;
+    req.extensions_mut().insert(ctx);
+
+    Ok(next.run(req).await)
+}
+
+/// Valida las credenciales de la petición. También la usa el stream en vivo
+/// para volver a validarlas mientras sigue abierto.
+async fn resolve_auth(state: &AppState, headers: &HeaderMap) -> Result<AuthContext, DomainError> {
+    let invalid = || DomainError::Unauthorized("credenciales inválidas".to_string());
+
+    // Las integraciones usan su propio header; nunca se combina con un JWT.
+    if let Some(key) = headers.get(API_KEY_HEADER) {
+        let key = key.to_str().map_err(|_| invalid())?;
+        let api_key = application::api_keys::authenticate(&state.repo, key).await?;
+        return Ok(AuthContext {
+            user_id: None,
+            permissions: api_key.permissions,
+            api_key_id: Some(api_key.id),
+        });
+    }
+
+    let auth = headers
         .get(AUTHORIZATION)
         .and_then(|h| h.to_str().ok())
         .and_then(parse_bearer_token)
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+        .ok_or_else(invalid)?;
 
-    let claims = state
-        .jwt
-        .verify(auth)
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
-    let user_id = parse_uuid(&claims.sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let claims = state.jwt.verify(auth).map_err(|_| invalid())?;
+    let user_id = parse_uuid(&claims.sub).map_err(|_| invalid())?;
 
-    req.extensions_mut().insert(AuthContext {
-        user_id,
-        role: claims.role,
-    });
+    // El rol se toma de la base: un cambio de rol aplica sin esperar a que
+    // venza el token.
+    let user = application::auth::service::authenticate(&state.repo, user_id, claims.gen).await?;
 
-    Ok(next.run(req).await)
+    Ok(AuthContext::for_user(user_id, &user.role))
+}
+
+/// Capa por ruta (ver `router.rs`): corta si el usuario autenticado o la API
+/// key no tiene el permiso.
+pub async fn require_permission(
+    State(permission): State<Permission>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let allowed = req
+        .extensions()
+        .get::<AuthContext>()
+        .is_some_and(|ctx| ctx.has(permission));
+    if !allowed {
+        return map_error(DomainError::Unauthorized(format!(
+            "requiere permiso {}",
+            permission.as_str()
+        )))
+        .into_response();
+    }
+    next.run(req).await
+}
+
+/// Rutas sobre la cuenta propia: no tienen sentido para una API key.
+pub async fn require_user_session(req: Request, next: Next) -> Response {
+    let is_api_key = req
+        .extensions()
+        .get::<AuthContext>()
+        .is_none_or(|ctx| ctx.api_key_id.is_some());
+    if is_api_key {
+        return map_error(DomainError::Unauthorized(
+            "requiere una sesión de usuario".to_string(),
+        ))
+        .into_response();
+    }
+    next.run(req).await
+}
+
+/// Claves públicas para que otros servicios validen nuestros tokens; en
+/// modo HS256 la lista está vacía.
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    responses(
+        (status = 200, description = "JSON Web Key Set with the active and previous signing keys")
+    ),
+    tag = "auth"
+)]
+pub async fn jwks(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(
+            CACHE_CONTROL,
+            HeaderValue::from_static("public, max-age=300"),
+        )],
+        Json(state.jwt.jwks().clone()),
+    )
 }
 
 pub async fn health() -> Json<serde_json::Value> {
@@ -204,6 +449,52 @@ pub struct LoginRequest {
 pub struct LoginResponse {
     pub access_token: String,
     pub token_type: &'static str,
+    /// Vigencia del access token en segundos.
+    pub expires_in: i64,
+    pub refresh_token: String,
+}
+
+impl From<TokenPair> for LoginResponse {
+    fn from(value: TokenPair) -> Self {
+        LoginResponse {
+            access_token: value.access_token,
+            token_type: "Bearer",
+            expires_in: value.expires_in,
+            refresh_token: value.refresh_token,
+        }
+    }
+}
+
+/// Contraseña correcta pero falta el segundo factor: `ENROLL` pide activarlo
+/// con `/auth/2fa/enroll` y `VERIFY`, un código en `/auth/2fa/verify`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorChallengeResponse {
+    pub two_factor: ChallengePurpose,
+    pub challenge_token: String,
+    /// Vigencia del desafío en segundos.
+    pub expires_in: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum LoginResult {
+    Tokens(LoginResponse),
+    TwoFactorRequired(TwoFactorChallengeResponse),
+}
+
+impl From<LoginOutcome> for LoginResult {
+    fn from(value: LoginOutcome) -> Self {
+        match value {
+            LoginOutcome::Tokens(tokens) => LoginResult::Tokens(tokens.into()),
+            LoginOutcome::TwoFactorRequired(challenge) => {
+                LoginResult::TwoFactorRequired(TwoFactorChallengeResponse {
+                    two_factor: challenge.purpose,
+                    challenge_token: challenge.challenge_token,
+                    expires_in: challenge.expires_in,
+                })
+            }
+        }
+    }
 }
 
 #[utoipa::path(
@@ -211,28 +502,85 @@ pub struct LoginResponse {
     path = "/auth/login",
     request_body = LoginRequest,
     responses(
-        (status = 200, description = "Login successful", body = LoginResponse),
-        (status = 401, description = "Unauthorized")
+        (status = 200, description = "Tokens, or a two-factor challenge when 2FA applies", body = LoginResult),
+        (status = 401, description = "Unauthorized or account locked"),
+        (status = 429, description = "Too many failed attempts; see Retry-After")
     ),
     tag = "auth"
 )]
 pub async fn login(
     State(state): State<AppState>,
+    audit: AuditContext,
     Json(payload): Json<LoginRequest>,
-) -> Result<Json<LoginResponse>, (StatusCode, Json<serde_json::Value>)> {
-    let token = application::auth::service::login(
+) -> Result<Json<LoginResult>, Response> {
+    let outcome = application::auth::service::login(
         &state.repo,
         &state.jwt,
+        &state.login_policy,
         payload.username,
         payload.password,
+        &audit,
     )
     .await
-    .map_err(map_error)?;
+    .map_err(error_response)?;
 
-    Ok(Json(LoginResponse {
-        access_token: token,
-        token_type: "Bearer",
-    }))
+    Ok(Json(outcome.into()))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/refresh",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, description = "New access token and rotated refresh token", body = LoginResponse),
+        (status = 401, description = "Invalid, expired or reused refresh token")
+    ),
+    tag = "auth"
+)]
+pub async fn refresh(
+    State(state): State<AppState>,
+    Json(payload): Json<RefreshRequest>,
+) -> Result<Json<LoginResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let tokens =
+        application::auth::service::refresh(&state.repo, &state.jwt, &payload.refresh_token)
+            .await
+            .map_err(map_error)?;
+
+    Ok(Json(tokens.into()))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LogoutRequest {
+    pub refresh_token: String,
+    /// Cierra también las demás sesiones del usuario.
+    #[serde(default)]
+    pub all_sessions: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/logout",
+    request_body = LogoutRequest,
+    responses(
+        (status = 204, description = "Session closed"),
+        (status = 401, description = "Unknown refresh token")
+    ),
+    tag = "auth"
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    Json(payload): Json<LogoutRequest>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    application::auth::service::logout(&state.repo, &payload.refresh_token, payload.all_sessions)
+        .await
+        .map_err(map_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -240,6 +588,8 @@ pub struct MeResponse {
     pub id: Uuid,
     pub username: String,
     pub role: Role,
+    pub permissions: Vec<Permission>,
+    pub two_factor_enabled: bool,
 }
 
 #[utoipa::path(
@@ -258,538 +608,3580 @@ pub async fn me(
     State(state): State<AppState>,
     Extension(ctx): Extension<AuthContext>,
 ) -> Result<Json<MeResponse>, (StatusCode, Json<serde_json::Value>)> {
-    let user = application::auth::service::me(&state.repo, ctx.user_id)
+    let user = application::auth::service::me(&state.repo, ctx.session_user().map_err(map_error)?)
         .await
         .map_err(map_error)?;
 
     Ok(Json(MeResponse {
         id: user.id,
+        two_factor_enabled: user.two_factor_enabled(),
         username: user.username,
+        permissions: user.role.permissions().to_vec(),
         role: user.role,
     }))
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
-pub struct CreateOrderRequest {
-    pub address: String,
-    pub zone: String,
-    pub scheduled_date: String,
-    pub time_slot: String,
-    pub quantity: i32,
-    pub notes: Option<String>,
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
 }
 
 #[utoipa::path(
     post,
-    path = "/orders",
-    request_body = CreateOrderRequest,
+    path = "/me/password",
+    request_body = ChangePasswordRequest,
     responses(
-        (status = 201, description = "Order created successfully", body = Order),
-        (status = 400, description = "Invalid input"),
-        (status = 401, description = "Unauthorized")
+        (status = 200, description = "Password changed; other sessions closed", body = LoginResponse),
+        (status = 400, description = "New password rejected by policy"),
+        (status = 401, description = "Wrong current password")
     ),
-    tag = "orders",
+    tag = "auth",
     security(
         ("bearer_auth" = [])
     )
 )]
-pub async fn create_order(
+pub async fn change_password(
     State(state): State<AppState>,
     Extension(ctx): Extension<AuthContext>,
-    Json(payload): Json<CreateOrderRequest>,
-) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
-    require_admin(&ctx).map_err(map_error)?;
-
-    let input = NewOrder {
-        address: payload.address,
-        zone: payload.zone,
-        scheduled_date: parse_date(&payload.scheduled_date).map_err(map_error)?,
-        time_slot: payload.time_slot,
-        quantity: payload.quantity,
-        notes: payload.notes,
-    };
-
-    let order = application::orders::create_order::execute(&state.repo, input)
-        .await
-        .map_err(map_error)?;
+    audit: AuditContext,
+    Json(payload): Json<ChangePasswordRequest>,
+) -> Result<Json<LoginResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let tokens = application::auth::change_password::execute(
+        &state.repo,
+        &state.jwt,
+        &state.login_policy,
+        ctx.session_user().map_err(map_error)?,
+        &payload.current_password,
+        &payload.new_password,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
 
-    Ok((StatusCode::CREATED, Json(order)))
+    Ok(Json(tokens.into()))
 }
 
-#[derive(Debug, Deserialize)]
-pub struct ListOrdersQuery {
-    pub date: Option<String>,
-    pub status: Option<String>,
-    pub assignee: Option<Uuid>,
-    pub page: Option<i64>,
-    pub page_size: Option<i64>,
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CompletePasswordResetRequest {
+    pub username: String,
+    pub code: String,
+    pub new_password: String,
 }
 
 #[utoipa::path(
-    get,
-    path = "/orders",
-    params(
-        ("date" = Option<String>, Query, description = "Filter by scheduled date (YYYY-MM-DD)"),
-        ("status" = Option<String>, Query, description = "Filter by status"),
-        ("assignee" = Option<Uuid>, Query, description = "Filter by assignee ID"),
-        ("page" = Option<i64>, Query, description = "Page number"),
-        ("page_size" = Option<i64>, Query, description = "Items per page")
-    ),
+    post,
+    path = "/auth/password-reset",
+    request_body = CompletePasswordResetRequest,
     responses(
-        (status = 200, description = "List of orders", body = PaginatedOrders),
-        (status = 401, description = "Unauthorized")
+        (status = 200, description = "New password set; tokens or a two-factor challenge", body = LoginResult),
+        (status = 400, description = "New password rejected by policy"),
+        (status = 401, description = "Invalid, used or expired code"),
+        (status = 429, description = "Too many failed attempts; see Retry-After")
     ),
-    tag = "orders",
-    security(
-        ("bearer_auth" = [])
-    )
+    tag = "auth"
 )]
-pub async fn list_orders(
+pub async fn complete_password_reset(
     State(state): State<AppState>,
-    Extension(ctx): Extension<AuthContext>,
-    Query(query): Query<ListOrdersQuery>,
-) -> Result<Json<crate::domain::orders::PaginatedOrders>, (StatusCode, Json<serde_json::Value>)> {
-    let mut filter = OrderFilter {
-        date: query
-            .date
-            .as_deref()
-            .map(parse_date)
-            .transpose()
-            .map_err(map_error)?,
-        status: query
-            .status
-            .as_deref()
-            .map(parse_status)
-            .transpose()
-            .map_err(map_error)?,
-        assignee: query.assignee,
-        page: parse_page(query.page).map_err(map_error)?,
-        page_size: parse_page_size(query.page_size).map_err(map_error)?,
-    };
-
-    if ctx.role == Role::Repartidor {
-        filter.assignee = Some(ctx.user_id);
-    }
-
-    let orders = application::orders::list_orders::execute(&state.repo, filter)
-        .await
-        .map_err(map_error)?;
+    audit: AuditContext,
+    Json(payload): Json<CompletePasswordResetRequest>,
+) -> Result<Json<LoginResult>, Response> {
+    let outcome = application::auth::change_password::with_reset_code(
+        &state.repo,
+        &state.jwt,
+        &state.login_policy,
+        &payload.username,
+        &payload.code,
+        &payload.new_password,
+        &audit,
+    )
+    .await
+    .map_err(error_response)?;
 
-    Ok(Json(orders))
+    Ok(Json(outcome.into()))
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
-pub struct ChangeStatusRequest {
-    pub status: String,
+pub struct TwoFactorEnrollChallengeRequest {
+    pub challenge_token: String,
 }
 
 #[utoipa::path(
-    patch,
-    path = "/orders/{id}/status",
-    params(
-        ("id" = Uuid, Path, description = "Order ID")
-    ),
-    request_body = ChangeStatusRequest,
+    post,
+    path = "/auth/2fa/enroll",
+    request_body = TwoFactorEnrollChallengeRequest,
     responses(
-        (status = 200, description = "Order status updated", body = Order),
-        (status = 400, description = "Invalid transition"),
-        (status = 401, description = "Unauthorized"),
-        (status = 404, description = "Order not found")
+        (status = 200, description = "TOTP secret to load in the authenticator app", body = TwoFactorEnrollment),
+        (status = 401, description = "Invalid or expired challenge")
     ),
-    tag = "orders",
-    security(
-        ("bearer_auth" = [])
-    )
+    tag = "auth"
 )]
-pub async fn change_order_status(
+pub async fn enroll_two_factor_challenge(
     State(state): State<AppState>,
-    Extension(ctx): Extension<AuthContext>,
-    Path(id): Path<Uuid>,
-    Json(payload): Json<ChangeStatusRequest>,
-) -> Result<Json<crate::domain::orders::Order>, (StatusCode, Json<serde_json::Value>)> {
-    require_admin(&ctx).map_err(map_error)?;
+    Json(payload): Json<TwoFactorEnrollChallengeRequest>,
+) -> Result<Json<TwoFactorEnrollment>, (StatusCode, Json<serde_json::Value>)> {
+    let enrollment =
+        application::auth::two_factor::enroll_with_challenge(&state.repo, &payload.challenge_token)
+            .await
+            .map_err(map_error)?;
 
-    let target = parse_status(&payload.status).map_err(map_error)?;
-    let order = application::orders::change_status::execute(&state.repo, id, target)
-        .await
-        .map_err(map_error)?;
+    Ok(Json(enrollment))
+}
 
-    state
-        .repo
-        .record_audit_event(NewAuditEvent {
-            actor_id: Some(ctx.user_id),
-            entity: "order".to_string(),
-            entity_id: Some(order.id),
-            action: "status_changed".to_string(),
-            details: json!({ "status": order.status.as_str() }),
-        })
-        .await
-        .map_err(map_error)?;
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TwoFactorVerifyRequest {
+    pub challenge_token: String,
+    /// Código TOTP de 6 dígitos o código de recuperación.
+    pub code: String,
+}
 
-    Ok(Json(order))
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TwoFactorLoginResponse {
+    #[serde(flatten)]
+    pub tokens: LoginResponse,
+    /// Sólo al activar 2FA desde el login; se muestran esta única vez.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>,
+}
+
+impl From<TwoFactorLogin> for TwoFactorLoginResponse {
+    fn from(value: TwoFactorLogin) -> Self {
+        TwoFactorLoginResponse {
+            tokens: value.tokens.into(),
+            recovery_codes: value.recovery_codes,
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/auth/2fa/verify",
+    request_body = TwoFactorVerifyRequest,
+    responses(
+        (status = 200, description = "Second factor accepted; logged in", body = TwoFactorLoginResponse),
+        (status = 401, description = "Invalid code or expired challenge"),
+        (status = 429, description = "Too many failed attempts; see Retry-After")
+    ),
+    tag = "auth"
+)]
+pub async fn verify_two_factor(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<TwoFactorVerifyRequest>,
+) -> Result<Json<TwoFactorLoginResponse>, Response> {
+    let login = application::auth::two_factor::verify_challenge(
+        &state.repo,
+        &state.jwt,
+        &state.login_policy,
+        &payload.challenge_token,
+        &payload.code,
+        &audit,
+    )
+    .await
+    .map_err(error_response)?;
+
+    Ok(Json(login.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/me/2fa/enroll",
+    responses(
+        (status = 200, description = "Pending TOTP secret; confirm it with a code", body = TwoFactorEnrollment),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "2FA already enabled")
+    ),
+    tag = "auth",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn enroll_two_factor(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+) -> Result<Json<TwoFactorEnrollment>, (StatusCode, Json<serde_json::Value>)> {
+    let enrollment = application::auth::two_factor::start_enrollment(
+        &state.repo,
+        ctx.session_user().map_err(map_error)?,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(enrollment))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConfirmTwoFactorRequest {
+    pub code: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/me/2fa/confirm",
+    request_body = ConfirmTwoFactorRequest,
+    responses(
+        (status = 200, description = "2FA enabled; recovery codes shown only once", body = RecoveryCodes),
+        (status = 400, description = "No pending enrollment"),
+        (status = 401, description = "Invalid code"),
+        (status = 409, description = "2FA already enabled")
+    ),
+    tag = "auth",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn confirm_two_factor(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Json(payload): Json<ConfirmTwoFactorRequest>,
+) -> Result<Json<RecoveryCodes>, (StatusCode, Json<serde_json::Value>)> {
+    let codes = application::auth::two_factor::confirm_enrollment(
+        &state.repo,
+        ctx.session_user().map_err(map_error)?,
+        &payload.code,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(codes))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TwoFactorReauthRequest {
+    pub password: String,
+    /// Código TOTP de 6 dígitos o código de recuperación.
+    pub code: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/me/2fa/disable",
+    request_body = TwoFactorReauthRequest,
+    responses(
+        (status = 204, description = "2FA disabled"),
+        (status = 400, description = "2FA not enabled"),
+        (status = 401, description = "Wrong password or code"),
+        (status = 409, description = "2FA is mandatory for the user's role")
+    ),
+    tag = "auth",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn disable_two_factor(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Json(payload): Json<TwoFactorReauthRequest>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    application::auth::two_factor::disable(
+        &state.repo,
+        &state.login_policy,
+        ctx.session_user().map_err(map_error)?,
+        &payload.password,
+        &payload.code,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/me/2fa/recovery-codes",
+    request_body = TwoFactorReauthRequest,
+    responses(
+        (status = 200, description = "New recovery codes; previous ones stop working", body = RecoveryCodes),
+        (status = 400, description = "2FA not enabled"),
+        (status = 401, description = "Wrong password or code")
+    ),
+    tag = "auth",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn regenerate_recovery_codes(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Json(payload): Json<TwoFactorReauthRequest>,
+) -> Result<Json<RecoveryCodes>, (StatusCode, Json<serde_json::Value>)> {
+    let codes = application::auth::two_factor::regenerate_recovery_codes(
+        &state.repo,
+        &state.login_policy,
+        ctx.session_user().map_err(map_error)?,
+        &payload.password,
+        &payload.code,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(codes))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateOrderRequest {
+    pub address: String,
+    pub zone: String,
+    pub scheduled_date: String,
+    pub time_slot: String,
+    pub quantity: i32,
+    pub notes: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/orders",
+    request_body = CreateOrderRequest,
+    responses(
+        (status = 201, description = "Order created successfully", body = Order),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_order(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<CreateOrderRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let input = NewOrder {
+        address: payload.address,
+        zone: payload.zone,
+        scheduled_date: parse_date(&payload.scheduled_date).map_err(map_error)?,
+        time_slot: payload.time_slot,
+        quantity: payload.quantity,
+        notes: payload.notes,
+        customer_id: None,
+        address_id: None,
+    };
+
+    let order = application::orders::create_order::execute(
+        &state.repo,
+        input,
+        state.calendar_policy.today(),
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(order)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListOrdersQuery {
+    pub date: Option<String>,
+    pub status: Option<String>,
+    pub assignee: Option<Uuid>,
+    pub customer: Option<Uuid>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/orders",
+    params(
+        ("date" = Option<String>, Query, description = "Filter by scheduled date (YYYY-MM-DD)"),
+        ("status" = Option<String>, Query, description = "Filter by status"),
+        ("assignee" = Option<Uuid>, Query, description = "Filter by assignee ID"),
+        ("customer" = Option<Uuid>, Query, description = "Filter by customer ID"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("page_size" = Option<i64>, Query, description = "Items per page")
+    ),
+    responses(
+        (status = 200, description = "List of orders", body = PaginatedOrders),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_orders(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    Query(query): Query<ListOrdersQuery>,
+) -> Result<Json<crate::domain::orders::PaginatedOrders>, (StatusCode, Json<serde_json::Value>)> {
+    let mut filter = OrderFilter {
+        date: query
+            .date
+            .as_deref()
+            .map(parse_date)
+            .transpose()
+            .map_err(map_error)?,
+        status: query
+            .status
+            .as_deref()
+            .map(parse_status)
+            .transpose()
+            .map_err(map_error)?,
+        from_date: None,
+        assignee: query.assignee,
+        customer: query.customer,
+        page: parse_page(query.page).map_err(map_error)?,
+        page_size: parse_page_size(query.page_size).map_err(map_error)?,
+    };
+
+    if !ctx.has(Permission::OrdersReadAll) {
+        let user_id = ctx.session_user().map_err(map_error)?;
+        if ctx.has(Permission::OrdersSelfService) {
+            filter.customer = Some(user_id);
+        } else {
+            filter.assignee = Some(user_id);
+        }
+    }
+
+    let orders = application::orders::list_orders::execute(&state.repo, filter)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(orders))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChangeStatusRequest {
+    pub status: String,
+}
+
+#[utoipa::path(
+    patch,
+    path = "/orders/{id}/status",
+    params(
+        ("id" = Uuid, Path, description = "Order ID")
+    ),
+    request_body = ChangeStatusRequest,
+    responses(
+        (status = 200, description = "Order status updated", body = Order),
+        (status = 400, description = "Invalid transition"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Order not found")
+    ),
+    tag = "orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn change_order_status(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ChangeStatusRequest>,
+) -> Result<Json<crate::domain::orders::Order>, (StatusCode, Json<serde_json::Value>)> {
+    let target = parse_status(&payload.status).map_err(map_error)?;
+    let order = application::orders::change_status::execute(&state.repo, id, target, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(order))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateAddressRequest {
+    pub label: String,
+    pub address: String,
+    pub zone: String,
+    pub notes: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/me/addresses",
+    request_body = CreateAddressRequest,
+    responses(
+        (status = 201, description = "Saved address created", body = CustomerAddress),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "customers",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_my_address(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Json(payload): Json<CreateAddressRequest>,
+) -> Result<(StatusCode, Json<CustomerAddress>), (StatusCode, Json<serde_json::Value>)> {
+    let input = application::customers::addresses::AddressInput {
+        label: payload.label,
+        address: payload.address,
+        zone: payload.zone,
+        notes: payload.notes,
+    };
+
+    let address = application::customers::addresses::create(
+        &state.repo,
+        ctx.session_user().map_err(map_error)?,
+        input,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(address)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/me/addresses",
+    responses(
+        (status = 200, description = "Saved addresses of the current customer", body = [CustomerAddress]),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "customers",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_my_addresses(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+) -> Result<Json<Vec<CustomerAddress>>, (StatusCode, Json<serde_json::Value>)> {
+    let addresses = application::customers::addresses::list(
+        &state.repo,
+        ctx.session_user().map_err(map_error)?,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(addresses))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/me/addresses/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Address ID")
+    ),
+    responses(
+        (status = 204, description = "Saved address removed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Address not found")
+    ),
+    tag = "customers",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_my_address(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    application::customers::addresses::remove(
+        &state.repo,
+        ctx.session_user().map_err(map_error)?,
+        id,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateMyOrderRequest {
+    pub address_id: Uuid,
+    pub scheduled_date: String,
+    pub time_slot: String,
+    pub quantity: i32,
+    pub notes: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/me/orders",
+    request_body = CreateMyOrderRequest,
+    responses(
+        (status = 201, description = "Order created for a saved address", body = Order),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Address not found"),
+        (status = 409, description = "Slot without capacity")
+    ),
+    tag = "customers",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_my_order(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Json(payload): Json<CreateMyOrderRequest>,
+) -> Result<(StatusCode, Json<Order>), (StatusCode, Json<serde_json::Value>)> {
+    let input = application::customers::create_order::CustomerOrderInput {
+        address_id: payload.address_id,
+        scheduled_date: parse_date(&payload.scheduled_date).map_err(map_error)?,
+        time_slot: payload.time_slot,
+        quantity: payload.quantity,
+        notes: payload.notes,
+    };
+
+    let order = application::customers::create_order::execute(
+        &state.repo,
+        ctx.session_user().map_err(map_error)?,
+        input,
+        state.calendar_policy.today(),
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(order)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListMyOrdersQuery {
+    pub include_past: Option<bool>,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/me/orders",
+    params(
+        ("include_past" = Option<bool>, Query, description = "Include orders scheduled before today"),
+        ("page" = Option<i64>, Query, description = "Page number"),
+        ("page_size" = Option<i64>, Query, description = "Items per page")
+    ),
+    responses(
+        (status = 200, description = "Orders of the current customer", body = PaginatedOrders),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "customers",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_my_orders(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    Query(query): Query<ListMyOrdersQuery>,
+) -> Result<Json<PaginatedOrders>, (StatusCode, Json<serde_json::Value>)> {
+    let filter = OrderFilter {
+        date: None,
+        from_date: (!query.include_past.unwrap_or(false)).then(|| state.calendar_policy.today()),
+        status: None,
+        assignee: None,
+        customer: Some(ctx.session_user().map_err(map_error)?),
+        page: parse_page(query.page).map_err(map_error)?,
+        page_size: parse_page_size(query.page_size).map_err(map_error)?,
+    };
+
+    let orders = application::orders::list_orders::execute(&state.repo, filter)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(orders))
+}
+
+#[utoipa::path(
+    post,
+    path = "/me/orders/{id}/cancel",
+    params(
+        ("id" = Uuid, Path, description = "Order ID")
+    ),
+    responses(
+        (status = 200, description = "Order cancelled", body = Order),
+        (status = 400, description = "Order can no longer be cancelled"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Order not found")
+    ),
+    tag = "customers",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn cancel_my_order(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Order>, (StatusCode, Json<serde_json::Value>)> {
+    let order = application::customers::cancel_order::execute(
+        &state.repo,
+        ctx.session_user().map_err(map_error)?,
+        id,
+        state.calendar_policy.today(),
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(order))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AssignOrdersRequest {
+    pub order_ids: Vec<Uuid>,
+    pub driver_id: Uuid,
+}
+
+#[utoipa::path(
+    post,
+    path = "/dispatch/assign",
+    request_body = AssignOrdersRequest,
+    responses(
+        (status = 204, description = "Orders assigned successfully"),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "dispatch",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn assign_orders(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<AssignOrdersRequest>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    application::dispatch::assign_orders::execute(
+        &state.repo,
+        payload.order_ids,
+        payload.driver_id,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterDeliveryRequest {
+    pub order_id: Uuid,
+    pub llenas_entregadas: i32,
+    pub vacias_recibidas: i32,
+    pub notes: Option<String>,
+    /// Sin posición se usa el último fix reciente del repartidor.
+    pub position: Option<PositionFix>,
+    /// Qué hacer con el saldo si se entregó menos de lo pedido (default
+    /// `PARCIAL`).
+    pub remainder: Option<DeliveryRemainder>,
+    /// Fecha (YYYY-MM-DD) del pedido de seguimiento.
+    pub follow_up_date: Option<String>,
+    pub follow_up_time_slot: Option<String>,
+    /// Registrar más llenas que lo pedido más la tolerancia; exige
+    /// `deliveries:correct`.
+    #[serde(default)]
+    pub override_quantity: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/deliveries",
+    request_body = RegisterDeliveryRequest,
+    responses(
+        (status = 201, description = "Delivery registered successfully", body = Delivery),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Order not found")
+    ),
+    tag = "deliveries",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn register_delivery(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Json(payload): Json<RegisterDeliveryRequest>,
+) -> Result<
+    (StatusCode, Json<crate::domain::delivery::Delivery>),
+    (StatusCode, Json<serde_json::Value>),
+> {
+    let order = state
+        .repo
+        .get_order_by_id(payload.order_id)
+        .await
+        .map_err(map_error)?
+        .ok_or_else(|| map_error(DomainError::NotFound("pedido no encontrado".to_string())))?;
+
+    ensure_delivery_access(&ctx, &order).map_err(map_error)?;
+    if payload.override_quantity && !ctx.has(Permission::DeliveriesCorrect) {
+        return Err(map_error(DomainError::Unauthorized(
+            "no podés registrar más llenas que las pedidas".to_string(),
+        )));
+    }
+
+    let input = application::deliveries::register_delivery::RegisterDelivery {
+        delivery: NewDelivery {
+            order_id: payload.order_id,
+            llenas_entregadas: payload.llenas_entregadas,
+            vacias_recibidas: payload.vacias_recibidas,
+            notes: payload.notes,
+            position: payload.position,
+        },
+        remainder: payload.remainder,
+        follow_up_date: payload
+            .follow_up_date
+            .as_deref()
+            .map(parse_date)
+            .transpose()
+            .map_err(map_error)?,
+        follow_up_time_slot: payload.follow_up_time_slot,
+        override_quantity: payload.override_quantity,
+    };
+
+    let delivery = application::deliveries::register_delivery::execute(
+        &state.repo,
+        input,
+        &state.delivery_policy,
+        state.calendar_policy.today(),
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(delivery)))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterFailedDeliveryRequest {
+    pub order_id: Uuid,
+    pub reason: String,
+    pub reprogram_date: Option<String>,
+    pub reprogram_time_slot: Option<String>,
+    /// Sin posición se usa el último fix reciente del repartidor.
+    pub position: Option<PositionFix>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/deliveries/failed",
+    request_body = RegisterFailedDeliveryRequest,
+    responses(
+        (status = 201, description = "Failed delivery registered successfully", body = FailedDelivery),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Order not found")
+    ),
+    tag = "deliveries",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn register_failed_delivery(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Json(payload): Json<RegisterFailedDeliveryRequest>,
+) -> Result<
+    (StatusCode, Json<crate::domain::delivery::FailedDelivery>),
+    (StatusCode, Json<serde_json::Value>),
+> {
+    let order = state
+        .repo
+        .get_order_by_id(payload.order_id)
+        .await
+        .map_err(map_error)?
+        .ok_or_else(|| map_error(DomainError::NotFound("pedido no encontrado".to_string())))?;
+
+    ensure_delivery_access(&ctx, &order).map_err(map_error)?;
+
+    let input = NewFailedDelivery {
+        order_id: payload.order_id,
+        reason: payload.reason,
+        reprogram_date: payload
+            .reprogram_date
+            .as_deref()
+            .map(parse_date)
+            .transpose()
+            .map_err(map_error)?,
+        reprogram_time_slot: payload.reprogram_time_slot,
+        position: payload.position,
+    };
+
+    let failed = application::deliveries::register_failed_delivery::execute(
+        &state.repo,
+        input,
+        state.calendar_policy.today(),
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(failed)))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CorrectDeliveryRequest {
+    pub llenas_entregadas: i32,
+    pub vacias_recibidas: i32,
+    pub reason: String,
+    /// Aceptar más llenas que lo pedido más la tolerancia.
+    #[serde(default)]
+    pub override_quantity: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/deliveries/{id}/correction",
+    params(
+        ("id" = Uuid, Path, description = "Delivery ID")
+    ),
+    request_body = CorrectDeliveryRequest,
+    responses(
+        (status = 201, description = "Counts amended; stock gets the difference", body = DeliveryCorrection),
+        (status = 400, description = "Invalid input, missing reason, no change or over tolerance"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Delivery not found"),
+        (status = 409, description = "Delivery already voided, follow-up already on route or slot full")
+    ),
+    tag = "deliveries",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn correct_delivery(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CorrectDeliveryRequest>,
+) -> Result<(StatusCode, Json<DeliveryCorrection>), (StatusCode, Json<serde_json::Value>)> {
+    let input = application::deliveries::correct_delivery::CorrectDelivery {
+        delivery_id: id,
+        llenas_entregadas: payload.llenas_entregadas,
+        vacias_recibidas: payload.vacias_recibidas,
+        reason: payload.reason,
+        override_quantity: payload.override_quantity,
+    };
+
+    let correction = application::deliveries::correct_delivery::execute(
+        &state.repo,
+        input,
+        &state.delivery_policy,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(correction)))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VoidDeliveryRequest {
+    pub reason: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/deliveries/{id}/void",
+    params(
+        ("id" = Uuid, Path, description = "Delivery ID")
+    ),
+    request_body = VoidDeliveryRequest,
+    responses(
+        (status = 201, description = "Delivery voided and order back to ASIGNADO", body = DeliveryCorrection),
+        (status = 400, description = "Missing reason"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Delivery not found"),
+        (status = 409, description = "Delivery already voided or follow-up already on route")
+    ),
+    tag = "deliveries",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn void_delivery(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<VoidDeliveryRequest>,
+) -> Result<(StatusCode, Json<DeliveryCorrection>), (StatusCode, Json<serde_json::Value>)> {
+    let correction =
+        application::deliveries::void_delivery::execute(&state.repo, id, &payload.reason, &audit)
+            .await
+            .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(correction)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/deliveries/{id}/corrections",
+    params(
+        ("id" = Uuid, Path, description = "Delivery ID")
+    ),
+    responses(
+        (status = 200, description = "Corrections in chronological order", body = Vec<DeliveryCorrection>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Delivery not found")
+    ),
+    tag = "deliveries",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_delivery_corrections(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<DeliveryCorrection>>, (StatusCode, Json<serde_json::Value>)> {
+    let corrections =
+        application::deliveries::list_corrections::execute(&state.repo, id, &order_viewer(&ctx))
+            .await
+            .map_err(map_error)?;
+
+    Ok(Json(corrections))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttachmentUploadQuery {
+    pub kind: AttachmentKind,
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn upload_attachment(
+    state: &AppState,
+    ctx: &AuthContext,
+    audit: &AuditContext,
+    target: AttachmentTarget,
+    record_id: Uuid,
+    kind: AttachmentKind,
+    headers: &HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<Attachment>), (StatusCode, Json<serde_json::Value>)> {
+    let order = application::attachments::attachment_order(&state.repo, target, record_id)
+        .await
+        .map_err(map_error)?;
+    ensure_delivery_access(ctx, &order).map_err(map_error)?;
+
+    let input = application::attachments::upload_attachment::UploadAttachment {
+        target,
+        record_id,
+        order_id: order.id,
+        kind,
+        content_type: headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned),
+        bytes: body.to_vec(),
+        uploaded_by: ctx.user_id,
+    };
+
+    let attachment = application::attachments::upload_attachment::execute(
+        &state.repo,
+        state.blobs.as_ref(),
+        input,
+        audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(attachment)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/deliveries/{id}/attachments",
+    params(
+        ("id" = Uuid, Path, description = "Delivery ID"),
+        ("kind" = AttachmentKind, Query, description = "FOTO or FIRMA")
+    ),
+    request_body(content = Vec<u8>, description = "JPEG or PNG image (photo up to 5 MB, signature up to 512 KB)", content_type = "image/jpeg"),
+    responses(
+        (status = 201, description = "Attachment stored", body = Attachment),
+        (status = 400, description = "Unsupported format or file too large"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Delivery not found"),
+        (status = 409, description = "Attachment limit reached")
+    ),
+    tag = "deliveries",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn upload_delivery_attachment(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+    Query(query): Query<AttachmentUploadQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<Attachment>), (StatusCode, Json<serde_json::Value>)> {
+    upload_attachment(
+        &state,
+        &ctx,
+        &audit,
+        AttachmentTarget::Entrega,
+        id,
+        query.kind,
+        &headers,
+        body,
+    )
+    .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/deliveries/failed/{id}/attachments",
+    params(
+        ("id" = Uuid, Path, description = "Failed delivery ID"),
+        ("kind" = AttachmentKind, Query, description = "FOTO or FIRMA")
+    ),
+    request_body(content = Vec<u8>, description = "JPEG or PNG image (photo up to 5 MB, signature up to 512 KB)", content_type = "image/jpeg"),
+    responses(
+        (status = 201, description = "Attachment stored", body = Attachment),
+        (status = 400, description = "Unsupported format or file too large"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Failed delivery not found"),
+        (status = 409, description = "Attachment limit reached")
+    ),
+    tag = "deliveries",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn upload_failed_delivery_attachment(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+    Query(query): Query<AttachmentUploadQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<Attachment>), (StatusCode, Json<serde_json::Value>)> {
+    upload_attachment(
+        &state,
+        &ctx,
+        &audit,
+        AttachmentTarget::EntregaFallida,
+        id,
+        query.kind,
+        &headers,
+        body,
+    )
+    .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/deliveries/{id}/attachments",
+    params(
+        ("id" = Uuid, Path, description = "Delivery ID")
+    ),
+    responses(
+        (status = 200, description = "Attachments of the delivery", body = Vec<Attachment>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Delivery not found")
+    ),
+    tag = "deliveries",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_delivery_attachments(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<Attachment>>, (StatusCode, Json<serde_json::Value>)> {
+    let attachments = application::attachments::list_attachments::execute(
+        &state.repo,
+        AttachmentTarget::Entrega,
+        id,
+        &order_viewer(&ctx),
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(attachments))
+}
+
+#[utoipa::path(
+    get,
+    path = "/deliveries/failed/{id}/attachments",
+    params(
+        ("id" = Uuid, Path, description = "Failed delivery ID")
+    ),
+    responses(
+        (status = 200, description = "Attachments of the failed delivery", body = Vec<Attachment>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Failed delivery not found")
+    ),
+    tag = "deliveries",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_failed_delivery_attachments(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<Attachment>>, (StatusCode, Json<serde_json::Value>)> {
+    let attachments = application::attachments::list_attachments::execute(
+        &state.repo,
+        AttachmentTarget::EntregaFallida,
+        id,
+        &order_viewer(&ctx),
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(attachments))
+}
+
+async fn attachment_file(
+    state: &AppState,
+    ctx: &AuthContext,
+    id: Uuid,
+    thumbnail: bool,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    let content = application::attachments::download_attachment::execute(
+        &state.repo,
+        state.blobs.as_ref(),
+        id,
+        thumbnail,
+        &order_viewer(ctx),
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, content.content_type),
+            (CACHE_CONTROL, "private, max-age=300".to_string()),
+            (X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        content.bytes,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/attachments/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Attachment ID")
+    ),
+    responses(
+        (status = 200, description = "Original file", content_type = "image/jpeg", body = Vec<u8>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Attachment not found")
+    ),
+    tag = "deliveries",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn download_attachment(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    attachment_file(&state, &ctx, id, false).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/attachments/{id}/thumbnail",
+    params(
+        ("id" = Uuid, Path, description = "Attachment ID")
+    ),
+    responses(
+        (status = 200, description = "Thumbnail, at most 256 px on the longest side", content_type = "image/png", body = Vec<u8>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Attachment not found or without thumbnail")
+    ),
+    tag = "deliveries",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn download_attachment_thumbnail(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    Path(id): Path<Uuid>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    attachment_file(&state, &ctx, id, true).await
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateInboundRequest {
+    pub date: String,
+    pub cantidad_llenas: i32,
+    pub notes: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/stock/inbounds",
+    request_body = CreateInboundRequest,
+    responses(
+        (status = 201, description = "Inbound stock registered"),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "stock",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_inbound(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<CreateInboundRequest>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    let input = Inbound {
+        date: parse_date(&payload.date).map_err(map_error)?,
+        cantidad_llenas: payload.cantidad_llenas,
+        notes: payload.notes,
+    };
+
+    application::stock::register_inbound::execute(&state.repo, input, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(StatusCode::CREATED)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StockSummaryQuery {
+    pub date: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/stock/summary",
+    params(
+        ("date" = Option<String>, Query, description = "Reference date (YYYY-MM-DD)")
+    ),
+    responses(
+        (status = 200, description = "Stock summary", body = StockSummary),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "stock",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn stock_summary(
+    State(state): State<AppState>,
+    Query(query): Query<StockSummaryQuery>,
+) -> Result<Json<crate::domain::stock::StockSummary>, (StatusCode, Json<serde_json::Value>)> {
+    let date = query
+        .date
+        .as_deref()
+        .map(parse_date)
+        .transpose()
+        .map_err(map_error)?;
+
+    let summary = application::stock::summary::execute(&state.repo, date, &state.calendar_policy)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(summary))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DailyReportQuery {
+    pub date: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/reports/daily",
+    params(
+        ("date" = Option<String>, Query, description = "Report date (YYYY-MM-DD)")
+    ),
+    responses(
+        (status = 200, description = "Daily operational report", body = DailyOperationalReport),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "reports",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn daily_report(
+    State(state): State<AppState>,
+    Query(query): Query<DailyReportQuery>,
+) -> Result<Json<crate::domain::stock::DailyOperationalReport>, (StatusCode, Json<serde_json::Value>)>
+{
+    let date = query
+        .date
+        .as_deref()
+        .map(parse_date)
+        .transpose()
+        .map_err(map_error)?;
+
+    let report =
+        application::stock::daily_report::execute(&state.repo, date, &state.calendar_policy)
+            .await
+            .map_err(map_error)?;
+
+    Ok(Json(report))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateZoneRequest {
+    pub name: String,
+    pub polygon: Option<Vec<GeoPoint>>,
+    pub service_days: Vec<DayOfWeek>,
+    #[serde(default)]
+    pub default_slots: Vec<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/zones",
+    request_body = CreateZoneRequest,
+    responses(
+        (status = 201, description = "Zone created", body = Zone),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Duplicated zone name")
+    ),
+    tag = "zones",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_zone(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<CreateZoneRequest>,
+) -> Result<(StatusCode, Json<Zone>), (StatusCode, Json<serde_json::Value>)> {
+    let input = NewZone {
+        name: payload.name,
+        polygon: payload.polygon,
+        service_days: payload.service_days,
+        default_slots: payload.default_slots,
+    };
+
+    let zone = application::zones::create_zone::execute(&state.repo, input, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(zone)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListZonesQuery {
+    pub include_inactive: Option<bool>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/zones",
+    params(
+        ("include_inactive" = Option<bool>, Query, description = "Include deactivated zones")
+    ),
+    responses(
+        (status = 200, description = "Zone catalog", body = [Zone]),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "zones",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_zones(
+    State(state): State<AppState>,
+    Query(query): Query<ListZonesQuery>,
+) -> Result<Json<Vec<Zone>>, (StatusCode, Json<serde_json::Value>)> {
+    let zones = application::zones::list_zones::execute(
+        &state.repo,
+        query.include_inactive.unwrap_or(false),
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(zones))
+}
+
+#[utoipa::path(
+    get,
+    path = "/zones/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Zone ID")
+    ),
+    responses(
+        (status = 200, description = "Zone detail", body = Zone),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Zone not found")
+    ),
+    tag = "zones",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_zone(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Zone>, (StatusCode, Json<serde_json::Value>)> {
+    let zone = application::zones::get_zone::execute(&state.repo, id)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(zone))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateZoneRequest {
+    pub name: String,
+    pub polygon: Option<Vec<GeoPoint>>,
+    pub service_days: Vec<DayOfWeek>,
+    #[serde(default)]
+    pub default_slots: Vec<String>,
+    pub active: bool,
+}
+
+#[utoipa::path(
+    put,
+    path = "/zones/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Zone ID")
+    ),
+    request_body = UpdateZoneRequest,
+    responses(
+        (status = 200, description = "Zone updated", body = Zone),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Zone not found"),
+        (status = 409, description = "Duplicated zone name")
+    ),
+    tag = "zones",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_zone(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateZoneRequest>,
+) -> Result<Json<Zone>, (StatusCode, Json<serde_json::Value>)> {
+    let input = ZoneUpdate {
+        name: payload.name,
+        polygon: payload.polygon,
+        service_days: payload.service_days,
+        default_slots: payload.default_slots,
+        active: payload.active,
+    };
+
+    let zone = application::zones::update_zone::execute(&state.repo, id, input, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(zone))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/zones/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Zone ID")
+    ),
+    responses(
+        (status = 200, description = "Zone deactivated", body = Zone),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Zone not found")
+    ),
+    tag = "zones",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn deactivate_zone(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Zone>, (StatusCode, Json<serde_json::Value>)> {
+    let zone = application::zones::deactivate_zone::execute(&state.repo, id, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(zone))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateSlotRequest {
+    pub name: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub max_orders: Option<i32>,
+    pub max_cylinders: Option<i32>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/slots",
+    request_body = CreateSlotRequest,
+    responses(
+        (status = 201, description = "Time slot created", body = TimeSlot),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Duplicated slot name")
+    ),
+    tag = "slots",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_slot(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<CreateSlotRequest>,
+) -> Result<(StatusCode, Json<TimeSlot>), (StatusCode, Json<serde_json::Value>)> {
+    let input = NewTimeSlot {
+        name: payload.name,
+        start_time: parse_time(&payload.start_time).map_err(map_error)?,
+        end_time: parse_time(&payload.end_time).map_err(map_error)?,
+        max_orders: payload.max_orders,
+        max_cylinders: payload.max_cylinders,
+    };
+
+    let slot = application::slots::create_slot::execute(&state.repo, input, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(slot)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListSlotsQuery {
+    pub include_inactive: Option<bool>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/slots",
+    params(
+        ("include_inactive" = Option<bool>, Query, description = "Include deactivated slots")
+    ),
+    responses(
+        (status = 200, description = "Time slot catalog", body = [TimeSlot]),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "slots",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_slots(
+    State(state): State<AppState>,
+    Query(query): Query<ListSlotsQuery>,
+) -> Result<Json<Vec<TimeSlot>>, (StatusCode, Json<serde_json::Value>)> {
+    let slots = application::slots::list_slots::execute(
+        &state.repo,
+        query.include_inactive.unwrap_or(false),
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(slots))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateSlotRequest {
+    pub name: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub max_orders: Option<i32>,
+    pub max_cylinders: Option<i32>,
+    pub active: bool,
+}
+
+#[utoipa::path(
+    put,
+    path = "/slots/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Time slot ID")
+    ),
+    request_body = UpdateSlotRequest,
+    responses(
+        (status = 200, description = "Time slot updated", body = TimeSlot),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Time slot not found")
+    ),
+    tag = "slots",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_slot(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateSlotRequest>,
+) -> Result<Json<TimeSlot>, (StatusCode, Json<serde_json::Value>)> {
+    let input = TimeSlotUpdate {
+        name: payload.name,
+        start_time: parse_time(&payload.start_time).map_err(map_error)?,
+        end_time: parse_time(&payload.end_time).map_err(map_error)?,
+        max_orders: payload.max_orders,
+        max_cylinders: payload.max_cylinders,
+        active: payload.active,
+    };
+
+    let slot = application::slots::update_slot::execute(&state.repo, id, input, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(slot))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SlotZoneCapacityRequest {
+    pub max_orders: Option<i32>,
+    pub max_cylinders: Option<i32>,
+}
+
+#[utoipa::path(
+    put,
+    path = "/slots/{id}/zones/{zone_id}/capacity",
+    params(
+        ("id" = Uuid, Path, description = "Time slot ID"),
+        ("zone_id" = Uuid, Path, description = "Zone ID")
+    ),
+    request_body = SlotZoneCapacityRequest,
+    responses(
+        (status = 200, description = "Zone capacity set for the slot", body = SlotZoneCapacity),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Slot or zone not found")
+    ),
+    tag = "slots",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn set_slot_zone_capacity(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path((id, zone_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<SlotZoneCapacityRequest>,
+) -> Result<Json<SlotZoneCapacity>, (StatusCode, Json<serde_json::Value>)> {
+    let capacity = application::slots::set_zone_capacity::execute(
+        &state.repo,
+        SlotZoneCapacity {
+            slot_id: id,
+            zone_id,
+            max_orders: payload.max_orders,
+            max_cylinders: payload.max_cylinders,
+        },
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(capacity))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/slots/{id}/zones/{zone_id}/capacity",
+    params(
+        ("id" = Uuid, Path, description = "Time slot ID"),
+        ("zone_id" = Uuid, Path, description = "Zone ID")
+    ),
+    responses(
+        (status = 204, description = "Zone capacity removed; slot defaults apply"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Capacity not found")
+    ),
+    tag = "slots",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn remove_slot_zone_capacity(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path((id, zone_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    application::slots::set_zone_capacity::remove(&state.repo, id, zone_id, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlotAvailabilityQuery {
+    pub date: String,
+    pub zone: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/slots/availability",
+    params(
+        ("date" = String, Query, description = "Delivery date (YYYY-MM-DD)"),
+        ("zone" = String, Query, description = "Zone name")
+    ),
+    responses(
+        (status = 200, description = "Slots offered for the zone and date with their remaining capacity", body = [SlotAvailability]),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Zone not found")
+    ),
+    tag = "slots",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn slot_availability(
+    State(state): State<AppState>,
+    Query(query): Query<SlotAvailabilityQuery>,
+) -> Result<Json<Vec<SlotAvailability>>, (StatusCode, Json<serde_json::Value>)> {
+    let date = parse_date(&query.date).map_err(map_error)?;
+
+    let availability = application::slots::availability::execute(&state.repo, date, &query.zone)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(availability))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CalendarQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/calendar",
+    params(
+        ("from" = Option<String>, Query, description = "First holiday date to include (YYYY-MM-DD)"),
+        ("to" = Option<String>, Query, description = "Last holiday date to include (YYYY-MM-DD)")
+    ),
+    responses(
+        (status = 200, description = "Weekly closing days and non-working dates", body = WorkingCalendar),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "calendar",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_calendar(
+    State(state): State<AppState>,
+    Query(query): Query<CalendarQuery>,
+) -> Result<Json<WorkingCalendar>, (StatusCode, Json<serde_json::Value>)> {
+    let from = query
+        .from
+        .as_deref()
+        .map(parse_date)
+        .transpose()
+        .map_err(map_error)?;
+    let to = query
+        .to
+        .as_deref()
+        .map(parse_date)
+        .transpose()
+        .map_err(map_error)?;
+
+    let calendar = application::calendar::get_calendar::execute(&state.repo, from, to)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(calendar))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateHolidayRequest {
+    pub date: String,
+    pub description: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/calendar/holidays",
+    request_body = CreateHolidayRequest,
+    responses(
+        (status = 201, description = "Non-working date added", body = Holiday),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Date already registered")
+    ),
+    tag = "calendar",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_holiday(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<CreateHolidayRequest>,
+) -> Result<(StatusCode, Json<Holiday>), (StatusCode, Json<serde_json::Value>)> {
+    let input = NewHoliday {
+        date: parse_date(&payload.date).map_err(map_error)?,
+        description: payload.description,
+    };
+
+    let holiday = application::calendar::manage_holidays::add(&state.repo, input, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(holiday)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/calendar/holidays/{date}",
+    params(
+        ("date" = String, Path, description = "Non-working date (YYYY-MM-DD)")
+    ),
+    responses(
+        (status = 204, description = "Non-working date removed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Date not registered")
+    ),
+    tag = "calendar",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn delete_holiday(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(date): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    let date = parse_date(&date).map_err(map_error)?;
+    application::calendar::manage_holidays::remove(&state.repo, date, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ClosedWeekdaysRequest {
+    pub days: Vec<DayOfWeek>,
+}
+
+#[utoipa::path(
+    put,
+    path = "/calendar/closed-weekdays",
+    request_body = ClosedWeekdaysRequest,
+    responses(
+        (status = 200, description = "Weekly closing days replaced", body = [DayOfWeek]),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "calendar",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn set_closed_weekdays(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<ClosedWeekdaysRequest>,
+) -> Result<Json<Vec<DayOfWeek>>, (StatusCode, Json<serde_json::Value>)> {
+    let days =
+        application::calendar::set_closed_weekdays::execute(&state.repo, payload.days, &audit)
+            .await
+            .map_err(map_error)?;
+
+    Ok(Json(days))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NextAvailableQuery {
+    pub from: Option<String>,
+    pub zone: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NextAvailableResponse {
+    pub date: NaiveDate,
+}
+
+#[utoipa::path(
+    get,
+    path = "/calendar/next-available",
+    params(
+        ("from" = Option<String>, Query, description = "Search start (YYYY-MM-DD), defaults to today"),
+        ("zone" = Option<String>, Query, description = "Only dates served by this zone")
+    ),
+    responses(
+        (status = 200, description = "Next schedulable date", body = NextAvailableResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Zone not found or no date available")
+    ),
+    tag = "calendar",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn next_available_date(
+    State(state): State<AppState>,
+    Query(query): Query<NextAvailableQuery>,
+) -> Result<Json<NextAvailableResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let from = query
+        .from
+        .as_deref()
+        .map(parse_date)
+        .transpose()
+        .map_err(map_error)?;
+
+    let date = application::calendar::next_available::execute(
+        &state.repo,
+        from,
+        query.zone.as_deref(),
+        state.calendar_policy.today(),
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(NextAvailableResponse { date }))
+}
+
+use utoipa::OpenApi;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateRecurringOrderRequest {
+    pub address: String,
+    pub zone: String,
+    pub time_slot: String,
+    pub quantity: i32,
+    /// Cada cuántas semanas se repite (1 = semanal, 2 = quincenal).
+    pub interval_weeks: i32,
+    pub start_date: String,
+    pub notes: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/recurring-orders",
+    request_body = CreateRecurringOrderRequest,
+    responses(
+        (status = 201, description = "Recurring order created", body = RecurringOrder),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "recurring-orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_recurring_order(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<CreateRecurringOrderRequest>,
+) -> Result<(StatusCode, Json<RecurringOrder>), (StatusCode, Json<serde_json::Value>)> {
+    let input = application::recurring::create_recurring_order::RecurringOrderInput {
+        address: payload.address,
+        zone: payload.zone,
+        time_slot: payload.time_slot,
+        quantity: payload.quantity,
+        interval_weeks: payload.interval_weeks,
+        notes: payload.notes,
+        start_date: parse_date(&payload.start_date).map_err(map_error)?,
+    };
+
+    let recurring = application::recurring::create_recurring_order::execute(
+        &state.repo,
+        input,
+        state.calendar_policy.today(),
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(recurring)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListRecurringOrdersQuery {
+    pub status: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/recurring-orders",
+    params(
+        ("status" = Option<String>, Query, description = "ACTIVA, PAUSADA or FINALIZADA")
+    ),
+    responses(
+        (status = 200, description = "Recurring orders", body = [RecurringOrder]),
+        (status = 400, description = "Invalid status"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "recurring-orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_recurring_orders(
+    State(state): State<AppState>,
+    Query(query): Query<ListRecurringOrdersQuery>,
+) -> Result<Json<Vec<RecurringOrder>>, (StatusCode, Json<serde_json::Value>)> {
+    let status = query
+        .status
+        .as_deref()
+        .map(|value| {
+            RecurringStatus::from_str(value)
+                .ok_or_else(|| DomainError::Validation("status inválido".to_string()))
+        })
+        .transpose()
+        .map_err(map_error)?;
+
+    let items = application::recurring::list_recurring_orders::execute(&state.repo, status)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(items))
+}
+
+#[utoipa::path(
+    get,
+    path = "/recurring-orders/{id}/occurrences",
+    params(
+        ("id" = Uuid, Path, description = "Recurring order ID")
+    ),
+    responses(
+        (status = 200, description = "Generated, skipped and failed dates", body = [RecurringOccurrence]),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Recurring order not found")
+    ),
+    tag = "recurring-orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_recurring_occurrences(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<RecurringOccurrence>>, (StatusCode, Json<serde_json::Value>)> {
+    let items = application::recurring::list_recurring_orders::occurrences(&state.repo, id)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(items))
+}
+
+async fn change_recurring_status(
+    state: &AppState,
+    audit: &AuditContext,
+    id: Uuid,
+    target_status: RecurringStatus,
+) -> Result<Json<RecurringOrder>, (StatusCode, Json<serde_json::Value>)> {
+    let recurring = application::recurring::change_status::execute(
+        &state.repo,
+        id,
+        target_status,
+        state.calendar_policy.today(),
+        audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(recurring))
+}
+
+#[utoipa::path(
+    post,
+    path = "/recurring-orders/{id}/pause",
+    params(
+        ("id" = Uuid, Path, description = "Recurring order ID")
+    ),
+    responses(
+        (status = 200, description = "Recurring order paused", body = RecurringOrder),
+        (status = 400, description = "Invalid transition"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Recurring order not found")
+    ),
+    tag = "recurring-orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn pause_recurring_order(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RecurringOrder>, (StatusCode, Json<serde_json::Value>)> {
+    change_recurring_status(&state, &audit, id, RecurringStatus::Pausada).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/recurring-orders/{id}/resume",
+    params(
+        ("id" = Uuid, Path, description = "Recurring order ID")
+    ),
+    responses(
+        (status = 200, description = "Recurring order resumed", body = RecurringOrder),
+        (status = 400, description = "Invalid transition"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Recurring order not found")
+    ),
+    tag = "recurring-orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn resume_recurring_order(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RecurringOrder>, (StatusCode, Json<serde_json::Value>)> {
+    change_recurring_status(&state, &audit, id, RecurringStatus::Activa).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/recurring-orders/{id}/end",
+    params(
+        ("id" = Uuid, Path, description = "Recurring order ID")
+    ),
+    responses(
+        (status = 200, description = "Recurring order ended", body = RecurringOrder),
+        (status = 400, description = "Invalid transition"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Recurring order not found")
+    ),
+    tag = "recurring-orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn end_recurring_order(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RecurringOrder>, (StatusCode, Json<serde_json::Value>)> {
+    change_recurring_status(&state, &audit, id, RecurringStatus::Finalizada).await
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct SkipOccurrenceRequest {
+    /// Fecha a omitir; si no se envía se omite la próxima.
+    pub date: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/recurring-orders/{id}/skip",
+    params(
+        ("id" = Uuid, Path, description = "Recurring order ID")
+    ),
+    request_body = SkipOccurrenceRequest,
+    responses(
+        (status = 201, description = "Occurrence skipped", body = RecurringOccurrence),
+        (status = 400, description = "Date not in the cadence"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Recurring order not found"),
+        (status = 409, description = "Date already processed")
+    ),
+    tag = "recurring-orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn skip_recurring_occurrence(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SkipOccurrenceRequest>,
+) -> Result<(StatusCode, Json<RecurringOccurrence>), (StatusCode, Json<serde_json::Value>)> {
+    let date = payload
+        .date
+        .as_deref()
+        .map(parse_date)
+        .transpose()
+        .map_err(map_error)?;
+
+    let occurrence =
+        application::recurring::skip_occurrence::execute(&state.repo, id, date, &audit)
+            .await
+            .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(occurrence)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MaterializeQuery {
+    pub days_ahead: Option<i64>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/recurring-orders/materialize",
+    params(
+        ("days_ahead" = Option<i64>, Query, description = "Horizon in days (default 7)")
+    ),
+    responses(
+        (status = 200, description = "Pending occurrences processed", body = MaterializeReport),
+        (status = 400, description = "Invalid horizon"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "recurring-orders",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn materialize_recurring_orders(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Query(query): Query<MaterializeQuery>,
+) -> Result<Json<MaterializeReport>, (StatusCode, Json<serde_json::Value>)> {
+    let days_ahead = query.days_ahead.unwrap_or(DEFAULT_RECURRING_DAYS_AHEAD);
+    if !(0..=NEXT_AVAILABLE_HORIZON_DAYS).contains(&days_ahead) {
+        return Err(map_error(DomainError::Validation(format!(
+            "days_ahead debe estar entre 0 y {}",
+            NEXT_AVAILABLE_HORIZON_DAYS
+        ))));
+    }
+
+    let today = state.calendar_policy.today();
+    let until = today + chrono::Duration::days(days_ahead);
+    let report = application::recurring::materialize::execute(&state.repo, until, today, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/jobs",
+    responses(
+        (status = 200, description = "Background jobs with schedule and last run", body = [JobSummary]),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "jobs",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_jobs(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<JobSummary>>, (StatusCode, Json<serde_json::Value>)> {
+    let jobs = application::jobs::list_jobs::execute(&state.repo, &state.jobs)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(jobs))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JobRunsQuery {
+    pub limit: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/jobs/{name}/runs",
+    params(
+        ("name" = String, Path, description = "Job name"),
+        ("limit" = Option<i64>, Query, description = "Most recent runs to return (default 20)")
+    ),
+    responses(
+        (status = 200, description = "Job run history", body = [JobRun]),
+        (status = 400, description = "Invalid limit"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Unknown job")
+    ),
+    tag = "jobs",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_job_runs(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<JobRunsQuery>,
+) -> Result<Json<Vec<JobRun>>, (StatusCode, Json<serde_json::Value>)> {
+    let runs = application::jobs::list_jobs::runs(
+        &state.repo,
+        &state.jobs,
+        &name,
+        query.limit.unwrap_or(DEFAULT_JOB_RUNS_LIMIT),
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(runs))
+}
+
+#[utoipa::path(
+    post,
+    path = "/jobs/{name}/run",
+    params(
+        ("name" = String, Path, description = "Job name")
+    ),
+    responses(
+        (status = 200, description = "Job executed; check status for the outcome", body = JobRun),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Unknown job"),
+        (status = 409, description = "Job already running")
+    ),
+    tag = "jobs",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn run_job(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(name): Path<String>,
+) -> Result<Json<JobRun>, (StatusCode, Json<serde_json::Value>)> {
+    let run = application::jobs::run_job::trigger(&state.repo, &state.jobs, &name, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(run))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateUserRequest {
+    pub username: String,
+    pub password: String,
+    pub role: Role,
+}
+
+#[utoipa::path(
+    post,
+    path = "/users",
+    request_body = CreateUserRequest,
+    responses(
+        (status = 201, description = "User created", body = UserAccount),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "Username already taken")
+    ),
+    tag = "users",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_user(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Json(payload): Json<CreateUserRequest>,
+) -> Result<(StatusCode, Json<UserAccount>), (StatusCode, Json<serde_json::Value>)> {
+    let user = application::users::create_user::execute(
+        &state.repo,
+        payload.username,
+        payload.password,
+        payload.role,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(user.into())))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListUsersQuery {
+    pub role: Option<String>,
+    pub include_inactive: Option<bool>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/users",
+    params(
+        ("role" = Option<String>, Query, description = "Filter by role"),
+        ("include_inactive" = Option<bool>, Query, description = "Include deactivated users")
+    ),
+    responses(
+        (status = 200, description = "Users", body = [UserAccount]),
+        (status = 400, description = "Invalid role"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "users",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_users(
+    State(state): State<AppState>,
+    Query(query): Query<ListUsersQuery>,
+) -> Result<Json<Vec<UserAccount>>, (StatusCode, Json<serde_json::Value>)> {
+    let role = query
+        .role
+        .as_deref()
+        .map(|value| {
+            Role::from_str(value).ok_or_else(|| DomainError::Validation("rol inválido".to_string()))
+        })
+        .transpose()
+        .map_err(map_error)?;
+
+    let users = application::users::list_users::execute(
+        &state.repo,
+        UserFilter {
+            role,
+            include_inactive: query.include_inactive.unwrap_or(false),
+        },
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(users.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/legacy-passwords",
+    responses(
+        (status = 200, description = "Users still on plaintext or low-cost hashes", body = [LegacyPasswordUser]),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "users",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_legacy_passwords(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<LegacyPasswordUser>>, (StatusCode, Json<serde_json::Value>)> {
+    let users = application::users::legacy_passwords::execute(&state.repo)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(users))
+}
+
+#[utoipa::path(
+    get,
+    path = "/users/{id}",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User detail", body = UserAccount),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "users",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn get_user(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<UserAccount>, (StatusCode, Json<serde_json::Value>)> {
+    let user = application::users::get_user(&state.repo, id)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(user.into()))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateUserRequest {
+    pub username: String,
+}
+
+#[utoipa::path(
+    put,
+    path = "/users/{id}",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    request_body = UpdateUserRequest,
+    responses(
+        (status = 200, description = "User updated", body = UserAccount),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found"),
+        (status = 409, description = "Username already taken")
+    ),
+    tag = "users",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_user(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateUserRequest>,
+) -> Result<Json<UserAccount>, (StatusCode, Json<serde_json::Value>)> {
+    let user = application::users::update_user::execute(&state.repo, id, payload.username, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(user.into()))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChangeRoleRequest {
+    pub role: Role,
+}
+
+#[utoipa::path(
+    patch,
+    path = "/users/{id}/role",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    request_body = ChangeRoleRequest,
+    responses(
+        (status = 200, description = "Role changed", body = UserAccount),
+        (status = 400, description = "Cannot change own role"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found"),
+        (status = 409, description = "Last active admin")
+    ),
+    tag = "users",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn change_user_role(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ChangeRoleRequest>,
+) -> Result<Json<UserAccount>, (StatusCode, Json<serde_json::Value>)> {
+    let user = application::users::change_role::execute(
+        &state.repo,
+        ctx.session_user().map_err(map_error)?,
+        id,
+        payload.role,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(user.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/{id}/deactivate",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User deactivated", body = UserAccount),
+        (status = 400, description = "Cannot deactivate yourself"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found"),
+        (status = 409, description = "Last active admin")
+    ),
+    tag = "users",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn deactivate_user(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<UserAccount>, (StatusCode, Json<serde_json::Value>)> {
+    let user = application::users::set_active::deactivate(
+        &state.repo,
+        ctx.session_user().map_err(map_error)?,
+        id,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(user.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/{id}/reactivate",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "User reactivated", body = UserAccount),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "users",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn reactivate_user(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<UserAccount>, (StatusCode, Json<serde_json::Value>)> {
+    let user = application::users::set_active::reactivate(&state.repo, id, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(user.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/{id}/unlock",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Lockout lifted", body = UserAccount),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "users",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn unlock_user(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<UserAccount>, (StatusCode, Json<serde_json::Value>)> {
+    let user = application::users::unlock::execute(&state.repo, id, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(user.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/{id}/2fa/reset",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "2FA removed; the user enrolls again if required", body = UserAccount),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "users",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn reset_user_two_factor(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<UserAccount>, (StatusCode, Json<serde_json::Value>)> {
+    let user = application::auth::two_factor::reset(&state.repo, id, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(user.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/{id}/password-reset",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 201, description = "One-time reset code (shown only once)", body = PasswordResetIssued),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found"),
+        (status = 409, description = "User is deactivated")
+    ),
+    tag = "users",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn reset_user_password(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<(StatusCode, Json<PasswordResetIssued>), (StatusCode, Json<serde_json::Value>)> {
+    let issued = application::users::reset_password::execute(
+        &state.repo,
+        ctx.session_user().map_err(map_error)?,
+        id,
+        state.login_policy.reset_code_minutes,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(issued)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/users/{id}/revoke-sessions",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 204, description = "All tokens of the user revoked"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found")
+    ),
+    tag = "users",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn revoke_user_sessions(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    application::users::revoke_sessions::execute(&state.repo, id, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
-pub struct AssignOrdersRequest {
-    pub order_ids: Vec<Uuid>,
-    pub driver_id: Uuid,
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub permissions: Vec<Permission>,
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 #[utoipa::path(
     post,
-    path = "/dispatch/assign",
-    request_body = AssignOrdersRequest,
+    path = "/api-keys",
+    request_body = CreateApiKeyRequest,
     responses(
-        (status = 204, description = "Orders assigned successfully"),
+        (status = 201, description = "API key issued; the key is only shown here", body = ApiKeyIssued),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "api-keys",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Json(payload): Json<CreateApiKeyRequest>,
+) -> Result<(StatusCode, Json<ApiKeyIssued>), (StatusCode, Json<serde_json::Value>)> {
+    let issued = application::api_keys::create_api_key::execute(
+        &state.repo,
+        ctx.session_user().map_err(map_error)?,
+        payload.name,
+        payload.permissions,
+        payload.expires_at,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(issued)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api-keys",
+    responses(
+        (status = 200, description = "Issued API keys, without the secret", body = [ApiKey]),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "api-keys",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ApiKey>>, (StatusCode, Json<serde_json::Value>)> {
+    let keys = application::api_keys::list_api_keys::execute(&state.repo)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(keys))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api-keys/{id}/revoke",
+    params(
+        ("id" = Uuid, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 200, description = "API key revoked", body = ApiKey),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "API key not found")
+    ),
+    tag = "api-keys",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ApiKey>, (StatusCode, Json<serde_json::Value>)> {
+    let api_key = application::api_keys::revoke_api_key::execute(&state.repo, id, &audit)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(api_key))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub event_types: Vec<DomainEventType>,
+    /// Mínimo 16 caracteres; si falta se genera uno.
+    pub secret: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 201, description = "Subscription created; the secret is only shown here", body = WebhookSubscriptionCreated),
         (status = 400, description = "Invalid input"),
         (status = 401, description = "Unauthorized")
     ),
-    tag = "dispatch",
+    tag = "webhooks",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthContext>,
+    audit: AuditContext,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<WebhookSubscriptionCreated>), (StatusCode, Json<serde_json::Value>)> {
+    let created = application::webhooks::create_subscription::execute(
+        &state.repo,
+        &state.webhook_policy,
+        ctx.session_user().map_err(map_error)?,
+        payload.url,
+        payload.event_types,
+        payload.secret,
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    responses(
+        (status = 200, description = "Webhook subscriptions, without their secrets", body = [WebhookSubscription]),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "webhooks",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<WebhookSubscription>>, (StatusCode, Json<serde_json::Value>)> {
+    let subscriptions = application::webhooks::list_subscriptions::execute(&state.repo)
+        .await
+        .map_err(map_error)?;
+
+    Ok(Json(subscriptions))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub event_types: Option<Vec<DomainEventType>>,
+    /// Reemplaza el secreto de firma.
+    pub secret: Option<String>,
+    pub active: Option<bool>,
+}
+
+#[utoipa::path(
+    patch,
+    path = "/webhooks/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Subscription ID")
+    ),
+    request_body = UpdateWebhookRequest,
+    responses(
+        (status = 200, description = "Subscription updated", body = WebhookSubscription),
+        (status = 400, description = "Invalid input"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Subscription not found")
+    ),
+    tag = "webhooks",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn update_webhook(
+    State(state): State<AppState>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateWebhookRequest>,
+) -> Result<Json<WebhookSubscription>, (StatusCode, Json<serde_json::Value>)> {
+    let subscription = application::webhooks::update_subscription::execute(
+        &state.repo,
+        &state.webhook_policy,
+        id,
+        WebhookSubscriptionUpdate {
+            url: payload.url,
+            event_types: payload.event_types,
+            secret: payload.secret,
+            active: payload.active,
+        },
+        &audit,
+    )
+    .await
+    .map_err(map_error)?;
+
+    Ok(Json(subscription))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Subscription ID")
+    ),
+    responses(
+        (status = 204, description = "Subscription and its delivery log deleted"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Subscription not found")
+    ),
+    tag = "webhooks",
     security(
         ("bearer_auth" = [])
     )
 )]
-pub async fn assign_orders(
+pub async fn delete_webhook(
     State(state): State<AppState>,
-    Extension(ctx): Extension<AuthContext>,
-    Json(payload): Json<AssignOrdersRequest>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
 ) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
-    require_admin(&ctx).map_err(map_error)?;
-
-    let order_ids = payload.order_ids;
-    let driver_id = payload.driver_id;
-
-    application::dispatch::assign_orders::execute(&state.repo, order_ids.clone(), driver_id)
+    application::webhooks::delete_subscription::execute(&state.repo, id, &audit)
         .await
         .map_err(map_error)?;
 
-    for order_id in order_ids {
-        state
-            .repo
-            .record_audit_event(NewAuditEvent {
-                actor_id: Some(ctx.user_id),
-                entity: "order".to_string(),
-                entity_id: Some(order_id),
-                action: "assigned".to_string(),
-                details: json!({ "driver_id": driver_id }),
-            })
-            .await
-            .map_err(map_error)?;
-    }
-
     Ok(StatusCode::NO_CONTENT)
 }
 
-#[derive(Debug, Deserialize, ToSchema)]
-pub struct RegisterDeliveryRequest {
-    pub order_id: Uuid,
-    pub llenas_entregadas: i32,
-    pub vacias_recibidas: i32,
-    pub notes: Option<String>,
+#[derive(Debug, Deserialize)]
+pub struct WebhookDeliveriesQuery {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
 }
 
 #[utoipa::path(
-    post,
-    path = "/deliveries",
-    request_body = RegisterDeliveryRequest,
+    get,
+    path = "/webhooks/{id}/deliveries",
+    params(
+        ("id" = Uuid, Path, description = "Subscription ID"),
+        ("status" = Option<String>, Query, description = "PENDIENTE, ENTREGADA or DESCARTADA"),
+        ("limit" = Option<i64>, Query, description = "Most recent deliveries to return (default 50, max 200)")
+    ),
     responses(
-        (status = 201, description = "Delivery registered successfully", body = Delivery),
-        (status = 400, description = "Invalid input"),
+        (status = 200, description = "Delivery log, most recent first", body = [WebhookDelivery]),
+        (status = 400, description = "Invalid status or limit"),
         (status = 401, description = "Unauthorized"),
-        (status = 404, description = "Order not found")
+        (status = 404, description = "Subscription not found")
     ),
-    tag = "deliveries",
+    tag = "webhooks",
     security(
         ("bearer_auth" = [])
     )
 )]
-pub async fn register_delivery(
+pub async fn list_webhook_deliveries(
     State(state): State<AppState>,
-    Extension(ctx): Extension<AuthContext>,
-    Json(payload): Json<RegisterDeliveryRequest>,
-) -> Result<
-    (StatusCode, Json<crate::domain::delivery::Delivery>),
-    (StatusCode, Json<serde_json::Value>),
-> {
-    let order = state
-        .repo
-        .get_order_by_id(payload.order_id)
-        .await
-        .map_err(map_error)?
-        .ok_or_else(|| map_error(DomainError::NotFound("pedido no encontrado".to_string())))?;
-
-    ensure_delivery_access(&ctx, &order).map_err(map_error)?;
-
-    let input = NewDelivery {
-        order_id: payload.order_id,
-        llenas_entregadas: payload.llenas_entregadas,
-        vacias_recibidas: payload.vacias_recibidas,
-        notes: payload.notes,
-    };
-
-    let delivery = application::deliveries::register_delivery::execute(&state.repo, input)
-        .await
-        .map_err(map_error)?;
-
-    state
-        .repo
-        .record_audit_event(NewAuditEvent {
-            actor_id: Some(ctx.user_id),
-            entity: "delivery".to_string(),
-            entity_id: Some(delivery.id),
-            action: "created".to_string(),
-            details: json!({
-                "order_id": delivery.order_id,
-                "llenas_entregadas": delivery.llenas_entregadas,
-                "vacias_recibidas": delivery.vacias_recibidas
-            }),
+    Path(id): Path<Uuid>,
+    Query(query): Query<WebhookDeliveriesQuery>,
+) -> Result<Json<Vec<WebhookDelivery>>, (StatusCode, Json<serde_json::Value>)> {
+    let status = query
+        .status
+        .as_deref()
+        .map(|value| {
+            WebhookDeliveryStatus::from_str(value)
+                .ok_or_else(|| DomainError::Validation("status inválido".to_string()))
         })
-        .await
+        .transpose()
         .map_err(map_error)?;
 
-    Ok((StatusCode::CREATED, Json(delivery)))
-}
+    let deliveries = application::webhooks::list_subscriptions::deliveries(
+        &state.repo,
+        id,
+        WebhookDeliveryFilter {
+            status,
+            limit: query.limit.unwrap_or(DEFAULT_WEBHOOK_DELIVERIES_LIMIT),
+        },
+    )
+    .await
+    .map_err(map_error)?;
 
-#[derive(Debug, Deserialize, ToSchema)]
-pub struct RegisterFailedDeliveryRequest {
-    pub order_id: Uuid,
-    pub reason: String,
-    pub reprogram_date: Option<String>,
-    pub reprogram_time_slot: Option<String>,
+    Ok(Json(deliveries))
 }
 
 #[utoipa::path(
     post,
-    path = "/deliveries/failed",
-    request_body = RegisterFailedDeliveryRequest,
+    path = "/webhooks/deliveries/{id}/redeliver",
+    params(
+        ("id" = Uuid, Path, description = "Delivery ID")
+    ),
     responses(
-        (status = 201, description = "Failed delivery registered successfully", body = FailedDelivery),
-        (status = 400, description = "Invalid input"),
+        (status = 200, description = "Delivery queued again with its retries reset", body = WebhookDelivery),
         (status = 401, description = "Unauthorized"),
-        (status = 404, description = "Order not found")
+        (status = 404, description = "Delivery not found"),
+        (status = 409, description = "Delivery is still pending")
     ),
-    tag = "deliveries",
+    tag = "webhooks",
     security(
         ("bearer_auth" = [])
     )
 )]
-pub async fn register_failed_delivery(
+pub async fn redeliver_webhook(
     State(state): State<AppState>,
-    Extension(ctx): Extension<AuthContext>,
-    Json(payload): Json<RegisterFailedDeliveryRequest>,
-) -> Result<
-    (StatusCode, Json<crate::domain::delivery::FailedDelivery>),
-    (StatusCode, Json<serde_json::Value>),
-> {
-    let order = state
-        .repo
-        .get_order_by_id(payload.order_id)
-        .await
-        .map_err(map_error)?
-        .ok_or_else(|| map_error(DomainError::NotFound("pedido no encontrado".to_string())))?;
-
-    ensure_delivery_access(&ctx, &order).map_err(map_error)?;
-
-    let input = NewFailedDelivery {
-        order_id: payload.order_id,
-        reason: payload.reason,
-        reprogram_date: payload
-            .reprogram_date
-            .as_deref()
-            .map(parse_date)
-            .transpose()
-            .map_err(map_error)?,
-        reprogram_time_slot: payload.reprogram_time_slot,
-    };
-
-    let failed = application::deliveries::register_failed_delivery::execute(&state.repo, input)
-        .await
-        .map_err(map_error)?;
-
-    state
-        .repo
-        .record_audit_event(NewAuditEvent {
-            actor_id: Some(ctx.user_id),
-            entity: "delivery_failure".to_string(),
-            entity_id: Some(failed.id),
-            action: "created".to_string(),
-            details: json!({
-                "order_id": failed.order_id,
-                "reason": failed.reason,
-                "reprogram_date": failed.reprogram_date,
-                "reprogram_time_slot": failed.reprogram_time_slot
-            }),
-        })
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WebhookDelivery>, (StatusCode, Json<serde_json::Value>)> {
+    let delivery = application::webhooks::redeliver::execute(&state.repo, id, &audit)
         .await
         .map_err(map_error)?;
 
-    Ok((StatusCode::CREATED, Json(failed)))
+    Ok(Json(delivery))
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
-pub struct CreateInboundRequest {
-    pub date: String,
-    pub cantidad_llenas: i32,
-    pub notes: Option<String>,
+pub struct RecordPositionsRequest {
+    pub positions: Vec<PositionFix>,
 }
 
 #[utoipa::path(
     post,
-    path = "/stock/inbounds",
-    request_body = CreateInboundRequest,
+    path = "/drivers/me/positions",
+    request_body = RecordPositionsRequest,
     responses(
-        (status = 201, description = "Inbound stock registered"),
-        (status = 400, description = "Invalid input"),
+        (status = 200, description = "Batch stored; stale and repeated fixes are only counted", body = PositionBatchReport),
+        (status = 400, description = "Invalid fix or batch too large"),
         (status = 401, description = "Unauthorized")
     ),
-    tag = "stock",
+    tag = "drivers",
     security(
         ("bearer_auth" = [])
     )
 )]
-pub async fn create_inbound(
+pub async fn record_positions(
     State(state): State<AppState>,
     Extension(ctx): Extension<AuthContext>,
-    Json(payload): Json<CreateInboundRequest>,
-) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
-    require_admin(&ctx).map_err(map_error)?;
-
-    let input = Inbound {
-        date: parse_date(&payload.date).map_err(map_error)?,
-        cantidad_llenas: payload.cantidad_llenas,
-        notes: payload.notes,
-    };
+    Json(payload): Json<RecordPositionsRequest>,
+) -> Result<Json<PositionBatchReport>, (StatusCode, Json<serde_json::Value>)> {
+    let report = application::positions::record_positions::execute(
+        &state.repo,
+        ctx.session_user().map_err(map_error)?,
+        payload.positions,
+        Utc::now(),
+    )
+    .await
+    .map_err(map_error)?;
 
-    application::stock::register_inbound::execute(&state.repo, input)
-        .await
-        .map_err(map_error)?;
+    Ok(Json(report))
+}
 
-    state
-        .repo
-        .record_audit_event(NewAuditEvent {
-            actor_id: Some(ctx.user_id),
-            entity: "stock_inbound".to_string(),
-            entity_id: None,
-            action: "created".to_string(),
-            details: json!({
-                "date": payload.date,
-                "cantidad_llenas": payload.cantidad_llenas
-            }),
-        })
+#[utoipa::path(
+    get,
+    path = "/drivers/positions/latest",
+    responses(
+        (status = 200, description = "Last known position of each active driver", body = Vec<LatestDriverPosition>),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "drivers",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn latest_driver_positions(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<LatestDriverPosition>>, (StatusCode, Json<serde_json::Value>)> {
+    let positions = application::positions::latest_positions::execute(&state.repo)
         .await
         .map_err(map_error)?;
 
-    Ok(StatusCode::CREATED)
+    Ok(Json(positions))
 }
 
 #[derive(Debug, Deserialize)]
-pub struct StockSummaryQuery {
+pub struct BreadcrumbQuery {
     pub date: Option<String>,
 }
 
 #[utoipa::path(
     get,
-    path = "/stock/summary",
+    path = "/drivers/{id}/positions",
     params(
-        ("date" = Option<String>, Query, description = "Reference date (YYYY-MM-DD)")
+        ("id" = Uuid, Path, description = "Driver ID"),
+        ("date" = Option<String>, Query, description = "Day in the business timezone (YYYY-MM-DD), default today; spans local midnight to midnight")
     ),
     responses(
-        (status = 200, description = "Stock summary", body = StockSummary),
+        (status = 200, description = "Positions of the day in chronological order", body = Vec<DriverPosition>),
+        (status = 400, description = "Invalid date"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Driver not found")
+    ),
+    tag = "drivers",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn driver_breadcrumb(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<BreadcrumbQuery>,
+) -> Result<Json<Vec<DriverPosition>>, (StatusCode, Json<serde_json::Value>)> {
+    let date = match query.date.as_deref() {
+        Some(date) => parse_date(date).map_err(map_error)?,
+        None => state.calendar_policy.today(),
+    };
+
+    let positions =
+        application::positions::breadcrumb::execute(&state.repo, id, date, &state.calendar_policy)
+            .await
+            .map_err(map_error)?;
+
+    Ok(Json(positions))
+}
+
+#[utoipa::path(
+    get,
+    path = "/events/stream",
+    responses(
+        (status = 200, description = "Server-sent domain events visible to the caller; each event is named after its type and carries the webhook envelope. Credentials are re-checked periodically and the stream closes once they are no longer valid", content_type = "text/event-stream", body = String),
         (status = 401, description = "Unauthorized")
     ),
-    tag = "stock",
+    tag = "events",
     security(
         ("bearer_auth" = [])
     )
 )]
-pub async fn stock_summary(
+pub async fn event_stream(
     State(state): State<AppState>,
     Extension(ctx): Extension<AuthContext>,
-    Query(query): Query<StockSummaryQuery>,
-) -> Result<Json<crate::domain::stock::StockSummary>, (StatusCode, Json<serde_json::Value>)> {
-    require_admin(&ctx).map_err(map_error)?;
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    enum StreamItem {
+        Event(Result<Arc<OutboxEvent>, BroadcastStreamRecvError>),
+        Recheck,
+    }
 
-    let date = query
-        .date
-        .as_deref()
-        .map(parse_date)
-        .transpose()
-        .map_err(map_error)?;
+    let period = std::time::Duration::from_secs(EVENT_STREAM_AUTH_RECHECK_SECONDS);
+    let rechecks = IntervalStream::new(tokio::time::interval_at(
+        tokio::time::Instant::now() + period,
+        period,
+    ))
+    .map(|_| StreamItem::Recheck);
+    let items = BroadcastStream::new(state.live_events.subscribe())
+        .map(StreamItem::Event)
+        .merge(rechecks);
 
-    let summary = application::stock::summary::execute(&state.repo, date)
-        .await
-        .map_err(map_error)?;
+    // Las credenciales se vuelven a validar cada tanto: una sesión revocada,
+    // un usuario desactivado o bloqueado o un token vencido cierran el
+    // stream, y un cambio de rol cambia lo que se ve.
+    let stream = futures_util::stream::unfold(
+        (items, event_viewer(&ctx)),
+        move |(mut items, mut viewer)| {
+            let state = state.clone();
+            let headers = headers.clone();
+            async move {
+                loop {
+                    let event = match items.next().await? {
+                        StreamItem::Recheck => {
+                            viewer = event_viewer(&resolve_auth(&state, &headers).await.ok()?);
+                            continue;
+                        }
+                        StreamItem::Event(Ok(event)) if event.visible_to(&viewer) => {
+                            Event::default()
+                                .id(event.id.to_string())
+                                .event(event.event_type.as_str())
+                                .data(event.envelope().to_string())
+                        }
+                        StreamItem::Event(Ok(_)) => continue,
+                        // El cliente no leyó a tiempo: se le avisa cuántos
+                        // perdió para que vuelva a consultar el estado por la API.
+                        StreamItem::Event(Err(BroadcastStreamRecvError::Lagged(missed))) => {
+                            Event::default()
+                                .event("lagged")
+                                .data(json!({ "missed": missed }).to_string())
+                        }
+                    };
+                    return Some((Ok(event), (items, viewer)));
+                }
+            }
+        },
+    );
 
-    Ok(Json(summary))
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+fn event_viewer(ctx: &AuthContext) -> EventViewer {
+    EventViewer {
+        orders: order_viewer(ctx),
+        read_stock: ctx.has(Permission::StockRead),
+    }
 }
 
 #[derive(Debug, Deserialize)]
-pub struct DailyReportQuery {
-    pub date: Option<String>,
+pub struct ListAuditQuery {
+    pub entity: Option<String>,
+    pub entity_id: Option<Uuid>,
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+fn parse_timestamp(field: &str, value: &str) -> Result<DateTime<Utc>, DomainError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|value| value.with_timezone(&Utc))
+        .map_err(|_| DomainError::Validation(format!("{} inválido (usar RFC 3339)", field)))
 }
 
 #[utoipa::path(
     get,
-    path = "/reports/daily",
+    path = "/audit",
     params(
-        ("date" = Option<String>, Query, description = "Report date (YYYY-MM-DD)")
+        ("entity" = Option<String>, Query, description = "Entity type (order, user, auth, ...)"),
+        ("entity_id" = Option<Uuid>, Query, description = "Entity ID"),
+        ("actor" = Option<String>, Query, description = "Actor username"),
+        ("action" = Option<String>, Query, description = "Action name"),
+        ("from" = Option<String>, Query, description = "Events at or after this instant (RFC 3339)"),
+        ("to" = Option<String>, Query, description = "Events before this instant (RFC 3339)"),
+        ("cursor" = Option<String>, Query, description = "next_cursor from the previous page"),
+        ("limit" = Option<i64>, Query, description = "Page size (default 50, max 200)")
     ),
     responses(
-        (status = 200, description = "Daily operational report", body = DailyOperationalReport),
+        (status = 200, description = "Audit events, newest first", body = AuditPage),
+        (status = 400, description = "Invalid filter or cursor"),
         (status = 401, description = "Unauthorized")
     ),
-    tag = "reports",
+    tag = "audit",
     security(
         ("bearer_auth" = [])
     )
 )]
-pub async fn daily_report(
+pub async fn list_audit_events(
     State(state): State<AppState>,
-    Extension(ctx): Extension<AuthContext>,
-    Query(query): Query<DailyReportQuery>,
-) -> Result<Json<crate::domain::stock::DailyOperationalReport>, (StatusCode, Json<serde_json::Value>)>
-{
-    require_admin(&ctx).map_err(map_error)?;
+    Query(query): Query<ListAuditQuery>,
+) -> Result<Json<AuditPage>, (StatusCode, Json<serde_json::Value>)> {
+    let limit = query.limit.unwrap_or(DEFAULT_AUDIT_PAGE_SIZE);
+    if !(1..=MAX_AUDIT_PAGE_SIZE).contains(&limit) {
+        return Err(map_error(DomainError::Validation(format!(
+            "limit debe estar entre 1 y {}",
+            MAX_AUDIT_PAGE_SIZE
+        ))));
+    }
 
-    let date = query
-        .date
-        .as_deref()
-        .map(parse_date)
-        .transpose()
+    let filter = AuditFilter {
+        entity: query.entity,
+        entity_id: query.entity_id,
+        actor: query.actor,
+        action: query.action,
+        from: query
+            .from
+            .as_deref()
+            .map(|value| parse_timestamp("from", value))
+            .transpose()
+            .map_err(map_error)?,
+        to: query
+            .to
+            .as_deref()
+            .map(|value| parse_timestamp("to", value))
+            .transpose()
+            .map_err(map_error)?,
+        cursor: query
+            .cursor
+            .as_deref()
+            .map(AuditCursor::decode)
+            .transpose()
+            .map_err(map_error)?,
+        limit,
+    };
+
+    let page = application::audit::list_events::execute(&state.repo, filter)
+        .await
         .map_err(map_error)?;
 
-    let report = application::stock::daily_report::execute(&state.repo, date)
+    Ok(Json(page))
+}
+
+#[utoipa::path(
+    get,
+    path = "/audit/verify",
+    responses(
+        (status = 200, description = "Hash chain check; reports the first broken link", body = AuditChainReport),
+        (status = 401, description = "Unauthorized")
+    ),
+    tag = "audit",
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub async fn verify_audit_chain(
+    State(state): State<AppState>,
+) -> Result<Json<AuditChainReport>, (StatusCode, Json<serde_json::Value>)> {
+    let report = application::audit::verify_chain::execute(&state.repo)
         .await
         .map_err(map_error)?;
 
     Ok(Json(report))
 }
 
-use utoipa::OpenApi;
-
 #[derive(OpenApi)]
 #[openapi(
     paths(
+        jwks,
         login,
+        refresh,
+        logout,
+        complete_password_reset,
+        enroll_two_factor_challenge,
+        verify_two_factor,
         me,
+        change_password,
+        enroll_two_factor,
+        confirm_two_factor,
+        disable_two_factor,
+        regenerate_recovery_codes,
         create_order,
         list_orders,
         change_order_status,
+        create_my_address,
+        list_my_addresses,
+        delete_my_address,
+        create_my_order,
+        list_my_orders,
+        cancel_my_order,
         assign_orders,
         register_delivery,
         register_failed_delivery,
+        correct_delivery,
+        void_delivery,
+        list_delivery_corrections,
+        upload_delivery_attachment,
+        upload_failed_delivery_attachment,
+        list_delivery_attachments,
+        list_failed_delivery_attachments,
+        download_attachment,
+        download_attachment_thumbnail,
         create_inbound,
         stock_summary,
-        daily_report
+        daily_report,
+        create_zone,
+        list_zones,
+        get_zone,
+        update_zone,
+        deactivate_zone,
+        create_slot,
+        list_slots,
+        update_slot,
+        set_slot_zone_capacity,
+        remove_slot_zone_capacity,
+        slot_availability,
+        get_calendar,
+        create_holiday,
+        delete_holiday,
+        set_closed_weekdays,
+        next_available_date,
+        create_recurring_order,
+        list_recurring_orders,
+        list_recurring_occurrences,
+        pause_recurring_order,
+        resume_recurring_order,
+        end_recurring_order,
+        skip_recurring_occurrence,
+        materialize_recurring_orders,
+        list_jobs,
+        list_job_runs,
+        run_job,
+        create_user,
+        list_users,
+        list_legacy_passwords,
+        get_user,
+        update_user,
+        change_user_role,
+        deactivate_user,
+        reactivate_user,
+        unlock_user,
+        reset_user_two_factor,
+        reset_user_password,
+        revoke_user_sessions,
+        create_api_key,
+        list_api_keys,
+        revoke_api_key,
+        create_webhook,
+        list_webhooks,
+        update_webhook,
+        delete_webhook,
+        list_webhook_deliveries,
+        redeliver_webhook,
+        record_positions,
+        latest_driver_positions,
+        driver_breadcrumb,
+        event_stream,
+        list_audit_events,
+        verify_audit_chain
     ),
     components(
         schemas(
-            LoginRequest, LoginResponse, MeResponse, Role,
+            LoginRequest, LoginResponse, RefreshRequest, LogoutRequest, MeResponse,
+            Permission, ChangePasswordRequest, CompletePasswordResetRequest, Role,
+            LoginResult, TwoFactorChallengeResponse, ChallengePurpose,
+            TwoFactorEnrollChallengeRequest, TwoFactorVerifyRequest, TwoFactorLoginResponse,
+            TwoFactorEnrollment, ConfirmTwoFactorRequest, TwoFactorReauthRequest, RecoveryCodes,
             CreateOrderRequest, Order, OrderStatus, PaginatedOrders,
             ChangeStatusRequest, AssignOrdersRequest,
+            CreateAddressRequest, CustomerAddress, CreateMyOrderRequest,
             RegisterDeliveryRequest, Delivery,
             RegisterFailedDeliveryRequest, FailedDelivery,
-            CreateInboundRequest, StockSummary, DailyOperationalReport
+            CorrectDeliveryRequest, VoidDeliveryRequest, DeliveryCorrection,
+            DeliveryCorrectionKind, DeliveryRemainder,
+            Attachment, AttachmentKind, AttachmentTarget,
+            CreateInboundRequest, StockSummary, DailyOperationalReport,
+            CreateZoneRequest, UpdateZoneRequest, Zone, GeoPoint, DayOfWeek,
+            CreateSlotRequest, UpdateSlotRequest, SlotZoneCapacityRequest,
+            TimeSlot, SlotZoneCapacity, SlotAvailability,
+            CreateHolidayRequest, ClosedWeekdaysRequest, NextAvailableResponse,
+            Holiday, WorkingCalendar,
+            CreateRecurringOrderRequest, SkipOccurrenceRequest, RecurringOrder,
+            RecurringStatus, RecurringOccurrence, OccurrenceStatus, MaterializeReport,
+            JobSummary, JobRun, JobRunStatus, JobTrigger,
+            CreateUserRequest, UpdateUserRequest, ChangeRoleRequest, UserAccount,
+            LegacyPasswordUser, PasswordScheme, PasswordResetIssued,
+            CreateApiKeyRequest, ApiKey, ApiKeyIssued,
+            CreateWebhookRequest, UpdateWebhookRequest, WebhookSubscription,
+            WebhookSubscriptionCreated, WebhookDelivery, WebhookDeliveryStatus, DomainEventType,
+            RecordPositionsRequest, PositionFix, PositionBatchReport, LatestDriverPosition,
+            DriverPosition,
+            AuditEvent, AuditPage, AuditChainReport, BrokenAuditLink
         )
     ),
     modifiers(&SecurityAddon)
@@ -824,12 +4216,15 @@ mod tests {
             id: Uuid::new_v4(),
             address: "Calle 1".to_string(),
             zone: "Z1".to_string(),
+            zone_id: None,
             scheduled_date: chrono::NaiveDate::from_ymd_opt(2026, 2, 16).unwrap(),
             time_slot: "MANANA".to_string(),
             quantity: 1,
             notes: None,
             status: OrderStatus::Asignado,
             assignee_id,
+            customer_id: None,
+            address_id: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -837,10 +4232,7 @@ mod tests {
 
     #[test]
     fn repartidor_cannot_access_unassigned_order() {
-        let ctx = AuthContext {
-            user_id: Uuid::new_v4(),
-            role: Role::Repartidor,
-        };
+        let ctx = AuthContext::for_user(Uuid::new_v4(), &Role::Repartidor);
         let order = sample_order(None);
 
         let result = ensure_delivery_access(&ctx, &order);
@@ -850,10 +4242,7 @@ mod tests {
     #[test]
     fn repartidor_can_access_own_order() {
         let user_id = Uuid::new_v4();
-        let ctx = AuthContext {
-            user_id,
-            role: Role::Repartidor,
-        };
+        let ctx = AuthContext::for_user(user_id, &Role::Repartidor);
         let order = sample_order(Some(user_id));
 
         let result = ensure_delivery_access(&ctx, &order);
@@ -862,16 +4251,34 @@ mod tests {
 
     #[test]
     fn admin_can_access_any_order() {
-        let ctx = AuthContext {
-            user_id: Uuid::new_v4(),
-            role: Role::Admin,
-        };
+        let ctx = AuthContext::for_user(Uuid::new_v4(), &Role::Admin);
         let order = sample_order(None);
 
         let result = ensure_delivery_access(&ctx, &order);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn api_key_acts_with_its_own_permissions() {
+        let ctx = AuthContext {
+            user_id: None,
+            permissions: vec![Permission::OrdersCreate],
+            api_key_id: Some(Uuid::new_v4()),
+        };
+
+        assert!(ctx.has(Permission::OrdersCreate));
+        assert!(!ctx.has(Permission::OrdersReadAll));
+        assert_eq!(ctx.actor_id(), None);
+        assert!(ctx.session_user().is_err());
+        assert!(ensure_delivery_access(&ctx, &sample_order(None)).is_err());
+
+        let admin = Uuid::new_v4();
+        assert_eq!(
+            AuthContext::for_user(admin, &Role::Admin).actor_id(),
+            Some(admin)
+        );
+    }
+
     #[test]
     fn validate_page_and_page_size_bounds() {
         assert!(parse_page(Some(0)).is_err());
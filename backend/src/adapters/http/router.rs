@@ -1,7 +1,10 @@
 use crate::adapters::http::handlers::{self, ApiDoc};
+use crate::domain::attachments::MAX_PHOTO_BYTES;
+use crate::domain::auth::Permission;
 use crate::AppState;
+use axum::extract::DefaultBodyLimit;
 use axum::middleware;
-use axum::routing::{get, patch, post};
+use axum::routing::{delete, get, patch, post, put};
 use axum::Router;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
@@ -11,29 +14,282 @@ pub fn build_router(state: AppState) -> Router {
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
         .route("/health", get(handlers::health))
         .route("/metrics", get(handlers::metrics))
-        .route("/auth/login", post(handlers::login));
+        .route("/.well-known/jwks.json", get(handlers::jwks))
+        .route("/auth/login", post(handlers::login))
+        .route("/auth/refresh", post(handlers::refresh))
+        .route("/auth/logout", post(handlers::logout))
+        .route(
+            "/auth/password-reset",
+            post(handlers::complete_password_reset),
+        )
+        .route(
+            "/auth/2fa/enroll",
+            post(handlers::enroll_two_factor_challenge),
+        )
+        .route("/auth/2fa/verify", post(handlers::verify_two_factor));
 
-    let protected_routes = Router::new()
-        .route("/me", get(handlers::me))
+    // Rutas abiertas a cualquier usuario autenticado; las que filtran por
+    // usuario (pedidos, entregas) lo resuelven en el handler.
+    let authenticated_routes = Router::new()
+        .merge(
+            Router::new()
+                .route("/me", get(handlers::me))
+                .route("/me/password", post(handlers::change_password))
+                .route("/me/2fa/enroll", post(handlers::enroll_two_factor))
+                .route("/me/2fa/confirm", post(handlers::confirm_two_factor))
+                .route("/me/2fa/disable", post(handlers::disable_two_factor))
+                .route(
+                    "/me/2fa/recovery-codes",
+                    post(handlers::regenerate_recovery_codes),
+                )
+                .route_layer(middleware::from_fn(handlers::require_user_session)),
+        )
+        .route("/orders", get(handlers::list_orders))
+        .route("/events/stream", get(handlers::event_stream))
         .route(
-            "/orders",
-            post(handlers::create_order).get(handlers::list_orders),
+            "/deliveries/:id/attachments",
+            get(handlers::list_delivery_attachments),
         )
-        .route("/orders/:id/status", patch(handlers::change_order_status))
-        .route("/dispatch/assign", post(handlers::assign_orders))
-        .route("/deliveries", post(handlers::register_delivery))
         .route(
-            "/deliveries/failed",
-            post(handlers::register_failed_delivery),
+            "/deliveries/failed/:id/attachments",
+            get(handlers::list_failed_delivery_attachments),
         )
-        .route("/stock/inbounds", post(handlers::create_inbound))
-        .route("/stock/summary", get(handlers::stock_summary))
-        .route("/reports/daily", get(handlers::daily_report))
-        .layer(middleware::from_fn_with_state(
-            state.clone(),
-            handlers::auth_middleware,
+        .route(
+            "/deliveries/:id/corrections",
+            get(handlers::list_delivery_corrections),
+        )
+        .route("/attachments/:id", get(handlers::download_attachment))
+        .route(
+            "/attachments/:id/thumbnail",
+            get(handlers::download_attachment_thumbnail),
+        )
+        .route("/zones", get(handlers::list_zones))
+        .route("/zones/:id", get(handlers::get_zone))
+        .route("/slots", get(handlers::list_slots))
+        .route("/slots/availability", get(handlers::slot_availability))
+        .route("/calendar", get(handlers::get_calendar))
+        .route(
+            "/calendar/next-available",
+            get(handlers::next_available_date),
+        );
+
+    let guarded_routes = Router::new()
+        .merge(guarded(
+            Permission::OrdersCreate,
+            Router::new().route("/orders", post(handlers::create_order)),
+        ))
+        .merge(guarded(
+            Permission::OrdersSelfService,
+            Router::new()
+                .route(
+                    "/me/addresses",
+                    post(handlers::create_my_address).get(handlers::list_my_addresses),
+                )
+                .route("/me/addresses/:id", delete(handlers::delete_my_address))
+                .route(
+                    "/me/orders",
+                    post(handlers::create_my_order).get(handlers::list_my_orders),
+                )
+                .route("/me/orders/:id/cancel", post(handlers::cancel_my_order)),
+        ))
+        .merge(guarded(
+            Permission::OrdersUpdateStatus,
+            Router::new().route("/orders/:id/status", patch(handlers::change_order_status)),
+        ))
+        .merge(guarded(
+            Permission::DispatchAssign,
+            Router::new().route("/dispatch/assign", post(handlers::assign_orders)),
+        ))
+        .merge(guarded(
+            Permission::DeliveriesRegister,
+            Router::new()
+                .route("/deliveries", post(handlers::register_delivery))
+                .route(
+                    "/deliveries/failed",
+                    post(handlers::register_failed_delivery),
+                ),
+        ))
+        .merge(guarded(
+            Permission::DeliveriesRegister,
+            // El body es la imagen cruda; el límite por defecto de axum es 2 MB.
+            Router::new()
+                .route(
+                    "/deliveries/:id/attachments",
+                    post(handlers::upload_delivery_attachment),
+                )
+                .route(
+                    "/deliveries/failed/:id/attachments",
+                    post(handlers::upload_failed_delivery_attachment),
+                )
+                .layer(DefaultBodyLimit::max(MAX_PHOTO_BYTES)),
+        ))
+        .merge(guarded(
+            Permission::DeliveriesCorrect,
+            Router::new()
+                .route(
+                    "/deliveries/:id/correction",
+                    post(handlers::correct_delivery),
+                )
+                .route("/deliveries/:id/void", post(handlers::void_delivery)),
+        ))
+        .merge(guarded(
+            Permission::StockWrite,
+            Router::new().route("/stock/inbounds", post(handlers::create_inbound)),
+        ))
+        .merge(guarded(
+            Permission::StockRead,
+            Router::new().route("/stock/summary", get(handlers::stock_summary)),
+        ))
+        .merge(guarded(
+            Permission::ReportsRead,
+            Router::new().route("/reports/daily", get(handlers::daily_report)),
+        ))
+        .merge(guarded(
+            Permission::CatalogWrite,
+            Router::new()
+                .route("/zones", post(handlers::create_zone))
+                .route(
+                    "/zones/:id",
+                    put(handlers::update_zone).delete(handlers::deactivate_zone),
+                )
+                .route("/slots", post(handlers::create_slot))
+                .route("/slots/:id", put(handlers::update_slot))
+                .route(
+                    "/slots/:id/zones/:zone_id/capacity",
+                    put(handlers::set_slot_zone_capacity)
+                        .delete(handlers::remove_slot_zone_capacity),
+                )
+                .route("/calendar/holidays", post(handlers::create_holiday))
+                .route("/calendar/holidays/:date", delete(handlers::delete_holiday))
+                .route(
+                    "/calendar/closed-weekdays",
+                    put(handlers::set_closed_weekdays),
+                ),
+        ))
+        .merge(guarded(
+            Permission::RecurringManage,
+            Router::new()
+                .route(
+                    "/recurring-orders",
+                    post(handlers::create_recurring_order).get(handlers::list_recurring_orders),
+                )
+                .route(
+                    "/recurring-orders/materialize",
+                    post(handlers::materialize_recurring_orders),
+                )
+                .route(
+                    "/recurring-orders/:id/occurrences",
+                    get(handlers::list_recurring_occurrences),
+                )
+                .route(
+                    "/recurring-orders/:id/pause",
+                    post(handlers::pause_recurring_order),
+                )
+                .route(
+                    "/recurring-orders/:id/resume",
+                    post(handlers::resume_recurring_order),
+                )
+                .route(
+                    "/recurring-orders/:id/end",
+                    post(handlers::end_recurring_order),
+                )
+                .route(
+                    "/recurring-orders/:id/skip",
+                    post(handlers::skip_recurring_occurrence),
+                ),
+        ))
+        .merge(guarded(
+            Permission::JobsManage,
+            Router::new()
+                .route("/jobs", get(handlers::list_jobs))
+                .route("/jobs/:name/runs", get(handlers::list_job_runs))
+                .route("/jobs/:name/run", post(handlers::run_job)),
+        ))
+        .merge(guarded(
+            Permission::UsersManage,
+            Router::new()
+                .route(
+                    "/users",
+                    post(handlers::create_user).get(handlers::list_users),
+                )
+                .route(
+                    "/users/legacy-passwords",
+                    get(handlers::list_legacy_passwords),
+                )
+                .route(
+                    "/users/:id",
+                    get(handlers::get_user).put(handlers::update_user),
+                )
+                .route("/users/:id/role", patch(handlers::change_user_role))
+                .route("/users/:id/deactivate", post(handlers::deactivate_user))
+                .route("/users/:id/reactivate", post(handlers::reactivate_user))
+                .route("/users/:id/unlock", post(handlers::unlock_user))
+                .route(
+                    "/users/:id/2fa/reset",
+                    post(handlers::reset_user_two_factor),
+                )
+                .route(
+                    "/users/:id/password-reset",
+                    post(handlers::reset_user_password),
+                )
+                .route(
+                    "/users/:id/revoke-sessions",
+                    post(handlers::revoke_user_sessions),
+                )
+                .route(
+                    "/api-keys",
+                    post(handlers::create_api_key).get(handlers::list_api_keys),
+                )
+                .route("/api-keys/:id/revoke", post(handlers::revoke_api_key)),
+        ))
+        .merge(guarded(
+            Permission::WebhooksManage,
+            Router::new()
+                .route(
+                    "/webhooks",
+                    post(handlers::create_webhook).get(handlers::list_webhooks),
+                )
+                .route(
+                    "/webhooks/:id",
+                    patch(handlers::update_webhook).delete(handlers::delete_webhook),
+                )
+                .route(
+                    "/webhooks/:id/deliveries",
+                    get(handlers::list_webhook_deliveries),
+                )
+                .route(
+                    "/webhooks/deliveries/:id/redeliver",
+                    post(handlers::redeliver_webhook),
+                ),
+        ))
+        .merge(guarded(
+            Permission::PositionsReport,
+            Router::new().route("/drivers/me/positions", post(handlers::record_positions)),
+        ))
+        .merge(guarded(
+            Permission::PositionsRead,
+            Router::new()
+                .route(
+                    "/drivers/positions/latest",
+                    get(handlers::latest_driver_positions),
+                )
+                .route("/drivers/:id/positions", get(handlers::driver_breadcrumb)),
+        ))
+        .merge(guarded(
+            Permission::AuditRead,
+            Router::new()
+                .route("/audit", get(handlers::list_audit_events))
+                .route("/audit/verify", get(handlers::verify_audit_chain)),
         ));
 
+    let protected_routes =
+        authenticated_routes
+            .merge(guarded_routes)
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                handlers::auth_middleware,
+            ));
+
     public_routes
         .merge(protected_routes)
         .layer(middleware::from_fn_with_state(
@@ -43,3 +299,12 @@ pub fn build_router(state: AppState) -> Router {
         .layer(middleware::from_fn(handlers::request_id_middleware))
         .with_state(state)
 }
+
+/// Las rutas de `routes` exigen `permission`; corre después de
+/// `auth_middleware`, que es la capa exterior.
+fn guarded(permission: Permission, routes: Router<AppState>) -> Router<AppState> {
+    routes.route_layer(middleware::from_fn_with_state(
+        permission,
+        handlers::require_permission,
+    ))
+}
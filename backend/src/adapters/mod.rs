@@ -1,4 +1,6 @@
 pub mod auth;
 pub mod db;
+pub mod events;
 pub mod http;
 pub mod observability;
+pub mod storage;
@@ -1,3 +1,7 @@
+use crate::domain::error::DomainError;
+use crate::domain::events::{DomainEventType, OutboxEvent};
+use crate::ports::event_sink_port::EventSubscriber;
+use async_trait::async_trait;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 #[derive(Debug, Default)]
@@ -6,6 +10,8 @@ pub struct MetricsRegistry {
     requests_2xx: AtomicU64,
     requests_4xx: AtomicU64,
     requests_5xx: AtomicU64,
+    /// Indexado como `DomainEventType::ALL`.
+    domain_events: [AtomicU64; DomainEventType::ALL.len()],
 }
 
 impl MetricsRegistry {
@@ -23,15 +29,47 @@ impl MetricsRegistry {
         }
     }
 
+    pub fn record_domain_event(&self, event_type: DomainEventType) {
+        if let Some(index) = DomainEventType::ALL
+            .iter()
+            .position(|kind| *kind == event_type)
+        {
+            self.domain_events[index].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
     pub fn render_prometheus(&self) -> String {
         let requests_total = self.requests_total.load(Ordering::Relaxed);
         let requests_2xx = self.requests_2xx.load(Ordering::Relaxed);
         let requests_4xx = self.requests_4xx.load(Ordering::Relaxed);
         let requests_5xx = self.requests_5xx.load(Ordering::Relaxed);
 
-        format!(
+        let mut body = format!(
             "# HELP gasflow_http_requests_total Total HTTP requests.\n# TYPE gasflow_http_requests_total counter\ngasflow_http_requests_total {}\n# HELP gasflow_http_responses_total HTTP responses by status family.\n# TYPE gasflow_http_responses_total counter\ngasflow_http_responses_total{{family=\"2xx\"}} {}\ngasflow_http_responses_total{{family=\"4xx\"}} {}\ngasflow_http_responses_total{{family=\"5xx\"}} {}\n",
             requests_total, requests_2xx, requests_4xx, requests_5xx
-        )
+        );
+
+        body.push_str("# HELP gasflow_domain_events_total Domain events dispatched from the outbox.\n# TYPE gasflow_domain_events_total counter\n");
+        for (kind, counter) in DomainEventType::ALL.iter().zip(&self.domain_events) {
+            body.push_str(&format!(
+                "gasflow_domain_events_total{{type=\"{}\"}} {}\n",
+                kind.as_str(),
+                counter.load(Ordering::Relaxed)
+            ));
+        }
+        body
+    }
+}
+
+/// Suscriptor en proceso: cuenta los eventos despachados por tipo.
+#[async_trait]
+impl EventSubscriber for MetricsRegistry {
+    fn name(&self) -> &str {
+        "metrics"
+    }
+
+    async fn handle(&self, event: &OutboxEvent) -> Result<(), DomainError> {
+        self.record_domain_event(event.event_type);
+        Ok(())
     }
 }
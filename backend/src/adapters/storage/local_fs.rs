@@ -0,0 +1,102 @@
+use crate::domain::error::DomainError;
+use crate::ports::blob_store_port::BlobStore;
+use async_trait::async_trait;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Blobs como archivos bajo un directorio raíz. Sirve para una sola réplica o
+/// con el directorio montado en volumen compartido.
+#[derive(Debug, Clone)]
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Sólo segmentos simples: una clave nunca sale de la raíz.
+    fn path_for(&self, key: &str) -> Result<PathBuf, DomainError> {
+        let valid = !key.is_empty()
+            && key.split('/').all(|segment| {
+                !segment.is_empty()
+                    && segment != "."
+                    && segment != ".."
+                    && segment
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            });
+        if !valid {
+            return Err(DomainError::Infrastructure(format!(
+                "clave de blob inválida: {}",
+                key
+            )));
+        }
+        Ok(self.root.join(Path::new(key)))
+    }
+}
+
+fn io_error(err: std::io::Error) -> DomainError {
+    DomainError::Infrastructure(format!("error de almacenamiento: {}", err))
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<(), DomainError> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(io_error)?;
+        }
+        // Se escribe aparte y se renombra: un lector nunca ve un archivo a medias.
+        let staging = path.with_extension(format!("{}.tmp", Uuid::new_v4().simple()));
+        tokio::fs::write(&staging, bytes).await.map_err(io_error)?;
+        if let Err(err) = tokio::fs::rename(&staging, &path).await {
+            let _ = tokio::fs::remove_file(&staging).await;
+            return Err(io_error(err));
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, DomainError> {
+        match tokio::fs::read(self.path_for(key)?).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(io_error(err)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), DomainError> {
+        match tokio::fs::remove_file(self.path_for(key)?).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(io_error(err)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stores_reads_and_deletes_under_root() {
+        let root = std::env::temp_dir().join(format!("gasflow-blobs-{}", Uuid::new_v4()));
+        let store = LocalBlobStore::new(&root);
+
+        store.put("entregas/a/foto.jpg", b"datos").await.unwrap();
+        assert_eq!(
+            store.get("entregas/a/foto.jpg").await.unwrap().as_deref(),
+            Some(&b"datos"[..])
+        );
+        store.delete("entregas/a/foto.jpg").await.unwrap();
+        assert_eq!(store.get("entregas/a/foto.jpg").await.unwrap(), None);
+        store.delete("entregas/a/foto.jpg").await.unwrap();
+
+        assert!(store.put("../fuera.jpg", b"x").await.is_err());
+        assert!(store.get("/etc/passwd").await.is_err());
+
+        let _ = tokio::fs::remove_dir_all(&root).await;
+    }
+}
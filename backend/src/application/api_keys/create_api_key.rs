@@ -0,0 +1,71 @@
+use crate::application::auth::service::hash_token;
+use crate::domain::api_keys::{validate_api_key_fields, ApiKeyIssued, NewApiKey, API_KEY_PREFIX};
+use crate::domain::audit::AuditContext;
+use crate::domain::auth::Permission;
+use crate::domain::error::DomainError;
+use crate::ports::api_keys_port::ApiKeysPort;
+use crate::ports::audit_port::AuditPort;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use uuid::Uuid;
+
+const API_KEY_BYTES: usize = 32;
+/// Caracteres de la key, además de `API_KEY_PREFIX`, que se guardan visibles.
+const VISIBLE_PREFIX_CHARS: usize = 8;
+
+fn generate_api_key() -> String {
+    let mut bytes = [0u8; API_KEY_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("{}{}", API_KEY_PREFIX, hex::encode(bytes))
+}
+
+pub async fn execute<P: ApiKeysPort + AuditPort>(
+    port: &P,
+    created_by: Uuid,
+    name: String,
+    permissions: Vec<Permission>,
+    expires_at: Option<DateTime<Utc>>,
+    audit: &AuditContext,
+) -> Result<ApiKeyIssued, DomainError> {
+    validate_api_key_fields(&name, &permissions, expires_at, Utc::now())?;
+    let mut unique = Vec::with_capacity(permissions.len());
+    for permission in permissions {
+        if !unique.contains(&permission) {
+            unique.push(permission);
+        }
+    }
+
+    let key = generate_api_key();
+    let api_key = port
+        .create_api_key(NewApiKey {
+            name: name.trim().to_string(),
+            prefix: key[..API_KEY_PREFIX.len() + VISIBLE_PREFIX_CHARS].to_string(),
+            key_hash: hash_token(&key),
+            permissions: unique,
+            created_by,
+            expires_at,
+        })
+        .await?;
+    // La key en claro nunca llega al evento: el snapshot sólo lleva el prefijo.
+    port.record_audit_event(
+        audit
+            .event("api_key", Some(api_key.id), "created")
+            .after(&api_key),
+    )
+    .await?;
+
+    Ok(ApiKeyIssued { key, api_key })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_keys_are_prefixed_and_random() {
+        let first = generate_api_key();
+        assert!(first.starts_with(API_KEY_PREFIX));
+        assert_eq!(first.len(), API_KEY_PREFIX.len() + API_KEY_BYTES * 2);
+        assert_ne!(first, generate_api_key());
+    }
+}
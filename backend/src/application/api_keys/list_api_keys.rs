@@ -0,0 +1,7 @@
+use crate::domain::api_keys::ApiKey;
+use crate::domain::error::DomainError;
+use crate::ports::api_keys_port::ApiKeysPort;
+
+pub async fn execute<P: ApiKeysPort>(port: &P) -> Result<Vec<ApiKey>, DomainError> {
+    port.list_api_keys().await
+}
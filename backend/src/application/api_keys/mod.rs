@@ -0,0 +1,36 @@
+pub mod create_api_key;
+pub mod list_api_keys;
+pub mod revoke_api_key;
+
+use crate::application::auth::service::hash_token;
+use crate::domain::api_keys::ApiKey;
+use crate::domain::error::DomainError;
+use crate::ports::api_keys_port::ApiKeysPort;
+use crate::ports::auth_port::AuthPort;
+use chrono::Utc;
+
+/// Key vigente detrás del header; revocada, vencida, desconocida o emitida por
+/// un usuario que ya no está activo responden igual.
+pub async fn authenticate<P: ApiKeysPort + AuthPort>(
+    port: &P,
+    key: &str,
+) -> Result<ApiKey, DomainError> {
+    let now = Utc::now();
+    let invalid = || DomainError::Unauthorized("API key inválida".to_string());
+    let api_key = port
+        .find_api_key_by_hash(&hash_token(key.trim()))
+        .await?
+        .filter(|api_key| api_key.is_usable(now))
+        .ok_or_else(invalid)?;
+
+    let creator_active = port
+        .find_user_by_id(api_key.created_by)
+        .await?
+        .is_some_and(|user| user.active);
+    if !creator_active {
+        return Err(invalid());
+    }
+
+    port.touch_api_key(api_key.id, now).await?;
+    Ok(api_key)
+}
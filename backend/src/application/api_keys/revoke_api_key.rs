@@ -0,0 +1,31 @@
+use crate::domain::api_keys::ApiKey;
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::ports::api_keys_port::ApiKeysPort;
+use crate::ports::audit_port::AuditPort;
+use uuid::Uuid;
+
+pub async fn execute<P: ApiKeysPort + AuditPort>(
+    port: &P,
+    api_key_id: Uuid,
+    audit: &AuditContext,
+) -> Result<ApiKey, DomainError> {
+    let before = port
+        .get_api_key_by_id(api_key_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("API key no encontrada".to_string()))?;
+    let api_key = port
+        .revoke_api_key(api_key_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("API key no encontrada".to_string()))?;
+
+    port.record_audit_event(
+        audit
+            .event("api_key", Some(api_key.id), "revoked")
+            .before(&before)
+            .after(&api_key),
+    )
+    .await?;
+
+    Ok(api_key)
+}
@@ -0,0 +1,46 @@
+use crate::domain::attachments::AttachmentContent;
+use crate::domain::error::DomainError;
+use crate::domain::orders::OrderViewer;
+use crate::ports::attachments_port::AttachmentsPort;
+use crate::ports::blob_store_port::BlobStore;
+use crate::ports::orders_port::OrdersPort;
+use uuid::Uuid;
+
+pub async fn execute<P: AttachmentsPort + OrdersPort>(
+    port: &P,
+    blobs: &dyn BlobStore,
+    attachment_id: Uuid,
+    thumbnail: bool,
+    viewer: &OrderViewer,
+) -> Result<AttachmentContent, DomainError> {
+    let stored = port
+        .find_attachment(attachment_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("adjunto no encontrado".to_string()))?;
+
+    let order = port
+        .get_order_by_id(stored.attachment.order_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
+    if !viewer.can_see(&order) {
+        return Err(DomainError::Unauthorized(
+            "no podés ver adjuntos de este pedido".to_string(),
+        ));
+    }
+
+    let key = if thumbnail {
+        stored
+            .thumbnail_key
+            .ok_or_else(|| DomainError::NotFound("el adjunto no tiene miniatura".to_string()))?
+    } else {
+        stored.blob_key
+    };
+    let bytes = blobs.get(&key).await?.ok_or_else(|| {
+        DomainError::Infrastructure(format!("falta el archivo del adjunto {}", attachment_id))
+    })?;
+
+    Ok(AttachmentContent {
+        content_type: stored.attachment.content_type,
+        bytes,
+    })
+}
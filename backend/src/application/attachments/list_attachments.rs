@@ -0,0 +1,24 @@
+use crate::application::attachments::attachment_order;
+use crate::domain::attachments::{Attachment, AttachmentTarget};
+use crate::domain::error::DomainError;
+use crate::domain::orders::OrderViewer;
+use crate::ports::attachments_port::AttachmentsPort;
+use crate::ports::deliveries_port::DeliveriesPort;
+use crate::ports::orders_port::OrdersPort;
+use uuid::Uuid;
+
+pub async fn execute<P: AttachmentsPort + DeliveriesPort + OrdersPort>(
+    port: &P,
+    target: AttachmentTarget,
+    record_id: Uuid,
+    viewer: &OrderViewer,
+) -> Result<Vec<Attachment>, DomainError> {
+    let order = attachment_order(port, target, record_id).await?;
+    if !viewer.can_see(&order) {
+        return Err(DomainError::Unauthorized(
+            "no podés ver adjuntos de este pedido".to_string(),
+        ));
+    }
+
+    port.list_attachments(target, record_id).await
+}
@@ -0,0 +1,36 @@
+pub mod download_attachment;
+pub mod list_attachments;
+pub mod thumbnail;
+pub mod upload_attachment;
+
+use crate::domain::attachments::AttachmentTarget;
+use crate::domain::error::DomainError;
+use crate::domain::orders::Order;
+use crate::ports::deliveries_port::DeliveriesPort;
+use crate::ports::orders_port::OrdersPort;
+use uuid::Uuid;
+
+/// Pedido de la entrega o entrega fallida: de él sale quién puede ver o
+/// agregar adjuntos.
+pub async fn attachment_order<P: DeliveriesPort + OrdersPort>(
+    port: &P,
+    target: AttachmentTarget,
+    record_id: Uuid,
+) -> Result<Order, DomainError> {
+    let order_id = match target {
+        AttachmentTarget::Entrega => port
+            .get_delivery(record_id)
+            .await?
+            .map(|delivery| delivery.order_id)
+            .ok_or_else(|| DomainError::NotFound("entrega no encontrada".to_string()))?,
+        AttachmentTarget::EntregaFallida => port
+            .get_failed_delivery(record_id)
+            .await?
+            .map(|failed| failed.order_id)
+            .ok_or_else(|| DomainError::NotFound("entrega fallida no encontrada".to_string()))?,
+    };
+
+    port.get_order_by_id(order_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))
+}
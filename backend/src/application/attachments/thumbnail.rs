@@ -0,0 +1,105 @@
+//! Miniaturas de las fotos adjuntas, en el mismo formato que el original.
+//! Decodificar es CPU pura, así que corre en el pool bloqueante de tokio; si
+//! la imagen no se puede leer o excede los límites queda sin miniatura.
+
+use crate::domain::attachments::{ImageFormat, THUMBNAIL_MAX_SIDE};
+use image::{DynamicImage, ImageDecoder, ImageReader, Limits};
+use std::io::Cursor;
+use tracing::warn;
+
+/// Una imagen chica comprimida puede inflarse a gigabytes: más allá de estas
+/// dimensiones o de esta memoria no se decodifica.
+const MAX_DECODE_SIDE: u32 = 8192;
+const MAX_DECODE_BYTES: u64 = 128 * 1024 * 1024;
+
+pub async fn generate(format: ImageFormat, bytes: Vec<u8>) -> Option<Vec<u8>> {
+    match tokio::task::spawn_blocking(move || render(format, &bytes)).await {
+        Ok(thumbnail) => thumbnail,
+        Err(err) => {
+            warn!(error = %err, "thumbnail task failed");
+            None
+        }
+    }
+}
+
+fn render(format: ImageFormat, bytes: &[u8]) -> Option<Vec<u8>> {
+    let codec = match format {
+        ImageFormat::Jpeg => image::ImageFormat::Jpeg,
+        ImageFormat::Png => image::ImageFormat::Png,
+    };
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DECODE_SIDE);
+    limits.max_image_height = Some(MAX_DECODE_SIDE);
+    limits.max_alloc = Some(MAX_DECODE_BYTES);
+
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), codec);
+    reader.limits(limits);
+    let mut decoder = reader.into_decoder().ok()?;
+    // Los celulares guardan la foto acostada y anotan el giro en EXIF.
+    let orientation = decoder.orientation().ok()?;
+    let mut image = DynamicImage::from_decoder(decoder).ok()?;
+    image.apply_orientation(orientation);
+
+    if image.width() > THUMBNAIL_MAX_SIDE || image.height() > THUMBNAIL_MAX_SIDE {
+        image = image.thumbnail(THUMBNAIL_MAX_SIDE, THUMBNAIL_MAX_SIDE);
+    }
+    // JPEG no admite canal alfa.
+    if codec == image::ImageFormat::Jpeg {
+        image = DynamicImage::ImageRgb8(image.to_rgb8());
+    }
+
+    let mut out = Cursor::new(Vec::new());
+    image.write_to(&mut out, codec).ok()?;
+    Some(out.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, RgbImage};
+
+    fn encode(width: u32, height: u32, codec: image::ImageFormat) -> Vec<u8> {
+        let image = RgbImage::from_fn(width, height, |x, y| {
+            image::Rgb([(x % 256) as u8, (y % 256) as u8, 200])
+        });
+        let mut out = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(image)
+            .write_to(&mut out, codec)
+            .unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn large_images_are_scaled_to_the_max_side() {
+        for (format, codec) in [
+            (ImageFormat::Png, image::ImageFormat::Png),
+            (ImageFormat::Jpeg, image::ImageFormat::Jpeg),
+        ] {
+            let thumbnail = render(format, &encode(1024, 512, codec)).unwrap();
+            let decoded = image::load_from_memory_with_format(&thumbnail, codec).unwrap();
+            assert_eq!(
+                decoded.dimensions(),
+                (THUMBNAIL_MAX_SIDE, THUMBNAIL_MAX_SIDE / 2)
+            );
+        }
+    }
+
+    #[test]
+    fn small_images_keep_their_size() {
+        let thumbnail = render(ImageFormat::Png, &encode(40, 30, image::ImageFormat::Png)).unwrap();
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!(decoded.dimensions(), (40, 30));
+    }
+
+    #[test]
+    fn unreadable_or_oversized_images_get_no_thumbnail() {
+        let mut truncated = encode(300, 300, image::ImageFormat::Png);
+        truncated.truncate(truncated.len() / 2);
+        assert!(render(ImageFormat::Png, &truncated).is_none());
+        assert!(render(ImageFormat::Jpeg, &[0xFF, 0xD8, 0xFF, 0xD9]).is_none());
+
+        let oversized = encode(MAX_DECODE_SIDE + 1, 1, image::ImageFormat::Png);
+        assert!(render(ImageFormat::Png, &oversized).is_none());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::application::attachments::thumbnail;
+use crate::domain::attachments::{
+    ensure_attachment_room, validate_attachment, Attachment, AttachmentKind, AttachmentTarget,
+    NewAttachment,
+};
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::ports::attachments_port::AttachmentsPort;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::blob_store_port::BlobStore;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct UploadAttachment {
+    pub target: AttachmentTarget,
+    pub record_id: Uuid,
+    /// Pedido del registro, ya resuelto por el handler al verificar acceso.
+    pub order_id: Uuid,
+    pub kind: AttachmentKind,
+    pub content_type: Option<String>,
+    pub bytes: Vec<u8>,
+    pub uploaded_by: Option<Uuid>,
+}
+
+/// Quién puede adjuntar lo decide el handler, igual que al registrar la
+/// entrega.
+pub async fn execute<P>(
+    port: &P,
+    blobs: &dyn BlobStore,
+    input: UploadAttachment,
+    audit: &AuditContext,
+) -> Result<Attachment, DomainError>
+where
+    P: AttachmentsPort + AuditPort,
+{
+    let format = validate_attachment(input.kind, input.content_type.as_deref(), &input.bytes)?;
+
+    // Evita subir archivos que no van a entrar; el tope se vuelve a
+    // verificar con lock al insertar.
+    ensure_attachment_room(
+        port.count_attachments(input.target, input.record_id)
+            .await?,
+    )?;
+
+    let id = Uuid::new_v4();
+    let prefix = format!("adjuntos/{}/{}", input.record_id, id);
+    let blob_key = format!("{}.{}", prefix, format.extension());
+    blobs.put(&blob_key, &input.bytes).await?;
+
+    let thumbnail_key = match thumbnail::generate(format, input.bytes.clone()).await {
+        Some(thumbnail) => {
+            let key = format!("{}.thumb.{}", prefix, format.extension());
+            if let Err(err) = blobs.put(&key, &thumbnail).await {
+                discard_blobs(blobs, &[blob_key.as_str()]).await;
+                return Err(err);
+            }
+            Some(key)
+        }
+        None => None,
+    };
+
+    let created = port
+        .create_attachment(NewAttachment {
+            id,
+            target: input.target,
+            record_id: input.record_id,
+            order_id: input.order_id,
+            kind: input.kind,
+            content_type: format.content_type().to_string(),
+            size_bytes: input.bytes.len() as i64,
+            sha256: hex::encode(Sha256::digest(&input.bytes)),
+            blob_key: blob_key.clone(),
+            thumbnail_key: thumbnail_key.clone(),
+            uploaded_by: input.uploaded_by,
+        })
+        .await;
+
+    let attachment = match created {
+        Ok(attachment) => attachment,
+        Err(err) => {
+            let mut keys = vec![blob_key.as_str()];
+            keys.extend(thumbnail_key.as_deref());
+            discard_blobs(blobs, &keys).await;
+            return Err(err);
+        }
+    };
+
+    port.record_audit_event(
+        audit
+            .event("attachment", Some(attachment.id), "created")
+            .after(&attachment),
+    )
+    .await?;
+
+    Ok(attachment)
+}
+
+/// Sin metadatos los archivos quedan huérfanos: se borran.
+async fn discard_blobs(blobs: &dyn BlobStore, keys: &[&str]) {
+    for key in keys {
+        if let Err(cleanup) = blobs.delete(key).await {
+            warn!(key = %key, error = %cleanup, "orphan attachment blob not removed");
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use crate::domain::audit::{AuditCursor, AuditFilter, AuditPage};
+use crate::domain::error::DomainError;
+use crate::ports::audit_port::AuditPort;
+
+pub async fn execute<P: AuditPort>(
+    port: &P,
+    filter: AuditFilter,
+) -> Result<AuditPage, DomainError> {
+    if let (Some(from), Some(to)) = (filter.from, filter.to) {
+        if from > to {
+            return Err(DomainError::Validation(
+                "from debe ser anterior a to".to_string(),
+            ));
+        }
+    }
+
+    // Se pide uno de más para saber si hay otra página sin contar el total.
+    let mut items = port.list_audit_events(&filter, filter.limit + 1).await?;
+    let next_cursor = if items.len() as i64 > filter.limit {
+        items.truncate(filter.limit as usize);
+        items.last().map(|event| AuditCursor::after(event).encode())
+    } else {
+        None
+    };
+
+    Ok(AuditPage { items, next_cursor })
+}
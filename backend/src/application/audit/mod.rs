@@ -0,0 +1,2 @@
+pub mod list_events;
+pub mod verify_chain;
@@ -0,0 +1,203 @@
+use crate::domain::audit::{
+    AuditChainReport, BrokenAuditLink, ChainedAuditEvent, AUDIT_GENESIS_HASH,
+};
+use crate::domain::error::DomainError;
+use crate::ports::audit_port::AuditPort;
+
+const VERIFY_BATCH_SIZE: i64 = 500;
+
+fn broken(event: &ChainedAuditEvent, reason: String) -> BrokenAuditLink {
+    BrokenAuditLink {
+        seq: event.seq,
+        event_id: event.id,
+        reason,
+    }
+}
+
+/// Motivo por el que `event` no continúa la cadena cuyo último eslabón es
+/// (`last_seq`, `last_hash`).
+fn check_link(event: &ChainedAuditEvent, last_seq: i64, last_hash: &str) -> Option<String> {
+    if event.seq != last_seq + 1 {
+        return Some(format!(
+            "faltan eventos entre seq {} y {}",
+            last_seq, event.seq
+        ));
+    }
+    let Some(hash) = &event.hash else {
+        return Some("evento sin sellar".to_string());
+    };
+    if event.prev_hash.as_deref() != Some(last_hash) {
+        return Some("prev_hash no coincide con el evento anterior".to_string());
+    }
+    if *hash != event.compute_hash(last_hash) {
+        return Some("el contenido no coincide con su hash".to_string());
+    }
+    None
+}
+
+/// Recorre la cadena completa y reporta el primer eslabón roto.
+pub async fn execute<P: AuditPort>(port: &P) -> Result<AuditChainReport, DomainError> {
+    let mut last_seq = 0;
+    let mut last_hash = AUDIT_GENESIS_HASH.to_string();
+    let mut checked = 0;
+
+    loop {
+        let batch = port.list_audit_chain(last_seq, VERIFY_BATCH_SIZE).await?;
+        if batch.is_empty() {
+            break;
+        }
+        for event in &batch {
+            if let Some(reason) = check_link(event, last_seq, &last_hash) {
+                return Ok(AuditChainReport {
+                    valid: false,
+                    checked,
+                    head_hash: (checked > 0).then_some(last_hash),
+                    first_broken: Some(broken(event, reason)),
+                });
+            }
+            last_seq = event.seq;
+            last_hash = event.hash.clone().unwrap_or_default();
+            checked += 1;
+        }
+    }
+
+    Ok(AuditChainReport {
+        valid: true,
+        checked,
+        head_hash: (checked > 0).then_some(last_hash),
+        first_broken: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::audit::{AuditEvent, AuditFilter, NewAuditEvent, AUDIT_HASH_VERSION};
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn chain(length: i64) -> Vec<ChainedAuditEvent> {
+        let mut prev = AUDIT_GENESIS_HASH.to_string();
+        (1..=length)
+            .map(|seq| {
+                let mut event = ChainedAuditEvent {
+                    seq,
+                    id: Uuid::new_v4(),
+                    actor_id: None,
+                    api_key_id: None,
+                    entity: "order".to_string(),
+                    entity_id: None,
+                    action: "created".to_string(),
+                    before: None,
+                    after: Some(json!({ "seq": seq })),
+                    details: json!({ "seq": seq }),
+                    request_id: None,
+                    ip: None,
+                    user_agent: None,
+                    created_at: Utc::now(),
+                    hash_version: AUDIT_HASH_VERSION,
+                    prev_hash: Some(prev.clone()),
+                    hash: None,
+                };
+                let hash = event.compute_hash(&prev);
+                event.hash = Some(hash.clone());
+                prev = hash;
+                event
+            })
+            .collect()
+    }
+
+    /// Cadena en memoria que se sirve por páginas como el repositorio.
+    struct MemoryAudit(Vec<ChainedAuditEvent>);
+
+    #[async_trait]
+    impl AuditPort for MemoryAudit {
+        async fn record_audit_event(&self, _event: NewAuditEvent) -> Result<(), DomainError> {
+            unimplemented!()
+        }
+
+        async fn list_audit_events(
+            &self,
+            _filter: &AuditFilter,
+            _limit: i64,
+        ) -> Result<Vec<AuditEvent>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn list_audit_chain(
+            &self,
+            after_seq: i64,
+            limit: i64,
+        ) -> Result<Vec<ChainedAuditEvent>, DomainError> {
+            Ok(self
+                .0
+                .iter()
+                .filter(|event| event.seq > after_seq)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+    }
+
+    async fn first_broken(events: &[ChainedAuditEvent]) -> Option<(i64, String)> {
+        let report = execute(&MemoryAudit(events.to_vec())).await.unwrap();
+        assert_eq!(report.valid, report.first_broken.is_none());
+        report.first_broken.map(|link| (link.seq, link.reason))
+    }
+
+    #[tokio::test]
+    async fn detects_edits_and_deletions() {
+        let events = chain(4);
+        assert!(first_broken(&events).await.is_none());
+
+        let mut edited = events.clone();
+        edited[1].details = json!({ "seq": 99 });
+        assert_eq!(first_broken(&edited).await.unwrap().0, 2);
+
+        let mut deleted = events.clone();
+        deleted.remove(2);
+        assert_eq!(first_broken(&deleted).await.unwrap().0, 4);
+
+        // Renumerar tras borrar tampoco alcanza: el prev_hash ya no cierra.
+        deleted[2].seq = 3;
+        assert_eq!(
+            first_broken(&deleted).await.unwrap(),
+            (
+                3,
+                "prev_hash no coincide con el evento anterior".to_string()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn follows_the_chain_across_batches() {
+        let events = chain(2 * VERIFY_BATCH_SIZE + 10);
+        let report = execute(&MemoryAudit(events.clone())).await.unwrap();
+        assert!(report.valid);
+        assert_eq!(report.checked, events.len() as i64);
+        assert_eq!(report.head_hash, events.last().unwrap().hash);
+
+        // El primer evento de la segunda página se compara contra el último
+        // de la primera.
+        let boundary = VERIFY_BATCH_SIZE as usize;
+        let mut edited = events.clone();
+        edited[boundary].details = json!({ "seq": -1 });
+        let report = execute(&MemoryAudit(edited)).await.unwrap();
+        assert!(!report.valid);
+        assert_eq!(report.checked, VERIFY_BATCH_SIZE);
+        assert_eq!(report.first_broken.unwrap().seq, VERIFY_BATCH_SIZE + 1);
+        assert_eq!(report.head_hash, events[boundary - 1].hash);
+
+        let mut relinked = events.clone();
+        relinked[boundary].prev_hash = Some(AUDIT_GENESIS_HASH.to_string());
+        assert_eq!(
+            first_broken(&relinked).await.unwrap(),
+            (
+                VERIFY_BATCH_SIZE + 1,
+                "prev_hash no coincide con el evento anterior".to_string()
+            )
+        );
+    }
+}
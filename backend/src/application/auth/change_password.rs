@@ -0,0 +1,133 @@
+use crate::adapters::auth::jwt::JwtService;
+use crate::application::auth::password::hash_password;
+use crate::application::auth::service::{
+    account_locked, complete_login, ensure_not_throttled, hash_token, invalid_credentials,
+    issue_tokens, normalize_reset_code, record_failure, verify_current_password,
+};
+use crate::domain::audit::AuditContext;
+use crate::domain::auth::{validate_new_password, LoginPolicy, LoginScope, TokenPair, UserAccount};
+use crate::domain::error::DomainError;
+use crate::domain::two_factor::LoginOutcome;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::auth_port::AuthPort;
+use crate::ports::two_factor_port::TwoFactorPort;
+use chrono::Utc;
+use uuid::Uuid;
+
+/// Cambio de contraseña del propio usuario. Cierra las demás sesiones y
+/// devuelve tokens nuevos para la actual.
+pub async fn execute<P: AuthPort + AuditPort>(
+    auth_port: &P,
+    jwt: &JwtService,
+    policy: &LoginPolicy,
+    user_id: Uuid,
+    current_password: &str,
+    new_password: &str,
+    audit: &AuditContext,
+) -> Result<TokenPair, DomainError> {
+    let user = auth_port
+        .find_user_by_id(user_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("usuario no encontrado".to_string()))?;
+
+    verify_current_password(
+        auth_port,
+        policy,
+        &user,
+        current_password,
+        audit.ip.as_deref(),
+    )
+    .await?;
+    validate_new_password(&user.username, new_password, Some(current_password))?;
+
+    let updated = auth_port
+        .change_password(user.id, &hash_password(new_password)?)
+        .await?;
+
+    auth_port
+        .record_audit_event(
+            audit
+                .event("user", Some(user.id), "password_changed")
+                .before(&UserAccount::from(user))
+                .after(&UserAccount::from(updated.clone())),
+        )
+        .await?;
+
+    issue_tokens(auth_port, jwt, &updated, Uuid::new_v4()).await
+}
+
+/// Completa un reset administrativo: el código vale una sola vez y los fallos
+/// cuentan como intentos de login. El código no reemplaza al segundo factor.
+pub async fn with_reset_code<P: AuthPort + TwoFactorPort + AuditPort>(
+    auth_port: &P,
+    jwt: &JwtService,
+    policy: &LoginPolicy,
+    username: &str,
+    code: &str,
+    new_password: &str,
+    audit: &AuditContext,
+) -> Result<LoginOutcome, DomainError> {
+    let client_ip = audit.ip.as_deref();
+    let now = Utc::now();
+    let username_key = username.trim().to_lowercase();
+
+    ensure_not_throttled(auth_port, policy, LoginScope::Username, &username_key, now).await?;
+    if let Some(ip) = client_ip {
+        ensure_not_throttled(auth_port, policy, LoginScope::Ip, ip, now).await?;
+    }
+
+    let user = auth_port.find_user_by_username(username).await?;
+    let reset = match &user {
+        Some(user) => {
+            auth_port
+                .find_password_reset(user.id, &hash_token(&normalize_reset_code(code)))
+                .await?
+        }
+        None => None,
+    };
+    let (user, reset) = match (user, reset.filter(|reset| reset.expires_at > now)) {
+        (Some(user), Some(reset)) => (user, reset),
+        (user, _) => {
+            record_failure(
+                auth_port,
+                policy,
+                &username_key,
+                client_ip,
+                user.as_ref(),
+                now,
+            )
+            .await?;
+            return Err(invalid_credentials());
+        }
+    };
+    // Como en el login, el bloqueo sólo se informa a quien tiene el código.
+    if user.locked_at.is_some() {
+        return Err(account_locked());
+    }
+
+    if !user.active {
+        return Err(DomainError::Unauthorized("usuario desactivado".to_string()));
+    }
+    validate_new_password(&user.username, new_password, None)?;
+
+    if !auth_port.consume_password_reset(reset.id).await? {
+        return Err(DomainError::Unauthorized(
+            "código de restablecimiento ya utilizado".to_string(),
+        ));
+    }
+    let updated = auth_port
+        .change_password(user.id, &hash_password(new_password)?)
+        .await?;
+
+    auth_port
+        .record_audit_event(
+            audit
+                .acting_as(user.id)
+                .event("user", Some(user.id), "password_reset_completed")
+                .before(&UserAccount::from(user))
+                .after(&UserAccount::from(updated.clone())),
+        )
+        .await?;
+
+    complete_login(auth_port, jwt, policy, &updated, &username_key).await
+}
@@ -1 +1,4 @@
+pub mod change_password;
+pub mod password;
 pub mod service;
+pub mod two_factor;
@@ -0,0 +1,68 @@
+use crate::domain::auth::{PasswordScheme, StoredPassword};
+use crate::domain::error::DomainError;
+use bcrypt::{hash, verify, DEFAULT_COST};
+
+/// Costo bcrypt vigente; los hashes más débiles se actualizan en el login.
+pub const PASSWORD_HASH_COST: u32 = DEFAULT_COST;
+
+pub fn hash_password(password: &str) -> Result<String, DomainError> {
+    hash(password, PASSWORD_HASH_COST)
+        .map_err(|err| DomainError::Infrastructure(format!("no se pudo hashear: {err}")))
+}
+
+/// Con `allow_plaintext` en falso una contraseña en texto plano nunca valida.
+pub fn verify_password(
+    stored_password: &str,
+    candidate: &str,
+    allow_plaintext: bool,
+) -> Result<bool, DomainError> {
+    match StoredPassword::detect(stored_password).scheme {
+        PasswordScheme::Bcrypt => verify(candidate, stored_password)
+            .map_err(|err| DomainError::Infrastructure(format!("password hash inválido: {err}"))),
+        // Compatibilidad temporal para bases previas sin migrar hash.
+        PasswordScheme::Plaintext => Ok(allow_plaintext && stored_password == candidate),
+    }
+}
+
+pub fn needs_rehash(stored_password: &str) -> bool {
+    StoredPassword::detect(stored_password).is_legacy(PASSWORD_HASH_COST)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_plaintext_password_for_legacy_records() {
+        let ok = verify_password("admin123", "admin123", true).expect("verify should work");
+        assert!(ok);
+    }
+
+    #[test]
+    fn reject_plaintext_when_disabled() {
+        let ok = verify_password("admin123", "admin123", false).expect("verify should work");
+        assert!(!ok);
+    }
+
+    #[test]
+    fn verify_bcrypt_password() {
+        let hashed = hash("admin123", DEFAULT_COST).expect("hash should work");
+        let ok = verify_password(&hashed, "admin123", false).expect("verify should work");
+        assert!(ok);
+        assert!(!needs_rehash(&hashed));
+    }
+
+    #[test]
+    fn reject_invalid_password() {
+        let hashed = hash("admin123", DEFAULT_COST).expect("hash should work");
+        let ok = verify_password(&hashed, "wrong", true).expect("verify should work");
+        assert!(!ok);
+    }
+
+    #[test]
+    fn low_cost_hashes_need_rehash() {
+        let hashed = hash("admin123", 4).expect("hash should work");
+        assert!(needs_rehash(&hashed));
+        assert!(needs_rehash("admin123"));
+    }
+}
@@ -1,41 +1,374 @@
 use crate::adapters::auth::jwt::JwtService;
+use crate::application::auth::password::{hash_password, needs_rehash, verify_password};
+use crate::application::auth::two_factor;
+use crate::domain::audit::AuditContext;
+use crate::domain::auth::{LoginPolicy, LoginScope, NewRefreshToken, TokenPair, User};
 use crate::domain::error::DomainError;
+use crate::domain::two_factor::LoginOutcome;
+use crate::ports::audit_port::AuditPort;
 use crate::ports::auth_port::AuthPort;
-use bcrypt::verify as bcrypt_verify;
+use crate::ports::two_factor_port::TwoFactorPort;
+use chrono::{DateTime, Utc};
+use rand::{Rng, RngCore};
+use serde_json::json;
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
-fn is_bcrypt_hash(value: &str) -> bool {
-    value.starts_with("$2a$") || value.starts_with("$2b$") || value.starts_with("$2y$")
+const REFRESH_TOKEN_BYTES: usize = 32;
+const RESET_CODE_LENGTH: usize = 12;
+const RESET_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+fn generate_refresh_token() -> String {
+    let mut bytes = [0u8; REFRESH_TOKEN_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+// Refresh tokens y códigos de reset son aleatorios de alta entropía: alcanza
+// con SHA-256 y la búsqueda por hash sigue siendo directa.
+pub(crate) fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Código legible para dictar o copiar: sin caracteres ambiguos (0/O, 1/I).
+pub(crate) fn generate_code(length: usize) -> String {
+    let mut rng = rand::thread_rng();
+    (0..length)
+        .map(|_| RESET_CODE_ALPHABET[rng.gen_range(0..RESET_CODE_ALPHABET.len())] as char)
+        .collect()
+}
+
+pub(crate) fn generate_reset_code() -> String {
+    generate_code(RESET_CODE_LENGTH)
+}
+
+pub(crate) fn normalize_reset_code(code: &str) -> String {
+    code.trim().to_uppercase()
+}
+
+fn invalid_refresh_token() -> DomainError {
+    DomainError::Unauthorized("refresh token inválido".to_string())
+}
+
+pub(crate) async fn issue_tokens<P: AuthPort>(
+    auth_port: &P,
+    jwt: &JwtService,
+    user: &User,
+    family_id: Uuid,
+) -> Result<TokenPair, DomainError> {
+    let access_token = jwt.issue(user.id, user.role.clone(), user.token_generation)?;
+    let refresh_token = generate_refresh_token();
+
+    auth_port
+        .store_refresh_token(NewRefreshToken {
+            user_id: user.id,
+            token_hash: hash_token(&refresh_token),
+            family_id,
+            token_generation: user.token_generation,
+            expires_at: Utc::now() + jwt.refresh_token_ttl(),
+        })
+        .await?;
+
+    Ok(TokenPair {
+        user_id: user.id,
+        access_token,
+        expires_in: jwt.access_token_ttl().num_seconds(),
+        refresh_token,
+    })
 }
 
-fn verify_password(stored_password: &str, candidate: &str) -> Result<bool, DomainError> {
-    if is_bcrypt_hash(stored_password) {
-        return bcrypt_verify(candidate, stored_password)
-            .map_err(|err| DomainError::Infrastructure(format!("password hash inválido: {err}")));
+pub(crate) fn invalid_credentials() -> DomainError {
+    DomainError::Unauthorized("credenciales inválidas".to_string())
+}
+
+pub(crate) fn account_locked() -> DomainError {
+    DomainError::Unauthorized(
+        "cuenta bloqueada por intentos fallidos; contactar a un administrador".to_string(),
+    )
+}
+
+pub(crate) async fn ensure_not_throttled<P: AuthPort>(
+    auth_port: &P,
+    policy: &LoginPolicy,
+    scope: LoginScope,
+    key: &str,
+    now: DateTime<Utc>,
+) -> Result<(), DomainError> {
+    let Some(failures) = auth_port.find_login_failures(scope, key).await? else {
+        return Ok(());
+    };
+    match policy.retry_after(scope, &failures, now) {
+        Some(retry_after_seconds) => Err(DomainError::TooManyRequests {
+            message: "demasiados intentos fallidos, reintentar más tarde".to_string(),
+            retry_after_seconds,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Cuenta el fallo de quien ya presentó la contraseña (el segundo factor):
+/// a él sí se le informa que la cuenta quedó bloqueada.
+pub(crate) async fn register_failure<P: AuthPort>(
+    auth_port: &P,
+    policy: &LoginPolicy,
+    username_key: &str,
+    client_ip: Option<&str>,
+    user: Option<&User>,
+    now: DateTime<Utc>,
+) -> Result<DomainError, DomainError> {
+    if record_failure(auth_port, policy, username_key, client_ip, user, now).await? {
+        return Ok(account_locked());
+    }
+    Ok(invalid_credentials())
+}
+
+/// Verifica la contraseña actual desde una sesión abierta con el mismo límite
+/// que el login: los fallos suman por usuario y por IP y bloquean la cuenta.
+pub(crate) async fn verify_current_password<P: AuthPort>(
+    auth_port: &P,
+    policy: &LoginPolicy,
+    user: &User,
+    password: &str,
+    client_ip: Option<&str>,
+) -> Result<(), DomainError> {
+    let now = Utc::now();
+    let username_key = user.username.trim().to_lowercase();
+
+    ensure_not_throttled(auth_port, policy, LoginScope::Username, &username_key, now).await?;
+    if let Some(ip) = client_ip {
+        ensure_not_throttled(auth_port, policy, LoginScope::Ip, ip, now).await?;
+    }
+
+    if !verify_password(&user.password, password, policy.allow_plaintext_passwords)? {
+        record_failure(auth_port, policy, &username_key, client_ip, Some(user), now).await?;
+        return Err(DomainError::Unauthorized(
+            "contraseña actual incorrecta".to_string(),
+        ));
+    }
+    if user.locked_at.is_some() {
+        return Err(account_locked());
+    }
+    Ok(())
+}
+
+/// Cuenta el fallo por usuario y por IP y bloquea la cuenta al llegar al
+/// umbral; devuelve `true` si con él la cuenta quedó bloqueada. A quien no
+/// presentó la contraseña no se le dice: sólo recibe credenciales inválidas,
+/// así el bloqueo no revela que el usuario existe ni su estado.
+pub(crate) async fn record_failure<P: AuthPort>(
+    auth_port: &P,
+    policy: &LoginPolicy,
+    username_key: &str,
+    client_ip: Option<&str>,
+    user: Option<&User>,
+    now: DateTime<Utc>,
+) -> Result<bool, DomainError> {
+    let window_start = policy.window_start(now);
+    let failures = auth_port
+        .record_login_failure(LoginScope::Username, username_key, now, window_start)
+        .await?;
+    if let Some(ip) = client_ip {
+        auth_port
+            .record_login_failure(LoginScope::Ip, ip, now, window_start)
+            .await?;
+    }
+
+    if let Some(user) = user.filter(|user| user.locked_at.is_none()) {
+        if failures.failures >= policy.lockout_threshold {
+            auth_port.lock_user(user.id).await?;
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Contraseña ya verificada: emite los tokens o, si corresponde segundo
+/// factor, un desafío. Los fallos por usuario se limpian recién con el login
+/// completo, así los códigos TOTP erróneos siguen sumando.
+pub(crate) async fn complete_login<P: AuthPort + TwoFactorPort>(
+    auth_port: &P,
+    jwt: &JwtService,
+    policy: &LoginPolicy,
+    user: &User,
+    username_key: &str,
+) -> Result<LoginOutcome, DomainError> {
+    if user.two_factor_enabled() || policy.requires_two_factor(&user.role) {
+        let challenge = two_factor::issue_challenge(auth_port, user).await?;
+        return Ok(LoginOutcome::TwoFactorRequired(challenge));
     }
 
-    // Compatibilidad temporal para bases previas sin migrar hash.
-    Ok(stored_password == candidate)
+    // Los fallos por IP no se limpian: un login válido no debe habilitar a
+    // seguir probando otras cuentas desde la misma dirección.
+    auth_port
+        .clear_login_failures(LoginScope::Username, username_key)
+        .await?;
+
+    let tokens = issue_tokens(auth_port, jwt, user, Uuid::new_v4()).await?;
+    Ok(LoginOutcome::Tokens(tokens))
 }
 
-pub async fn login<P: AuthPort>(
+/// Login con protección contra fuerza bruta: los fallos se cuentan por
+/// usuario y por IP, con espera exponencial y bloqueo de la cuenta. Cada
+/// intento queda auditado, exitoso o no.
+pub async fn login<P: AuthPort + TwoFactorPort + AuditPort>(
     auth_port: &P,
     jwt: &JwtService,
+    policy: &LoginPolicy,
     username: String,
     password: String,
-) -> Result<String, DomainError> {
-    let user = auth_port
-        .find_user_by_username(&username)
-        .await?
-        .ok_or_else(|| DomainError::Unauthorized("credenciales inválidas".to_string()))?;
+    audit: &AuditContext,
+) -> Result<LoginOutcome, DomainError> {
+    let result = attempt_login(
+        auth_port,
+        jwt,
+        policy,
+        &username,
+        password,
+        audit.ip.as_deref(),
+    )
+    .await;
+
+    let event = match &result {
+        Ok(LoginOutcome::Tokens(tokens)) => audit
+            .acting_as(tokens.user_id)
+            .event("auth", Some(tokens.user_id), "login_succeeded")
+            .details(json!({ "username": username })),
+        Ok(LoginOutcome::TwoFactorRequired(challenge)) => audit
+            .event("auth", Some(challenge.user_id), "login_challenged")
+            .details(json!({
+                "username": username,
+                "two_factor": challenge.purpose.as_str(),
+            })),
+        // Con la base caída no hay dónde auditar: se devuelve el error original.
+        Err(DomainError::Infrastructure(_)) => return result,
+        Err(err) => audit
+            .event("auth", None, "login_failed")
+            .details(json!({ "username": username, "reason": err.to_string() })),
+    };
+    auth_port.record_audit_event(event).await?;
+
+    result
+}
+
+async fn attempt_login<P: AuthPort + TwoFactorPort>(
+    auth_port: &P,
+    jwt: &JwtService,
+    policy: &LoginPolicy,
+    username: &str,
+    password: String,
+    client_ip: Option<&str>,
+) -> Result<LoginOutcome, DomainError> {
+    let now = Utc::now();
+    let username_key = username.trim().to_lowercase();
+
+    ensure_not_throttled(auth_port, policy, LoginScope::Username, &username_key, now).await?;
+    if let Some(ip) = client_ip {
+        ensure_not_throttled(auth_port, policy, LoginScope::Ip, ip, now).await?;
+    }
+
+    let user = auth_port.find_user_by_username(username).await?;
+    let valid = match &user {
+        Some(user) => verify_password(&user.password, &password, policy.allow_plaintext_passwords)?,
+        None => false,
+    };
+    let user = match user {
+        Some(user) if valid => user,
+        user => {
+            record_failure(
+                auth_port,
+                policy,
+                &username_key,
+                client_ip,
+                user.as_ref(),
+                now,
+            )
+            .await?;
+            return Err(invalid_credentials());
+        }
+    };
+    // Sólo quien presentó la contraseña correcta se entera del bloqueo.
+    if user.locked_at.is_some() {
+        return Err(account_locked());
+    }
+
+    if !user.active {
+        return Err(DomainError::Unauthorized("usuario desactivado".to_string()));
+    }
+
+    // Único momento en que se tiene la contraseña en claro: se aprovecha para
+    // llevar texto plano y costos viejos a la política vigente.
+    if needs_rehash(&user.password) {
+        auth_port
+            .update_password_hash(user.id, &user.password, &hash_password(&password)?)
+            .await?;
+    }
 
-    if !verify_password(&user.password, &password)? {
+    if user.must_change_password {
         return Err(DomainError::Unauthorized(
-            "credenciales inválidas".to_string(),
+            "debe fijar una contraseña nueva con el código de restablecimiento".to_string(),
         ));
     }
 
-    jwt.issue(user.id, user.role)
+    complete_login(auth_port, jwt, policy, &user, &username_key).await
+}
+
+/// Rota el refresh token: el recibido queda inutilizado y se entrega uno nuevo
+/// de la misma familia.
+pub async fn refresh<P: AuthPort>(
+    auth_port: &P,
+    jwt: &JwtService,
+    refresh_token: &str,
+) -> Result<TokenPair, DomainError> {
+    let stored = auth_port
+        .find_refresh_token(&hash_token(refresh_token))
+        .await?
+        .ok_or_else(invalid_refresh_token)?;
+
+    // Reusar un token ya rotado indica que pudo haber sido robado: se corta
+    // la sesión completa.
+    if stored.revoked_at.is_some() || !auth_port.consume_refresh_token(stored.id).await? {
+        auth_port.revoke_refresh_family(stored.family_id).await?;
+        return Err(invalid_refresh_token());
+    }
+    if stored.expires_at <= Utc::now() {
+        return Err(invalid_refresh_token());
+    }
+
+    // Una cuenta bloqueada no renueva sesiones abiertas antes del bloqueo. Se
+    // mira antes de `authenticate`: el bloqueo sube `token_generation` y el
+    // token ya no coincidiría.
+    if auth_port
+        .find_user_by_id(stored.user_id)
+        .await?
+        .is_some_and(|user| user.locked_at.is_some())
+    {
+        auth_port.revoke_refresh_family(stored.family_id).await?;
+        return Err(account_locked());
+    }
+    let user = authenticate(auth_port, stored.user_id, stored.token_generation)
+        .await
+        .map_err(|_| invalid_refresh_token())?;
+
+    issue_tokens(auth_port, jwt, &user, stored.family_id).await
+}
+
+/// Cierra la sesión del refresh token o, con `all_sessions`, todas las del
+/// usuario (incluidos los access tokens vigentes).
+pub async fn logout<P: AuthPort>(
+    auth_port: &P,
+    refresh_token: &str,
+    all_sessions: bool,
+) -> Result<(), DomainError> {
+    let stored = auth_port
+        .find_refresh_token(&hash_token(refresh_token))
+        .await?
+        .ok_or_else(invalid_refresh_token)?;
+
+    if all_sessions {
+        auth_port.revoke_user_tokens(stored.user_id).await
+    } else {
+        auth_port.revoke_refresh_family(stored.family_id).await
+    }
 }
 
 pub async fn me<P: AuthPort>(
@@ -48,28 +381,42 @@ pub async fn me<P: AuthPort>(
         .ok_or_else(|| DomainError::NotFound("usuario no encontrado".to_string()))
 }
 
+/// Usuario vigente detrás de un token: un token emitido antes de desactivar
+/// al usuario o de revocar sus sesiones deja de servir de inmediato.
+pub async fn authenticate<P: AuthPort>(
+    auth_port: &P,
+    user_id: Uuid,
+    token_generation: i64,
+) -> Result<User, DomainError> {
+    auth_port
+        .find_user_by_id(user_id)
+        .await?
+        .filter(|user| user.active && user.token_generation == token_generation)
+        .ok_or_else(|| DomainError::Unauthorized("sesión revocada o usuario inactivo".to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use bcrypt::{hash, DEFAULT_COST};
-
-    #[test]
-    fn verify_plaintext_password_for_legacy_records() {
-        let ok = verify_password("admin123", "admin123").expect("verify should work");
-        assert!(ok);
-    }
 
     #[test]
-    fn verify_bcrypt_password() {
-        let hashed = hash("admin123", DEFAULT_COST).expect("hash should work");
-        let ok = verify_password(&hashed, "admin123").expect("verify should work");
-        assert!(ok);
+    fn refresh_tokens_are_random_and_hashed() {
+        let first = generate_refresh_token();
+        let second = generate_refresh_token();
+        assert_ne!(first, second);
+        assert_eq!(first.len(), REFRESH_TOKEN_BYTES * 2);
+        assert_eq!(hash_token(&first), hash_token(&first));
+        assert_ne!(hash_token(&first), first);
     }
 
     #[test]
-    fn reject_invalid_password() {
-        let hashed = hash("admin123", DEFAULT_COST).expect("hash should work");
-        let ok = verify_password(&hashed, "wrong").expect("verify should work");
-        assert!(!ok);
+    fn reset_codes_use_unambiguous_alphabet() {
+        let code = generate_reset_code();
+        assert_eq!(code.len(), RESET_CODE_LENGTH);
+        assert!(code.bytes().all(|c| RESET_CODE_ALPHABET.contains(&c)));
+        assert_eq!(
+            normalize_reset_code(&format!(" {} ", code.to_lowercase())),
+            code
+        );
     }
 }
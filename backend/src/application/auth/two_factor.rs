@@ -0,0 +1,566 @@
+use crate::adapters::auth::jwt::JwtService;
+use crate::application::auth::service::{
+    account_locked, ensure_not_throttled, generate_code, hash_token, issue_tokens,
+    register_failure, verify_current_password,
+};
+use crate::domain::audit::AuditContext;
+use crate::domain::auth::{LoginPolicy, LoginScope, User, UserAccount};
+use crate::domain::error::DomainError;
+use crate::domain::two_factor::{
+    ChallengePurpose, LoginChallenge, NewLoginChallenge, RecoveryCodes, SecondFactor,
+    TwoFactorChallenge, TwoFactorEnrollment, TwoFactorLogin, LOGIN_CHALLENGE_MINUTES,
+    MAX_CHALLENGE_ATTEMPTS, RECOVERY_CODE_COUNT, TOTP_DIGITS, TOTP_ISSUER, TOTP_SECRET_BYTES,
+    TOTP_SKEW_STEPS, TOTP_STEP_SECONDS,
+};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::auth_port::AuthPort;
+use crate::ports::two_factor_port::TwoFactorPort;
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use ring::hmac;
+use serde_json::json;
+use uuid::Uuid;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const RECOVERY_CODE_LENGTH: usize = 10;
+const CHALLENGE_TOKEN_BYTES: usize = 32;
+
+/// Base32 (RFC 4648) sin relleno, el formato que esperan las apps
+/// autenticadoras.
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    encoded
+}
+
+fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in encoded.trim_end_matches('=').bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+fn hotp(secret: &[u8], counter: u64) -> u32 {
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, secret);
+    let digest = hmac::sign(&key, &counter.to_be_bytes());
+    let digest = digest.as_ref();
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    binary % 10u32.pow(TOTP_DIGITS)
+}
+
+fn step_at(at: DateTime<Utc>) -> i64 {
+    at.timestamp().div_euclid(TOTP_STEP_SECONDS)
+}
+
+/// Código TOTP vigente en `at` para un secreto en base32.
+pub fn totp_code(secret: &str, at: DateTime<Utc>) -> Option<String> {
+    let secret = base32_decode(secret)?;
+    Some(format!(
+        "{:0width$}",
+        hotp(&secret, step_at(at) as u64),
+        width = TOTP_DIGITS as usize
+    ))
+}
+
+/// Paso en el que `code` es válido, dentro del margen de desfase y posterior
+/// a `last_step`.
+fn matching_step(
+    secret: &str,
+    code: &str,
+    now: DateTime<Utc>,
+    last_step: Option<i64>,
+) -> Option<i64> {
+    let secret = base32_decode(secret)?;
+    let code: u32 = code.parse().ok()?;
+    let current = step_at(now);
+    (current - TOTP_SKEW_STEPS..=current + TOTP_SKEW_STEPS)
+        .filter(|step| last_step.is_none_or(|last| *step > last))
+        .find(|step| hotp(&secret, *step as u64) == code)
+}
+
+fn is_totp_code(code: &str) -> bool {
+    code.len() == TOTP_DIGITS as usize && code.bytes().all(|c| c.is_ascii_digit())
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; TOTP_SECRET_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    base32_encode(&bytes)
+}
+
+fn otpauth_uri(username: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{issuer}:{username}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={digits}&period={period}",
+        issuer = TOTP_ISSUER,
+        digits = TOTP_DIGITS,
+        period = TOTP_STEP_SECONDS,
+    )
+}
+
+/// Códigos en la forma `XXXXX-XXXXX`; se comparan sin el guión.
+fn generate_recovery_codes() -> (Vec<String>, Vec<String>) {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let code = generate_code(RECOVERY_CODE_LENGTH);
+            let hash = hash_token(&code);
+            let (head, tail) = code.split_at(RECOVERY_CODE_LENGTH / 2);
+            (format!("{}-{}", head, tail), hash)
+        })
+        .unzip()
+}
+
+fn normalize_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_uppercase()
+}
+
+fn invalid_code() -> DomainError {
+    DomainError::Unauthorized("código 2FA inválido".to_string())
+}
+
+fn invalid_challenge() -> DomainError {
+    DomainError::Unauthorized("desafío 2FA inválido o vencido".to_string())
+}
+
+async fn find_user<P: AuthPort>(auth_port: &P, user_id: Uuid) -> Result<User, DomainError> {
+    auth_port
+        .find_user_by_id(user_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("usuario no encontrado".to_string()))
+}
+
+/// Verifica un código TOTP o de recuperación de un usuario con 2FA activo.
+/// Ambos valen una sola vez.
+async fn verify_second_factor<P: TwoFactorPort>(
+    port: &P,
+    user: &User,
+    code: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<SecondFactor>, DomainError> {
+    let code = normalize_code(code);
+    let Some(secret) = user
+        .totp_secret
+        .as_deref()
+        .filter(|_| user.two_factor_enabled())
+    else {
+        return Ok(None);
+    };
+
+    if is_totp_code(&code) {
+        let Some(step) = matching_step(secret, &code, now, user.totp_last_step) else {
+            return Ok(None);
+        };
+        return Ok(port
+            .record_totp_step(user.id, step)
+            .await?
+            .then_some(SecondFactor::Totp));
+    }
+    Ok(port
+        .consume_recovery_code(user.id, &hash_token(&code))
+        .await?
+        .then_some(SecondFactor::RecoveryCode))
+}
+
+async fn begin_enrollment<P: TwoFactorPort>(
+    port: &P,
+    user: &User,
+) -> Result<TwoFactorEnrollment, DomainError> {
+    let secret = generate_secret();
+    if !port.set_pending_totp_secret(user.id, &secret).await? {
+        return Err(DomainError::Conflict("2FA ya está activado".to_string()));
+    }
+    Ok(TwoFactorEnrollment {
+        otpauth_uri: otpauth_uri(&user.username, &secret),
+        secret,
+    })
+}
+
+/// Confirma la activación pendiente con un código de la app y entrega los
+/// códigos de recuperación.
+async fn finish_enrollment<P: TwoFactorPort>(
+    port: &P,
+    user: &User,
+    code: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<RecoveryCodes>, DomainError> {
+    let Some(secret) = user.totp_secret.as_deref() else {
+        return Err(DomainError::Validation(
+            "no hay una activación de 2FA pendiente".to_string(),
+        ));
+    };
+    if user.two_factor_enabled() {
+        return Err(DomainError::Conflict("2FA ya está activado".to_string()));
+    }
+    let Some(step) = matching_step(secret, &normalize_code(code), now, None) else {
+        return Ok(None);
+    };
+
+    let (codes, hashes) = generate_recovery_codes();
+    if !port.enable_totp(user.id, step, hashes).await? {
+        return Err(DomainError::Conflict("2FA ya está activado".to_string()));
+    }
+    Ok(Some(RecoveryCodes { codes }))
+}
+
+pub(crate) async fn issue_challenge<P: TwoFactorPort>(
+    port: &P,
+    user: &User,
+) -> Result<TwoFactorChallenge, DomainError> {
+    let mut bytes = [0u8; CHALLENGE_TOKEN_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let challenge_token = hex::encode(bytes);
+    let purpose = if user.two_factor_enabled() {
+        ChallengePurpose::Verify
+    } else {
+        ChallengePurpose::Enroll
+    };
+    let ttl = Duration::minutes(LOGIN_CHALLENGE_MINUTES);
+
+    port.create_login_challenge(NewLoginChallenge {
+        user_id: user.id,
+        token_hash: hash_token(&challenge_token),
+        purpose,
+        expires_at: Utc::now() + ttl,
+    })
+    .await?;
+
+    Ok(TwoFactorChallenge {
+        user_id: user.id,
+        challenge_token,
+        purpose,
+        expires_in: ttl.num_seconds(),
+    })
+}
+
+async fn find_challenge<P: TwoFactorPort>(
+    port: &P,
+    challenge_token: &str,
+    now: DateTime<Utc>,
+) -> Result<LoginChallenge, DomainError> {
+    port.find_login_challenge(&hash_token(challenge_token))
+        .await?
+        .filter(|challenge| challenge.expires_at > now)
+        .ok_or_else(invalid_challenge)
+}
+
+/// Secreto para el usuario que debe activar 2FA para poder entrar.
+pub async fn enroll_with_challenge<P: AuthPort + TwoFactorPort>(
+    port: &P,
+    challenge_token: &str,
+) -> Result<TwoFactorEnrollment, DomainError> {
+    let challenge = find_challenge(port, challenge_token, Utc::now()).await?;
+    if challenge.purpose != ChallengePurpose::Enroll {
+        return Err(invalid_challenge());
+    }
+    let user = find_user(port, challenge.user_id).await?;
+    begin_enrollment(port, &user).await
+}
+
+/// Segundo paso del login. Los códigos erróneos cuentan como intentos de
+/// login fallidos y, pasado el límite, invalidan el desafío.
+pub async fn verify_challenge<P: AuthPort + TwoFactorPort + AuditPort>(
+    port: &P,
+    jwt: &JwtService,
+    policy: &LoginPolicy,
+    challenge_token: &str,
+    code: &str,
+    audit: &AuditContext,
+) -> Result<TwoFactorLogin, DomainError> {
+    let result = attempt_challenge(port, jwt, policy, challenge_token, code, audit).await;
+    match &result {
+        // Con la base caída no hay dónde auditar: se devuelve el error original.
+        Ok(_) | Err(DomainError::Infrastructure(_)) => {}
+        Err(err) => {
+            port.record_audit_event(
+                audit
+                    .event("auth", None, "two_factor_failed")
+                    .details(json!({ "reason": err.to_string() })),
+            )
+            .await?
+        }
+    }
+    result
+}
+
+async fn attempt_challenge<P: AuthPort + TwoFactorPort + AuditPort>(
+    port: &P,
+    jwt: &JwtService,
+    policy: &LoginPolicy,
+    challenge_token: &str,
+    code: &str,
+    audit: &AuditContext,
+) -> Result<TwoFactorLogin, DomainError> {
+    let client_ip = audit.ip.as_deref();
+    let now = Utc::now();
+    let challenge = find_challenge(port, challenge_token, now).await?;
+    let user = find_user(port, challenge.user_id).await?;
+    let username_key = user.username.to_lowercase();
+
+    ensure_not_throttled(port, policy, LoginScope::Username, &username_key, now).await?;
+    if let Some(ip) = client_ip {
+        ensure_not_throttled(port, policy, LoginScope::Ip, ip, now).await?;
+    }
+    if user.locked_at.is_some() {
+        return Err(account_locked());
+    }
+    if !user.active {
+        return Err(DomainError::Unauthorized("usuario desactivado".to_string()));
+    }
+
+    let verified = match challenge.purpose {
+        ChallengePurpose::Verify => verify_second_factor(port, &user, code, now)
+            .await?
+            .map(|method| (method, None)),
+        ChallengePurpose::Enroll => finish_enrollment(port, &user, code, now)
+            .await?
+            .map(|codes| (SecondFactor::Enrollment, Some(codes.codes))),
+    };
+    let Some((method, recovery_codes)) = verified else {
+        port.record_challenge_failure(challenge.id, MAX_CHALLENGE_ATTEMPTS)
+            .await?;
+        return Err(
+            register_failure(port, policy, &username_key, client_ip, Some(&user), now).await?,
+        );
+    };
+
+    if !port.consume_login_challenge(challenge.id).await? {
+        return Err(invalid_challenge());
+    }
+    port.clear_login_failures(LoginScope::Username, &username_key)
+        .await?;
+
+    let audit = audit.acting_as(user.id);
+    if recovery_codes.is_some() {
+        let enabled = find_user(port, user.id).await?;
+        port.record_audit_event(
+            audit
+                .event("user", Some(user.id), "two_factor_enabled")
+                .before(&UserAccount::from(user.clone()))
+                .after(&UserAccount::from(enabled)),
+        )
+        .await?;
+    }
+    port.record_audit_event(
+        audit
+            .event("auth", Some(user.id), "login_succeeded")
+            .details(json!({ "second_factor": method })),
+    )
+    .await?;
+
+    Ok(TwoFactorLogin {
+        tokens: issue_tokens(port, jwt, &user, Uuid::new_v4()).await?,
+        method,
+        recovery_codes,
+    })
+}
+
+/// Activación voluntaria desde una sesión: genera un secreto pendiente que se
+/// confirma con `confirm_enrollment`.
+pub async fn start_enrollment<P: AuthPort + TwoFactorPort>(
+    port: &P,
+    user_id: Uuid,
+) -> Result<TwoFactorEnrollment, DomainError> {
+    let user = find_user(port, user_id).await?;
+    begin_enrollment(port, &user).await
+}
+
+pub async fn confirm_enrollment<P: AuthPort + TwoFactorPort + AuditPort>(
+    port: &P,
+    user_id: Uuid,
+    code: &str,
+    audit: &AuditContext,
+) -> Result<RecoveryCodes, DomainError> {
+    let user = find_user(port, user_id).await?;
+    let codes = finish_enrollment(port, &user, code, Utc::now())
+        .await?
+        .ok_or_else(invalid_code)?;
+
+    let enabled = find_user(port, user_id).await?;
+    port.record_audit_event(
+        audit
+            .event("user", Some(user_id), "two_factor_enabled")
+            .before(&UserAccount::from(user))
+            .after(&UserAccount::from(enabled)),
+    )
+    .await?;
+
+    Ok(codes)
+}
+
+/// Contraseña y segundo factor vigentes: lo que se pide para tocar la
+/// configuración de 2FA desde una sesión.
+async fn reauthenticate<P: AuthPort + TwoFactorPort>(
+    port: &P,
+    policy: &LoginPolicy,
+    user_id: Uuid,
+    password: &str,
+    code: &str,
+    client_ip: Option<&str>,
+) -> Result<User, DomainError> {
+    let user = find_user(port, user_id).await?;
+    if !user.two_factor_enabled() {
+        return Err(DomainError::Validation("2FA no está activado".to_string()));
+    }
+    verify_current_password(port, policy, &user, password, client_ip).await?;
+    if verify_second_factor(port, &user, code, Utc::now())
+        .await?
+        .is_none()
+    {
+        return Err(invalid_code());
+    }
+    Ok(user)
+}
+
+pub async fn disable<P: AuthPort + TwoFactorPort + AuditPort>(
+    port: &P,
+    policy: &LoginPolicy,
+    user_id: Uuid,
+    password: &str,
+    code: &str,
+    audit: &AuditContext,
+) -> Result<(), DomainError> {
+    let user = find_user(port, user_id).await?;
+    if policy.requires_two_factor(&user.role) {
+        return Err(DomainError::Conflict(format!(
+            "2FA es obligatorio para el rol {}",
+            user.role.as_str()
+        )));
+    }
+    reauthenticate(port, policy, user_id, password, code, audit.ip.as_deref()).await?;
+    port.disable_totp(user_id).await?;
+
+    let disabled = find_user(port, user_id).await?;
+    port.record_audit_event(
+        audit
+            .event("user", Some(user_id), "two_factor_disabled")
+            .before(&UserAccount::from(user))
+            .after(&UserAccount::from(disabled)),
+    )
+    .await
+}
+
+/// Invalida los códigos de recuperación anteriores.
+pub async fn regenerate_recovery_codes<P: AuthPort + TwoFactorPort + AuditPort>(
+    port: &P,
+    policy: &LoginPolicy,
+    user_id: Uuid,
+    password: &str,
+    code: &str,
+    audit: &AuditContext,
+) -> Result<RecoveryCodes, DomainError> {
+    reauthenticate(port, policy, user_id, password, code, audit.ip.as_deref()).await?;
+    let (codes, hashes) = generate_recovery_codes();
+    port.replace_recovery_codes(user_id, hashes).await?;
+
+    // Los códigos no van al evento: sólo cuántos quedaron vigentes.
+    port.record_audit_event(
+        audit
+            .event("user", Some(user_id), "recovery_codes_regenerated")
+            .details(json!({ "codes": codes.len() })),
+    )
+    .await?;
+
+    Ok(RecoveryCodes { codes })
+}
+
+/// Para quien perdió la app y los códigos: un administrador quita el 2FA. Si
+/// el rol lo exige, el próximo login vuelve a pedir la activación. El
+/// dispositivo perdido puede estar en otras manos: se cierran todas las
+/// sesiones.
+pub async fn reset<P: AuthPort + TwoFactorPort + AuditPort>(
+    port: &P,
+    user_id: Uuid,
+    audit: &AuditContext,
+) -> Result<User, DomainError> {
+    let before = find_user(port, user_id).await?;
+    port.disable_totp(user_id).await?;
+    port.revoke_user_tokens(user_id).await?;
+    let user = find_user(port, user_id).await?;
+
+    port.record_audit_event(
+        audit
+            .event("user", Some(user_id), "two_factor_reset")
+            .before(&UserAccount::from(before))
+            .after(&UserAccount::from(user.clone())),
+    )
+    .await?;
+
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    // Vectores del apéndice B de RFC 6238 (SHA-1), recortados a 6 dígitos.
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn totp_matches_rfc_6238_vectors() {
+        let secret = base32_encode(RFC_SECRET);
+        assert_eq!(secret, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(base32_decode(&secret).unwrap(), RFC_SECRET);
+
+        let at = |seconds| Utc.timestamp_opt(seconds, 0).unwrap();
+        assert_eq!(totp_code(&secret, at(59)).unwrap(), "287082");
+        assert_eq!(totp_code(&secret, at(1111111109)).unwrap(), "081804");
+        assert_eq!(totp_code(&secret, at(1234567890)).unwrap(), "005924");
+    }
+
+    #[test]
+    fn codes_are_accepted_once_within_skew() {
+        let secret = generate_secret();
+        let now = Utc::now();
+        let previous = totp_code(&secret, now - Duration::seconds(TOTP_STEP_SECONDS)).unwrap();
+        let step = matching_step(&secret, &previous, now, None).unwrap();
+        assert_eq!(step, step_at(now) - 1);
+        assert!(matching_step(&secret, &previous, now, Some(step)).is_none());
+
+        let stale = totp_code(&secret, now - Duration::minutes(5)).unwrap();
+        assert!(matching_step(&secret, &stale, now, None).is_none());
+    }
+
+    #[test]
+    fn recovery_codes_are_grouped_and_hashed() {
+        let (codes, hashes) = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(codes[0].len(), RECOVERY_CODE_LENGTH + 1);
+        assert_eq!(
+            hash_token(&normalize_code(&codes[0].to_lowercase())),
+            hashes[0]
+        );
+        assert!(!is_totp_code(&normalize_code(&codes[0])));
+    }
+}
@@ -0,0 +1,23 @@
+use crate::domain::calendar::WorkingCalendar;
+use crate::domain::error::DomainError;
+use crate::ports::calendar_port::CalendarPort;
+use chrono::NaiveDate;
+
+pub async fn execute<P: CalendarPort>(
+    port: &P,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<WorkingCalendar, DomainError> {
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err(DomainError::Validation(
+                "from debe ser anterior o igual a to".to_string(),
+            ));
+        }
+    }
+
+    Ok(WorkingCalendar {
+        closed_weekdays: port.closed_weekdays().await?,
+        holidays: port.list_holidays(from, to).await?,
+    })
+}
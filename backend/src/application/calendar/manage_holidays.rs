@@ -0,0 +1,44 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::calendar::{Holiday, NewHoliday};
+use crate::domain::error::DomainError;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::calendar_port::CalendarPort;
+use chrono::NaiveDate;
+
+pub async fn add<P: CalendarPort + AuditPort>(
+    port: &P,
+    mut input: NewHoliday,
+    audit: &AuditContext,
+) -> Result<Holiday, DomainError> {
+    if input.description.trim().is_empty() {
+        return Err(DomainError::Validation(
+            "description es obligatorio".to_string(),
+        ));
+    }
+
+    input.description = input.description.trim().to_string();
+    let holiday = port.add_holiday(input).await?;
+    port.record_audit_event(
+        audit
+            .event("calendar", None, "holiday_added")
+            .after(&holiday),
+    )
+    .await?;
+
+    Ok(holiday)
+}
+
+pub async fn remove<P: CalendarPort + AuditPort>(
+    port: &P,
+    date: NaiveDate,
+    audit: &AuditContext,
+) -> Result<(), DomainError> {
+    let before = port.list_holidays(Some(date), Some(date)).await?;
+    port.remove_holiday(date).await?;
+
+    let mut event = audit.event("calendar", None, "holiday_removed");
+    if let Some(holiday) = before.first() {
+        event = event.before(holiday);
+    }
+    port.record_audit_event(event).await
+}
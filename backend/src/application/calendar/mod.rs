@@ -0,0 +1,22 @@
+pub mod get_calendar;
+pub mod manage_holidays;
+pub mod next_available;
+pub mod set_closed_weekdays;
+
+use crate::domain::calendar::WorkingCalendar;
+use crate::domain::error::DomainError;
+use crate::ports::calendar_port::CalendarPort;
+use chrono::NaiveDate;
+
+/// Rechaza fechas pasadas, feriados y días de cierre semanal.
+pub async fn ensure_schedulable<P: CalendarPort>(
+    port: &P,
+    date: NaiveDate,
+    today: NaiveDate,
+) -> Result<(), DomainError> {
+    let calendar = WorkingCalendar {
+        closed_weekdays: port.closed_weekdays().await?,
+        holidays: port.list_holidays(Some(date), Some(date)).await?,
+    };
+    calendar.ensure_schedulable(date, today)
+}
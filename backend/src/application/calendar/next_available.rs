@@ -0,0 +1,39 @@
+use crate::domain::calendar::{WorkingCalendar, NEXT_AVAILABLE_HORIZON_DAYS};
+use crate::domain::error::DomainError;
+use crate::domain::zones::normalize_catalog_name;
+use crate::ports::calendar_port::CalendarPort;
+use crate::ports::zones_port::ZonesPort;
+use chrono::{Duration, NaiveDate};
+
+pub async fn execute<P: CalendarPort + ZonesPort>(
+    port: &P,
+    from: Option<NaiveDate>,
+    zone_name: Option<&str>,
+    today: NaiveDate,
+) -> Result<NaiveDate, DomainError> {
+    let from = from.map_or(today, |date| date.max(today));
+
+    let zone = match zone_name {
+        Some(name) => Some(
+            port.find_zone_by_name(&normalize_catalog_name(name))
+                .await?
+                .filter(|zone| zone.active)
+                .ok_or_else(|| DomainError::NotFound("zona no encontrada".to_string()))?,
+        ),
+        None => None,
+    };
+
+    let calendar = WorkingCalendar {
+        closed_weekdays: port.closed_weekdays().await?,
+        holidays: port
+            .list_holidays(
+                Some(from),
+                Some(from + Duration::days(NEXT_AVAILABLE_HORIZON_DAYS)),
+            )
+            .await?,
+    };
+
+    calendar
+        .next_available(from, zone.as_ref())
+        .ok_or_else(|| DomainError::NotFound("no hay fechas disponibles".to_string()))
+}
@@ -0,0 +1,32 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::zones::DayOfWeek;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::calendar_port::CalendarPort;
+
+pub async fn execute<P: CalendarPort + AuditPort>(
+    port: &P,
+    mut days: Vec<DayOfWeek>,
+    audit: &AuditContext,
+) -> Result<Vec<DayOfWeek>, DomainError> {
+    days.sort();
+    days.dedup();
+
+    if days.len() == DayOfWeek::ALL.len() {
+        return Err(DomainError::Validation(
+            "no se pueden cerrar todos los días de la semana".to_string(),
+        ));
+    }
+
+    let before = port.closed_weekdays().await?;
+    port.set_closed_weekdays(&days).await?;
+    port.record_audit_event(
+        audit
+            .event("calendar", None, "closed_weekdays_set")
+            .before(&before)
+            .after(&days),
+    )
+    .await?;
+
+    Ok(days)
+}
@@ -0,0 +1,92 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::customers::{validate_address_fields, CustomerAddress, NewCustomerAddress};
+use crate::domain::error::DomainError;
+use crate::domain::zones::normalize_catalog_name;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::customers_port::CustomersPort;
+use crate::ports::zones_port::ZonesPort;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct AddressInput {
+    pub label: String,
+    pub address: String,
+    pub zone: String,
+    pub notes: Option<String>,
+}
+
+pub async fn create<P: CustomersPort + ZonesPort + AuditPort>(
+    port: &P,
+    customer_id: Uuid,
+    input: AddressInput,
+    audit: &AuditContext,
+) -> Result<CustomerAddress, DomainError> {
+    validate_address_fields(&input.label, &input.address)?;
+
+    let zone = port
+        .find_zone_by_name(&normalize_catalog_name(&input.zone))
+        .await?
+        .filter(|zone| zone.active)
+        .ok_or_else(|| {
+            DomainError::Validation(format!("zona inexistente: {}", input.zone.trim()))
+        })?;
+
+    let address = port
+        .create_customer_address(NewCustomerAddress {
+            customer_id,
+            label: input.label.trim().to_string(),
+            address: input.address.trim().to_string(),
+            zone_id: zone.id,
+            notes: input.notes,
+        })
+        .await?;
+
+    port.record_audit_event(
+        audit
+            .event("address", Some(address.id), "created")
+            .after(&address),
+    )
+    .await?;
+
+    Ok(address)
+}
+
+pub async fn list<P: CustomersPort>(
+    port: &P,
+    customer_id: Uuid,
+) -> Result<Vec<CustomerAddress>, DomainError> {
+    port.list_customer_addresses(customer_id).await
+}
+
+/// Los pedidos ya hechos conservan la dirección; sólo deja de ofrecerse.
+pub async fn remove<P: CustomersPort + AuditPort>(
+    port: &P,
+    customer_id: Uuid,
+    address_id: Uuid,
+    audit: &AuditContext,
+) -> Result<(), DomainError> {
+    let not_found = || DomainError::NotFound("dirección no encontrada".to_string());
+    let address = port
+        .find_customer_address(customer_id, address_id)
+        .await?
+        .filter(|address| address.active)
+        .ok_or_else(not_found)?;
+    if !port
+        .deactivate_customer_address(customer_id, address_id)
+        .await?
+    {
+        return Err(not_found());
+    }
+
+    let removed = CustomerAddress {
+        active: false,
+        ..address.clone()
+    };
+    port.record_audit_event(
+        audit
+            .event("address", Some(address.id), "deactivated")
+            .before(&address)
+            .after(&removed),
+    )
+    .await
+}
@@ -0,0 +1,43 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::orders::{Order, OrderStatus};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::orders_port::OrdersPort;
+use chrono::NaiveDate;
+use serde_json::json;
+use uuid::Uuid;
+
+pub async fn execute<P: OrdersPort + AuditPort>(
+    port: &P,
+    customer_id: Uuid,
+    order_id: Uuid,
+    today: NaiveDate,
+    audit: &AuditContext,
+) -> Result<Order, DomainError> {
+    // Un pedido ajeno responde igual que uno inexistente.
+    let order = port
+        .get_order_by_id(order_id)
+        .await?
+        .filter(|order| order.customer_id == Some(customer_id))
+        .ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
+
+    if !order.cancellable_by_customer(today) {
+        return Err(DomainError::Validation(
+            "sólo se pueden cancelar pedidos PENDIENTE o ASIGNADO de hoy en adelante".to_string(),
+        ));
+    }
+
+    let cancelled = port
+        .update_order_status(order.id, OrderStatus::Cancelado)
+        .await?;
+    port.record_audit_event(
+        audit
+            .event("order", Some(order.id), "cancelled")
+            .before(&order)
+            .after(&cancelled)
+            .details(json!({ "by": "customer" })),
+    )
+    .await?;
+
+    Ok(cancelled)
+}
@@ -0,0 +1,57 @@
+use crate::application::orders::create_order;
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::orders::{NewOrder, Order};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::calendar_port::CalendarPort;
+use crate::ports::customers_port::CustomersPort;
+use crate::ports::orders_port::OrdersPort;
+use crate::ports::slots_port::SlotsPort;
+use crate::ports::zones_port::ZonesPort;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct CustomerOrderInput {
+    pub address_id: Uuid,
+    pub scheduled_date: NaiveDate,
+    pub time_slot: String,
+    pub quantity: i32,
+    pub notes: Option<String>,
+}
+
+/// Pedido de autoservicio: la dirección y la zona salen de una dirección
+/// guardada del propio cliente, nunca del texto que envía.
+pub async fn execute<P>(
+    port: &P,
+    customer_id: Uuid,
+    input: CustomerOrderInput,
+    today: NaiveDate,
+    audit: &AuditContext,
+) -> Result<Order, DomainError>
+where
+    P: CustomersPort + OrdersPort + ZonesPort + SlotsPort + CalendarPort + AuditPort,
+{
+    let address = port
+        .find_customer_address(customer_id, input.address_id)
+        .await?
+        .filter(|address| address.active)
+        .ok_or_else(|| DomainError::NotFound("dirección no encontrada".to_string()))?;
+
+    create_order::execute(
+        port,
+        NewOrder {
+            address: address.address,
+            zone: address.zone,
+            scheduled_date: input.scheduled_date,
+            time_slot: input.time_slot,
+            quantity: input.quantity,
+            notes: input.notes.or(address.notes),
+            customer_id: Some(customer_id),
+            address_id: Some(address.id),
+        },
+        today,
+        audit,
+    )
+    .await
+}
@@ -0,0 +1,3 @@
+pub mod addresses;
+pub mod cancel_order;
+pub mod create_order;
@@ -0,0 +1,156 @@
+use crate::application::deliveries::{correction_reason, record_follow_up_change};
+use crate::application::slots::availability::{resolve_slot, slot_reservation};
+use crate::domain::audit::AuditContext;
+use crate::domain::delivery::{
+    corrected_order_status, DeliveryCorrection, DeliveryCorrectionKind, DeliveryPolicy,
+    FollowUpChange, NewDeliveryCorrection,
+};
+use crate::domain::error::DomainError;
+use crate::domain::orders::Order;
+use crate::domain::slots::SlotReservation;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::deliveries_port::DeliveriesPort;
+use crate::ports::orders_port::OrdersPort;
+use crate::ports::slots_port::SlotsPort;
+use crate::ports::zones_port::ZonesPort;
+use serde_json::json;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct CorrectDelivery {
+    pub delivery_id: Uuid,
+    pub llenas_entregadas: i32,
+    pub vacias_recibidas: i32,
+    pub reason: String,
+    /// Acepta más llenas que lo pedido más la tolerancia, como al registrar.
+    pub override_quantity: bool,
+}
+
+/// Reemplaza las cantidades vigentes de la entrega. La fila original queda
+/// como se registró y el stock recibe la diferencia. Las llenas pasan por la
+/// misma política que al registrar: el pedido vuelve a quedar `ENTREGADO` o
+/// `ENTREGADO_PARCIAL` y el seguimiento, si hay, se ajusta al saldo nuevo.
+pub async fn execute<P>(
+    port: &P,
+    input: CorrectDelivery,
+    policy: &DeliveryPolicy,
+    audit: &AuditContext,
+) -> Result<DeliveryCorrection, DomainError>
+where
+    P: DeliveriesPort + OrdersPort + ZonesPort + SlotsPort + AuditPort,
+{
+    if input.llenas_entregadas < 0 || input.vacias_recibidas < 0 {
+        return Err(DomainError::Validation(
+            "llenas_entregadas y vacias_recibidas deben ser >= 0".to_string(),
+        ));
+    }
+    let reason = correction_reason(&input.reason)?;
+
+    let delivery = port
+        .get_delivery(input.delivery_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("entrega no encontrada".to_string()))?;
+    let order = port
+        .get_order_by_id(delivery.order_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
+    let follow_up = match delivery.follow_up_order_id {
+        Some(id) => port.get_order_by_id(id).await?,
+        None => None,
+    };
+
+    let shortfall = policy.shortfall(
+        order.quantity,
+        input.llenas_entregadas,
+        input.override_quantity,
+    )?;
+    let change = match &follow_up {
+        Some(follow_up) => FollowUpChange::after_correction(follow_up, shortfall)?,
+        None => FollowUpChange::Keep,
+    };
+    let follow_up_reservation = match (&follow_up, change) {
+        (Some(follow_up), FollowUpChange::Resize(quantity)) if quantity > follow_up.quantity => {
+            follow_up_reservation(port, follow_up).await?
+        }
+        _ => None,
+    };
+
+    let correction = port
+        .correct_delivery(NewDeliveryCorrection {
+            delivery_id: input.delivery_id,
+            kind: DeliveryCorrectionKind::Correccion,
+            llenas_entregadas: input.llenas_entregadas,
+            vacias_recibidas: input.vacias_recibidas,
+            reason,
+            corrected_by: audit.actor_id,
+            order_status: corrected_order_status(shortfall, follow_up.as_ref()),
+            follow_up: change,
+            follow_up_reservation,
+        })
+        .await?;
+
+    port.record_audit_event(
+        audit
+            .event("delivery", Some(correction.delivery_id), "corrected")
+            .after(&correction),
+    )
+    .await?;
+    if policy.exceeds_tolerance(order.quantity, correction.llenas_despues) {
+        port.record_audit_event(
+            audit
+                .event(
+                    "delivery",
+                    Some(correction.delivery_id),
+                    "quantity_overridden",
+                )
+                .after(&json!({
+                    "quantity": order.quantity,
+                    "llenas_entregadas": correction.llenas_despues,
+                    "over_tolerance": policy.over_tolerance,
+                })),
+        )
+        .await?;
+    }
+    let corrected = port
+        .get_order_by_id(order.id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
+    if corrected.status != order.status {
+        port.record_audit_event(
+            audit
+                .event("order", Some(order.id), "status_changed")
+                .before(&order)
+                .after(&corrected),
+        )
+        .await?;
+    }
+    if let Some(follow_up) = follow_up {
+        let action = match change {
+            FollowUpChange::Keep => None,
+            FollowUpChange::Cancel => Some("cancelled"),
+            FollowUpChange::Resize(_) => Some("updated"),
+        };
+        if let Some(action) = action {
+            record_follow_up_change(port, &follow_up, action, audit).await?;
+        }
+    }
+
+    Ok(correction)
+}
+
+/// Cupo de la franja del seguimiento. Pedidos previos al catálogo de zonas
+/// no tienen zona para medirlo.
+async fn follow_up_reservation<P: ZonesPort + SlotsPort>(
+    port: &P,
+    follow_up: &Order,
+) -> Result<Option<SlotReservation>, DomainError> {
+    let Some(zone_id) = follow_up.zone_id else {
+        return Ok(None);
+    };
+    let zone = port
+        .get_zone_by_id(zone_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("zona no encontrada".to_string()))?;
+    let slot = resolve_slot(port, &follow_up.time_slot).await?;
+    Ok(Some(slot_reservation(port, &zone, &slot).await?))
+}
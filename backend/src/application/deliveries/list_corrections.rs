@@ -0,0 +1,28 @@
+use crate::domain::delivery::DeliveryCorrection;
+use crate::domain::error::DomainError;
+use crate::domain::orders::OrderViewer;
+use crate::ports::deliveries_port::DeliveriesPort;
+use crate::ports::orders_port::OrdersPort;
+use uuid::Uuid;
+
+pub async fn execute<P: DeliveriesPort + OrdersPort>(
+    port: &P,
+    delivery_id: Uuid,
+    viewer: &OrderViewer,
+) -> Result<Vec<DeliveryCorrection>, DomainError> {
+    let delivery = port
+        .get_delivery(delivery_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("entrega no encontrada".to_string()))?;
+    let order = port
+        .get_order_by_id(delivery.order_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
+    if !viewer.can_see(&order) {
+        return Err(DomainError::Unauthorized(
+            "no podés ver las correcciones de este pedido".to_string(),
+        ));
+    }
+
+    port.list_delivery_corrections(delivery_id).await
+}
@@ -1,2 +1,41 @@
+pub mod correct_delivery;
+pub mod list_corrections;
 pub mod register_delivery;
 pub mod register_failed_delivery;
+pub mod void_delivery;
+
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::orders::Order;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::orders_port::OrdersPort;
+
+/// Toda corrección explica por qué se cambió algo que el repartidor ya había
+/// registrado.
+fn correction_reason(reason: &str) -> Result<String, DomainError> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(DomainError::Validation("reason es obligatorio".to_string()));
+    }
+    Ok(reason.to_string())
+}
+
+/// Deja en la auditoría cómo quedó el seguimiento tras corregir o anular.
+async fn record_follow_up_change<P: OrdersPort + AuditPort>(
+    port: &P,
+    follow_up: &Order,
+    action: &str,
+    audit: &AuditContext,
+) -> Result<(), DomainError> {
+    let after = port
+        .get_order_by_id(follow_up.id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
+    port.record_audit_event(
+        audit
+            .event("order", Some(follow_up.id), action)
+            .before(follow_up)
+            .after(&after),
+    )
+    .await
+}
@@ -1,21 +1,63 @@
-use crate::domain::delivery::{Delivery, NewDelivery};
+use crate::application::calendar::next_available;
+use crate::application::orders::create_order;
+use crate::application::positions::delivery_position;
+use crate::domain::audit::AuditContext;
+use crate::domain::delivery::{
+    Delivery, DeliveryPolicy, DeliveryRemainder, NewDelivery, RegisteredDelivery,
+};
 use crate::domain::error::DomainError;
-use crate::domain::orders::OrderStatus;
+use crate::domain::orders::{NewOrder, Order, OrderStatus};
+use crate::domain::slots::SlotReservation;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::calendar_port::CalendarPort;
 use crate::ports::deliveries_port::DeliveriesPort;
 use crate::ports::orders_port::OrdersPort;
+use crate::ports::positions_port::PositionsPort;
+use crate::ports::slots_port::SlotsPort;
+use crate::ports::zones_port::ZonesPort;
+use chrono::{Duration, NaiveDate, Utc};
+use serde_json::json;
 
-pub async fn execute<P: OrdersPort + DeliveriesPort>(
+#[derive(Debug, Clone)]
+pub struct RegisterDelivery {
+    pub delivery: NewDelivery,
+    /// Sólo cuenta si se entregó menos de lo pedido; por defecto `PARCIAL`.
+    pub remainder: Option<DeliveryRemainder>,
+    /// Fecha del pedido de seguimiento; por defecto el próximo día hábil de
+    /// la zona a partir de mañana.
+    pub follow_up_date: Option<NaiveDate>,
+    /// Franja del pedido de seguimiento; por defecto la del original.
+    pub follow_up_time_slot: Option<String>,
+    /// Acepta más llenas que lo pedido más la tolerancia. Quién puede pedirlo
+    /// lo decide el handler.
+    pub override_quantity: bool,
+}
+
+pub async fn execute<P>(
     port: &P,
-    input: NewDelivery,
-) -> Result<Delivery, DomainError> {
-    if input.llenas_entregadas < 0 || input.vacias_recibidas < 0 {
+    input: RegisterDelivery,
+    policy: &DeliveryPolicy,
+    today: NaiveDate,
+    audit: &AuditContext,
+) -> Result<Delivery, DomainError>
+where
+    P: OrdersPort
+        + DeliveriesPort
+        + PositionsPort
+        + ZonesPort
+        + SlotsPort
+        + CalendarPort
+        + AuditPort,
+{
+    let mut new_delivery = input.delivery;
+    if new_delivery.llenas_entregadas < 0 || new_delivery.vacias_recibidas < 0 {
         return Err(DomainError::Validation(
             "llenas_entregadas y vacias_recibidas deben ser >= 0".to_string(),
         ));
     }
 
     let order = port
-        .get_order_by_id(input.order_id)
+        .get_order_by_id(new_delivery.order_id)
         .await?
         .ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
 
@@ -25,10 +67,116 @@ pub async fn execute<P: OrdersPort + DeliveriesPort>(
         ));
     }
 
-    let delivery = port.create_delivery(input).await?;
-    let _ = port
-        .update_order_status(delivery.order_id, OrderStatus::Entregado)
+    let shortfall = policy.shortfall(
+        order.quantity,
+        new_delivery.llenas_entregadas,
+        input.override_quantity,
+    )?;
+    let remainder = input.remainder.unwrap_or(DeliveryRemainder::Parcial);
+
+    new_delivery.position =
+        delivery_position(port, &order, new_delivery.position, Utc::now()).await?;
+
+    // El seguimiento, la entrega y el estado del pedido se escriben juntos:
+    // si la fecha o la franja del seguimiento no tienen lugar, no queda nada
+    // registrado.
+    let follow_up = match shortfall {
+        Some(pending) if remainder == DeliveryRemainder::Seguimiento => Some(
+            prepare_follow_up(
+                port,
+                &order,
+                pending,
+                input.follow_up_date,
+                input.follow_up_time_slot,
+                today,
+            )
+            .await?,
+        ),
+        _ => None,
+    };
+    let status = if shortfall.is_some() && follow_up.is_none() {
+        OrderStatus::EntregadoParcial
+    } else {
+        OrderStatus::Entregado
+    };
+
+    let RegisteredDelivery {
+        delivery,
+        order: delivered,
+        follow_up,
+    } = port
+        .create_delivery(new_delivery, status, follow_up)
         .await?;
 
+    if let Some(follow_up) = &follow_up {
+        create_order::record_created(port, follow_up, audit).await?;
+    }
+    port.record_audit_event(
+        audit
+            .event("delivery", Some(delivery.id), "created")
+            .after(&delivery),
+    )
+    .await?;
+    if policy.exceeds_tolerance(order.quantity, delivery.llenas_entregadas) {
+        port.record_audit_event(
+            audit
+                .event("delivery", Some(delivery.id), "quantity_overridden")
+                .after(&json!({
+                    "quantity": order.quantity,
+                    "llenas_entregadas": delivery.llenas_entregadas,
+                    "over_tolerance": policy.over_tolerance,
+                })),
+        )
+        .await?;
+    }
+    port.record_audit_event(
+        audit
+            .event("order", Some(order.id), "status_changed")
+            .before(&order)
+            .after(&delivered),
+    )
+    .await?;
+
     Ok(delivery)
 }
+
+async fn prepare_follow_up<P>(
+    port: &P,
+    order: &Order,
+    quantity: i32,
+    date: Option<NaiveDate>,
+    time_slot: Option<String>,
+    today: NaiveDate,
+) -> Result<(NewOrder, SlotReservation), DomainError>
+where
+    P: ZonesPort + SlotsPort + CalendarPort,
+{
+    let scheduled_date = match date {
+        Some(date) => date,
+        None => {
+            next_available::execute(
+                port,
+                Some(today + Duration::days(1)),
+                Some(&order.zone),
+                today,
+            )
+            .await?
+        }
+    };
+
+    create_order::prepare(
+        port,
+        NewOrder {
+            address: order.address.clone(),
+            zone: order.zone.clone(),
+            scheduled_date,
+            time_slot: time_slot.unwrap_or_else(|| order.time_slot.clone()),
+            quantity,
+            notes: Some(format!("Saldo del pedido {}", order.id)),
+            customer_id: order.customer_id,
+            address_id: order.address_id,
+        },
+        today,
+    )
+    .await
+}
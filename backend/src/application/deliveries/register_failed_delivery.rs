@@ -1,13 +1,34 @@
-use crate::domain::delivery::{FailedDelivery, NewFailedDelivery};
+use crate::application::calendar::ensure_schedulable;
+use crate::application::positions::delivery_position;
+use crate::application::slots::availability::{ensure_capacity, resolve_slot, slot_reservation};
+use crate::domain::audit::AuditContext;
+use crate::domain::delivery::{FailedDelivery, NewFailedDelivery, RegisteredFailure};
 use crate::domain::error::DomainError;
 use crate::domain::orders::OrderStatus;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::calendar_port::CalendarPort;
 use crate::ports::deliveries_port::DeliveriesPort;
 use crate::ports::orders_port::OrdersPort;
+use crate::ports::positions_port::PositionsPort;
+use crate::ports::slots_port::SlotsPort;
+use crate::ports::zones_port::ZonesPort;
+use chrono::{NaiveDate, Utc};
 
-pub async fn execute<P: OrdersPort + DeliveriesPort>(
+pub async fn execute<P>(
     port: &P,
-    input: NewFailedDelivery,
-) -> Result<FailedDelivery, DomainError> {
+    mut input: NewFailedDelivery,
+    today: NaiveDate,
+    audit: &AuditContext,
+) -> Result<FailedDelivery, DomainError>
+where
+    P: OrdersPort
+        + DeliveriesPort
+        + ZonesPort
+        + SlotsPort
+        + CalendarPort
+        + PositionsPort
+        + AuditPort,
+{
     if input.reason.trim().is_empty() {
         return Err(DomainError::Validation("reason es obligatorio".to_string()));
     }
@@ -23,13 +44,72 @@ pub async fn execute<P: OrdersPort + DeliveriesPort>(
         ));
     }
 
-    let failed = port.create_failed_delivery(input.clone()).await?;
-
     let new_date = input.reprogram_date.unwrap_or(order.scheduled_date);
-    let new_slot = input.reprogram_time_slot.unwrap_or(order.time_slot);
+    let mut new_slot = input
+        .reprogram_time_slot
+        .clone()
+        .unwrap_or_else(|| order.time_slot.clone());
+
+    if let Some(reprogram_date) = input.reprogram_date {
+        ensure_schedulable(port, reprogram_date, today).await?;
+    }
+
+    let mut reservation = None;
+    if input.reprogram_date.is_some() || input.reprogram_time_slot.is_some() {
+        let slot = resolve_slot(port, &new_slot).await?;
+        // Pedidos previos al catálogo de zonas no tienen zona para medir cupo.
+        if let Some(zone_id) = order.zone_id {
+            let zone = port
+                .get_zone_by_id(zone_id)
+                .await?
+                .ok_or_else(|| DomainError::NotFound("zona no encontrada".to_string()))?;
+            if !zone.serves(new_date) {
+                return Err(DomainError::Validation(format!(
+                    "la zona {} no tiene reparto ese día",
+                    zone.name
+                )));
+            }
+            let slot_reservation = slot_reservation(port, &zone, &slot).await?;
+            ensure_capacity(
+                port,
+                new_date,
+                &slot_reservation,
+                order.quantity,
+                Some(order.id),
+            )
+            .await?;
+            reservation = Some(slot_reservation);
+        }
+        new_slot = slot.name;
+        if input.reprogram_time_slot.is_some() {
+            input.reprogram_time_slot = Some(new_slot.clone());
+        }
+    }
+
+    input.position = delivery_position(port, &order, input.position, Utc::now()).await?;
+    // Reprogramación mínima: se mantiene el pedido en ASIGNADO para nuevo
+    // intento. La falla y la reprogramación se escriben juntas: si la franja
+    // se llenó mientras tanto, no queda nada registrado.
+    let RegisteredFailure {
+        failed,
+        order: reprogrammed,
+    } = port
+        .create_failed_delivery(input, new_date, new_slot, reservation)
+        .await?;
 
-    // Reprogramación mínima: se mantiene el pedido en ASIGNADO para nuevo intento.
-    let _ = port.reprogram_order(order.id, new_date, new_slot).await?;
+    port.record_audit_event(
+        audit
+            .event("delivery_failure", Some(failed.id), "created")
+            .after(&failed),
+    )
+    .await?;
+    port.record_audit_event(
+        audit
+            .event("order", Some(order.id), "reprogrammed")
+            .before(&order)
+            .after(&reprogrammed),
+    )
+    .await?;
 
     Ok(failed)
 }
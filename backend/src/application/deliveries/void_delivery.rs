@@ -0,0 +1,78 @@
+use crate::application::deliveries::{correction_reason, record_follow_up_change};
+use crate::domain::audit::AuditContext;
+use crate::domain::delivery::{
+    DeliveryCorrection, DeliveryCorrectionKind, FollowUpChange, NewDeliveryCorrection,
+};
+use crate::domain::error::DomainError;
+use crate::domain::orders::OrderStatus;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::deliveries_port::DeliveriesPort;
+use crate::ports::orders_port::OrdersPort;
+use uuid::Uuid;
+
+/// Anula una entrega registrada por error: compensa todo su efecto en el
+/// stock y devuelve el pedido a `ASIGNADO` para registrar la entrega real.
+/// El seguimiento de una entrega parcial se cancela con ella; si ya salió a
+/// reparto, la anulación se rechaza.
+pub async fn execute<P: DeliveriesPort + OrdersPort + AuditPort>(
+    port: &P,
+    delivery_id: Uuid,
+    reason: &str,
+    audit: &AuditContext,
+) -> Result<DeliveryCorrection, DomainError> {
+    let reason = correction_reason(reason)?;
+    let delivery = port
+        .get_delivery(delivery_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("entrega no encontrada".to_string()))?;
+    let order = port
+        .get_order_by_id(delivery.order_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
+    let follow_up = match delivery.follow_up_order_id {
+        Some(id) => port.get_order_by_id(id).await?,
+        None => None,
+    };
+    let change = match &follow_up {
+        Some(follow_up) => FollowUpChange::after_void(follow_up)?,
+        None => FollowUpChange::Keep,
+    };
+
+    let correction = port
+        .correct_delivery(NewDeliveryCorrection {
+            delivery_id,
+            kind: DeliveryCorrectionKind::Anulacion,
+            llenas_entregadas: 0,
+            vacias_recibidas: 0,
+            reason,
+            corrected_by: audit.actor_id,
+            order_status: OrderStatus::Asignado,
+            follow_up: change,
+            follow_up_reservation: None,
+        })
+        .await?;
+    let reopened = port
+        .get_order_by_id(order.id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("pedido no encontrado".to_string()))?;
+
+    port.record_audit_event(
+        audit
+            .event("delivery", Some(delivery_id), "voided")
+            .before(&delivery)
+            .after(&correction),
+    )
+    .await?;
+    port.record_audit_event(
+        audit
+            .event("order", Some(order.id), "status_changed")
+            .before(&order)
+            .after(&reopened),
+    )
+    .await?;
+    if let (Some(follow_up), FollowUpChange::Cancel) = (&follow_up, change) {
+        record_follow_up_change(port, follow_up, "cancelled", audit).await?;
+    }
+
+    Ok(correction)
+}
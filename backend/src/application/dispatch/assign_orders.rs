@@ -1,11 +1,15 @@
+use crate::domain::audit::AuditContext;
 use crate::domain::error::DomainError;
+use crate::ports::audit_port::AuditPort;
 use crate::ports::orders_port::OrdersPort;
+use serde_json::json;
 use uuid::Uuid;
 
-pub async fn execute<P: OrdersPort>(
+pub async fn execute<P: OrdersPort + AuditPort>(
     port: &P,
     order_ids: Vec<Uuid>,
     driver_id: Uuid,
+    audit: &AuditContext,
 ) -> Result<(), DomainError> {
     if order_ids.is_empty() {
         return Err(DomainError::Validation(
@@ -13,5 +17,25 @@ pub async fn execute<P: OrdersPort>(
         ));
     }
 
-    port.assign_orders(&order_ids, driver_id).await
+    let mut before = Vec::with_capacity(order_ids.len());
+    for order_id in &order_ids {
+        before.push(port.get_order_by_id(*order_id).await?);
+    }
+
+    port.assign_orders(&order_ids, driver_id).await?;
+
+    for (order_id, before) in order_ids.into_iter().zip(before) {
+        let mut event = audit
+            .event("order", Some(order_id), "assigned")
+            .details(json!({ "driver_id": driver_id }));
+        if let Some(before) = before {
+            event = event.before(&before);
+        }
+        if let Some(after) = port.get_order_by_id(order_id).await? {
+            event = event.after(&after);
+        }
+        port.record_audit_event(event).await?;
+    }
+
+    Ok(())
 }
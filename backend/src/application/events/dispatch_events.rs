@@ -0,0 +1,176 @@
+use crate::domain::error::DomainError;
+use crate::domain::events::{
+    outbox_retry_delay, OUTBOX_BATCH_SIZE, OUTBOX_CLAIM_LEASE_SECONDS, OUTBOX_CLAIM_RENEW_SECONDS,
+};
+use crate::ports::event_sink_port::EventSink;
+use crate::ports::outbox_port::OutboxPort;
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+use std::time::Instant;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DispatchReport {
+    pub claimed: usize,
+    pub published: usize,
+    pub failed: usize,
+}
+
+/// Entrega un lote del outbox a los sinks. Un evento queda publicado sólo si
+/// todos los sinks que lo aceptan lo recibieron; si no, se reintenta entero.
+pub async fn execute<P: OutboxPort>(
+    port: &P,
+    sinks: &[Arc<dyn EventSink>],
+    now: DateTime<Utc>,
+) -> Result<DispatchReport, DomainError> {
+    dispatch(
+        port,
+        sinks,
+        now,
+        std::time::Duration::from_secs(OUTBOX_CLAIM_RENEW_SECONDS as u64),
+    )
+    .await
+}
+
+async fn dispatch<P: OutboxPort>(
+    port: &P,
+    sinks: &[Arc<dyn EventSink>],
+    now: DateTime<Utc>,
+    renew_after: std::time::Duration,
+) -> Result<DispatchReport, DomainError> {
+    let events = port.claim_outbox_events(now, OUTBOX_BATCH_SIZE).await?;
+    let mut report = DispatchReport {
+        claimed: events.len(),
+        ..DispatchReport::default()
+    };
+
+    let started = Instant::now();
+    let mut renewed = started;
+    for (index, event) in events.iter().enumerate() {
+        // Si la reserva vence a mitad del lote, otra réplica (o el próximo
+        // ciclo) vuelve a tomar lo que falta y lo entrega dos veces.
+        if renewed.elapsed() >= renew_after {
+            let elapsed = Duration::from_std(started.elapsed()).unwrap_or_default();
+            let pending: Vec<_> = events[index..].iter().map(|event| event.id).collect();
+            port.extend_outbox_claims(
+                &pending,
+                now + elapsed + Duration::seconds(OUTBOX_CLAIM_LEASE_SECONDS),
+            )
+            .await?;
+            renewed = Instant::now();
+        }
+
+        let mut errors = Vec::new();
+        for sink in sinks.iter().filter(|sink| sink.accepts(event.event_type)) {
+            if let Err(err) = sink.publish(event).await {
+                errors.push(format!("{}: {}", sink.name(), err));
+            }
+        }
+
+        if errors.is_empty() {
+            port.mark_event_published(event.id, now).await?;
+            report.published += 1;
+        } else {
+            let retry_at = outbox_retry_delay(event.attempts).map(|delay| now + delay);
+            port.mark_event_failed(event.id, &errors.join("; "), retry_at)
+                .await?;
+            report.failed += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::events::{DomainEventType, OutboxEvent};
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    /// Outbox en memoria que anota las renovaciones de la reserva.
+    struct MemoryOutbox {
+        events: Vec<OutboxEvent>,
+        extended: Mutex<Vec<Vec<Uuid>>>,
+    }
+
+    #[async_trait]
+    impl OutboxPort for MemoryOutbox {
+        async fn claim_outbox_events(
+            &self,
+            _now: DateTime<Utc>,
+            _limit: i64,
+        ) -> Result<Vec<OutboxEvent>, DomainError> {
+            Ok(self.events.clone())
+        }
+
+        async fn extend_outbox_claims(
+            &self,
+            event_ids: &[Uuid],
+            _until: DateTime<Utc>,
+        ) -> Result<(), DomainError> {
+            self.extended.lock().unwrap().push(event_ids.to_vec());
+            Ok(())
+        }
+
+        async fn find_outbox_event(
+            &self,
+            _event_id: Uuid,
+        ) -> Result<Option<OutboxEvent>, DomainError> {
+            unimplemented!()
+        }
+
+        async fn mark_event_published(
+            &self,
+            _event_id: Uuid,
+            _now: DateTime<Utc>,
+        ) -> Result<(), DomainError> {
+            Ok(())
+        }
+
+        async fn mark_event_failed(
+            &self,
+            _event_id: Uuid,
+            _error: &str,
+            _retry_at: Option<DateTime<Utc>>,
+        ) -> Result<(), DomainError> {
+            Ok(())
+        }
+    }
+
+    fn event() -> OutboxEvent {
+        OutboxEvent {
+            id: Uuid::new_v4(),
+            event_type: DomainEventType::OrderCreated,
+            aggregate_id: Uuid::new_v4(),
+            payload: json!({}),
+            occurred_at: Utc::now(),
+            attempts: 1,
+            assignee_id: None,
+            customer_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn renews_the_claim_on_what_is_left_of_the_batch() {
+        let outbox = MemoryOutbox {
+            events: vec![event(), event(), event()],
+            extended: Mutex::new(Vec::new()),
+        };
+        let ids: Vec<Uuid> = outbox.events.iter().map(|event| event.id).collect();
+
+        let report = dispatch(&outbox, &[], Utc::now(), std::time::Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(report.published, 3);
+        assert_eq!(
+            *outbox.extended.lock().unwrap(),
+            vec![ids.clone(), ids[1..].to_vec(), ids[2..].to_vec()]
+        );
+
+        outbox.extended.lock().unwrap().clear();
+        execute(&outbox, &[], Utc::now()).await.unwrap();
+        assert!(outbox.extended.lock().unwrap().is_empty());
+    }
+}
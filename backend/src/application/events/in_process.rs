@@ -0,0 +1,54 @@
+use crate::domain::error::DomainError;
+use crate::domain::events::{DomainEventType, OutboxEvent};
+use crate::ports::event_sink_port::{EventSink, EventSubscriber};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Sink que reparte los eventos entre suscriptores del mismo proceso.
+#[derive(Clone, Default)]
+pub struct InProcessSink {
+    subscribers: Vec<Arc<dyn EventSubscriber>>,
+}
+
+impl InProcessSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(mut self, subscriber: Arc<dyn EventSubscriber>) -> Self {
+        self.subscribers.push(subscriber);
+        self
+    }
+}
+
+#[async_trait]
+impl EventSink for InProcessSink {
+    fn name(&self) -> &str {
+        "in_process"
+    }
+
+    fn accepts(&self, event_type: DomainEventType) -> bool {
+        self.subscribers
+            .iter()
+            .any(|subscriber| subscriber.handles(event_type))
+    }
+
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), DomainError> {
+        let mut errors = Vec::new();
+        for subscriber in self
+            .subscribers
+            .iter()
+            .filter(|subscriber| subscriber.handles(event.event_type))
+        {
+            if let Err(err) = subscriber.handle(event).await {
+                errors.push(format!("{}: {}", subscriber.name(), err));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(DomainError::Infrastructure(errors.join("; ")))
+        }
+    }
+}
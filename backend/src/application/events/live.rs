@@ -0,0 +1,36 @@
+use crate::domain::events::OutboxEvent;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Eventos pendientes por suscriptor antes de que éste empiece a perderlos.
+const LIVE_EVENTS_BUFFER: usize = 1024;
+
+/// Difusión en memoria de los eventos recién confirmados hacia los streams
+/// abiertos en esta réplica. Lo alimenta el listener de `NOTIFY`, así cada
+/// réplica recibe también lo que escribieron las demás.
+#[derive(Clone)]
+pub struct LiveEvents {
+    sender: broadcast::Sender<Arc<OutboxEvent>>,
+}
+
+impl LiveEvents {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(LIVE_EVENTS_BUFFER);
+        Self { sender }
+    }
+
+    /// Sin suscriptores el evento simplemente se descarta.
+    pub fn publish(&self, event: OutboxEvent) {
+        let _ = self.sender.send(Arc::new(event));
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<OutboxEvent>> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for LiveEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,3 @@
+pub mod dispatch_events;
+pub mod in_process;
+pub mod live;
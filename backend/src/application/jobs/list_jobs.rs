@@ -0,0 +1,37 @@
+use crate::application::jobs::JobRegistry;
+use crate::domain::error::DomainError;
+use crate::domain::jobs::{JobRun, JobSummary, MAX_JOB_RUNS_LIMIT};
+use crate::ports::jobs_port::JobsPort;
+
+pub async fn execute<P: JobsPort>(
+    port: &P,
+    registry: &JobRegistry,
+) -> Result<Vec<JobSummary>, DomainError> {
+    let mut result = Vec::new();
+    for job in registry.jobs() {
+        result.push(JobSummary {
+            name: job.name().to_string(),
+            description: job.kind.description().to_string(),
+            schedule: job.schedule.expression().to_string(),
+            next_run_at: port.job_next_run_at(job.name()).await?,
+            last_run: port.list_job_runs(job.name(), 1).await?.into_iter().next(),
+        });
+    }
+    Ok(result)
+}
+
+pub async fn runs<P: JobsPort>(
+    port: &P,
+    registry: &JobRegistry,
+    name: &str,
+    limit: i64,
+) -> Result<Vec<JobRun>, DomainError> {
+    if !(1..=MAX_JOB_RUNS_LIMIT).contains(&limit) {
+        return Err(DomainError::Validation(format!(
+            "limit debe estar entre 1 y {}",
+            MAX_JOB_RUNS_LIMIT
+        )));
+    }
+    let job = registry.find(name)?;
+    port.list_job_runs(job.name(), limit).await
+}
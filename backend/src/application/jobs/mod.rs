@@ -0,0 +1,134 @@
+pub mod list_jobs;
+pub mod register_jobs;
+pub mod run_job;
+
+use crate::domain::calendar::CalendarPolicy;
+use crate::domain::error::DomainError;
+use crate::domain::jobs::CronSchedule;
+use crate::domain::positions::DEFAULT_POSITIONS_RETENTION_DAYS;
+use crate::domain::recurring::DEFAULT_RECURRING_DAYS_AHEAD;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobKind {
+    /// Genera los pedidos de las plantillas recurrentes.
+    RecurringOrders {
+        days_ahead: i64,
+        calendar: CalendarPolicy,
+    },
+    /// Borra el historial de ejecuciones viejo.
+    PurgeJobRuns { retention_days: i64 },
+    /// Borra los refresh tokens vencidos.
+    PurgeRefreshTokens,
+    /// Borra las posiciones GPS fuera de la retención.
+    PurgeDriverPositions { retention_days: i64 },
+}
+
+impl JobKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::RecurringOrders { .. } => "recurring_orders",
+            Self::PurgeJobRuns { .. } => "purge_job_runs",
+            Self::PurgeRefreshTokens => "purge_refresh_tokens",
+            Self::PurgeDriverPositions { .. } => "purge_driver_positions",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::RecurringOrders { .. } => "Materializa pedidos recurrentes",
+            Self::PurgeJobRuns { .. } => "Depura el historial de ejecuciones de jobs",
+            Self::PurgeRefreshTokens => "Elimina refresh tokens vencidos",
+            Self::PurgeDriverPositions { .. } => "Depura posiciones GPS de repartidores",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JobDefinition {
+    pub kind: JobKind,
+    pub schedule: CronSchedule,
+}
+
+impl JobDefinition {
+    pub fn name(&self) -> &'static str {
+        self.kind.name()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct JobsConfig {
+    pub recurring_orders_cron: String,
+    pub recurring_days_ahead: i64,
+    /// Calendario con el que se cuenta `recurring_days_ahead` desde hoy.
+    pub calendar: CalendarPolicy,
+    pub purge_job_runs_cron: String,
+    pub job_runs_retention_days: i64,
+    pub purge_refresh_tokens_cron: String,
+    pub purge_driver_positions_cron: String,
+    pub driver_positions_retention_days: i64,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            recurring_orders_cron: "0 * * * *".to_string(),
+            recurring_days_ahead: DEFAULT_RECURRING_DAYS_AHEAD,
+            calendar: CalendarPolicy::default(),
+            purge_job_runs_cron: "30 3 * * *".to_string(),
+            job_runs_retention_days: 30,
+            purge_refresh_tokens_cron: "0 4 * * *".to_string(),
+            purge_driver_positions_cron: "15 4 * * *".to_string(),
+            driver_positions_retention_days: DEFAULT_POSITIONS_RETENTION_DAYS,
+        }
+    }
+}
+
+/// Jobs conocidos por el proceso; el código define qué hace cada uno y la
+/// configuración cuándo corre.
+#[derive(Debug, Clone)]
+pub struct JobRegistry {
+    jobs: Vec<JobDefinition>,
+}
+
+impl JobRegistry {
+    pub fn build(config: &JobsConfig) -> Result<Self, DomainError> {
+        Ok(Self {
+            jobs: vec![
+                JobDefinition {
+                    kind: JobKind::RecurringOrders {
+                        days_ahead: config.recurring_days_ahead,
+                        calendar: config.calendar,
+                    },
+                    schedule: CronSchedule::parse(&config.recurring_orders_cron)?,
+                },
+                JobDefinition {
+                    kind: JobKind::PurgeJobRuns {
+                        retention_days: config.job_runs_retention_days,
+                    },
+                    schedule: CronSchedule::parse(&config.purge_job_runs_cron)?,
+                },
+                JobDefinition {
+                    kind: JobKind::PurgeRefreshTokens,
+                    schedule: CronSchedule::parse(&config.purge_refresh_tokens_cron)?,
+                },
+                JobDefinition {
+                    kind: JobKind::PurgeDriverPositions {
+                        retention_days: config.driver_positions_retention_days,
+                    },
+                    schedule: CronSchedule::parse(&config.purge_driver_positions_cron)?,
+                },
+            ],
+        })
+    }
+
+    pub fn jobs(&self) -> &[JobDefinition] {
+        &self.jobs
+    }
+
+    pub fn find(&self, name: &str) -> Result<&JobDefinition, DomainError> {
+        self.jobs
+            .iter()
+            .find(|job| job.name() == name)
+            .ok_or_else(|| DomainError::NotFound(format!("job no encontrado: {}", name)))
+    }
+}
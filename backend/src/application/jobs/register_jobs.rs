@@ -0,0 +1,19 @@
+use crate::application::jobs::JobRegistry;
+use crate::domain::error::DomainError;
+use crate::ports::jobs_port::JobsPort;
+use chrono::{DateTime, Utc};
+
+pub async fn execute<P: JobsPort>(
+    port: &P,
+    registry: &JobRegistry,
+    now: DateTime<Utc>,
+) -> Result<(), DomainError> {
+    for job in registry.jobs() {
+        let next_run_at = job.schedule.next_after(now).ok_or_else(|| {
+            DomainError::Infrastructure(format!("el job {} no tiene próxima ejecución", job.name()))
+        })?;
+        port.register_job(job.name(), job.schedule.expression(), next_run_at)
+            .await?;
+    }
+    Ok(())
+}
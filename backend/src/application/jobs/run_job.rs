@@ -0,0 +1,137 @@
+use crate::application::jobs::{JobDefinition, JobKind, JobRegistry};
+use crate::application::recurring::materialize;
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::jobs::{JobRun, JobRunStatus, JobTrigger};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::auth_port::AuthPort;
+use crate::ports::calendar_port::CalendarPort;
+use crate::ports::jobs_port::{ClaimedJobRun, JobsPort};
+use crate::ports::orders_port::OrdersPort;
+use crate::ports::positions_port::PositionsPort;
+use crate::ports::recurring_port::RecurringOrdersPort;
+use crate::ports::slots_port::SlotsPort;
+use crate::ports::zones_port::ZonesPort;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::json;
+
+/// Ejecuta el job si esta réplica logra tomarlo; `None` significa que no
+/// correspondía (otra réplica lo tiene o todavía no venció).
+pub async fn execute<P>(
+    port: &P,
+    job: &JobDefinition,
+    trigger: JobTrigger,
+    now: DateTime<Utc>,
+    audit: &AuditContext,
+) -> Result<Option<JobRun>, DomainError>
+where
+    P: JobsPort
+        + AuthPort
+        + AuditPort
+        + RecurringOrdersPort
+        + OrdersPort
+        + ZonesPort
+        + SlotsPort
+        + CalendarPort
+        + PositionsPort,
+{
+    let next_run_at = job.schedule.next_after(now).ok_or_else(|| {
+        DomainError::Infrastructure(format!("el job {} no tiene próxima ejecución", job.name()))
+    })?;
+
+    let Some(ClaimedJobRun { run, lease }) = port
+        .claim_job_run(job.name(), trigger, now, next_run_at)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    // El fallo del job queda registrado en la corrida, no corta al scheduler.
+    let finished = match perform(port, &job.kind, audit).await {
+        Ok(output) => {
+            port.finish_job_run(run.id, JobRunStatus::Exitosa, Some(output), None)
+                .await?
+        }
+        Err(err) => {
+            port.finish_job_run(run.id, JobRunStatus::Fallida, None, Some(err.to_string()))
+                .await?
+        }
+    };
+    lease.release().await?;
+    Ok(Some(finished))
+}
+
+/// Ejecución pedida por un administrador: ignora el cron pero respeta el lock.
+pub async fn trigger<P>(
+    port: &P,
+    registry: &JobRegistry,
+    name: &str,
+    audit: &AuditContext,
+) -> Result<JobRun, DomainError>
+where
+    P: JobsPort
+        + AuthPort
+        + AuditPort
+        + RecurringOrdersPort
+        + OrdersPort
+        + ZonesPort
+        + SlotsPort
+        + CalendarPort
+        + PositionsPort,
+{
+    let job = registry.find(name)?;
+    let run = execute(port, job, JobTrigger::Manual, Utc::now(), audit)
+        .await?
+        .ok_or_else(|| DomainError::Conflict(format!("el job {} ya está en ejecución", name)))?;
+
+    port.record_audit_event(audit.event("job", Some(run.id), "triggered").after(&run))
+        .await?;
+
+    Ok(run)
+}
+
+async fn perform<P>(
+    port: &P,
+    kind: &JobKind,
+    audit: &AuditContext,
+) -> Result<serde_json::Value, DomainError>
+where
+    P: JobsPort
+        + AuthPort
+        + AuditPort
+        + RecurringOrdersPort
+        + OrdersPort
+        + ZonesPort
+        + SlotsPort
+        + CalendarPort
+        + PositionsPort,
+{
+    match kind {
+        JobKind::RecurringOrders {
+            days_ahead,
+            calendar,
+        } => {
+            let today = calendar.today();
+            let report =
+                materialize::execute(port, today + Duration::days(*days_ahead), today, audit)
+                    .await?;
+            Ok(json!(report))
+        }
+        JobKind::PurgeJobRuns { retention_days } => {
+            let purged = port
+                .purge_job_runs(Utc::now() - Duration::days(*retention_days))
+                .await?;
+            Ok(json!({ "purged": purged }))
+        }
+        JobKind::PurgeRefreshTokens => {
+            let purged = port.purge_refresh_tokens(Utc::now()).await?;
+            Ok(json!({ "purged": purged }))
+        }
+        JobKind::PurgeDriverPositions { retention_days } => {
+            let purged = port
+                .purge_driver_positions(Utc::now() - Duration::days(*retention_days))
+                .await?;
+            Ok(json!({ "purged": purged }))
+        }
+    }
+}
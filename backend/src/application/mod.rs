@@ -1,5 +1,18 @@
+pub mod api_keys;
+pub mod attachments;
+pub mod audit;
 pub mod auth;
+pub mod calendar;
+pub mod customers;
 pub mod deliveries;
 pub mod dispatch;
+pub mod events;
+pub mod jobs;
 pub mod orders;
+pub mod positions;
+pub mod recurring;
+pub mod slots;
 pub mod stock;
+pub mod users;
+pub mod webhooks;
+pub mod zones;
@@ -1,12 +1,15 @@
+use crate::domain::audit::AuditContext;
 use crate::domain::error::DomainError;
 use crate::domain::orders::{Order, OrderStatus};
+use crate::ports::audit_port::AuditPort;
 use crate::ports::orders_port::OrdersPort;
 use uuid::Uuid;
 
-pub async fn execute<P: OrdersPort>(
+pub async fn execute<P: OrdersPort + AuditPort>(
     port: &P,
     order_id: Uuid,
     target_status: OrderStatus,
+    audit: &AuditContext,
 ) -> Result<Order, DomainError> {
     let current = port
         .get_order_by_id(order_id)
@@ -21,5 +24,14 @@ pub async fn execute<P: OrdersPort>(
         )));
     }
 
-    port.update_order_status(order_id, target_status).await
+    let order = port.update_order_status(order_id, target_status).await?;
+    port.record_audit_event(
+        audit
+            .event("order", Some(order.id), "status_changed")
+            .before(&current)
+            .after(&order),
+    )
+    .await?;
+
+    Ok(order)
 }
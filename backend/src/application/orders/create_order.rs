@@ -1,8 +1,36 @@
+use crate::application::calendar::ensure_schedulable;
+use crate::application::slots::availability::{resolve_slot, slot_reservation};
+use crate::domain::audit::AuditContext;
 use crate::domain::error::DomainError;
 use crate::domain::orders::{NewOrder, Order};
+use crate::domain::slots::SlotReservation;
+use crate::domain::zones::normalize_catalog_name;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::calendar_port::CalendarPort;
 use crate::ports::orders_port::OrdersPort;
+use crate::ports::slots_port::SlotsPort;
+use crate::ports::zones_port::ZonesPort;
+use chrono::NaiveDate;
 
-pub async fn execute<P: OrdersPort>(port: &P, input: NewOrder) -> Result<Order, DomainError> {
+pub async fn execute<P: OrdersPort + ZonesPort + SlotsPort + CalendarPort + AuditPort>(
+    port: &P,
+    input: NewOrder,
+    today: NaiveDate,
+    audit: &AuditContext,
+) -> Result<Order, DomainError> {
+    let (input, reservation) = prepare(port, input, today).await?;
+    let order = port.create_order(input, reservation).await?;
+    record_created(port, &order, audit).await?;
+    Ok(order)
+}
+
+/// Valida el pedido contra calendario y catálogo y devuelve el pedido con los
+/// nombres canónicos junto con la reserva de franja a escribir.
+pub async fn prepare<P: ZonesPort + SlotsPort + CalendarPort>(
+    port: &P,
+    mut input: NewOrder,
+    today: NaiveDate,
+) -> Result<(NewOrder, SlotReservation), DomainError> {
     if input.quantity <= 0 {
         return Err(DomainError::Validation(
             "quantity debe ser mayor que 0".to_string(),
@@ -17,5 +45,38 @@ pub async fn execute<P: OrdersPort>(port: &P, input: NewOrder) -> Result<Order,
         ));
     }
 
-    port.create_order(input).await
+    ensure_schedulable(port, input.scheduled_date, today).await?;
+
+    let zone = port
+        .find_zone_by_name(&normalize_catalog_name(&input.zone))
+        .await?
+        .filter(|zone| zone.active)
+        .ok_or_else(|| {
+            DomainError::Validation(format!("zona inexistente: {}", input.zone.trim()))
+        })?;
+
+    if !zone.serves(input.scheduled_date) {
+        return Err(DomainError::Validation(format!(
+            "la zona {} no tiene reparto ese día",
+            zone.name
+        )));
+    }
+
+    let slot = resolve_slot(port, &input.time_slot).await?;
+    let reservation = slot_reservation(port, &zone, &slot).await?;
+
+    // El pedido guarda los nombres canónicos del catálogo, no el texto tipeado.
+    input.zone = zone.name;
+    input.time_slot = slot.name;
+
+    Ok((input, reservation))
+}
+
+pub async fn record_created<P: AuditPort>(
+    port: &P,
+    order: &Order,
+    audit: &AuditContext,
+) -> Result<(), DomainError> {
+    port.record_audit_event(audit.event("order", Some(order.id), "created").after(order))
+        .await
 }
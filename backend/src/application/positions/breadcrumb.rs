@@ -0,0 +1,23 @@
+use crate::domain::auth::Role;
+use crate::domain::calendar::CalendarPolicy;
+use crate::domain::error::DomainError;
+use crate::domain::positions::DriverPosition;
+use crate::ports::auth_port::AuthPort;
+use crate::ports::positions_port::PositionsPort;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+pub async fn execute<P: PositionsPort + AuthPort>(
+    port: &P,
+    driver_id: Uuid,
+    date: NaiveDate,
+    calendar: &CalendarPolicy,
+) -> Result<Vec<DriverPosition>, DomainError> {
+    port.find_user_by_id(driver_id)
+        .await?
+        .filter(|user| user.role == Role::Repartidor)
+        .ok_or_else(|| DomainError::NotFound("repartidor no encontrado".to_string()))?;
+
+    port.driver_positions_on(driver_id, date, calendar.timezone)
+        .await
+}
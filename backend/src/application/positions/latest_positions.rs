@@ -0,0 +1,7 @@
+use crate::domain::error::DomainError;
+use crate::domain::positions::LatestDriverPosition;
+use crate::ports::positions_port::PositionsPort;
+
+pub async fn execute<P: PositionsPort>(port: &P) -> Result<Vec<LatestDriverPosition>, DomainError> {
+    port.latest_driver_positions().await
+}
@@ -0,0 +1,31 @@
+pub mod breadcrumb;
+pub mod latest_positions;
+pub mod record_positions;
+
+use crate::domain::error::DomainError;
+use crate::domain::orders::Order;
+use crate::domain::positions::{PositionFix, DELIVERY_POSITION_MAX_AGE_MINUTES};
+use crate::ports::positions_port::PositionsPort;
+use chrono::{DateTime, Duration, Utc};
+
+/// Posición a guardar con una entrega o un fallo: la enviada con el registro
+/// o, si no vino, el último fix reciente del repartidor asignado.
+pub async fn delivery_position<P: PositionsPort>(
+    port: &P,
+    order: &Order,
+    provided: Option<PositionFix>,
+    now: DateTime<Utc>,
+) -> Result<Option<PositionFix>, DomainError> {
+    if let Some(fix) = provided {
+        fix.validate(now)?;
+        return Ok(Some(fix));
+    }
+    let Some(driver_id) = order.assignee_id else {
+        return Ok(None);
+    };
+    port.latest_driver_position(
+        driver_id,
+        now - Duration::minutes(DELIVERY_POSITION_MAX_AGE_MINUTES),
+    )
+    .await
+}
@@ -0,0 +1,49 @@
+use crate::domain::error::DomainError;
+use crate::domain::positions::{PositionBatchReport, PositionFix, MAX_POSITIONS_PER_BATCH};
+use crate::ports::positions_port::PositionsPort;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Un fix inválido rechaza el lote entero; los viejos y los repetidos sólo
+/// se cuentan, así el dispositivo puede reenviar sin miedo.
+pub async fn execute<P: PositionsPort>(
+    port: &P,
+    driver_id: Uuid,
+    fixes: Vec<PositionFix>,
+    now: DateTime<Utc>,
+) -> Result<PositionBatchReport, DomainError> {
+    if fixes.is_empty() {
+        return Err(DomainError::Validation(
+            "positions no puede estar vacío".to_string(),
+        ));
+    }
+    if fixes.len() > MAX_POSITIONS_PER_BATCH {
+        return Err(DomainError::Validation(format!(
+            "se aceptan hasta {} posiciones por envío",
+            MAX_POSITIONS_PER_BATCH
+        )));
+    }
+    for fix in &fixes {
+        fix.validate(now)?;
+    }
+
+    let mut report = PositionBatchReport::default();
+    let mut seen = HashSet::new();
+    let mut fresh = Vec::with_capacity(fixes.len());
+    for fix in fixes {
+        if fix.is_stale(now) {
+            report.stale += 1;
+        } else if !seen.insert(fix.recorded_at) {
+            report.duplicates += 1;
+        } else {
+            fresh.push(fix);
+        }
+    }
+
+    if !fresh.is_empty() {
+        report.accepted = port.record_positions(driver_id, &fresh).await?;
+        report.duplicates += fresh.len() - report.accepted;
+    }
+    Ok(report)
+}
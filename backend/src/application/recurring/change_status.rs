@@ -0,0 +1,51 @@
+use crate::application::recurring::create_recurring_order::get;
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::recurring::{RecurringOrder, RecurringStatus};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::recurring_port::RecurringOrdersPort;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+pub async fn execute<P: RecurringOrdersPort + AuditPort>(
+    port: &P,
+    recurring_order_id: Uuid,
+    target_status: RecurringStatus,
+    today: NaiveDate,
+    audit: &AuditContext,
+) -> Result<RecurringOrder, DomainError> {
+    let current = get(port, recurring_order_id).await?;
+
+    if !current.status.can_transition_to(&target_status) {
+        return Err(DomainError::Validation(format!(
+            "transición inválida: {} -> {}",
+            current.status.as_str(),
+            target_status.as_str()
+        )));
+    }
+
+    // Al reanudar no se generan retroactivamente las fechas perdidas en la pausa.
+    let next_date = if target_status == RecurringStatus::Activa {
+        current.first_occurrence_from(today)
+    } else {
+        current.next_date
+    };
+
+    let action = match target_status {
+        RecurringStatus::Activa => "resumed",
+        RecurringStatus::Pausada => "paused",
+        RecurringStatus::Finalizada => "ended",
+    };
+    let recurring = port
+        .update_recurring_status(recurring_order_id, target_status, next_date)
+        .await?;
+    port.record_audit_event(
+        audit
+            .event("recurring_order", Some(recurring_order_id), action)
+            .before(&current)
+            .after(&recurring),
+    )
+    .await?;
+
+    Ok(recurring)
+}
@@ -0,0 +1,84 @@
+use crate::application::slots::availability::resolve_slot;
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::recurring::{validate_recurring_fields, NewRecurringOrder, RecurringOrder};
+use crate::domain::zones::normalize_catalog_name;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::recurring_port::RecurringOrdersPort;
+use crate::ports::slots_port::SlotsPort;
+use crate::ports::zones_port::ZonesPort;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct RecurringOrderInput {
+    pub address: String,
+    pub zone: String,
+    pub time_slot: String,
+    pub quantity: i32,
+    pub interval_weeks: i32,
+    pub notes: Option<String>,
+    pub start_date: NaiveDate,
+}
+
+pub async fn execute<P: RecurringOrdersPort + ZonesPort + SlotsPort + AuditPort>(
+    port: &P,
+    input: RecurringOrderInput,
+    today: NaiveDate,
+    audit: &AuditContext,
+) -> Result<RecurringOrder, DomainError> {
+    validate_recurring_fields(&input.address, input.quantity, input.interval_weeks)?;
+
+    if input.start_date < today {
+        return Err(DomainError::Validation(
+            "start_date no puede ser una fecha pasada".to_string(),
+        ));
+    }
+
+    let zone = port
+        .find_zone_by_name(&normalize_catalog_name(&input.zone))
+        .await?
+        .filter(|zone| zone.active)
+        .ok_or_else(|| {
+            DomainError::Validation(format!("zona inexistente: {}", input.zone.trim()))
+        })?;
+
+    // La cadencia es semanal: todas las fechas caen el mismo día que start_date.
+    if !zone.serves(input.start_date) {
+        return Err(DomainError::Validation(format!(
+            "la zona {} no tiene reparto ese día",
+            zone.name
+        )));
+    }
+
+    let slot = resolve_slot(port, &input.time_slot).await?;
+
+    let recurring = port
+        .create_recurring_order(NewRecurringOrder {
+            address: input.address.trim().to_string(),
+            zone_id: zone.id,
+            slot_id: slot.id,
+            quantity: input.quantity,
+            interval_weeks: input.interval_weeks,
+            notes: input.notes,
+            start_date: input.start_date,
+        })
+        .await?;
+    port.record_audit_event(
+        audit
+            .event("recurring_order", Some(recurring.id), "created")
+            .after(&recurring),
+    )
+    .await?;
+
+    Ok(recurring)
+}
+
+pub async fn get<P: RecurringOrdersPort>(
+    port: &P,
+    recurring_order_id: Uuid,
+) -> Result<RecurringOrder, DomainError> {
+    port.get_recurring_order_by_id(recurring_order_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("pedido recurrente no encontrado".to_string()))
+}
@@ -0,0 +1,20 @@
+use crate::application::recurring::create_recurring_order::get;
+use crate::domain::error::DomainError;
+use crate::domain::recurring::{RecurringOccurrence, RecurringOrder, RecurringStatus};
+use crate::ports::recurring_port::RecurringOrdersPort;
+use uuid::Uuid;
+
+pub async fn execute<P: RecurringOrdersPort>(
+    port: &P,
+    status: Option<RecurringStatus>,
+) -> Result<Vec<RecurringOrder>, DomainError> {
+    port.list_recurring_orders(status).await
+}
+
+pub async fn occurrences<P: RecurringOrdersPort>(
+    port: &P,
+    recurring_order_id: Uuid,
+) -> Result<Vec<RecurringOccurrence>, DomainError> {
+    get(port, recurring_order_id).await?;
+    port.list_occurrences(recurring_order_id).await
+}
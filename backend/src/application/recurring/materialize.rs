@@ -0,0 +1,129 @@
+use crate::application::orders::create_order;
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::orders::NewOrder;
+use crate::domain::recurring::{
+    MaterializeReport, NewOccurrence, OccurrenceStatus, RecurringOrder,
+};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::calendar_port::CalendarPort;
+use crate::ports::orders_port::OrdersPort;
+use crate::ports::recurring_port::RecurringOrdersPort;
+use crate::ports::slots_port::SlotsPort;
+use crate::ports::zones_port::ZonesPort;
+use chrono::NaiveDate;
+use tracing::warn;
+
+/// Genera los pedidos de todas las fechas pendientes hasta `until` inclusive.
+/// Cada fecha avanza `next_date` en la misma transacción que escribe el pedido
+/// o la ocurrencia fallida: dos ejecuciones concurrentes nunca crean el mismo
+/// pedido y un error deja la fecha pendiente en lugar de perderla. El error de
+/// una plantilla no frena a las demás.
+pub async fn execute<P>(
+    port: &P,
+    until: NaiveDate,
+    today: NaiveDate,
+    audit: &AuditContext,
+) -> Result<MaterializeReport, DomainError>
+where
+    P: RecurringOrdersPort + OrdersPort + ZonesPort + SlotsPort + CalendarPort + AuditPort,
+{
+    let mut report = MaterializeReport::default();
+
+    for recurring in port.due_recurring_orders(until).await? {
+        if let Err(err) =
+            materialize_template(port, &recurring, until, today, audit, &mut report).await
+        {
+            warn!(
+                recurring_order_id = %recurring.id,
+                error = %err,
+                "no se pudo materializar el pedido recurrente"
+            );
+            report.errors += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+async fn materialize_template<P>(
+    port: &P,
+    recurring: &RecurringOrder,
+    until: NaiveDate,
+    today: NaiveDate,
+    audit: &AuditContext,
+    report: &mut MaterializeReport,
+) -> Result<(), DomainError>
+where
+    P: RecurringOrdersPort + OrdersPort + ZonesPort + SlotsPort + CalendarPort + AuditPort,
+{
+    let mut date = recurring.next_date;
+    while date <= until {
+        let next = date + recurring.interval();
+
+        // Fecha ya procesada (p. ej. omitida a mano): sólo se avanza.
+        if port.find_occurrence(recurring.id, date).await?.is_some() {
+            if !port
+                .advance_recurring_order(recurring.id, date, next)
+                .await?
+            {
+                break;
+            }
+            report.skipped += 1;
+            date = next;
+            continue;
+        }
+
+        let input = NewOrder {
+            address: recurring.address.clone(),
+            zone: recurring.zone.clone(),
+            scheduled_date: date,
+            time_slot: recurring.time_slot.clone(),
+            quantity: recurring.quantity,
+            notes: recurring.notes.clone(),
+            customer_id: None,
+            address_id: None,
+        };
+
+        let created = match create_order::prepare(port, input, today).await {
+            Ok((input, reservation)) => {
+                port.materialize_occurrence(recurring.id, next, input, reservation)
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+
+        match created {
+            Ok(Some(order)) => {
+                report.created += 1;
+                create_order::record_created(port, &order, audit).await?;
+            }
+            // Otra ejecución ya tomó esta fecha.
+            Ok(None) => break,
+            // Un feriado o una franja completa no deben frenar al resto.
+            Err(DomainError::Validation(msg)) | Err(DomainError::Conflict(msg)) => {
+                let recorded = port
+                    .advance_with_occurrence(
+                        date,
+                        next,
+                        NewOccurrence {
+                            recurring_order_id: recurring.id,
+                            scheduled_date: date,
+                            status: OccurrenceStatus::Fallida,
+                            order_id: None,
+                            detail: Some(msg),
+                        },
+                    )
+                    .await?;
+                if recorded.is_none() {
+                    break;
+                }
+                report.failed += 1;
+            }
+            Err(err) => return Err(err),
+        }
+        date = next;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,5 @@
+pub mod change_status;
+pub mod create_recurring_order;
+pub mod list_recurring_orders;
+pub mod materialize;
+pub mod skip_occurrence;
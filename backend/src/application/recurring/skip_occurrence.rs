@@ -0,0 +1,67 @@
+use crate::application::recurring::create_recurring_order::get;
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::recurring::{
+    NewOccurrence, OccurrenceStatus, RecurringOccurrence, RecurringStatus,
+};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::recurring_port::RecurringOrdersPort;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+/// Marca una fecha futura como omitida; sin `date` omite la próxima.
+pub async fn execute<P: RecurringOrdersPort + AuditPort>(
+    port: &P,
+    recurring_order_id: Uuid,
+    date: Option<NaiveDate>,
+    audit: &AuditContext,
+) -> Result<RecurringOccurrence, DomainError> {
+    let recurring = get(port, recurring_order_id).await?;
+
+    if recurring.status == RecurringStatus::Finalizada {
+        return Err(DomainError::Validation(
+            "el pedido recurrente está finalizado".to_string(),
+        ));
+    }
+
+    let date = date.unwrap_or(recurring.next_date);
+    if !recurring.is_occurrence(date) {
+        return Err(DomainError::Validation(format!(
+            "{} no es una fecha pendiente del pedido recurrente",
+            date
+        )));
+    }
+
+    if port
+        .find_occurrence(recurring_order_id, date)
+        .await?
+        .is_some()
+    {
+        return Err(DomainError::Conflict(format!(
+            "la fecha {} ya fue procesada",
+            date
+        )));
+    }
+
+    let occurrence = port
+        .record_occurrence(NewOccurrence {
+            recurring_order_id,
+            scheduled_date: date,
+            status: OccurrenceStatus::Omitida,
+            order_id: None,
+            detail: None,
+        })
+        .await?;
+    port.record_audit_event(
+        audit
+            .event(
+                "recurring_order",
+                Some(recurring_order_id),
+                "occurrence_skipped",
+            )
+            .after(&occurrence),
+    )
+    .await?;
+
+    Ok(occurrence)
+}
@@ -0,0 +1,99 @@
+use crate::domain::error::DomainError;
+use crate::domain::slots::{SlotAvailability, SlotLimits, SlotReservation, TimeSlot};
+use crate::domain::zones::{normalize_catalog_name, Zone};
+use crate::ports::slots_port::SlotsPort;
+use crate::ports::zones_port::ZonesPort;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+pub async fn execute<P: SlotsPort + ZonesPort>(
+    port: &P,
+    date: NaiveDate,
+    zone_name: &str,
+) -> Result<Vec<SlotAvailability>, DomainError> {
+    let zone = port
+        .find_zone_by_name(&normalize_catalog_name(zone_name))
+        .await?
+        .filter(|zone| zone.active)
+        .ok_or_else(|| DomainError::NotFound("zona no encontrada".to_string()))?;
+
+    if !zone.serves(date) {
+        return Ok(Vec::new());
+    }
+
+    let overrides = port.zone_capacities(zone.id).await?;
+
+    let mut result = Vec::new();
+    for slot in port.list_slots(false).await? {
+        if !zone.offers_slot(&slot.name) {
+            continue;
+        }
+
+        let limits = SlotLimits::resolve(&slot, overrides.iter().find(|c| c.slot_id == slot.id));
+        let load = port.slot_load(date, zone.id, &slot.name, None).await?;
+
+        result.push(SlotAvailability {
+            slot_id: slot.id,
+            name: slot.name,
+            start_time: slot.start_time,
+            end_time: slot.end_time,
+            max_orders: limits.max_orders,
+            max_cylinders: limits.max_cylinders,
+            booked_orders: load.orders,
+            booked_cylinders: load.cylinders,
+            available: limits.fits(load, 1),
+        });
+    }
+
+    Ok(result)
+}
+
+pub async fn resolve_slot<P: SlotsPort>(port: &P, name: &str) -> Result<TimeSlot, DomainError> {
+    port.find_slot_by_name(&normalize_catalog_name(name))
+        .await?
+        .filter(|slot| slot.active)
+        .ok_or_else(|| DomainError::Validation(format!("franja inexistente: {}", name.trim())))
+}
+
+/// Cupo que el repositorio debe respetar al escribir el pedido en la franja.
+/// Rechaza las franjas que la zona no ofrece, igual que la disponibilidad.
+pub async fn slot_reservation<P: SlotsPort>(
+    port: &P,
+    zone: &Zone,
+    slot: &TimeSlot,
+) -> Result<SlotReservation, DomainError> {
+    if !zone.offers_slot(&slot.name) {
+        return Err(DomainError::Validation(format!(
+            "la zona {} no ofrece la franja {}",
+            zone.name, slot.name
+        )));
+    }
+
+    let overrides = port.zone_capacities(zone.id).await?;
+    Ok(SlotReservation {
+        zone_id: zone.id,
+        zone_name: zone.name.clone(),
+        slot_name: slot.name.clone(),
+        limits: SlotLimits::resolve(slot, overrides.iter().find(|c| c.slot_id == slot.id)),
+    })
+}
+
+/// Chequeo previo para casos de uso que escriben otras filas antes que el
+/// pedido; la verificación definitiva la repite el repositorio bajo lock.
+pub async fn ensure_capacity<P: SlotsPort>(
+    port: &P,
+    date: NaiveDate,
+    reservation: &SlotReservation,
+    quantity: i32,
+    exclude_order_id: Option<Uuid>,
+) -> Result<(), DomainError> {
+    let load = port
+        .slot_load(
+            date,
+            reservation.zone_id,
+            &reservation.slot_name,
+            exclude_order_id,
+        )
+        .await?;
+    reservation.ensure_fits(date, load, quantity)
+}
@@ -0,0 +1,30 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::slots::{validate_slot_fields, NewTimeSlot, TimeSlot};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::slots_port::SlotsPort;
+
+pub async fn execute<P: SlotsPort + AuditPort>(
+    port: &P,
+    mut input: NewTimeSlot,
+    audit: &AuditContext,
+) -> Result<TimeSlot, DomainError> {
+    validate_slot_fields(
+        &input.name,
+        input.start_time,
+        input.end_time,
+        input.max_orders,
+        input.max_cylinders,
+    )?;
+
+    input.name = input.name.trim().to_string();
+    let slot = port.create_slot(input).await?;
+    port.record_audit_event(
+        audit
+            .event("time_slot", Some(slot.id), "created")
+            .after(&slot),
+    )
+    .await?;
+
+    Ok(slot)
+}
@@ -0,0 +1,10 @@
+use crate::domain::error::DomainError;
+use crate::domain::slots::TimeSlot;
+use crate::ports::slots_port::SlotsPort;
+
+pub async fn execute<P: SlotsPort>(
+    port: &P,
+    include_inactive: bool,
+) -> Result<Vec<TimeSlot>, DomainError> {
+    port.list_slots(include_inactive).await
+}
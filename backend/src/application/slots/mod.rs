@@ -0,0 +1,5 @@
+pub mod availability;
+pub mod create_slot;
+pub mod list_slots;
+pub mod set_zone_capacity;
+pub mod update_slot;
@@ -0,0 +1,64 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::slots::{validate_limits, SlotZoneCapacity};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::slots_port::SlotsPort;
+use crate::ports::zones_port::ZonesPort;
+use serde_json::json;
+use uuid::Uuid;
+
+async fn current_capacity<P: SlotsPort>(
+    port: &P,
+    slot_id: Uuid,
+    zone_id: Uuid,
+) -> Result<Option<SlotZoneCapacity>, DomainError> {
+    Ok(port
+        .zone_capacities(zone_id)
+        .await?
+        .into_iter()
+        .find(|capacity| capacity.slot_id == slot_id))
+}
+
+pub async fn execute<P: SlotsPort + ZonesPort + AuditPort>(
+    port: &P,
+    capacity: SlotZoneCapacity,
+    audit: &AuditContext,
+) -> Result<SlotZoneCapacity, DomainError> {
+    validate_limits(capacity.max_orders, capacity.max_cylinders)?;
+
+    port.get_zone_by_id(capacity.zone_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("zona no encontrada".to_string()))?;
+
+    let before = current_capacity(port, capacity.slot_id, capacity.zone_id).await?;
+    let capacity = port.set_zone_capacity(capacity).await?;
+
+    let mut event = audit
+        .event("time_slot", Some(capacity.slot_id), "zone_capacity_set")
+        .after(&capacity)
+        .details(json!({ "zone_id": capacity.zone_id }));
+    if let Some(before) = before {
+        event = event.before(&before);
+    }
+    port.record_audit_event(event).await?;
+
+    Ok(capacity)
+}
+
+pub async fn remove<P: SlotsPort + AuditPort>(
+    port: &P,
+    slot_id: Uuid,
+    zone_id: Uuid,
+    audit: &AuditContext,
+) -> Result<(), DomainError> {
+    let before = current_capacity(port, slot_id, zone_id).await?;
+    port.remove_zone_capacity(slot_id, zone_id).await?;
+
+    let mut event = audit
+        .event("time_slot", Some(slot_id), "zone_capacity_removed")
+        .details(json!({ "zone_id": zone_id }));
+    if let Some(before) = before {
+        event = event.before(&before);
+    }
+    port.record_audit_event(event).await
+}
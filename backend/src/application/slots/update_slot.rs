@@ -0,0 +1,39 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::slots::{validate_slot_fields, TimeSlot, TimeSlotUpdate};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::slots_port::SlotsPort;
+use uuid::Uuid;
+
+pub async fn execute<P: SlotsPort + AuditPort>(
+    port: &P,
+    slot_id: Uuid,
+    mut input: TimeSlotUpdate,
+    audit: &AuditContext,
+) -> Result<TimeSlot, DomainError> {
+    validate_slot_fields(
+        &input.name,
+        input.start_time,
+        input.end_time,
+        input.max_orders,
+        input.max_cylinders,
+    )?;
+
+    input.name = input.name.trim().to_string();
+    let before = port
+        .list_slots(true)
+        .await?
+        .into_iter()
+        .find(|slot| slot.id == slot_id)
+        .ok_or_else(|| DomainError::NotFound("franja no encontrada".to_string()))?;
+    let slot = port.update_slot(slot_id, input).await?;
+    port.record_audit_event(
+        audit
+            .event("time_slot", Some(slot.id), "updated")
+            .before(&before)
+            .after(&slot),
+    )
+    .await?;
+
+    Ok(slot)
+}
@@ -1,14 +1,18 @@
+use crate::domain::calendar::CalendarPolicy;
 use crate::domain::error::DomainError;
 use crate::domain::stock::DailyOperationalReport;
 use crate::ports::stock_port::StockPort;
-use chrono::{NaiveDate, Utc};
+use chrono::NaiveDate;
 
 pub async fn execute<P: StockPort>(
     port: &P,
     date: Option<NaiveDate>,
+    calendar: &CalendarPolicy,
 ) -> Result<DailyOperationalReport, DomainError> {
-    let report_date = date.unwrap_or_else(|| Utc::now().date_naive());
-    let totals = port.daily_report_totals(report_date).await?;
+    let report_date = date.unwrap_or_else(|| calendar.today());
+    let totals = port
+        .daily_report_totals(report_date, calendar.timezone)
+        .await?;
 
     Ok(DailyOperationalReport {
         date: report_date,
@@ -1,13 +1,21 @@
+use crate::domain::audit::AuditContext;
 use crate::domain::error::DomainError;
 use crate::domain::stock::Inbound;
+use crate::ports::audit_port::AuditPort;
 use crate::ports::stock_port::StockPort;
 
-pub async fn execute<P: StockPort>(port: &P, input: Inbound) -> Result<(), DomainError> {
+pub async fn execute<P: StockPort + AuditPort>(
+    port: &P,
+    input: Inbound,
+    audit: &AuditContext,
+) -> Result<(), DomainError> {
     if input.cantidad_llenas <= 0 {
         return Err(DomainError::Validation(
             "cantidad_llenas debe ser mayor que 0".to_string(),
         ));
     }
 
-    port.register_inbound(input).await
+    port.register_inbound(input.clone()).await?;
+    port.record_audit_event(audit.event("stock_inbound", None, "created").after(&input))
+        .await
 }
@@ -1,3 +1,4 @@
+use crate::domain::calendar::CalendarPolicy;
 use crate::domain::error::DomainError;
 use crate::domain::stock::StockSummary;
 use crate::ports::stock_port::StockPort;
@@ -6,8 +7,9 @@ use chrono::NaiveDate;
 pub async fn execute<P: StockPort>(
     port: &P,
     date: Option<NaiveDate>,
+    calendar: &CalendarPolicy,
 ) -> Result<StockSummary, DomainError> {
-    let totals = port.stock_totals(date).await?;
+    let totals = port.stock_totals(date, calendar.timezone).await?;
 
     let llenas_disponibles_estimadas = totals.inbound_full - totals.delivered_full;
     let pendientes_recuperar = totals.delivered_full - totals.recovered_empty;
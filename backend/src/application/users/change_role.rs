@@ -0,0 +1,31 @@
+use crate::application::users::{get_user, record_user_change};
+use crate::domain::audit::AuditContext;
+use crate::domain::auth::{Role, User};
+use crate::domain::error::DomainError;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::auth_port::AuthPort;
+use crate::ports::users_port::UsersPort;
+use uuid::Uuid;
+
+pub async fn execute<P: AuthPort + UsersPort + AuditPort>(
+    port: &P,
+    actor_id: Uuid,
+    user_id: Uuid,
+    role: Role,
+    audit: &AuditContext,
+) -> Result<User, DomainError> {
+    let user = get_user(port, user_id).await?;
+    if user.role == role {
+        return Ok(user);
+    }
+    if user_id == actor_id {
+        return Err(DomainError::Validation(
+            "no podés cambiar tu propio rol".to_string(),
+        ));
+    }
+
+    let updated = port.set_user_role(user_id, role).await?;
+    record_user_change(port, audit, "role_changed", user, &updated).await?;
+
+    Ok(updated)
+}
@@ -0,0 +1,38 @@
+use crate::application::auth::password::hash_password;
+use crate::domain::audit::AuditContext;
+use crate::domain::auth::{
+    validate_new_password, validate_username, NewUser, Role, User, UserAccount,
+};
+use crate::domain::error::DomainError;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::users_port::UsersPort;
+
+pub async fn execute<P: UsersPort + AuditPort>(
+    port: &P,
+    username: String,
+    password: String,
+    role: Role,
+    audit: &AuditContext,
+) -> Result<User, DomainError> {
+    let username = username.trim().to_string();
+    validate_username(&username)?;
+    validate_new_password(&username, &password, None)?;
+
+    let password_hash = hash_password(&password)?;
+
+    let user = port
+        .create_user(NewUser {
+            username,
+            password_hash,
+            role,
+        })
+        .await?;
+    port.record_audit_event(
+        audit
+            .event("user", Some(user.id), "created")
+            .after(&UserAccount::from(user.clone())),
+    )
+    .await?;
+
+    Ok(user)
+}
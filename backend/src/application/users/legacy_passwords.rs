@@ -0,0 +1,31 @@
+use crate::application::auth::password::PASSWORD_HASH_COST;
+use crate::domain::auth::{LegacyPasswordUser, StoredPassword, UserFilter};
+use crate::domain::error::DomainError;
+use crate::ports::users_port::UsersPort;
+
+/// Usuarios que todavía no entraron desde que cambió la política de hash.
+pub async fn execute<P: UsersPort>(port: &P) -> Result<Vec<LegacyPasswordUser>, DomainError> {
+    let users = port
+        .list_users(UserFilter {
+            role: None,
+            include_inactive: true,
+        })
+        .await?;
+
+    Ok(users
+        .into_iter()
+        .filter_map(|user| {
+            let stored = StoredPassword::detect(&user.password);
+            stored
+                .is_legacy(PASSWORD_HASH_COST)
+                .then_some(LegacyPasswordUser {
+                    id: user.id,
+                    username: user.username,
+                    role: user.role,
+                    active: user.active,
+                    scheme: stored.scheme,
+                    cost: stored.cost,
+                })
+        })
+        .collect())
+}
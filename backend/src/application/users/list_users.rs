@@ -0,0 +1,7 @@
+use crate::domain::auth::{User, UserFilter};
+use crate::domain::error::DomainError;
+use crate::ports::users_port::UsersPort;
+
+pub async fn execute<P: UsersPort>(port: &P, filter: UserFilter) -> Result<Vec<User>, DomainError> {
+    port.list_users(filter).await
+}
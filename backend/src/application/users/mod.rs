@@ -0,0 +1,40 @@
+pub mod change_role;
+pub mod create_user;
+pub mod legacy_passwords;
+pub mod list_users;
+pub mod reset_password;
+pub mod revoke_sessions;
+pub mod set_active;
+pub mod unlock;
+pub mod update_user;
+
+use crate::domain::audit::AuditContext;
+use crate::domain::auth::{User, UserAccount};
+use crate::domain::error::DomainError;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::auth_port::AuthPort;
+use uuid::Uuid;
+
+pub async fn get_user<P: AuthPort>(port: &P, user_id: Uuid) -> Result<User, DomainError> {
+    port.find_user_by_id(user_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("usuario no encontrado".to_string()))
+}
+
+/// Audita un cambio sobre la cuenta; los snapshots nunca incluyen el hash de
+/// la contraseña ni el secreto TOTP.
+async fn record_user_change<P: AuditPort>(
+    port: &P,
+    audit: &AuditContext,
+    action: &str,
+    before: User,
+    after: &User,
+) -> Result<(), DomainError> {
+    port.record_audit_event(
+        audit
+            .event("user", Some(after.id), action)
+            .before(&UserAccount::from(before))
+            .after(&UserAccount::from(after.clone())),
+    )
+    .await
+}
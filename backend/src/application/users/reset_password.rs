@@ -0,0 +1,57 @@
+use crate::application::auth::service::{generate_reset_code, hash_token};
+use crate::application::users::{get_user, unlock};
+use crate::domain::audit::AuditContext;
+use crate::domain::auth::{NewPasswordReset, PasswordResetIssued, UserAccount};
+use crate::domain::error::DomainError;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::auth_port::AuthPort;
+use crate::ports::users_port::UsersPort;
+use chrono::{Duration, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Emite un código de un solo uso para que el usuario fije una contraseña
+/// nueva. Cierra sus sesiones y levanta un bloqueo por intentos fallidos.
+pub async fn execute<P: AuthPort + UsersPort + AuditPort>(
+    port: &P,
+    actor_id: Uuid,
+    user_id: Uuid,
+    ttl_minutes: i64,
+    audit: &AuditContext,
+) -> Result<PasswordResetIssued, DomainError> {
+    let user = get_user(port, user_id).await?;
+    if !user.active {
+        return Err(DomainError::Conflict("usuario desactivado".to_string()));
+    }
+
+    let code = generate_reset_code();
+    let reset = port
+        .create_password_reset(NewPasswordReset {
+            user_id,
+            code_hash: hash_token(&code),
+            expires_at: Utc::now() + Duration::minutes(ttl_minutes),
+            created_by: actor_id,
+        })
+        .await?;
+    port.revoke_user_tokens(user_id).await?;
+    if user.locked_at.is_some() {
+        unlock::execute(port, user_id, audit).await?;
+    }
+
+    // El código queda fuera del evento: sólo su vencimiento.
+    let updated = get_user(port, user_id).await?;
+    port.record_audit_event(
+        audit
+            .event("user", Some(user_id), "password_reset_issued")
+            .before(&UserAccount::from(user))
+            .after(&UserAccount::from(updated))
+            .details(json!({ "expires_at": reset.expires_at })),
+    )
+    .await?;
+
+    Ok(PasswordResetIssued {
+        user_id,
+        code,
+        expires_at: reset.expires_at,
+    })
+}
@@ -0,0 +1,18 @@
+use crate::application::users::get_user;
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::auth_port::AuthPort;
+use uuid::Uuid;
+
+/// Invalida todos los access y refresh tokens vigentes del usuario.
+pub async fn execute<P: AuthPort + AuditPort>(
+    port: &P,
+    user_id: Uuid,
+    audit: &AuditContext,
+) -> Result<(), DomainError> {
+    get_user(port, user_id).await?;
+    port.revoke_user_tokens(user_id).await?;
+    port.record_audit_event(audit.event("user", Some(user_id), "sessions_revoked"))
+        .await
+}
@@ -0,0 +1,48 @@
+use crate::application::users::{get_user, record_user_change};
+use crate::domain::audit::AuditContext;
+use crate::domain::auth::User;
+use crate::domain::error::DomainError;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::auth_port::AuthPort;
+use crate::ports::users_port::UsersPort;
+use uuid::Uuid;
+
+pub async fn deactivate<P: AuthPort + UsersPort + AuditPort>(
+    port: &P,
+    actor_id: Uuid,
+    user_id: Uuid,
+    audit: &AuditContext,
+) -> Result<User, DomainError> {
+    if user_id == actor_id {
+        return Err(DomainError::Validation(
+            "no podés desactivar tu propio usuario".to_string(),
+        ));
+    }
+    let user = get_user(port, user_id).await?;
+    if !user.active {
+        return Ok(user);
+    }
+
+    let updated = port.set_user_active(user_id, false).await?;
+    // Al reactivarlo no recupera las sesiones que tenía abiertas.
+    port.revoke_user_tokens(user_id).await?;
+    record_user_change(port, audit, "deactivated", user, &updated).await?;
+
+    Ok(updated)
+}
+
+pub async fn reactivate<P: AuthPort + UsersPort + AuditPort>(
+    port: &P,
+    user_id: Uuid,
+    audit: &AuditContext,
+) -> Result<User, DomainError> {
+    let user = get_user(port, user_id).await?;
+    if user.active {
+        return Ok(user);
+    }
+
+    let updated = port.set_user_active(user_id, true).await?;
+    record_user_change(port, audit, "reactivated", user, &updated).await?;
+
+    Ok(updated)
+}
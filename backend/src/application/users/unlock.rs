@@ -0,0 +1,23 @@
+use crate::application::users::{get_user, record_user_change};
+use crate::domain::audit::AuditContext;
+use crate::domain::auth::{LoginScope, User};
+use crate::domain::error::DomainError;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::auth_port::AuthPort;
+use crate::ports::users_port::UsersPort;
+use uuid::Uuid;
+
+/// Levanta el bloqueo por intentos fallidos y reinicia el contador del usuario.
+pub async fn execute<P: AuthPort + UsersPort + AuditPort>(
+    port: &P,
+    user_id: Uuid,
+    audit: &AuditContext,
+) -> Result<User, DomainError> {
+    let before = get_user(port, user_id).await?;
+    let user = port.unlock_user(user_id).await?;
+    port.clear_login_failures(LoginScope::Username, &user.username)
+        .await?;
+    record_user_change(port, audit, "unlocked", before, &user).await?;
+
+    Ok(user)
+}
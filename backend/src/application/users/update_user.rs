@@ -0,0 +1,24 @@
+use crate::application::users::{get_user, record_user_change};
+use crate::domain::audit::AuditContext;
+use crate::domain::auth::{validate_username, User};
+use crate::domain::error::DomainError;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::auth_port::AuthPort;
+use crate::ports::users_port::UsersPort;
+use uuid::Uuid;
+
+pub async fn execute<P: AuthPort + UsersPort + AuditPort>(
+    port: &P,
+    user_id: Uuid,
+    username: String,
+    audit: &AuditContext,
+) -> Result<User, DomainError> {
+    let username = username.trim().to_string();
+    validate_username(&username)?;
+    let before = get_user(port, user_id).await?;
+
+    let user = port.update_username(user_id, &username).await?;
+    record_user_change(port, audit, "updated", before, &user).await?;
+
+    Ok(user)
+}
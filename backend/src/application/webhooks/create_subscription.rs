@@ -0,0 +1,79 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::events::DomainEventType;
+use crate::domain::webhooks::{
+    validate_event_types, validate_webhook_secret, NewWebhookSubscription,
+    WebhookSubscriptionCreated, WebhookTargetPolicy, WEBHOOK_SECRET_PREFIX,
+};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::webhooks_port::WebhooksPort;
+use rand::RngCore;
+use uuid::Uuid;
+
+const WEBHOOK_SECRET_BYTES: usize = 24;
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; WEBHOOK_SECRET_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("{}{}", WEBHOOK_SECRET_PREFIX, hex::encode(bytes))
+}
+
+/// Sin `secret` se genera uno; en ambos casos sólo se devuelve aquí.
+pub async fn execute<P: WebhooksPort + AuditPort>(
+    port: &P,
+    policy: &WebhookTargetPolicy,
+    created_by: Uuid,
+    url: String,
+    event_types: Vec<DomainEventType>,
+    secret: Option<String>,
+    audit: &AuditContext,
+) -> Result<WebhookSubscriptionCreated, DomainError> {
+    policy.validate_url(&url)?;
+    validate_event_types(&event_types)?;
+    let secret = match secret {
+        Some(secret) => {
+            validate_webhook_secret(&secret)?;
+            secret
+        }
+        None => generate_secret(),
+    };
+    let mut unique = Vec::with_capacity(event_types.len());
+    for event_type in event_types {
+        if !unique.contains(&event_type) {
+            unique.push(event_type);
+        }
+    }
+
+    let subscription = port
+        .create_webhook_subscription(NewWebhookSubscription {
+            url: url.trim().to_string(),
+            event_types: unique,
+            secret: secret.clone(),
+            created_by,
+        })
+        .await?;
+    port.record_audit_event(
+        audit
+            .event("webhook_subscription", Some(subscription.id), "created")
+            .after(&subscription),
+    )
+    .await?;
+
+    Ok(WebhookSubscriptionCreated {
+        secret,
+        subscription,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_secrets_are_prefixed_and_valid() {
+        let secret = generate_secret();
+        assert!(secret.starts_with(WEBHOOK_SECRET_PREFIX));
+        assert!(validate_webhook_secret(&secret).is_ok());
+        assert_ne!(secret, generate_secret());
+    }
+}
@@ -0,0 +1,24 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::ports::audit_port::AuditPort;
+use crate::ports::webhooks_port::WebhooksPort;
+use uuid::Uuid;
+
+pub async fn execute<P: WebhooksPort + AuditPort>(
+    port: &P,
+    subscription_id: Uuid,
+    audit: &AuditContext,
+) -> Result<(), DomainError> {
+    let before = port
+        .get_webhook_subscription(subscription_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("suscripción no encontrada".to_string()))?;
+    port.delete_webhook_subscription(subscription_id).await?;
+
+    port.record_audit_event(
+        audit
+            .event("webhook_subscription", Some(subscription_id), "deleted")
+            .before(&before),
+    )
+    .await
+}
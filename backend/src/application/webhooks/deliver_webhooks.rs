@@ -0,0 +1,103 @@
+use crate::application::webhooks::{sign_payload, SIGNATURE_HEADER};
+use crate::domain::error::DomainError;
+use crate::domain::webhooks::{
+    webhook_retry_delay, PendingWebhookDelivery, WebhookAttempt, WebhookDeliveryStatus,
+    WEBHOOK_BATCH_SIZE, WEBHOOK_CONCURRENCY,
+};
+use crate::ports::webhooks_port::{WebhookClient, WebhooksPort};
+use chrono::{DateTime, Utc};
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebhookDispatchReport {
+    pub claimed: usize,
+    pub delivered: usize,
+    pub retrying: usize,
+    pub dead_lettered: usize,
+}
+
+/// Envía un lote de entregas vencidas en `now`, hasta `WEBHOOK_CONCURRENCY`
+/// a la vez para que un destino lento no frene al resto. Sólo una respuesta
+/// 2xx cuenta como entregada; el resto se reintenta con backoff hasta el
+/// dead-letter.
+pub async fn execute<P: WebhooksPort>(
+    port: &P,
+    client: &dyn WebhookClient,
+    now: DateTime<Utc>,
+) -> Result<WebhookDispatchReport, DomainError> {
+    let pending = port
+        .claim_webhook_deliveries(now, WEBHOOK_BATCH_SIZE)
+        .await?;
+    let mut report = WebhookDispatchReport {
+        claimed: pending.len(),
+        ..WebhookDispatchReport::default()
+    };
+
+    let statuses: Vec<WebhookDeliveryStatus> = stream::iter(pending)
+        .map(|delivery| async move {
+            let attempt = send(client, &delivery).await;
+            let status = attempt.status;
+            port.record_webhook_attempt(delivery.delivery_id, attempt)
+                .await?;
+            Ok::<_, DomainError>(status)
+        })
+        .buffer_unordered(WEBHOOK_CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    for status in statuses {
+        match status {
+            WebhookDeliveryStatus::Entregada => report.delivered += 1,
+            WebhookDeliveryStatus::Pendiente => report.retrying += 1,
+            WebhookDeliveryStatus::Descartada => report.dead_lettered += 1,
+        }
+    }
+
+    Ok(report)
+}
+
+/// Firma con la hora del envío: los receptores que limitan la antigüedad de
+/// `t=` no deben rechazar las últimas entregas de un lote.
+async fn send(client: &dyn WebhookClient, delivery: &PendingWebhookDelivery) -> WebhookAttempt {
+    let attempted_at = Utc::now();
+    let body = delivery.event.envelope().to_string();
+    let headers = [
+        (
+            "X-GasFlow-Event",
+            delivery.event.event_type.as_str().to_string(),
+        ),
+        ("X-GasFlow-Event-Id", delivery.event.id.to_string()),
+        ("X-GasFlow-Delivery-Id", delivery.delivery_id.to_string()),
+        (
+            SIGNATURE_HEADER,
+            sign_payload(&delivery.secret, attempted_at.timestamp(), &body),
+        ),
+    ];
+
+    let (status_code, error) = match client.post_json(&delivery.url, &headers, body).await {
+        Ok(code) if (200..300).contains(&code) => {
+            return WebhookAttempt {
+                status: WebhookDeliveryStatus::Entregada,
+                status_code: Some(i32::from(code)),
+                error: None,
+                next_attempt_at: None,
+                attempted_at,
+            };
+        }
+        Ok(code) => (Some(i32::from(code)), format!("respuesta {}", code)),
+        Err(err) => (None, err.to_string()),
+    };
+
+    let next_attempt_at = webhook_retry_delay(delivery.attempts).map(|delay| Utc::now() + delay);
+    WebhookAttempt {
+        status: if next_attempt_at.is_some() {
+            WebhookDeliveryStatus::Pendiente
+        } else {
+            WebhookDeliveryStatus::Descartada
+        },
+        status_code,
+        error: Some(error),
+        next_attempt_at,
+        attempted_at,
+    }
+}
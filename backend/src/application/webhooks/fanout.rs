@@ -0,0 +1,30 @@
+use crate::domain::error::DomainError;
+use crate::domain::events::OutboxEvent;
+use crate::ports::event_sink_port::EventSink;
+use crate::ports::webhooks_port::WebhooksPort;
+use async_trait::async_trait;
+
+/// Sink del outbox que convierte cada evento en entregas pendientes, una por
+/// suscripción interesada. El envío lo hace `deliver_webhooks`, con reintentos
+/// propios de cada suscripción.
+pub struct WebhookFanoutSink<P> {
+    port: P,
+}
+
+impl<P> WebhookFanoutSink<P> {
+    pub fn new(port: P) -> Self {
+        Self { port }
+    }
+}
+
+#[async_trait]
+impl<P: WebhooksPort> EventSink for WebhookFanoutSink<P> {
+    fn name(&self) -> &str {
+        "webhook_subscriptions"
+    }
+
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), DomainError> {
+        self.port.enqueue_webhook_deliveries(event).await?;
+        Ok(())
+    }
+}
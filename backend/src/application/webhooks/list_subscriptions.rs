@@ -0,0 +1,28 @@
+use crate::domain::error::DomainError;
+use crate::domain::webhooks::{
+    WebhookDelivery, WebhookDeliveryFilter, WebhookSubscription, MAX_WEBHOOK_DELIVERIES_LIMIT,
+};
+use crate::ports::webhooks_port::WebhooksPort;
+use uuid::Uuid;
+
+pub async fn execute<P: WebhooksPort>(port: &P) -> Result<Vec<WebhookSubscription>, DomainError> {
+    port.list_webhook_subscriptions().await
+}
+
+/// Registro de entregas de una suscripción.
+pub async fn deliveries<P: WebhooksPort>(
+    port: &P,
+    subscription_id: Uuid,
+    filter: WebhookDeliveryFilter,
+) -> Result<Vec<WebhookDelivery>, DomainError> {
+    if !(1..=MAX_WEBHOOK_DELIVERIES_LIMIT).contains(&filter.limit) {
+        return Err(DomainError::Validation(format!(
+            "limit debe estar entre 1 y {}",
+            MAX_WEBHOOK_DELIVERIES_LIMIT
+        )));
+    }
+    port.get_webhook_subscription(subscription_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("suscripción no encontrada".to_string()))?;
+    port.list_webhook_deliveries(subscription_id, filter).await
+}
@@ -0,0 +1,37 @@
+pub mod create_subscription;
+pub mod delete_subscription;
+pub mod deliver_webhooks;
+pub mod fanout;
+pub mod list_subscriptions;
+pub mod redeliver;
+pub mod update_subscription;
+
+use ring::hmac;
+
+pub const SIGNATURE_HEADER: &str = "X-GasFlow-Signature";
+
+/// Firma `t=<unix>,v1=<hex>`: HMAC-SHA256 de `"<unix>.<body>"`. El timestamp
+/// permite al receptor descartar envíos viejos reutilizados.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let signed = format!("{}.{}", timestamp, body);
+    let tag = hmac::sign(&key, signed.as_bytes());
+    format!("t={},v1={}", timestamp, hex::encode(tag.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_matches_hmac_sha256() {
+        // HMAC-SHA256("key", "1700000000.{}") calculado aparte.
+        let signature = sign_payload("key", 1_700_000_000, "{}");
+        assert_eq!(
+            signature,
+            "t=1700000000,v1=9d713ed406bb7076d4123f0dc2c39d2df5c654ed4b0cd56b52c8b4c940bd63ae"
+        );
+        assert_ne!(signature, sign_payload("otra", 1_700_000_000, "{}"));
+        assert_ne!(signature, sign_payload("key", 1_700_000_001, "{}"));
+    }
+}
@@ -0,0 +1,38 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::webhooks::{WebhookDelivery, WebhookDeliveryStatus};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::webhooks_port::WebhooksPort;
+use chrono::Utc;
+use uuid::Uuid;
+
+/// Vuelve a encolar una entrega ya resuelta (entregada o en dead-letter) con
+/// los reintentos desde cero.
+pub async fn execute<P: WebhooksPort + AuditPort>(
+    port: &P,
+    delivery_id: Uuid,
+    audit: &AuditContext,
+) -> Result<WebhookDelivery, DomainError> {
+    let before = port
+        .get_webhook_delivery(delivery_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("entrega no encontrada".to_string()))?;
+    if before.status == WebhookDeliveryStatus::Pendiente {
+        return Err(DomainError::Conflict(
+            "la entrega ya está pendiente de envío".to_string(),
+        ));
+    }
+
+    let delivery = port
+        .requeue_webhook_delivery(delivery_id, Utc::now())
+        .await?;
+    port.record_audit_event(
+        audit
+            .event("webhook_delivery", Some(delivery.id), "redelivered")
+            .before(&before)
+            .after(&delivery),
+    )
+    .await?;
+
+    Ok(delivery)
+}
@@ -0,0 +1,58 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::webhooks::{
+    validate_event_types, validate_webhook_secret, WebhookSubscription, WebhookSubscriptionUpdate,
+    WebhookTargetPolicy,
+};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::webhooks_port::WebhooksPort;
+use serde_json::json;
+use uuid::Uuid;
+
+/// Desactivar una suscripción congela sus entregas pendientes hasta que se
+/// reactive.
+pub async fn execute<P: WebhooksPort + AuditPort>(
+    port: &P,
+    policy: &WebhookTargetPolicy,
+    subscription_id: Uuid,
+    mut update: WebhookSubscriptionUpdate,
+    audit: &AuditContext,
+) -> Result<WebhookSubscription, DomainError> {
+    if let Some(url) = &update.url {
+        policy.validate_url(url)?;
+        update.url = Some(url.trim().to_string());
+    }
+    if let Some(event_types) = &mut update.event_types {
+        validate_event_types(event_types)?;
+        let mut unique = Vec::with_capacity(event_types.len());
+        for event_type in event_types.drain(..) {
+            if !unique.contains(&event_type) {
+                unique.push(event_type);
+            }
+        }
+        *event_types = unique;
+    }
+    if let Some(secret) = &update.secret {
+        validate_webhook_secret(secret)?;
+    }
+
+    let before = port
+        .get_webhook_subscription(subscription_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("suscripción no encontrada".to_string()))?;
+    let secret_rotated = update.secret.is_some();
+    let subscription = port
+        .update_webhook_subscription(subscription_id, update)
+        .await?;
+
+    port.record_audit_event(
+        audit
+            .event("webhook_subscription", Some(subscription.id), "updated")
+            .before(&before)
+            .after(&subscription)
+            .details(json!({ "secret_rotated": secret_rotated })),
+    )
+    .await?;
+
+    Ok(subscription)
+}
@@ -0,0 +1,23 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::zones::{validate_zone_fields, NewZone, Zone};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::zones_port::ZonesPort;
+
+pub async fn execute<P: ZonesPort + AuditPort>(
+    port: &P,
+    mut input: NewZone,
+    audit: &AuditContext,
+) -> Result<Zone, DomainError> {
+    validate_zone_fields(&input.name, input.polygon.as_deref(), &input.service_days)?;
+
+    input.name = input.name.trim().to_string();
+    input.service_days.sort();
+    input.service_days.dedup();
+
+    let zone = port.create_zone(input).await?;
+    port.record_audit_event(audit.event("zone", Some(zone.id), "created").after(&zone))
+        .await?;
+
+    Ok(zone)
+}
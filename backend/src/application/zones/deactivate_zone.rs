@@ -0,0 +1,40 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::zones::{Zone, ZoneUpdate};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::zones_port::ZonesPort;
+use uuid::Uuid;
+
+// Las zonas no se borran físicamente: los pedidos históricos las referencian.
+pub async fn execute<P: ZonesPort + AuditPort>(
+    port: &P,
+    zone_id: Uuid,
+    audit: &AuditContext,
+) -> Result<Zone, DomainError> {
+    let zone = port
+        .get_zone_by_id(zone_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("zona no encontrada".to_string()))?;
+
+    let deactivated = port
+        .update_zone(
+            zone_id,
+            ZoneUpdate {
+                name: zone.name.clone(),
+                polygon: zone.polygon.clone(),
+                service_days: zone.service_days.clone(),
+                default_slots: zone.default_slots.clone(),
+                active: false,
+            },
+        )
+        .await?;
+    port.record_audit_event(
+        audit
+            .event("zone", Some(zone_id), "deactivated")
+            .before(&zone)
+            .after(&deactivated),
+    )
+    .await?;
+
+    Ok(deactivated)
+}
@@ -0,0 +1,10 @@
+use crate::domain::error::DomainError;
+use crate::domain::zones::Zone;
+use crate::ports::zones_port::ZonesPort;
+use uuid::Uuid;
+
+pub async fn execute<P: ZonesPort>(port: &P, zone_id: Uuid) -> Result<Zone, DomainError> {
+    port.get_zone_by_id(zone_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("zona no encontrada".to_string()))
+}
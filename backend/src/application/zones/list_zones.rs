@@ -0,0 +1,10 @@
+use crate::domain::error::DomainError;
+use crate::domain::zones::Zone;
+use crate::ports::zones_port::ZonesPort;
+
+pub async fn execute<P: ZonesPort>(
+    port: &P,
+    include_inactive: bool,
+) -> Result<Vec<Zone>, DomainError> {
+    port.list_zones(include_inactive).await
+}
@@ -0,0 +1,5 @@
+pub mod create_zone;
+pub mod deactivate_zone;
+pub mod get_zone;
+pub mod list_zones;
+pub mod update_zone;
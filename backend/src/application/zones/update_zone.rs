@@ -0,0 +1,34 @@
+use crate::domain::audit::AuditContext;
+use crate::domain::error::DomainError;
+use crate::domain::zones::{validate_zone_fields, Zone, ZoneUpdate};
+use crate::ports::audit_port::AuditPort;
+use crate::ports::zones_port::ZonesPort;
+use uuid::Uuid;
+
+pub async fn execute<P: ZonesPort + AuditPort>(
+    port: &P,
+    zone_id: Uuid,
+    mut input: ZoneUpdate,
+    audit: &AuditContext,
+) -> Result<Zone, DomainError> {
+    validate_zone_fields(&input.name, input.polygon.as_deref(), &input.service_days)?;
+
+    input.name = input.name.trim().to_string();
+    input.service_days.sort();
+    input.service_days.dedup();
+
+    let before = port
+        .get_zone_by_id(zone_id)
+        .await?
+        .ok_or_else(|| DomainError::NotFound("zona no encontrada".to_string()))?;
+    let zone = port.update_zone(zone_id, input).await?;
+    port.record_audit_event(
+        audit
+            .event("zone", Some(zone.id), "updated")
+            .before(&before)
+            .after(&zone),
+    )
+    .await?;
+
+    Ok(zone)
+}
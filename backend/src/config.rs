@@ -4,9 +4,41 @@ use anyhow::{Context, Result};
 pub struct Settings {
     pub database_url: String,
     pub database_max_connections: u32,
-    pub jwt_secret: String,
-    pub jwt_expiration_hours: i64,
+    /// Obligatorio sin `jwt_keys_dir`; con claves asimétricas sólo valida los
+    /// tokens HS256 emitidos antes de migrar.
+    pub jwt_secret: Option<String>,
+    pub jwt_keys_dir: Option<String>,
+    pub jwt_active_kid: Option<String>,
+    pub access_token_minutes: i64,
+    pub refresh_token_days: i64,
     pub port: u16,
+    pub jobs_enabled: bool,
+    pub recurring_orders_cron: String,
+    pub recurring_days_ahead: i64,
+    pub purge_job_runs_cron: String,
+    pub job_runs_retention_days: i64,
+    pub purge_refresh_tokens_cron: String,
+    pub purge_driver_positions_cron: String,
+    pub driver_positions_retention_days: i64,
+    pub delivery_over_tolerance: i32,
+    pub login_free_attempts: i32,
+    pub login_ip_free_attempts: i32,
+    pub login_max_backoff_seconds: i64,
+    pub login_lockout_threshold: i32,
+    pub login_failure_window_minutes: i64,
+    pub trust_proxy_headers: bool,
+    pub allow_plaintext_passwords: bool,
+    pub password_reset_code_minutes: i64,
+    pub require_admin_2fa: bool,
+    pub events_dispatch_enabled: bool,
+    /// Destino opcional del webhook genérico de eventos de dominio.
+    pub events_webhook_url: Option<String>,
+    /// Sólo desarrollo: webhooks por http y hacia la red local.
+    pub webhooks_allow_insecure: bool,
+    /// Directorio raíz de fotos y firmas de entregas.
+    pub attachments_dir: String,
+    /// Zona horaria con la que se calcula "hoy" para agenda y reportes.
+    pub business_tz: chrono_tz::Tz,
 }
 
 impl Settings {
@@ -19,23 +51,169 @@ impl Settings {
             .parse::<u32>()
             .context("invalid DATABASE_MAX_CONNECTIONS")?;
 
-        let jwt_secret = std::env::var("JWT_SECRET").context("missing JWT_SECRET")?;
-        let jwt_expiration_hours = std::env::var("JWT_EXPIRATION_HOURS")
-            .unwrap_or_else(|_| "12".to_string())
+        let jwt_keys_dir = std::env::var("JWT_KEYS_DIR").ok();
+        let jwt_active_kid = std::env::var("JWT_ACTIVE_KID").ok();
+        if jwt_keys_dir.is_some() && jwt_active_kid.is_none() {
+            anyhow::bail!("JWT_KEYS_DIR requires JWT_ACTIVE_KID");
+        }
+        let jwt_secret = std::env::var("JWT_SECRET").ok();
+        if jwt_keys_dir.is_none() && jwt_secret.is_none() {
+            anyhow::bail!("missing JWT_SECRET");
+        }
+        let access_token_minutes = std::env::var("ACCESS_TOKEN_MINUTES")
+            .unwrap_or_else(|_| "15".to_string())
             .parse::<i64>()
-            .context("invalid JWT_EXPIRATION_HOURS")?;
+            .context("invalid ACCESS_TOKEN_MINUTES")?;
+
+        let refresh_token_days = std::env::var("REFRESH_TOKEN_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<i64>()
+            .context("invalid REFRESH_TOKEN_DAYS")?;
 
         let port = std::env::var("PORT")
             .unwrap_or_else(|_| "8080".to_string())
             .parse::<u16>()
             .context("invalid PORT")?;
 
+        let recurring_days_ahead = std::env::var("RECURRING_DAYS_AHEAD")
+            .unwrap_or_else(|_| "7".to_string())
+            .parse::<i64>()
+            .context("invalid RECURRING_DAYS_AHEAD")?;
+
+        let jobs_enabled = std::env::var("JOBS_ENABLED")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse::<bool>()
+            .context("invalid JOBS_ENABLED")?;
+
+        let recurring_orders_cron =
+            std::env::var("RECURRING_ORDERS_CRON").unwrap_or_else(|_| "0 * * * *".to_string());
+
+        let purge_job_runs_cron =
+            std::env::var("PURGE_JOB_RUNS_CRON").unwrap_or_else(|_| "30 3 * * *".to_string());
+
+        let job_runs_retention_days = std::env::var("JOB_RUNS_RETENTION_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<i64>()
+            .context("invalid JOB_RUNS_RETENTION_DAYS")?;
+
+        let purge_refresh_tokens_cron =
+            std::env::var("PURGE_REFRESH_TOKENS_CRON").unwrap_or_else(|_| "0 4 * * *".to_string());
+
+        let purge_driver_positions_cron = std::env::var("PURGE_DRIVER_POSITIONS_CRON")
+            .unwrap_or_else(|_| "15 4 * * *".to_string());
+
+        let driver_positions_retention_days = std::env::var("DRIVER_POSITIONS_RETENTION_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<i64>()
+            .context("invalid DRIVER_POSITIONS_RETENTION_DAYS")?;
+
+        let delivery_over_tolerance = std::env::var("DELIVERY_OVER_TOLERANCE")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<i32>()
+            .context("invalid DELIVERY_OVER_TOLERANCE")?;
+        if delivery_over_tolerance < 0 {
+            anyhow::bail!("DELIVERY_OVER_TOLERANCE must be >= 0");
+        }
+
+        let login_free_attempts = std::env::var("LOGIN_FREE_ATTEMPTS")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse::<i32>()
+            .context("invalid LOGIN_FREE_ATTEMPTS")?;
+
+        let login_ip_free_attempts = std::env::var("LOGIN_IP_FREE_ATTEMPTS")
+            .unwrap_or_else(|_| "20".to_string())
+            .parse::<i32>()
+            .context("invalid LOGIN_IP_FREE_ATTEMPTS")?;
+
+        let login_max_backoff_seconds = std::env::var("LOGIN_MAX_BACKOFF_SECONDS")
+            .unwrap_or_else(|_| "900".to_string())
+            .parse::<i64>()
+            .context("invalid LOGIN_MAX_BACKOFF_SECONDS")?;
+
+        let login_lockout_threshold = std::env::var("LOGIN_LOCKOUT_THRESHOLD")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse::<i32>()
+            .context("invalid LOGIN_LOCKOUT_THRESHOLD")?;
+
+        let login_failure_window_minutes = std::env::var("LOGIN_FAILURE_WINDOW_MINUTES")
+            .unwrap_or_else(|_| "15".to_string())
+            .parse::<i64>()
+            .context("invalid LOGIN_FAILURE_WINDOW_MINUTES")?;
+
+        let trust_proxy_headers = std::env::var("TRUST_PROXY_HEADERS")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .context("invalid TRUST_PROXY_HEADERS")?;
+
+        let allow_plaintext_passwords = std::env::var("ALLOW_PLAINTEXT_PASSWORDS")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse::<bool>()
+            .context("invalid ALLOW_PLAINTEXT_PASSWORDS")?;
+
+        let password_reset_code_minutes = std::env::var("PASSWORD_RESET_CODE_MINUTES")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse::<i64>()
+            .context("invalid PASSWORD_RESET_CODE_MINUTES")?;
+
+        let require_admin_2fa = std::env::var("REQUIRE_ADMIN_2FA")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .context("invalid REQUIRE_ADMIN_2FA")?;
+
+        let events_dispatch_enabled = std::env::var("EVENTS_DISPATCH_ENABLED")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse::<bool>()
+            .context("invalid EVENTS_DISPATCH_ENABLED")?;
+
+        let events_webhook_url = std::env::var("EVENTS_WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+
+        let webhooks_allow_insecure = std::env::var("WEBHOOKS_ALLOW_INSECURE")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse::<bool>()
+            .context("invalid WEBHOOKS_ALLOW_INSECURE")?;
+
+        let attachments_dir =
+            std::env::var("ATTACHMENTS_DIR").unwrap_or_else(|_| "data/attachments".to_string());
+
+        let business_tz = std::env::var("BUSINESS_TZ")
+            .unwrap_or_else(|_| "America/Argentina/Buenos_Aires".to_string())
+            .parse::<chrono_tz::Tz>()
+            .map_err(|_| anyhow::anyhow!("invalid BUSINESS_TZ"))?;
+
         Ok(Self {
             database_url,
             database_max_connections,
             jwt_secret,
-            jwt_expiration_hours,
+            jwt_keys_dir,
+            jwt_active_kid,
+            access_token_minutes,
+            refresh_token_days,
             port,
+            jobs_enabled,
+            recurring_orders_cron,
+            recurring_days_ahead,
+            purge_job_runs_cron,
+            job_runs_retention_days,
+            purge_refresh_tokens_cron,
+            purge_driver_positions_cron,
+            driver_positions_retention_days,
+            delivery_over_tolerance,
+            login_free_attempts,
+            login_ip_free_attempts,
+            login_max_backoff_seconds,
+            login_lockout_threshold,
+            login_failure_window_minutes,
+            trust_proxy_headers,
+            allow_plaintext_passwords,
+            password_reset_code_minutes,
+            require_admin_2fa,
+            events_dispatch_enabled,
+            events_webhook_url,
+            webhooks_allow_insecure,
+            attachments_dir,
+            business_tz,
         })
     }
 }
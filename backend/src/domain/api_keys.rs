@@ -0,0 +1,100 @@
+use crate::domain::auth::Permission;
+use crate::domain::error::DomainError;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const API_KEY_PREFIX: &str = "gf_";
+pub const MAX_API_KEY_NAME_LENGTH: usize = 100;
+
+/// Credencial de una integración. Actúa sólo con sus propios permisos y en
+/// la auditoría queda como `api_key_id`, sin usuario actor.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    /// Comienzo de la key, para reconocerla sin guardarla.
+    pub prefix: String,
+    pub permissions: Vec<Permission>,
+    pub created_by: Uuid,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    pub fn is_usable(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewApiKey {
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub permissions: Vec<Permission>,
+    pub created_by: Uuid,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Única respuesta que contiene la key en claro.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiKeyIssued {
+    pub key: String,
+    pub api_key: ApiKey,
+}
+
+pub fn validate_api_key_fields(
+    name: &str,
+    permissions: &[Permission],
+    expires_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<(), DomainError> {
+    if name.trim().is_empty() || name.trim().chars().count() > MAX_API_KEY_NAME_LENGTH {
+        return Err(DomainError::Validation(format!(
+            "name es obligatorio y no puede superar {} caracteres",
+            MAX_API_KEY_NAME_LENGTH
+        )));
+    }
+    if permissions.is_empty() {
+        return Err(DomainError::Validation(
+            "permissions no puede estar vacío".to_string(),
+        ));
+    }
+    if let Some(permission) = permissions.iter().find(|p| !p.grantable_to_api_key()) {
+        return Err(DomainError::Validation(format!(
+            "permiso no asignable a una API key: {}",
+            permission.as_str()
+        )));
+    }
+    if expires_at.is_some_and(|expires_at| expires_at <= now) {
+        return Err(DomainError::Validation(
+            "expires_at debe ser una fecha futura".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn api_keys_cannot_manage_users() {
+        let now = Utc::now();
+        assert!(validate_api_key_fields("central", &[Permission::OrdersCreate], None, now).is_ok());
+        assert!(validate_api_key_fields("central", &[Permission::UsersManage], None, now).is_err());
+        assert!(validate_api_key_fields("central", &[], None, now).is_err());
+        assert!(validate_api_key_fields(
+            "central",
+            &[Permission::OrdersCreate],
+            Some(now - Duration::minutes(1)),
+            now
+        )
+        .is_err());
+    }
+}
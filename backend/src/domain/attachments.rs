@@ -0,0 +1,211 @@
+use crate::domain::error::DomainError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Tope del body en las rutas de carga; también es el máximo de una foto.
+pub const MAX_PHOTO_BYTES: usize = 5 * 1024 * 1024;
+/// Una firma es un trazo sobre fondo liso: más que esto no es una firma.
+pub const MAX_SIGNATURE_BYTES: usize = 512 * 1024;
+pub const MAX_ATTACHMENTS_PER_RECORD: i64 = 10;
+/// Lado mayor de las miniaturas generadas.
+pub const THUMBNAIL_MAX_SIDE: u32 = 256;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AttachmentKind {
+    /// Puerta, cilindros entregados o el motivo del fallo.
+    Foto,
+    /// Firma del cliente.
+    Firma,
+}
+
+impl AttachmentKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Foto => "FOTO",
+            Self::Firma => "FIRMA",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "FOTO" => Some(Self::Foto),
+            "FIRMA" => Some(Self::Firma),
+            _ => None,
+        }
+    }
+
+    pub fn max_bytes(&self) -> usize {
+        match self {
+            Self::Foto => MAX_PHOTO_BYTES,
+            Self::Firma => MAX_SIGNATURE_BYTES,
+        }
+    }
+}
+
+/// Registro al que pertenece el adjunto.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AttachmentTarget {
+    Entrega,
+    EntregaFallida,
+}
+
+/// Formatos aceptados, reconocidos por su firma binaria y no por el
+/// `Content-Type` declarado.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+}
+
+impl ImageFormat {
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(Self::Jpeg)
+        } else if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+            Some(Self::Png)
+        } else {
+            None
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Jpeg => "jpg",
+            Self::Png => "png",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub target: AttachmentTarget,
+    /// Id de la entrega o de la entrega fallida.
+    pub record_id: Uuid,
+    pub order_id: Uuid,
+    pub kind: AttachmentKind,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// SHA-256 en hex del archivo original, para demostrar que no cambió.
+    pub sha256: String,
+    pub has_thumbnail: bool,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewAttachment {
+    pub id: Uuid,
+    pub target: AttachmentTarget,
+    pub record_id: Uuid,
+    pub order_id: Uuid,
+    pub kind: AttachmentKind,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub blob_key: String,
+    pub thumbnail_key: Option<String>,
+    pub uploaded_by: Option<Uuid>,
+}
+
+/// Adjunto con las claves de sus blobs; no sale por la API.
+#[derive(Debug, Clone)]
+pub struct StoredAttachment {
+    pub attachment: Attachment,
+    pub blob_key: String,
+    pub thumbnail_key: Option<String>,
+}
+
+/// Archivo listo para descargar.
+#[derive(Debug, Clone)]
+pub struct AttachmentContent {
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Valida tamaño y formato; el `Content-Type` declarado, si vino, tiene que
+/// coincidir con el contenido.
+pub fn validate_attachment(
+    kind: AttachmentKind,
+    declared_content_type: Option<&str>,
+    bytes: &[u8],
+) -> Result<ImageFormat, DomainError> {
+    if bytes.is_empty() {
+        return Err(DomainError::Validation("el archivo está vacío".to_string()));
+    }
+    if bytes.len() > kind.max_bytes() {
+        return Err(DomainError::Validation(format!(
+            "el archivo supera el máximo de {} bytes para {}",
+            kind.max_bytes(),
+            kind.as_str()
+        )));
+    }
+    let format = ImageFormat::detect(bytes).ok_or_else(|| {
+        DomainError::Validation("formato no soportado (usar JPEG o PNG)".to_string())
+    })?;
+    if let Some(declared) = declared_content_type {
+        let declared = declared.split(';').next().unwrap_or_default().trim();
+        if !declared.eq_ignore_ascii_case(format.content_type()) {
+            return Err(DomainError::Validation(format!(
+                "Content-Type {} no coincide con el contenido ({})",
+                declared,
+                format.content_type()
+            )));
+        }
+    }
+    Ok(format)
+}
+
+/// Conflicto si el registro ya tiene el máximo de adjuntos.
+pub fn ensure_attachment_room(existing: i64) -> Result<(), DomainError> {
+    if existing >= MAX_ATTACHMENTS_PER_RECORD {
+        return Err(DomainError::Conflict(format!(
+            "se admiten hasta {} adjuntos por registro",
+            MAX_ATTACHMENTS_PER_RECORD
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG_HEADER: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+    #[test]
+    fn format_comes_from_content() {
+        let jpeg = [0xFF, 0xD8, 0xFF, 0xE0, 0x00];
+        assert_eq!(
+            validate_attachment(AttachmentKind::Foto, Some("image/jpeg"), &jpeg).unwrap(),
+            ImageFormat::Jpeg
+        );
+        assert_eq!(
+            validate_attachment(AttachmentKind::Firma, None, &PNG_HEADER).unwrap(),
+            ImageFormat::Png
+        );
+        assert!(validate_attachment(AttachmentKind::Foto, Some("image/png"), &jpeg).is_err());
+        assert!(validate_attachment(AttachmentKind::Foto, None, b"GIF89a").is_err());
+        assert!(validate_attachment(AttachmentKind::Foto, None, &[]).is_err());
+    }
+
+    #[test]
+    fn signatures_are_smaller_than_photos() {
+        let mut big = PNG_HEADER.to_vec();
+        big.resize(MAX_SIGNATURE_BYTES + 1, 0);
+        assert!(validate_attachment(AttachmentKind::Firma, None, &big).is_err());
+        assert!(validate_attachment(AttachmentKind::Foto, Some("image/png"), &big).is_ok());
+    }
+}
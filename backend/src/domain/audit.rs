@@ -1,11 +1,348 @@
-use serde_json::Value;
+use crate::domain::error::DomainError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+pub const DEFAULT_AUDIT_PAGE_SIZE: i64 = 50;
+pub const MAX_AUDIT_PAGE_SIZE: i64 = 200;
+/// `prev_hash` del primer evento de la cadena.
+pub const AUDIT_GENESIS_HASH: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+/// Versión 1: eventos previos a los snapshots. La 2 suma al hash el estado
+/// antes/después, la IP y el user agent.
+pub const AUDIT_HASH_VERSION: i16 = 2;
+
+/// Origen de un cambio: quién actuó y desde qué pedido. Lo arma el adaptador
+/// de entrada y los casos de uso lo copian en cada evento que registran.
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    pub actor_id: Option<Uuid>,
+    /// API key que actuó; excluye `actor_id`.
+    pub api_key_id: Option<Uuid>,
+    /// `X-Request-Id` del pedido HTTP.
+    pub request_id: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl AuditContext {
+    /// Tareas programadas: sin actor ni pedido HTTP.
+    pub fn system() -> Self {
+        Self::default()
+    }
+
+    /// Mismo origen con otro actor: el login recién conoce al usuario después
+    /// de validar la contraseña.
+    pub fn acting_as(&self, user_id: Uuid) -> Self {
+        Self {
+            actor_id: Some(user_id),
+            api_key_id: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn event(&self, entity: &str, entity_id: Option<Uuid>, action: &str) -> NewAuditEvent {
+        NewAuditEvent {
+            actor_id: self.actor_id,
+            api_key_id: self.api_key_id,
+            entity: entity.to_string(),
+            entity_id,
+            action: action.to_string(),
+            before: None,
+            after: None,
+            details: json!({}),
+            request_id: self.request_id.clone(),
+            ip: self.ip.clone(),
+            user_agent: self.user_agent.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct NewAuditEvent {
     pub actor_id: Option<Uuid>,
+    /// API key que actuó; excluye `actor_id`.
+    pub api_key_id: Option<Uuid>,
+    pub entity: String,
+    pub entity_id: Option<Uuid>,
+    pub action: String,
+    /// Estado de la entidad antes del cambio; ausente en altas.
+    pub before: Option<Value>,
+    /// Estado después del cambio; ausente en bajas definitivas.
+    pub after: Option<Value>,
+    pub details: Value,
+    pub request_id: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl NewAuditEvent {
+    pub fn before<T: Serialize>(mut self, state: &T) -> Self {
+        self.before = Some(snapshot(state));
+        self
+    }
+
+    pub fn after<T: Serialize>(mut self, state: &T) -> Self {
+        self.after = Some(snapshot(state));
+        self
+    }
+
+    pub fn details(mut self, details: Value) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+/// Las entidades del dominio siempre serializan a JSON; `Null` sólo cubriría
+/// un mapa con claves no textuales.
+fn snapshot<T: Serialize>(state: &T) -> Value {
+    serde_json::to_value(state).unwrap_or(Value::Null)
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuditEvent {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub actor_username: Option<String>,
+    pub api_key_id: Option<Uuid>,
+    pub entity: String,
+    pub entity_id: Option<Uuid>,
+    pub action: String,
+    #[schema(value_type = Option<Object>)]
+    pub before: Option<Value>,
+    #[schema(value_type = Option<Object>)]
+    pub after: Option<Value>,
+    #[schema(value_type = Object)]
+    pub details: Value,
+    /// `X-Request-Id` del pedido que generó el evento.
+    pub request_id: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Último evento entregado; la página siguiente arranca después de él en el
+/// orden `created_at DESC, id DESC`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl AuditCursor {
+    pub fn after(event: &AuditEvent) -> Self {
+        Self {
+            created_at: event.created_at,
+            id: event.id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!(
+            "{}_{}",
+            self.created_at.timestamp_micros(),
+            self.id
+        ))
+    }
+
+    pub fn decode(value: &str) -> Result<Self, DomainError> {
+        let invalid = || DomainError::Validation("cursor inválido".to_string());
+        let raw = URL_SAFE_NO_PAD.decode(value).map_err(|_| invalid())?;
+        let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+        let (micros, id) = raw.split_once('_').ok_or_else(invalid)?;
+        Ok(Self {
+            created_at: micros
+                .parse::<i64>()
+                .ok()
+                .and_then(DateTime::from_timestamp_micros)
+                .ok_or_else(invalid)?,
+            id: id.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditFilter {
+    pub entity: Option<String>,
+    pub entity_id: Option<Uuid>,
+    /// Username de quien actuó.
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub cursor: Option<AuditCursor>,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuditPage {
+    pub items: Vec<AuditEvent>,
+    /// Ausente en la última página.
+    pub next_cursor: Option<String>,
+}
+
+/// Evento tal como queda en la cadena. `hash` cubre el contenido y
+/// `prev_hash`: editar, borrar o reordenar filas rompe la cadena desde ahí.
+#[derive(Debug, Clone)]
+pub struct ChainedAuditEvent {
+    pub seq: i64,
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub api_key_id: Option<Uuid>,
     pub entity: String,
     pub entity_id: Option<Uuid>,
     pub action: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
     pub details: Value,
+    pub request_id: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub hash_version: i16,
+    pub prev_hash: Option<String>,
+    pub hash: Option<String>,
+}
+
+impl ChainedAuditEvent {
+    /// SHA-256 sobre un arreglo JSON con campos en orden fijo; los objetos de
+    /// `details` se serializan con las claves ordenadas, así el resultado no
+    /// depende de cómo los devuelva JSONB.
+    pub fn compute_hash(&self, prev_hash: &str) -> String {
+        let mut content = vec![
+            json!(prev_hash),
+            json!(self.seq),
+            json!(self.id),
+            json!(self.actor_id),
+            json!(self.api_key_id),
+            json!(self.entity),
+            json!(self.entity_id),
+            json!(self.action),
+            self.details.clone(),
+            json!(self.request_id),
+            json!(self.created_at.timestamp_micros()),
+        ];
+        if self.hash_version >= 2 {
+            content.extend([
+                json!(self.before),
+                json!(self.after),
+                json!(self.ip),
+                json!(self.user_agent),
+            ]);
+        }
+        hex::encode(Sha256::digest(Value::Array(content).to_string().as_bytes()))
+    }
+}
+
+/// Primer eslabón que no cierra.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BrokenAuditLink {
+    pub seq: i64,
+    pub event_id: Uuid,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuditChainReport {
+    pub valid: bool,
+    /// Eventos verificados antes del primer eslabón roto (o en total).
+    pub checked: i64,
+    pub head_hash: Option<String>,
+    pub first_broken: Option<BrokenAuditLink>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = AuditCursor {
+            created_at: DateTime::from_timestamp_micros(1_771_234_567_123_456).unwrap(),
+            id: Uuid::new_v4(),
+        };
+        assert_eq!(AuditCursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(AuditCursor::decode("no-es-un-cursor").is_err());
+    }
+
+    #[test]
+    fn chain_hash_covers_content_and_previous_link() {
+        let event = ChainedAuditEvent {
+            seq: 1,
+            id: Uuid::new_v4(),
+            actor_id: None,
+            api_key_id: None,
+            entity: "order".to_string(),
+            entity_id: Some(Uuid::new_v4()),
+            action: "created".to_string(),
+            before: None,
+            after: Some(json!({ "status": "PENDING" })),
+            details: json!({ "quantity": 2, "address": "Calle 1" }),
+            request_id: Some("req-1".to_string()),
+            ip: Some("10.0.0.1".to_string()),
+            user_agent: None,
+            created_at: Utc::now(),
+            hash_version: AUDIT_HASH_VERSION,
+            prev_hash: None,
+            hash: None,
+        };
+        let hash = event.compute_hash(AUDIT_GENESIS_HASH);
+        assert_eq!(hash.len(), 64);
+        assert_ne!(hash, event.compute_hash(&hash));
+
+        let reordered = ChainedAuditEvent {
+            details: json!({ "address": "Calle 1", "quantity": 2 }),
+            ..event.clone()
+        };
+        assert_eq!(reordered.compute_hash(AUDIT_GENESIS_HASH), hash);
+        let edited = ChainedAuditEvent {
+            details: json!({ "quantity": 3, "address": "Calle 1" }),
+            ..event.clone()
+        };
+        assert_ne!(edited.compute_hash(AUDIT_GENESIS_HASH), hash);
+        let edited_snapshot = ChainedAuditEvent {
+            after: Some(json!({ "status": "DELIVERED" })),
+            ..event.clone()
+        };
+        assert_ne!(edited_snapshot.compute_hash(AUDIT_GENESIS_HASH), hash);
+
+        // Los eventos de la versión 1 no cubren los campos agregados después.
+        let legacy = ChainedAuditEvent {
+            hash_version: 1,
+            ..event.clone()
+        };
+        let legacy_edited = ChainedAuditEvent {
+            ip: None,
+            ..legacy.clone()
+        };
+        assert_eq!(
+            legacy.compute_hash(AUDIT_GENESIS_HASH),
+            legacy_edited.compute_hash(AUDIT_GENESIS_HASH)
+        );
+    }
+
+    #[test]
+    fn context_builds_events_with_snapshots() {
+        let actor = Uuid::new_v4();
+        let context = AuditContext {
+            request_id: Some("req-9".to_string()),
+            ip: Some("10.0.0.2".to_string()),
+            ..AuditContext::default()
+        }
+        .acting_as(actor);
+        let event = context
+            .event("zone", None, "updated")
+            .before(&json!({ "name": "Norte" }))
+            .after(&json!({ "name": "Norte 2" }));
+        assert_eq!(event.actor_id, Some(actor));
+        assert_eq!(event.request_id.as_deref(), Some("req-9"));
+        assert_eq!(event.ip.as_deref(), Some("10.0.0.2"));
+        assert_eq!(event.before, Some(json!({ "name": "Norte" })));
+        assert_eq!(event.details, json!({}));
+    }
 }
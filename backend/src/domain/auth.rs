@@ -1,29 +1,198 @@
+use crate::domain::error::DomainError;
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+pub const MIN_USERNAME_LENGTH: usize = 3;
+pub const MAX_USERNAME_LENGTH: usize = 50;
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+/// bcrypt ignora lo que sigue a los primeros 72 bytes.
+pub const MAX_PASSWORD_BYTES: usize = 72;
+pub const DEFAULT_RESET_CODE_MINUTES: i64 = 60;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Role {
     Admin,
+    Supervisor,
     Repartidor,
+    Cliente,
 }
 
 impl Role {
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Admin => "ADMIN",
+            Self::Supervisor => "SUPERVISOR",
             Self::Repartidor => "REPARTIDOR",
+            Self::Cliente => "CLIENTE",
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(value: &str) -> Option<Self> {
         match value {
             "ADMIN" => Some(Self::Admin),
+            "SUPERVISOR" => Some(Self::Supervisor),
             "REPARTIDOR" => Some(Self::Repartidor),
+            "CLIENTE" => Some(Self::Cliente),
             _ => None,
         }
     }
+
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            // Todo menos pedir como cliente: un admin no tiene direcciones ni
+            // pedidos a su nombre.
+            Self::Admin => &[
+                Permission::OrdersCreate,
+                Permission::OrdersReadAll,
+                Permission::OrdersUpdateStatus,
+                Permission::DispatchAssign,
+                Permission::DeliveriesRegister,
+                Permission::DeliveriesAnyOrder,
+                Permission::DeliveriesCorrect,
+                Permission::StockRead,
+                Permission::StockWrite,
+                Permission::ReportsRead,
+                Permission::CatalogWrite,
+                Permission::RecurringManage,
+                Permission::JobsManage,
+                Permission::UsersManage,
+                Permission::AuditRead,
+                Permission::WebhooksManage,
+                Permission::PositionsReport,
+                Permission::PositionsRead,
+            ],
+            Self::Supervisor => &[
+                Permission::OrdersReadAll,
+                Permission::DispatchAssign,
+                Permission::StockRead,
+                Permission::ReportsRead,
+                Permission::PositionsRead,
+            ],
+            Self::Repartidor => &[Permission::DeliveriesRegister, Permission::PositionsReport],
+            Self::Cliente => &[Permission::OrdersSelfService],
+        }
+    }
+
+    pub fn has(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+/// Acción autorizable. Cada ruta protegida exige uno; los roles sólo son
+/// conjuntos de permisos.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+pub enum Permission {
+    #[serde(rename = "orders:create")]
+    OrdersCreate,
+    /// Sin este permiso sólo se ven los pedidos asignados a uno mismo (o, con
+    /// `orders:self_service`, los propios).
+    #[serde(rename = "orders:read_all")]
+    OrdersReadAll,
+    #[serde(rename = "orders:update_status")]
+    OrdersUpdateStatus,
+    /// Direcciones guardadas y pedidos a nombre propio.
+    #[serde(rename = "orders:self_service")]
+    OrdersSelfService,
+    #[serde(rename = "dispatch:assign")]
+    DispatchAssign,
+    #[serde(rename = "deliveries:register")]
+    DeliveriesRegister,
+    /// Registrar entregas de pedidos asignados a otro repartidor.
+    #[serde(rename = "deliveries:any_order")]
+    DeliveriesAnyOrder,
+    /// Corregir o anular entregas ya registradas y registrar más llenas que
+    /// las pedidas.
+    #[serde(rename = "deliveries:correct")]
+    DeliveriesCorrect,
+    #[serde(rename = "stock:read")]
+    StockRead,
+    #[serde(rename = "stock:write")]
+    StockWrite,
+    #[serde(rename = "reports:read")]
+    ReportsRead,
+    /// Zonas, franjas y calendario.
+    #[serde(rename = "catalog:write")]
+    CatalogWrite,
+    #[serde(rename = "recurring:manage")]
+    RecurringManage,
+    #[serde(rename = "jobs:manage")]
+    JobsManage,
+    #[serde(rename = "users:manage")]
+    UsersManage,
+    #[serde(rename = "audit:read")]
+    AuditRead,
+    /// Suscripciones a eventos y su registro de entregas.
+    #[serde(rename = "webhooks:manage")]
+    WebhooksManage,
+    /// Enviar las posiciones GPS propias.
+    #[serde(rename = "positions:report")]
+    PositionsReport,
+    /// Última posición y recorrido de los repartidores.
+    #[serde(rename = "positions:read")]
+    PositionsRead,
+}
+
+impl Permission {
+    pub const ALL: &'static [Permission] = &[
+        Self::OrdersCreate,
+        Self::OrdersReadAll,
+        Self::OrdersUpdateStatus,
+        Self::OrdersSelfService,
+        Self::DispatchAssign,
+        Self::DeliveriesRegister,
+        Self::DeliveriesAnyOrder,
+        Self::DeliveriesCorrect,
+        Self::StockRead,
+        Self::StockWrite,
+        Self::ReportsRead,
+        Self::CatalogWrite,
+        Self::RecurringManage,
+        Self::JobsManage,
+        Self::UsersManage,
+        Self::AuditRead,
+        Self::WebhooksManage,
+        Self::PositionsReport,
+        Self::PositionsRead,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OrdersCreate => "orders:create",
+            Self::OrdersReadAll => "orders:read_all",
+            Self::OrdersUpdateStatus => "orders:update_status",
+            Self::OrdersSelfService => "orders:self_service",
+            Self::DispatchAssign => "dispatch:assign",
+            Self::DeliveriesRegister => "deliveries:register",
+            Self::DeliveriesAnyOrder => "deliveries:any_order",
+            Self::DeliveriesCorrect => "deliveries:correct",
+            Self::StockRead => "stock:read",
+            Self::StockWrite => "stock:write",
+            Self::ReportsRead => "reports:read",
+            Self::CatalogWrite => "catalog:write",
+            Self::RecurringManage => "recurring:manage",
+            Self::JobsManage => "jobs:manage",
+            Self::UsersManage => "users:manage",
+            Self::AuditRead => "audit:read",
+            Self::WebhooksManage => "webhooks:manage",
+            Self::PositionsReport => "positions:report",
+            Self::PositionsRead => "positions:read",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(value: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|p| p.as_str() == value)
+    }
+
+    /// Permisos que exigen una persona detrás: una API key no puede
+    /// administrar usuarios (ni emitir otras keys) ni pedir como cliente.
+    pub fn grantable_to_api_key(&self) -> bool {
+        !matches!(self, Self::UsersManage | Self::OrdersSelfService)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -32,6 +201,183 @@ pub struct User {
     pub username: String,
     pub password: String,
     pub role: Role,
+    pub active: bool,
+    /// Se incrementa para invalidar de golpe todos los tokens del usuario.
+    pub token_generation: i64,
+    /// Bloqueo por intentos fallidos; sólo un administrador lo levanta.
+    pub locked_at: Option<DateTime<Utc>>,
+    /// Tras un reset administrativo el usuario sólo entra fijando una
+    /// contraseña nueva con el código recibido.
+    pub must_change_password: bool,
+    /// Secreto TOTP en base32; sin `totp_enabled_at` la activación está
+    /// pendiente de confirmar.
+    pub totp_secret: Option<String>,
+    pub totp_enabled_at: Option<DateTime<Utc>>,
+    /// Último paso TOTP aceptado: un código no se puede usar dos veces.
+    pub totp_last_step: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl User {
+    pub fn two_factor_enabled(&self) -> bool {
+        self.totp_enabled_at.is_some()
+    }
+}
+
+/// Vista pública de un usuario: nunca expone el hash de la contraseña.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserAccount {
+    pub id: Uuid,
+    pub username: String,
+    pub role: Role,
+    pub active: bool,
+    pub locked_at: Option<DateTime<Utc>>,
+    pub must_change_password: bool,
+    pub two_factor_enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<User> for UserAccount {
+    fn from(value: User) -> Self {
+        UserAccount {
+            two_factor_enabled: value.two_factor_enabled(),
+            id: value.id,
+            username: value.username,
+            role: value.role,
+            active: value.active,
+            locked_at: value.locked_at,
+            must_change_password: value.must_change_password,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewUser {
+    pub username: String,
+    pub password_hash: String,
+    pub role: Role,
+}
+
+#[derive(Debug, Clone)]
+pub struct UserFilter {
+    pub role: Option<Role>,
+    pub include_inactive: bool,
+}
+
+pub fn validate_username(username: &str) -> Result<(), DomainError> {
+    let valid_chars = username
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '_' | '-'));
+    if !(MIN_USERNAME_LENGTH..=MAX_USERNAME_LENGTH).contains(&username.len()) || !valid_chars {
+        return Err(DomainError::Validation(format!(
+            "username debe tener entre {} y {} caracteres: minúsculas, dígitos, '.', '_' o '-'",
+            MIN_USERNAME_LENGTH, MAX_USERNAME_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+pub fn validate_password(password: &str) -> Result<(), DomainError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(DomainError::Validation(format!(
+            "password debe tener al menos {} caracteres",
+            MIN_PASSWORD_LENGTH
+        )));
+    }
+    if password.len() > MAX_PASSWORD_BYTES {
+        return Err(DomainError::Validation(format!(
+            "password no puede superar los {} bytes",
+            MAX_PASSWORD_BYTES
+        )));
+    }
+    if password.trim().is_empty() {
+        return Err(DomainError::Validation(
+            "password no puede ser sólo espacios".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Política completa para una contraseña nueva de `username`; `current` es la
+/// contraseña que reemplaza, si se conoce.
+pub fn validate_new_password(
+    username: &str,
+    password: &str,
+    current: Option<&str>,
+) -> Result<(), DomainError> {
+    validate_password(password)?;
+    if password
+        .to_lowercase()
+        .contains(&username.trim().to_lowercase())
+    {
+        return Err(DomainError::Validation(
+            "password no puede contener el username".to_string(),
+        ));
+    }
+    if current == Some(password) {
+        return Err(DomainError::Validation(
+            "password nueva debe ser distinta de la actual".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PasswordScheme {
+    Plaintext,
+    Bcrypt,
+}
+
+/// Cómo está guardada una contraseña. Se deduce del valor almacenado: los
+/// hashes bcrypt tienen la forma `$2b$<costo>$...`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoredPassword {
+    pub scheme: PasswordScheme,
+    pub cost: Option<u32>,
+}
+
+impl StoredPassword {
+    pub fn detect(stored: &str) -> Self {
+        let mut parts = stored.splitn(4, '$');
+        let bcrypt_cost = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(""), Some("2a" | "2b" | "2y"), Some(cost), Some(_)) => cost.parse::<u32>().ok(),
+            _ => None,
+        };
+        match bcrypt_cost {
+            Some(cost) => Self {
+                scheme: PasswordScheme::Bcrypt,
+                cost: Some(cost),
+            },
+            None => Self {
+                scheme: PasswordScheme::Plaintext,
+                cost: None,
+            },
+        }
+    }
+
+    /// Texto plano o bcrypt con un costo menor al vigente.
+    pub fn is_legacy(&self, current_cost: u32) -> bool {
+        match self.scheme {
+            PasswordScheme::Plaintext => true,
+            PasswordScheme::Bcrypt => self.cost.is_some_and(|cost| cost < current_cost),
+        }
+    }
+}
+
+/// Usuario cuya contraseña todavía no cumple la política de hash vigente.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LegacyPasswordUser {
+    pub id: Uuid,
+    pub username: String,
+    pub role: Role,
+    pub active: bool,
+    pub scheme: PasswordScheme,
+    pub cost: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,4 +385,256 @@ pub struct Claims {
     pub sub: String,
     pub role: Role,
     pub exp: usize,
+    /// `token_generation` del usuario al emitir el token.
+    pub gen: i64,
+}
+
+/// Refresh token persistido; sólo se guarda el hash SHA-256 del valor.
+/// Todos los tokens obtenidos rotando desde un mismo login comparten `family_id`.
+#[derive(Debug, Clone)]
+pub struct RefreshToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub family_id: Uuid,
+    pub token_generation: i64,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewRefreshToken {
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub family_id: Uuid,
+    pub token_generation: i64,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenPair {
+    pub user_id: Uuid,
+    pub access_token: String,
+    pub expires_in: i64,
+    pub refresh_token: String,
+}
+
+/// Código de un solo uso emitido por un administrador; sólo se guarda su hash.
+#[derive(Debug, Clone)]
+pub struct PasswordReset {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewPasswordReset {
+    pub user_id: Uuid,
+    pub code_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_by: Uuid,
+}
+
+/// Respuesta al administrador: el código sólo se muestra esta vez.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PasswordResetIssued {
+    pub user_id: Uuid,
+    pub code: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Clave sobre la que se cuentan los intentos fallidos de login.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginScope {
+    Username,
+    Ip,
+}
+
+impl LoginScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Username => "USERNAME",
+            Self::Ip => "IP",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoginFailures {
+    pub failures: i32,
+    pub last_failure_at: DateTime<Utc>,
+}
+
+/// Reglas contra fuerza bruta en el login. Pasados los intentos libres, cada
+/// fallo duplica la espera hasta `max_delay_seconds`; los contadores se
+/// reinician tras `failure_window_minutes` sin fallos.
+#[derive(Debug, Clone)]
+pub struct LoginPolicy {
+    pub free_attempts: i32,
+    /// Una IP puede concentrar varios usuarios legítimos (NAT, oficina).
+    pub ip_free_attempts: i32,
+    pub base_delay_seconds: i64,
+    pub max_delay_seconds: i64,
+    /// Fallos consecutivos de un usuario existente que bloquean la cuenta.
+    pub lockout_threshold: i32,
+    pub failure_window_minutes: i64,
+    /// Acepta contraseñas guardadas en texto plano (se rehashean al entrar).
+    /// Desactivar una vez migrados todos los usuarios.
+    pub allow_plaintext_passwords: bool,
+    pub reset_code_minutes: i64,
+    /// Los ADMIN sin 2FA sólo pueden entrar activándolo.
+    pub require_admin_two_factor: bool,
+}
+
+impl Default for LoginPolicy {
+    fn default() -> Self {
+        Self {
+            free_attempts: 3,
+            ip_free_attempts: 20,
+            base_delay_seconds: 1,
+            max_delay_seconds: 900,
+            lockout_threshold: 10,
+            failure_window_minutes: 15,
+            allow_plaintext_passwords: true,
+            reset_code_minutes: DEFAULT_RESET_CODE_MINUTES,
+            require_admin_two_factor: false,
+        }
+    }
+}
+
+impl LoginPolicy {
+    /// El rol exige segundo factor aunque el usuario no lo haya activado.
+    pub fn requires_two_factor(&self, role: &Role) -> bool {
+        self.require_admin_two_factor && *role == Role::Admin
+    }
+
+    pub fn window_start(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::minutes(self.failure_window_minutes)
+    }
+
+    /// Segundos que faltan para aceptar otro intento, o `None` si se puede
+    /// intentar ya.
+    pub fn retry_after(
+        &self,
+        scope: LoginScope,
+        failures: &LoginFailures,
+        now: DateTime<Utc>,
+    ) -> Option<i64> {
+        let free = match scope {
+            LoginScope::Username => self.free_attempts,
+            LoginScope::Ip => self.ip_free_attempts,
+        };
+        if failures.failures < free || failures.last_failure_at < self.window_start(now) {
+            return None;
+        }
+
+        let exponent = (failures.failures - free).min(32) as u32;
+        let delay = self
+            .base_delay_seconds
+            .saturating_mul(2i64.saturating_pow(exponent))
+            .min(self.max_delay_seconds);
+        let remaining =
+            (failures.last_failure_at + Duration::seconds(delay) - now).num_milliseconds();
+        (remaining > 0).then_some((remaining + 999) / 1000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn username_must_be_lowercase_slug() {
+        assert!(validate_username("repartidor.2").is_ok());
+        assert!(validate_username("ab").is_err());
+        assert!(validate_username("Juan Perez").is_err());
+        assert!(validate_password("corta").is_err());
+        assert!(validate_password("suficiente").is_ok());
+    }
+
+    #[test]
+    fn supervisor_assigns_and_reads_reports_only() {
+        assert!(Role::Supervisor.has(Permission::DispatchAssign));
+        assert!(Role::Supervisor.has(Permission::ReportsRead));
+        assert!(!Role::Supervisor.has(Permission::UsersManage));
+        assert!(!Role::Supervisor.has(Permission::StockWrite));
+        assert!(!Role::Supervisor.has(Permission::AuditRead));
+        assert!(!Role::Supervisor.has(Permission::WebhooksManage));
+        assert!(!Role::Supervisor.has(Permission::DeliveriesCorrect));
+        assert!(Role::Supervisor.has(Permission::PositionsRead));
+        assert!(Role::Repartidor.has(Permission::PositionsReport));
+        assert!(!Role::Repartidor.has(Permission::PositionsRead));
+        assert!(!Role::Repartidor.has(Permission::OrdersReadAll));
+        assert!(Role::Cliente.has(Permission::OrdersSelfService));
+        assert!(!Role::Cliente.has(Permission::OrdersReadAll));
+        assert!(!Role::Cliente.has(Permission::OrdersCreate));
+        assert_eq!(Role::from_str("SUPERVISOR"), Some(Role::Supervisor));
+    }
+
+    #[test]
+    fn admin_has_everything_but_self_service() {
+        assert!(!Role::Admin.has(Permission::OrdersSelfService));
+        assert!(Permission::ALL
+            .iter()
+            .filter(|p| **p != Permission::OrdersSelfService)
+            .all(|p| Role::Admin.has(*p)));
+    }
+
+    #[test]
+    fn new_password_policy() {
+        assert!(validate_new_password("juan", "clave-segura", None).is_ok());
+        assert!(validate_new_password("juan", "JUAN-2026-clave", None).is_err());
+        assert!(validate_new_password("juan", "clave-segura", Some("clave-segura")).is_err());
+        assert!(validate_new_password("juan", &"x".repeat(MAX_PASSWORD_BYTES + 1), None).is_err());
+        assert!(validate_new_password("juan", "        ", None).is_err());
+    }
+
+    #[test]
+    fn detect_legacy_password_storage() {
+        let plaintext = StoredPassword::detect("admin123");
+        assert_eq!(plaintext.scheme, PasswordScheme::Plaintext);
+        assert!(plaintext.is_legacy(12));
+
+        let low_cost = StoredPassword::detect("$2b$04$abcdefghijklmnopqrstuv");
+        assert_eq!(low_cost.scheme, PasswordScheme::Bcrypt);
+        assert_eq!(low_cost.cost, Some(4));
+        assert!(low_cost.is_legacy(12));
+        assert!(!StoredPassword::detect("$2y$12$abcdefghijklmnopqrstuv").is_legacy(12));
+        assert!(StoredPassword::detect("$2b$").is_legacy(12));
+    }
+
+    #[test]
+    fn login_backoff_grows_exponentially_and_expires() {
+        let policy = LoginPolicy::default();
+        let now = Utc::now();
+        let failures = |count: i32, ago: i64| LoginFailures {
+            failures: count,
+            last_failure_at: now - Duration::seconds(ago),
+        };
+
+        assert_eq!(
+            policy.retry_after(LoginScope::Username, &failures(2, 0), now),
+            None
+        );
+        assert_eq!(
+            policy.retry_after(LoginScope::Username, &failures(3, 0), now),
+            Some(1)
+        );
+        assert_eq!(
+            policy.retry_after(LoginScope::Username, &failures(6, 0), now),
+            Some(8)
+        );
+        assert_eq!(
+            policy.retry_after(LoginScope::Username, &failures(6, 8), now),
+            None
+        );
+        assert_eq!(
+            policy.retry_after(LoginScope::Username, &failures(40, 0), now),
+            Some(policy.max_delay_seconds)
+        );
+        // La misma cantidad de fallos por IP todavía está dentro de lo tolerado.
+        assert_eq!(
+            policy.retry_after(LoginScope::Ip, &failures(6, 0), now),
+            None
+        );
+    }
 }
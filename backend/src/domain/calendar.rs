@@ -0,0 +1,153 @@
+use crate::domain::error::DomainError;
+use crate::domain::zones::{DayOfWeek, Zone};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Horizonte de búsqueda de la próxima fecha hábil.
+pub const NEXT_AVAILABLE_HORIZON_DAYS: i64 = 366;
+
+pub const DEFAULT_BUSINESS_TZ: Tz = chrono_tz::America::Argentina::Buenos_Aires;
+
+/// Fecha del calendario del negocio en el instante `at`.
+pub fn local_date(at: DateTime<Utc>, tz: Tz) -> NaiveDate {
+    at.with_timezone(&tz).date_naive()
+}
+
+/// Zona horaria con la que se calcula "hoy" para agenda y reportes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarPolicy {
+    pub timezone: Tz,
+}
+
+impl Default for CalendarPolicy {
+    fn default() -> Self {
+        Self {
+            timezone: DEFAULT_BUSINESS_TZ,
+        }
+    }
+}
+
+impl CalendarPolicy {
+    /// Hoy según el calendario del negocio: el día cambia a la medianoche
+    /// local, no a la de UTC.
+    pub fn today(&self) -> NaiveDate {
+        local_date(Utc::now(), self.timezone)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewHoliday {
+    pub date: NaiveDate,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WorkingCalendar {
+    pub closed_weekdays: Vec<DayOfWeek>,
+    pub holidays: Vec<Holiday>,
+}
+
+impl WorkingCalendar {
+    pub fn is_closed(&self, date: NaiveDate) -> bool {
+        self.closed_weekdays.contains(&DayOfWeek::of(date))
+            || self.holidays.iter().any(|holiday| holiday.date == date)
+    }
+
+    /// Valida que se pueda programar un reparto en `date`.
+    pub fn ensure_schedulable(&self, date: NaiveDate, today: NaiveDate) -> Result<(), DomainError> {
+        if date < today {
+            return Err(DomainError::Validation(format!(
+                "la fecha {} ya pasó",
+                date
+            )));
+        }
+        if let Some(holiday) = self.holidays.iter().find(|holiday| holiday.date == date) {
+            return Err(DomainError::Validation(format!(
+                "{} es feriado/no laborable: {}",
+                date, holiday.description
+            )));
+        }
+        if self.closed_weekdays.contains(&DayOfWeek::of(date)) {
+            return Err(DomainError::Validation(format!(
+                "no hay reparto los {}",
+                DayOfWeek::of(date).as_str()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Primera fecha desde `from` que no esté cerrada y, si se indica zona,
+    /// que la zona atienda.
+    pub fn next_available(&self, from: NaiveDate, zone: Option<&Zone>) -> Option<NaiveDate> {
+        (0..NEXT_AVAILABLE_HORIZON_DAYS)
+            .map(|offset| from + Duration::days(offset))
+            .find(|date| !self.is_closed(*date) && zone.is_none_or(|zone| zone.serves(*date)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn calendar() -> WorkingCalendar {
+        WorkingCalendar {
+            closed_weekdays: vec![DayOfWeek::Domingo],
+            holidays: vec![Holiday {
+                date: date(2026, 5, 25),
+                description: "Revolución de Mayo".to_string(),
+                created_at: Utc::now(),
+            }],
+        }
+    }
+
+    #[test]
+    fn reject_past_closed_and_holiday_dates() {
+        let calendar = calendar();
+        let today = date(2026, 5, 20);
+
+        assert!(calendar
+            .ensure_schedulable(date(2026, 5, 19), today)
+            .is_err());
+        assert!(calendar
+            .ensure_schedulable(date(2026, 5, 24), today)
+            .is_err());
+        assert!(calendar
+            .ensure_schedulable(date(2026, 5, 25), today)
+            .is_err());
+        assert!(calendar.ensure_schedulable(today, today).is_ok());
+        assert!(calendar
+            .ensure_schedulable(date(2026, 5, 26), today)
+            .is_ok());
+    }
+
+    #[test]
+    fn next_available_skips_sunday_and_holiday() {
+        // 2026-05-24 es domingo y 2026-05-25 feriado.
+        let next = calendar().next_available(date(2026, 5, 24), None);
+        assert_eq!(next, Some(date(2026, 5, 26)));
+    }
+
+    #[test]
+    fn local_date_follows_business_timezone() {
+        // 01:30 UTC del 17 todavía es el 16 en Buenos Aires (UTC-3).
+        let at = DateTime::parse_from_rfc3339("2026-05-17T01:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let buenos_aires = chrono_tz::America::Argentina::Buenos_Aires;
+        assert_eq!(local_date(at, buenos_aires), date(2026, 5, 16));
+        assert_eq!(local_date(at, chrono_tz::UTC), date(2026, 5, 17));
+    }
+}
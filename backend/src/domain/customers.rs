@@ -0,0 +1,46 @@
+use crate::domain::error::DomainError;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const MAX_ADDRESS_LABEL_LENGTH: usize = 50;
+
+/// Dirección guardada de un cliente. `zone` refleja el nombre actual del
+/// catálogo.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CustomerAddress {
+    pub id: Uuid,
+    pub customer_id: Uuid,
+    pub label: String,
+    pub address: String,
+    pub zone_id: Uuid,
+    pub zone: String,
+    pub notes: Option<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewCustomerAddress {
+    pub customer_id: Uuid,
+    pub label: String,
+    pub address: String,
+    pub zone_id: Uuid,
+    pub notes: Option<String>,
+}
+
+pub fn validate_address_fields(label: &str, address: &str) -> Result<(), DomainError> {
+    if label.trim().is_empty() || address.trim().is_empty() {
+        return Err(DomainError::Validation(
+            "label y address son obligatorios".to_string(),
+        ));
+    }
+    if label.trim().chars().count() > MAX_ADDRESS_LABEL_LENGTH {
+        return Err(DomainError::Validation(format!(
+            "label no puede superar {} caracteres",
+            MAX_ADDRESS_LABEL_LENGTH
+        )));
+    }
+    Ok(())
+}
@@ -1,5 +1,9 @@
+use crate::domain::error::DomainError;
+use crate::domain::orders::{Order, OrderStatus};
+use crate::domain::positions::PositionFix;
+use crate::domain::slots::SlotReservation;
 use chrono::{DateTime, NaiveDate, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -10,6 +14,13 @@ pub struct Delivery {
     pub llenas_entregadas: i32,
     pub vacias_recibidas: i32,
     pub notes: Option<String>,
+    /// Posición del repartidor al registrarla, si se conocía.
+    pub position: Option<PositionFix>,
+    /// Anulada por un administrador; las cantidades originales se conservan y
+    /// la compensación está en sus correcciones.
+    pub voided_at: Option<DateTime<Utc>>,
+    /// Pedido nuevo con el saldo de una entrega parcial.
+    pub follow_up_order_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -19,6 +30,142 @@ pub struct NewDelivery {
     pub llenas_entregadas: i32,
     pub vacias_recibidas: i32,
     pub notes: Option<String>,
+    pub position: Option<PositionFix>,
+}
+
+/// Lo que deja registrar una entrega: el pedido con su estado nuevo y el
+/// pedido de seguimiento, si se creó.
+#[derive(Debug, Clone)]
+pub struct RegisteredDelivery {
+    pub delivery: Delivery,
+    pub order: Order,
+    pub follow_up: Option<Order>,
+}
+
+/// Lo que deja registrar una entrega fallida: el pedido reprogramado.
+#[derive(Debug, Clone)]
+pub struct RegisteredFailure {
+    pub failed: FailedDelivery,
+    pub order: Order,
+}
+
+/// Qué pasa con lo que faltó entregar.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DeliveryRemainder {
+    /// El pedido queda `ENTREGADO_PARCIAL`; el saldo no se entrega.
+    Parcial,
+    /// El saldo pasa a un pedido nuevo y el original queda `ENTREGADO`.
+    Seguimiento,
+}
+
+pub const DEFAULT_DELIVERY_OVER_TOLERANCE: i32 = 0;
+
+/// Cuánto puede apartarse una entrega de lo pedido.
+#[derive(Debug, Clone)]
+pub struct DeliveryPolicy {
+    /// Llenas de más aceptadas sin autorización (el cliente pide una más en
+    /// la puerta).
+    pub over_tolerance: i32,
+}
+
+impl Default for DeliveryPolicy {
+    fn default() -> Self {
+        Self {
+            over_tolerance: DEFAULT_DELIVERY_OVER_TOLERANCE,
+        }
+    }
+}
+
+impl DeliveryPolicy {
+    /// Valida las llenas contra lo pedido y devuelve el saldo si la entrega
+    /// es parcial. `override_quantity` sólo habilita pasarse de la tolerancia.
+    pub fn shortfall(
+        &self,
+        ordered: i32,
+        delivered: i32,
+        override_quantity: bool,
+    ) -> Result<Option<i32>, DomainError> {
+        if delivered == 0 {
+            return Err(DomainError::Validation(
+                "sin llenas entregadas corresponde registrar una entrega fallida".to_string(),
+            ));
+        }
+        if delivered > ordered.saturating_add(self.over_tolerance) && !override_quantity {
+            return Err(DomainError::Validation(format!(
+                "llenas_entregadas ({}) supera lo pedido ({}) más la tolerancia ({})",
+                delivered, ordered, self.over_tolerance
+            )));
+        }
+        Ok((delivered < ordered).then_some(ordered - delivered))
+    }
+
+    pub fn exceeds_tolerance(&self, ordered: i32, delivered: i32) -> bool {
+        delivered > ordered.saturating_add(self.over_tolerance)
+    }
+}
+
+/// Qué le pasa al pedido de seguimiento al corregir o anular la entrega que
+/// lo creó.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowUpChange {
+    Keep,
+    /// Se cancela y su lugar en la franja queda libre.
+    Cancel,
+    /// Pasa a llevar el saldo nuevo.
+    Resize(i32),
+}
+
+impl FollowUpChange {
+    /// Sin saldo el seguimiento sobra; con otro saldo se ajusta. Una vez en
+    /// reparto o entregado ya no se toca, así que sólo se acepta una
+    /// corrección que deje el mismo saldo.
+    pub fn after_correction(
+        follow_up: &Order,
+        shortfall: Option<i32>,
+    ) -> Result<Self, DomainError> {
+        if follow_up.status == OrderStatus::Cancelado || shortfall == Some(follow_up.quantity) {
+            return Ok(Self::Keep);
+        }
+        ensure_follow_up_open(follow_up)?;
+        Ok(match shortfall {
+            Some(pending) => Self::Resize(pending),
+            None => Self::Cancel,
+        })
+    }
+
+    /// Al anular, el pedido original vuelve a llevar todo lo pedido y el
+    /// seguimiento sobra.
+    pub fn after_void(follow_up: &Order) -> Result<Self, DomainError> {
+        if follow_up.status == OrderStatus::Cancelado {
+            return Ok(Self::Keep);
+        }
+        ensure_follow_up_open(follow_up)?;
+        Ok(Self::Cancel)
+    }
+}
+
+/// El seguimiento sólo se cancela o ajusta mientras no salió a reparto.
+pub fn ensure_follow_up_open(follow_up: &Order) -> Result<(), DomainError> {
+    match &follow_up.status {
+        OrderStatus::Pendiente | OrderStatus::Asignado => Ok(()),
+        status => Err(DomainError::Conflict(format!(
+            "el pedido de seguimiento {} está {}",
+            follow_up.id,
+            status.as_str()
+        ))),
+    }
+}
+
+/// Estado del pedido con las llenas corregidas: si el saldo vive en un
+/// seguimiento vigente el pedido queda `ENTREGADO`, igual que al registrar.
+pub fn corrected_order_status(shortfall: Option<i32>, follow_up: Option<&Order>) -> OrderStatus {
+    let follow_up_open = follow_up.is_some_and(|f| f.status != OrderStatus::Cancelado);
+    if shortfall.is_some() && !follow_up_open {
+        OrderStatus::EntregadoParcial
+    } else {
+        OrderStatus::Entregado
+    }
 }
 
 #[derive(Debug, Clone, Serialize, ToSchema)]
@@ -28,6 +175,7 @@ pub struct FailedDelivery {
     pub reason: String,
     pub reprogram_date: Option<NaiveDate>,
     pub reprogram_time_slot: Option<String>,
+    pub position: Option<PositionFix>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -37,4 +185,152 @@ pub struct NewFailedDelivery {
     pub reason: String,
     pub reprogram_date: Option<NaiveDate>,
     pub reprogram_time_slot: Option<String>,
+    pub position: Option<PositionFix>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DeliveryCorrectionKind {
+    /// Cambia las cantidades vigentes.
+    Correccion,
+    /// Lleva las cantidades a cero y libera el pedido.
+    Anulacion,
+}
+
+impl DeliveryCorrectionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Correccion => "CORRECCION",
+            Self::Anulacion => "ANULACION",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "CORRECCION" => Some(Self::Correccion),
+            "ANULACION" => Some(Self::Anulacion),
+            _ => None,
+        }
+    }
+}
+
+/// Asiento de corrección de una entrega. El stock suma la diferencia entre
+/// `*_despues` y `*_antes` en la fecha de la corrección, no en la de la
+/// entrega: los informes de días cerrados no cambian.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeliveryCorrection {
+    pub id: Uuid,
+    pub delivery_id: Uuid,
+    pub order_id: Uuid,
+    pub kind: DeliveryCorrectionKind,
+    pub llenas_antes: i32,
+    pub vacias_antes: i32,
+    pub llenas_despues: i32,
+    pub vacias_despues: i32,
+    pub reason: String,
+    pub corrected_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewDeliveryCorrection {
+    pub delivery_id: Uuid,
+    pub kind: DeliveryCorrectionKind,
+    pub llenas_entregadas: i32,
+    pub vacias_recibidas: i32,
+    pub reason: String,
+    pub corrected_by: Option<Uuid>,
+    /// Estado en que queda el pedido; `ASIGNADO` al anular.
+    pub order_status: OrderStatus,
+    /// Qué le pasa al seguimiento de la entrega, si tiene.
+    pub follow_up: FollowUpChange,
+    /// Cupo de la franja del seguimiento cuando el saldo crece; `None` en
+    /// pedidos sin zona.
+    pub follow_up_reservation: Option<SlotReservation>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delivered_quantity_is_checked_against_the_order() {
+        let policy = DeliveryPolicy { over_tolerance: 1 };
+        assert_eq!(policy.shortfall(3, 3, false).unwrap(), None);
+        assert_eq!(policy.shortfall(3, 4, false).unwrap(), None);
+        assert_eq!(policy.shortfall(3, 1, false).unwrap(), Some(2));
+        assert!(policy.shortfall(3, 5, false).is_err());
+        assert_eq!(policy.shortfall(3, 50, true).unwrap(), None);
+        assert!(policy.shortfall(3, 0, true).is_err());
+        assert!(policy.exceeds_tolerance(3, 5));
+        assert!(!policy.exceeds_tolerance(3, 4));
+    }
+
+    fn follow_up(status: OrderStatus, quantity: i32) -> Order {
+        Order {
+            id: Uuid::new_v4(),
+            address: "Calle 1".to_string(),
+            zone: "Centro".to_string(),
+            zone_id: None,
+            scheduled_date: NaiveDate::from_ymd_opt(2025, 1, 2).unwrap(),
+            time_slot: "MAÑANA".to_string(),
+            quantity,
+            notes: None,
+            status,
+            assignee_id: None,
+            customer_id: None,
+            address_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn follow_up_tracks_the_corrected_shortfall() {
+        let open = follow_up(OrderStatus::Asignado, 2);
+        assert_eq!(
+            FollowUpChange::after_correction(&open, None).unwrap(),
+            FollowUpChange::Cancel
+        );
+        assert_eq!(
+            FollowUpChange::after_correction(&open, Some(1)).unwrap(),
+            FollowUpChange::Resize(1)
+        );
+        assert_eq!(
+            FollowUpChange::after_correction(&open, Some(2)).unwrap(),
+            FollowUpChange::Keep
+        );
+        assert_eq!(
+            FollowUpChange::after_void(&open).unwrap(),
+            FollowUpChange::Cancel
+        );
+
+        let on_route = follow_up(OrderStatus::EnReparto, 2);
+        assert!(FollowUpChange::after_correction(&on_route, None).is_err());
+        assert!(FollowUpChange::after_void(&on_route).is_err());
+        assert_eq!(
+            FollowUpChange::after_correction(&on_route, Some(2)).unwrap(),
+            FollowUpChange::Keep
+        );
+
+        let cancelled = follow_up(OrderStatus::Cancelado, 2);
+        assert_eq!(
+            FollowUpChange::after_void(&cancelled).unwrap(),
+            FollowUpChange::Keep
+        );
+        assert_eq!(
+            corrected_order_status(Some(1), Some(&cancelled)),
+            OrderStatus::EntregadoParcial
+        );
+        assert_eq!(
+            corrected_order_status(Some(1), Some(&open)),
+            OrderStatus::Entregado
+        );
+        assert_eq!(
+            corrected_order_status(Some(1), None),
+            OrderStatus::EntregadoParcial
+        );
+        assert_eq!(corrected_order_status(None, None), OrderStatus::Entregado);
+    }
 }
@@ -10,6 +10,11 @@ pub enum DomainError {
     Unauthorized(String),
     #[error("conflict: {0}")]
     Conflict(String),
+    #[error("too many requests: {message}")]
+    TooManyRequests {
+        message: String,
+        retry_after_seconds: i64,
+    },
     #[error("infrastructure error: {0}")]
     Infrastructure(String),
 }
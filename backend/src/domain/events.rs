@@ -0,0 +1,298 @@
+use crate::domain::delivery::{Delivery, DeliveryCorrection, FailedDelivery};
+use crate::domain::orders::{Order, OrderViewer};
+use crate::domain::stock::Inbound;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Eventos tomados por ciclo del dispatcher.
+pub const OUTBOX_BATCH_SIZE: i64 = 100;
+/// Mientras dura, ninguna otra réplica vuelve a tomar el evento reclamado.
+pub const OUTBOX_CLAIM_LEASE_SECONDS: i64 = 60;
+/// Cada cuánto el dispatcher renueva la reserva de lo que le queda del lote:
+/// con sinks lentos un lote entero dura más que la reserva. El margen cubre
+/// la entrega de un evento a todos los sinks.
+pub const OUTBOX_CLAIM_RENEW_SECONDS: i64 = OUTBOX_CLAIM_LEASE_SECONDS / 2;
+/// Intentos tras los que el evento queda descartado (con su último error).
+pub const OUTBOX_MAX_ATTEMPTS: i32 = 12;
+const OUTBOX_RETRY_BASE_SECONDS: i64 = 10;
+const OUTBOX_RETRY_MAX_SECONDS: i64 = 3600;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+pub enum DomainEventType {
+    #[serde(rename = "order.created")]
+    OrderCreated,
+    #[serde(rename = "order.assigned")]
+    OrderAssigned,
+    /// Cambio de estado fuera de la asignación, incluida la reprogramación
+    /// tras una entrega fallida.
+    #[serde(rename = "order.status_changed")]
+    OrderStatusChanged,
+    #[serde(rename = "delivery.registered")]
+    DeliveryRegistered,
+    #[serde(rename = "delivery.failed")]
+    DeliveryFailed,
+    /// Corrección o anulación administrativa de una entrega.
+    #[serde(rename = "delivery.corrected")]
+    DeliveryCorrected,
+    #[serde(rename = "stock.inbound_registered")]
+    StockInboundRegistered,
+}
+
+impl DomainEventType {
+    pub const ALL: [Self; 7] = [
+        Self::OrderCreated,
+        Self::OrderAssigned,
+        Self::OrderStatusChanged,
+        Self::DeliveryRegistered,
+        Self::DeliveryFailed,
+        Self::DeliveryCorrected,
+        Self::StockInboundRegistered,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OrderCreated => "order.created",
+            Self::OrderAssigned => "order.assigned",
+            Self::OrderStatusChanged => "order.status_changed",
+            Self::DeliveryRegistered => "delivery.registered",
+            Self::DeliveryFailed => "delivery.failed",
+            Self::DeliveryCorrected => "delivery.corrected",
+            Self::StockInboundRegistered => "stock.inbound_registered",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+}
+
+/// Hecho de negocio a publicar. Lo arma el adaptador de persistencia dentro
+/// de la misma transacción que el cambio de estado que lo origina.
+#[derive(Debug, Clone)]
+pub struct DomainEvent {
+    pub event_type: DomainEventType,
+    pub aggregate_id: Uuid,
+    pub payload: Value,
+    /// Repartidor y cliente del pedido involucrado: deciden quién ve el
+    /// evento en el stream en vivo.
+    pub assignee_id: Option<Uuid>,
+    pub customer_id: Option<Uuid>,
+}
+
+impl DomainEvent {
+    fn new<T: Serialize>(
+        event_type: DomainEventType,
+        aggregate_id: Uuid,
+        payload: &T,
+        order: Option<&Order>,
+    ) -> Self {
+        Self {
+            event_type,
+            aggregate_id,
+            payload: serde_json::to_value(payload).unwrap_or(Value::Null),
+            assignee_id: order.and_then(|order| order.assignee_id),
+            customer_id: order.and_then(|order| order.customer_id),
+        }
+    }
+
+    pub fn order_created(order: &Order) -> Self {
+        Self::new(DomainEventType::OrderCreated, order.id, order, Some(order))
+    }
+
+    pub fn order_assigned(order: &Order) -> Self {
+        Self::new(DomainEventType::OrderAssigned, order.id, order, Some(order))
+    }
+
+    pub fn order_status_changed(order: &Order) -> Self {
+        Self::new(
+            DomainEventType::OrderStatusChanged,
+            order.id,
+            order,
+            Some(order),
+        )
+    }
+
+    pub fn delivery_registered(delivery: &Delivery, order: &Order) -> Self {
+        Self::new(
+            DomainEventType::DeliveryRegistered,
+            delivery.id,
+            delivery,
+            Some(order),
+        )
+    }
+
+    pub fn delivery_failed(failed: &FailedDelivery, order: &Order) -> Self {
+        Self::new(
+            DomainEventType::DeliveryFailed,
+            failed.id,
+            failed,
+            Some(order),
+        )
+    }
+
+    pub fn delivery_corrected(correction: &DeliveryCorrection, order: &Order) -> Self {
+        Self::new(
+            DomainEventType::DeliveryCorrected,
+            correction.delivery_id,
+            correction,
+            Some(order),
+        )
+    }
+
+    pub fn stock_inbound_registered(inbound_id: Uuid, inbound: &Inbound) -> Self {
+        let mut event = Self::new(
+            DomainEventType::StockInboundRegistered,
+            inbound_id,
+            inbound,
+            None,
+        );
+        if let Value::Object(fields) = &mut event.payload {
+            fields.insert("id".to_string(), json!(inbound_id));
+        }
+        event
+    }
+}
+
+/// Quién mira el stream en vivo: los eventos de pedidos siguen el alcance
+/// de `GET /orders`.
+#[derive(Debug, Clone)]
+pub struct EventViewer {
+    pub orders: OrderViewer,
+    pub read_stock: bool,
+}
+
+/// Evento guardado en el outbox, pendiente de entregar a los sinks.
+#[derive(Debug, Clone)]
+pub struct OutboxEvent {
+    pub id: Uuid,
+    pub event_type: DomainEventType,
+    pub aggregate_id: Uuid,
+    pub payload: Value,
+    pub occurred_at: DateTime<Utc>,
+    /// Intentos de entrega, contando el actual.
+    pub attempts: i32,
+    pub assignee_id: Option<Uuid>,
+    pub customer_id: Option<Uuid>,
+}
+
+impl OutboxEvent {
+    pub fn visible_to(&self, viewer: &EventViewer) -> bool {
+        if self.event_type == DomainEventType::StockInboundRegistered {
+            return viewer.read_stock;
+        }
+        viewer.orders.read_all || viewer.orders.owns(self.assignee_id, self.customer_id)
+    }
+
+    /// Cuerpo que reciben los consumidores externos.
+    pub fn envelope(&self) -> Value {
+        json!({
+            "id": self.id,
+            "type": self.event_type.as_str(),
+            "aggregate_id": self.aggregate_id,
+            "occurred_at": self.occurred_at,
+            "data": self.payload,
+        })
+    }
+}
+
+/// Espera antes del próximo intento; `None` cuando ya no se reintenta.
+pub fn outbox_retry_delay(attempts: i32) -> Option<Duration> {
+    if attempts >= OUTBOX_MAX_ATTEMPTS {
+        return None;
+    }
+    let exponent = attempts.clamp(1, 20) - 1;
+    let seconds = OUTBOX_RETRY_BASE_SECONDS.saturating_mul(1 << exponent);
+    Some(Duration::seconds(seconds.min(OUTBOX_RETRY_MAX_SECONDS)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn event_types_round_trip() {
+        for kind in DomainEventType::ALL {
+            assert_eq!(DomainEventType::from_str(kind.as_str()), Some(kind));
+            assert_eq!(
+                serde_json::to_value(kind).unwrap(),
+                Value::String(kind.as_str().to_string())
+            );
+        }
+        assert_eq!(DomainEventType::from_str("order.deleted"), None);
+    }
+
+    #[test]
+    fn retry_delay_grows_and_gives_up() {
+        assert_eq!(outbox_retry_delay(1), Some(Duration::seconds(10)));
+        assert_eq!(outbox_retry_delay(2), Some(Duration::seconds(20)));
+        assert_eq!(outbox_retry_delay(4), Some(Duration::seconds(80)));
+        assert_eq!(
+            outbox_retry_delay(OUTBOX_MAX_ATTEMPTS - 1),
+            Some(Duration::seconds(3600))
+        );
+        assert_eq!(outbox_retry_delay(OUTBOX_MAX_ATTEMPTS), None);
+    }
+
+    fn outbox_event(event_type: DomainEventType, assignee_id: Option<Uuid>) -> OutboxEvent {
+        OutboxEvent {
+            id: Uuid::new_v4(),
+            event_type,
+            aggregate_id: Uuid::new_v4(),
+            payload: json!({}),
+            occurred_at: Utc::now(),
+            attempts: 1,
+            assignee_id,
+            customer_id: None,
+        }
+    }
+
+    #[test]
+    fn drivers_only_see_their_own_orders() {
+        let driver = Uuid::new_v4();
+        let viewer = EventViewer {
+            orders: OrderViewer {
+                user_id: Some(driver),
+                read_all: false,
+                self_service: false,
+            },
+            read_stock: false,
+        };
+        assert!(outbox_event(DomainEventType::OrderAssigned, Some(driver)).visible_to(&viewer));
+        assert!(
+            !outbox_event(DomainEventType::OrderAssigned, Some(Uuid::new_v4())).visible_to(&viewer)
+        );
+        assert!(!outbox_event(DomainEventType::OrderCreated, None).visible_to(&viewer));
+        assert!(!outbox_event(DomainEventType::StockInboundRegistered, None).visible_to(&viewer));
+
+        let admin = EventViewer {
+            orders: OrderViewer {
+                read_all: true,
+                ..viewer.orders
+            },
+            read_stock: true,
+        };
+        assert!(outbox_event(DomainEventType::OrderCreated, None).visible_to(&admin));
+        assert!(outbox_event(DomainEventType::StockInboundRegistered, None).visible_to(&admin));
+    }
+
+    #[test]
+    fn inbound_event_carries_its_id() {
+        let inbound_id = Uuid::new_v4();
+        let event = DomainEvent::stock_inbound_registered(
+            inbound_id,
+            &Inbound {
+                date: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+                cantidad_llenas: 40,
+                notes: None,
+            },
+        );
+        assert_eq!(event.aggregate_id, inbound_id);
+        assert_eq!(event.payload["id"], json!(inbound_id));
+        assert_eq!(event.payload["cantidad_llenas"], json!(40));
+    }
+}
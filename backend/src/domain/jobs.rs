@@ -0,0 +1,288 @@
+use crate::domain::error::DomainError;
+use chrono::{DateTime, Datelike, Duration, DurationRound, NaiveDate, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Motivo con el que se cierra una corrida `EN_CURSO` cuya réplica ya no
+/// sostiene el lock del job.
+pub const ABANDONED_JOB_RUN_ERROR: &str = "corrida abandonada: la réplica perdió el lock";
+pub const DEFAULT_JOB_RUNS_LIMIT: i64 = 20;
+pub const MAX_JOB_RUNS_LIMIT: i64 = 100;
+
+/// Búsqueda acotada: un cron que no dispara en cuatro años es inválido.
+const CRON_SEARCH_LIMIT_DAYS: i64 = 4 * 366;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobRunStatus {
+    EnCurso,
+    Exitosa,
+    Fallida,
+}
+
+impl JobRunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EnCurso => "EN_CURSO",
+            Self::Exitosa => "EXITOSA",
+            Self::Fallida => "FALLIDA",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "EN_CURSO" => Some(Self::EnCurso),
+            "EXITOSA" => Some(Self::Exitosa),
+            "FALLIDA" => Some(Self::Fallida),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum JobTrigger {
+    Programada,
+    Manual,
+}
+
+impl JobTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Programada => "PROGRAMADA",
+            Self::Manual => "MANUAL",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "PROGRAMADA" => Some(Self::Programada),
+            "MANUAL" => Some(Self::Manual),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobRun {
+    pub id: Uuid,
+    pub job_name: String,
+    pub trigger: JobTrigger,
+    pub status: JobRunStatus,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub output: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobSummary {
+    pub name: String,
+    pub description: String,
+    pub schedule: String,
+    /// `None` hasta que el scheduler registre el job.
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run: Option<JobRun>,
+}
+
+/// Expresión cron de cinco campos (minuto, hora, día del mes, mes, día de la
+/// semana) evaluada en UTC. Admite `*`, listas, rangos y pasos (`*/15`, `1-5`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, DomainError> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(DomainError::Validation(format!(
+                "cron inválido '{}': se esperan 5 campos",
+                expression
+            )));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7, expression)?;
+        // 7 y 0 son domingo.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        let schedule = Self {
+            expression: fields.join(" "),
+            minutes: parse_field(fields[0], 0, 59, expression)?,
+            hours: parse_field(fields[1], 0, 23, expression)?,
+            days_of_month: parse_field(fields[2], 1, 31, expression)?,
+            months: parse_field(fields[3], 1, 12, expression)?,
+            days_of_week,
+            any_day_of_month: fields[2] == "*",
+            any_day_of_week: fields[4] == "*",
+        };
+
+        let epoch = NaiveDate::from_ymd_opt(2000, 1, 1)
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|dt| dt.and_utc());
+        if epoch.and_then(|e| schedule.next_after(e)).is_none() {
+            return Err(DomainError::Validation(format!(
+                "cron inválido '{}': nunca se ejecuta",
+                expression
+            )));
+        }
+        Ok(schedule)
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    pub fn matches(&self, at: DateTime<Utc>) -> bool {
+        bit(self.minutes, at.minute())
+            && bit(self.hours, at.hour())
+            && bit(self.months, at.month())
+            && self.matches_day(at.date_naive())
+    }
+
+    // Semántica clásica de cron: si ambos campos de día están restringidos
+    // alcanza con que coincida cualquiera de los dos.
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let dom = bit(self.days_of_month, date.day());
+        let dow = bit(self.days_of_week, date.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => dow,
+            (false, true) => dom,
+            (false, false) => dom || dow,
+        }
+    }
+
+    /// Primer minuto estrictamente posterior a `after` que cumple la expresión.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut at = after.duration_trunc(Duration::minutes(1)).ok()? + Duration::minutes(1);
+        let limit = after + Duration::days(CRON_SEARCH_LIMIT_DAYS);
+
+        while at <= limit {
+            if !bit(self.months, at.month()) || !self.matches_day(at.date_naive()) {
+                at = (at.date_naive() + Duration::days(1))
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc();
+                continue;
+            }
+            if !bit(self.hours, at.hour()) {
+                at = at.duration_trunc(Duration::hours(1)).ok()? + Duration::hours(1);
+                continue;
+            }
+            if !bit(self.minutes, at.minute()) {
+                at += Duration::minutes(1);
+                continue;
+            }
+            return Some(at);
+        }
+        None
+    }
+}
+
+fn bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+fn parse_field(field: &str, min: u32, max: u32, expression: &str) -> Result<u64, DomainError> {
+    let invalid =
+        || DomainError::Validation(format!("cron inválido '{}': '{}'", expression, field));
+
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(invalid());
+        }
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                start.parse::<u32>().map_err(|_| invalid())?,
+                end.parse::<u32>().map_err(|_| invalid())?,
+            )
+        } else {
+            let value = range.parse::<u32>().map_err(|_| invalid())?;
+            // `5/10` equivale a `5-max/10`.
+            if part.contains('/') {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+
+        if start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn next_run_for_hourly_and_daily_schedules() {
+        let hourly = CronSchedule::parse("0 * * * *").unwrap();
+        assert_eq!(
+            hourly.next_after(at(2026, 2, 16, 10, 0)),
+            Some(at(2026, 2, 16, 11, 0))
+        );
+
+        let nightly = CronSchedule::parse("30 3 * * *").unwrap();
+        assert_eq!(
+            nightly.next_after(at(2026, 2, 16, 10, 15)),
+            Some(at(2026, 2, 17, 3, 30))
+        );
+    }
+
+    #[test]
+    fn steps_ranges_and_weekdays() {
+        // Cada 15 minutos, de lunes a viernes.
+        let schedule = CronSchedule::parse("*/15 8-9 * * 1-5").unwrap();
+        // 2026-02-14 es sábado.
+        assert_eq!(
+            schedule.next_after(at(2026, 2, 14, 12, 0)),
+            Some(at(2026, 2, 16, 8, 0))
+        );
+        assert_eq!(
+            schedule.next_after(at(2026, 2, 16, 8, 0)),
+            Some(at(2026, 2, 16, 8, 15))
+        );
+        assert!(CronSchedule::parse("0 0 * * 7")
+            .unwrap()
+            .matches(at(2026, 2, 15, 0, 0)));
+    }
+
+    #[test]
+    fn reject_malformed_or_impossible_expressions() {
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("0 0 31 2 *").is_err());
+    }
+}
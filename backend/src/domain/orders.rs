@@ -14,6 +14,9 @@ pub enum OrderStatus {
     Asignado,
     EnReparto,
     Entregado,
+    /// Se entregó menos de lo pedido y el saldo no se reprograma.
+    EntregadoParcial,
+    Cancelado,
 }
 
 impl OrderStatus {
@@ -23,15 +26,20 @@ impl OrderStatus {
             Self::Asignado => "ASIGNADO",
             Self::EnReparto => "EN_REPARTO",
             Self::Entregado => "ENTREGADO",
+            Self::EntregadoParcial => "ENTREGADO_PARCIAL",
+            Self::Cancelado => "CANCELADO",
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(value: &str) -> Option<Self> {
         match value {
             "PENDIENTE" => Some(Self::Pendiente),
             "ASIGNADO" => Some(Self::Asignado),
             "EN_REPARTO" => Some(Self::EnReparto),
             "ENTREGADO" => Some(Self::Entregado),
+            "ENTREGADO_PARCIAL" => Some(Self::EntregadoParcial),
+            "CANCELADO" => Some(Self::Cancelado),
             _ => None,
         }
     }
@@ -44,6 +52,10 @@ impl OrderStatus {
                 | (Self::Asignado, Self::Entregado)
                 | (Self::EnReparto, Self::Asignado)
                 | (Self::EnReparto, Self::Entregado)
+                | (Self::Asignado, Self::EntregadoParcial)
+                | (Self::EnReparto, Self::EntregadoParcial)
+                | (Self::Pendiente, Self::Cancelado)
+                | (Self::Asignado, Self::Cancelado)
         )
     }
 }
@@ -53,16 +65,56 @@ pub struct Order {
     pub id: Uuid,
     pub address: String,
     pub zone: String,
+    pub zone_id: Option<Uuid>,
     pub scheduled_date: NaiveDate,
     pub time_slot: String,
     pub quantity: i32,
     pub notes: Option<String>,
     pub status: OrderStatus,
     pub assignee_id: Option<Uuid>,
+    /// Cliente dueño del pedido cuando lo hizo por autoservicio.
+    pub customer_id: Option<Uuid>,
+    /// Dirección guardada del cliente de la que salió el pedido.
+    pub address_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl Order {
+    /// El cliente puede cancelar mientras el pedido no salió a reparto y la
+    /// fecha no pasó.
+    pub fn cancellable_by_customer(&self, today: NaiveDate) -> bool {
+        self.scheduled_date >= today
+            && matches!(self.status, OrderStatus::Pendiente | OrderStatus::Asignado)
+    }
+}
+
+/// Alcance de lectura de quien consulta, el mismo que aplica `GET /orders`:
+/// sin `read_all` sólo se ven los pedidos propios.
+#[derive(Debug, Clone)]
+pub struct OrderViewer {
+    /// `None` para una API key: no tiene pedidos propios.
+    pub user_id: Option<Uuid>,
+    pub read_all: bool,
+    /// Cliente: lo propio es lo que pidió, no lo asignado.
+    pub self_service: bool,
+}
+
+impl OrderViewer {
+    pub fn owns(&self, assignee_id: Option<Uuid>, customer_id: Option<Uuid>) -> bool {
+        let owner = if self.self_service {
+            customer_id
+        } else {
+            assignee_id
+        };
+        owner.is_some() && owner == self.user_id
+    }
+
+    pub fn can_see(&self, order: &Order) -> bool {
+        self.read_all || self.owns(order.assignee_id, order.customer_id)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct NewOrder {
     pub address: String,
@@ -71,13 +123,18 @@ pub struct NewOrder {
     pub time_slot: String,
     pub quantity: i32,
     pub notes: Option<String>,
+    pub customer_id: Option<Uuid>,
+    pub address_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone)]
 pub struct OrderFilter {
     pub date: Option<NaiveDate>,
+    /// Pedidos con fecha igual o posterior.
+    pub from_date: Option<NaiveDate>,
     pub status: Option<OrderStatus>,
     pub assignee: Option<Uuid>,
+    pub customer: Option<Uuid>,
     pub page: i64,
     pub page_size: i64,
 }
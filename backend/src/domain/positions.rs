@@ -0,0 +1,118 @@
+use crate::domain::error::DomainError;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Fixes aceptados por request; el celular acumula mientras no tiene señal.
+pub const MAX_POSITIONS_PER_BATCH: usize = 500;
+/// Tolerancia para relojes de celular adelantados.
+pub const POSITION_MAX_FUTURE_SKEW_SECONDS: i64 = 300;
+/// Un fix más viejo que esto se descarta al recibirlo: ya no sirve para el
+/// seguimiento y la depuración lo borraría igual.
+pub const POSITION_MAX_AGE_HOURS: i64 = 24;
+/// Antigüedad máxima del último fix para adjuntarlo a una entrega que no
+/// trae posición propia.
+pub const DELIVERY_POSITION_MAX_AGE_MINUTES: i64 = 15;
+pub const DEFAULT_POSITIONS_RETENTION_DAYS: i64 = 30;
+
+/// Lectura de GPS tal como la informa el dispositivo.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PositionFix {
+    pub lat: f64,
+    pub lng: f64,
+    /// Radio de error informado por el GPS, en metros.
+    pub accuracy_m: Option<f64>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl PositionFix {
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<(), DomainError> {
+        if !(-90.0..=90.0).contains(&self.lat) || !(-180.0..=180.0).contains(&self.lng) {
+            return Err(DomainError::Validation(
+                "lat debe estar entre -90 y 90 y lng entre -180 y 180".to_string(),
+            ));
+        }
+        if self
+            .accuracy_m
+            .is_some_and(|accuracy| accuracy.is_nan() || accuracy < 0.0)
+        {
+            return Err(DomainError::Validation(
+                "accuracy_m debe ser >= 0".to_string(),
+            ));
+        }
+        if self.recorded_at > now + Duration::seconds(POSITION_MAX_FUTURE_SKEW_SECONDS) {
+            return Err(DomainError::Validation(
+                "recorded_at no puede estar en el futuro".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        self.recorded_at < now - Duration::hours(POSITION_MAX_AGE_HOURS)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DriverPosition {
+    pub driver_id: Uuid,
+    pub lat: f64,
+    pub lng: f64,
+    pub accuracy_m: Option<f64>,
+    pub recorded_at: DateTime<Utc>,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Última posición conocida de un repartidor activo.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LatestDriverPosition {
+    pub driver_id: Uuid,
+    pub username: String,
+    pub lat: f64,
+    pub lng: f64,
+    pub accuracy_m: Option<f64>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct PositionBatchReport {
+    pub accepted: usize,
+    /// Repetidos de un envío anterior (mismo `recorded_at`).
+    pub duplicates: usize,
+    /// Más viejos que `POSITION_MAX_AGE_HOURS`.
+    pub stale: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fix(lat: f64, lng: f64, recorded_at: DateTime<Utc>) -> PositionFix {
+        PositionFix {
+            lat,
+            lng,
+            accuracy_m: Some(8.0),
+            recorded_at,
+        }
+    }
+
+    #[test]
+    fn fixes_need_valid_coordinates_and_time() {
+        let now = Utc::now();
+        assert!(fix(-34.6, -58.4, now).validate(now).is_ok());
+        assert!(fix(91.0, -58.4, now).validate(now).is_err());
+        assert!(fix(-34.6, 180.5, now).validate(now).is_err());
+        assert!(fix(f64::NAN, -58.4, now).validate(now).is_err());
+        assert!(fix(-34.6, -58.4, now + Duration::hours(1))
+            .validate(now)
+            .is_err());
+        assert!(fix(
+            -34.6,
+            -58.4,
+            now - Duration::hours(POSITION_MAX_AGE_HOURS + 1)
+        )
+        .is_stale(now));
+        assert!(!fix(-34.6, -58.4, now - Duration::hours(1)).is_stale(now));
+    }
+}
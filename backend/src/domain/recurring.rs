@@ -0,0 +1,225 @@
+use crate::domain::error::DomainError;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const MAX_INTERVAL_WEEKS: i32 = 12;
+pub const DEFAULT_RECURRING_DAYS_AHEAD: i64 = 7;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RecurringStatus {
+    Activa,
+    Pausada,
+    Finalizada,
+}
+
+impl RecurringStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Activa => "ACTIVA",
+            Self::Pausada => "PAUSADA",
+            Self::Finalizada => "FINALIZADA",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "ACTIVA" => Some(Self::Activa),
+            "PAUSADA" => Some(Self::Pausada),
+            "FINALIZADA" => Some(Self::Finalizada),
+            _ => None,
+        }
+    }
+
+    pub fn can_transition_to(&self, target: &Self) -> bool {
+        matches!(
+            (self, target),
+            (Self::Activa, Self::Pausada)
+                | (Self::Pausada, Self::Activa)
+                | (Self::Activa, Self::Finalizada)
+                | (Self::Pausada, Self::Finalizada)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OccurrenceStatus {
+    Creada,
+    Omitida,
+    Fallida,
+}
+
+impl OccurrenceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Creada => "CREADA",
+            Self::Omitida => "OMITIDA",
+            Self::Fallida => "FALLIDA",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "CREADA" => Some(Self::Creada),
+            "OMITIDA" => Some(Self::Omitida),
+            "FALLIDA" => Some(Self::Fallida),
+            _ => None,
+        }
+    }
+}
+
+/// Plantilla de pedido que se repite cada `interval_weeks` semanas.
+/// `zone` y `time_slot` reflejan el nombre actual del catálogo.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecurringOrder {
+    pub id: Uuid,
+    pub address: String,
+    pub zone_id: Uuid,
+    pub zone: String,
+    pub slot_id: Uuid,
+    pub time_slot: String,
+    pub quantity: i32,
+    pub interval_weeks: i32,
+    pub notes: Option<String>,
+    /// Próxima fecha pendiente de materializar.
+    pub next_date: NaiveDate,
+    pub status: RecurringStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RecurringOrder {
+    pub fn interval(&self) -> Duration {
+        Duration::weeks(i64::from(self.interval_weeks))
+    }
+
+    /// Indica si `date` cae en la cadencia a partir de `next_date`.
+    pub fn is_occurrence(&self, date: NaiveDate) -> bool {
+        date >= self.next_date
+            && (date - self.next_date).num_days() % self.interval().num_days() == 0
+    }
+
+    /// Primera fecha de la cadencia que no es anterior a `from`.
+    pub fn first_occurrence_from(&self, from: NaiveDate) -> NaiveDate {
+        let mut date = self.next_date;
+        while date < from {
+            date += self.interval();
+        }
+        date
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewRecurringOrder {
+    pub address: String,
+    pub zone_id: Uuid,
+    pub slot_id: Uuid,
+    pub quantity: i32,
+    pub interval_weeks: i32,
+    pub notes: Option<String>,
+    pub start_date: NaiveDate,
+}
+
+/// Resultado de una fecha de la plantilla; existe como mucho una por fecha.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecurringOccurrence {
+    pub recurring_order_id: Uuid,
+    pub scheduled_date: NaiveDate,
+    pub status: OccurrenceStatus,
+    pub order_id: Option<Uuid>,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewOccurrence {
+    pub recurring_order_id: Uuid,
+    pub scheduled_date: NaiveDate,
+    pub status: OccurrenceStatus,
+    pub order_id: Option<Uuid>,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct MaterializeReport {
+    pub created: i64,
+    pub skipped: i64,
+    pub failed: i64,
+    /// Plantillas que no se pudieron procesar por un error de infraestructura;
+    /// su fecha queda pendiente para la próxima corrida.
+    pub errors: i64,
+}
+
+pub fn validate_recurring_fields(
+    address: &str,
+    quantity: i32,
+    interval_weeks: i32,
+) -> Result<(), DomainError> {
+    if address.trim().is_empty() {
+        return Err(DomainError::Validation(
+            "address es obligatorio".to_string(),
+        ));
+    }
+    if quantity <= 0 {
+        return Err(DomainError::Validation(
+            "quantity debe ser mayor que 0".to_string(),
+        ));
+    }
+    if !(1..=MAX_INTERVAL_WEEKS).contains(&interval_weeks) {
+        return Err(DomainError::Validation(format!(
+            "interval_weeks debe estar entre 1 y {}",
+            MAX_INTERVAL_WEEKS
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(next_date: NaiveDate, interval_weeks: i32) -> RecurringOrder {
+        RecurringOrder {
+            id: Uuid::new_v4(),
+            address: "Calle 1".to_string(),
+            zone_id: Uuid::new_v4(),
+            zone: "Centro".to_string(),
+            slot_id: Uuid::new_v4(),
+            time_slot: "TARDE".to_string(),
+            quantity: 2,
+            interval_weeks,
+            notes: None,
+            next_date,
+            status: RecurringStatus::Activa,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn occurrences_follow_cadence() {
+        let start = NaiveDate::from_ymd_opt(2026, 2, 16).unwrap();
+        let every_two = template(start, 2);
+        assert!(every_two.is_occurrence(start));
+        assert!(every_two.is_occurrence(start + Duration::days(28)));
+        assert!(!every_two.is_occurrence(start + Duration::days(7)));
+        assert!(!every_two.is_occurrence(start - Duration::days(14)));
+        assert_eq!(
+            every_two.first_occurrence_from(start + Duration::days(15)),
+            start + Duration::days(28)
+        );
+    }
+
+    #[test]
+    fn ended_subscription_is_terminal() {
+        assert!(RecurringStatus::Activa.can_transition_to(&RecurringStatus::Pausada));
+        assert!(RecurringStatus::Pausada.can_transition_to(&RecurringStatus::Finalizada));
+        assert!(!RecurringStatus::Finalizada.can_transition_to(&RecurringStatus::Activa));
+        assert!(validate_recurring_fields("Calle 1", 1, 0).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use crate::domain::error::DomainError;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TimeSlot {
+    pub id: Uuid,
+    pub name: String,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    /// Máximo de pedidos por fecha y zona; `None` = sin límite.
+    pub max_orders: Option<i32>,
+    /// Máximo de garrafas por fecha y zona; `None` = sin límite.
+    pub max_cylinders: Option<i32>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewTimeSlot {
+    pub name: String,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub max_orders: Option<i32>,
+    pub max_cylinders: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TimeSlotUpdate {
+    pub name: String,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub max_orders: Option<i32>,
+    pub max_cylinders: Option<i32>,
+    pub active: bool,
+}
+
+/// Límites específicos de una zona para una franja; reemplazan a los de la franja.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SlotZoneCapacity {
+    pub slot_id: Uuid,
+    pub zone_id: Uuid,
+    pub max_orders: Option<i32>,
+    pub max_cylinders: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SlotLoad {
+    pub orders: i64,
+    pub cylinders: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotLimits {
+    pub max_orders: Option<i32>,
+    pub max_cylinders: Option<i32>,
+}
+
+impl SlotLimits {
+    pub fn resolve(slot: &TimeSlot, zone_override: Option<&SlotZoneCapacity>) -> Self {
+        match zone_override {
+            Some(capacity) => Self {
+                max_orders: capacity.max_orders,
+                max_cylinders: capacity.max_cylinders,
+            },
+            None => Self {
+                max_orders: slot.max_orders,
+                max_cylinders: slot.max_cylinders,
+            },
+        }
+    }
+
+    /// Indica si un pedido de `quantity` garrafas entra con la carga actual.
+    pub fn fits(&self, load: SlotLoad, quantity: i32) -> bool {
+        let orders_ok = self
+            .max_orders
+            .is_none_or(|max| load.orders < i64::from(max));
+        let cylinders_ok = self
+            .max_cylinders
+            .is_none_or(|max| load.cylinders + i64::from(quantity) <= i64::from(max));
+        orders_ok && cylinders_ok
+    }
+}
+
+/// Cupo de una franja para una zona. El repositorio mide la carga y escribe
+/// el pedido bajo un mismo lock, así dos reservas concurrentes no lo desbordan.
+#[derive(Debug, Clone)]
+pub struct SlotReservation {
+    pub zone_id: Uuid,
+    pub zone_name: String,
+    pub slot_name: String,
+    pub limits: SlotLimits,
+}
+
+impl SlotReservation {
+    pub fn ensure_fits(
+        &self,
+        date: NaiveDate,
+        load: SlotLoad,
+        quantity: i32,
+    ) -> Result<(), DomainError> {
+        if !self.limits.fits(load, quantity) {
+            return Err(DomainError::Conflict(format!(
+                "franja {} completa para la zona {} el {}",
+                self.slot_name, self.zone_name, date
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SlotAvailability {
+    pub slot_id: Uuid,
+    pub name: String,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    pub max_orders: Option<i32>,
+    pub max_cylinders: Option<i32>,
+    pub booked_orders: i64,
+    pub booked_cylinders: i64,
+    pub available: bool,
+}
+
+pub fn validate_slot_fields(
+    name: &str,
+    start_time: NaiveTime,
+    end_time: NaiveTime,
+    max_orders: Option<i32>,
+    max_cylinders: Option<i32>,
+) -> Result<(), DomainError> {
+    if name.trim().is_empty() {
+        return Err(DomainError::Validation(
+            "name de franja es obligatorio".to_string(),
+        ));
+    }
+    if start_time >= end_time {
+        return Err(DomainError::Validation(
+            "start_time debe ser anterior a end_time".to_string(),
+        ));
+    }
+    validate_limits(max_orders, max_cylinders)
+}
+
+pub fn validate_limits(
+    max_orders: Option<i32>,
+    max_cylinders: Option<i32>,
+) -> Result<(), DomainError> {
+    if max_orders.is_some_and(|v| v <= 0) || max_cylinders.is_some_and(|v| v <= 0) {
+        return Err(DomainError::Validation(
+            "max_orders y max_cylinders deben ser mayores que 0".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlimited_slot_always_fits() {
+        let limits = SlotLimits {
+            max_orders: None,
+            max_cylinders: None,
+        };
+        let load = SlotLoad {
+            orders: 1_000,
+            cylinders: 5_000,
+        };
+        assert!(limits.fits(load, 10));
+    }
+
+    #[test]
+    fn order_and_cylinder_limits_are_enforced() {
+        let limits = SlotLimits {
+            max_orders: Some(3),
+            max_cylinders: Some(10),
+        };
+        assert!(limits.fits(
+            SlotLoad {
+                orders: 2,
+                cylinders: 8
+            },
+            2
+        ));
+        assert!(!limits.fits(
+            SlotLoad {
+                orders: 3,
+                cylinders: 3
+            },
+            1
+        ));
+        assert!(!limits.fits(
+            SlotLoad {
+                orders: 1,
+                cylinders: 9
+            },
+            2
+        ));
+    }
+
+    #[test]
+    fn reject_inverted_slot_times() {
+        let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        let one = NaiveTime::from_hms_opt(13, 0, 0).unwrap();
+        assert!(validate_slot_fields("MAÑANA", nine, one, None, None).is_ok());
+        assert!(validate_slot_fields("MAÑANA", one, nine, None, None).is_err());
+        assert!(validate_slot_fields("MAÑANA", nine, one, Some(0), None).is_err());
+    }
+}
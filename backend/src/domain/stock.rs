@@ -2,7 +2,7 @@ use chrono::NaiveDate;
 use serde::Serialize;
 use utoipa::ToSchema;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Inbound {
     pub date: NaiveDate,
     pub cantidad_llenas: i32,
@@ -0,0 +1,117 @@
+use crate::domain::auth::TokenPair;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const TOTP_ISSUER: &str = "GasFlow";
+pub const TOTP_DIGITS: u32 = 6;
+pub const TOTP_STEP_SECONDS: i64 = 30;
+/// Pasos aceptados antes y después del actual, por relojes desfasados.
+pub const TOTP_SKEW_STEPS: i64 = 1;
+pub const TOTP_SECRET_BYTES: usize = 20;
+pub const RECOVERY_CODE_COUNT: usize = 10;
+pub const LOGIN_CHALLENGE_MINUTES: i64 = 5;
+/// Códigos erróneos tolerados por desafío; después hay que volver a loguearse.
+pub const MAX_CHALLENGE_ATTEMPTS: i32 = 5;
+
+/// Qué le falta al login para emitir los tokens.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ChallengePurpose {
+    /// El usuario tiene 2FA: debe enviar un código TOTP o de recuperación.
+    Verify,
+    /// El rol exige 2FA y el usuario todavía no lo activó.
+    Enroll,
+}
+
+impl ChallengePurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Verify => "VERIFY",
+            Self::Enroll => "ENROLL",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "VERIFY" => Some(Self::Verify),
+            "ENROLL" => Some(Self::Enroll),
+            _ => None,
+        }
+    }
+}
+
+/// Desafío persistido; sólo se guarda el hash del token entregado.
+#[derive(Debug, Clone)]
+pub struct LoginChallenge {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub purpose: ChallengePurpose,
+    pub attempts: i32,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewLoginChallenge {
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub purpose: ChallengePurpose,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TwoFactorChallenge {
+    pub user_id: Uuid,
+    pub challenge_token: String,
+    pub purpose: ChallengePurpose,
+    pub expires_in: i64,
+}
+
+/// Resultado de un login con contraseña correcta.
+#[derive(Debug, Clone)]
+pub enum LoginOutcome {
+    Tokens(TokenPair),
+    TwoFactorRequired(TwoFactorChallenge),
+}
+
+impl LoginOutcome {
+    pub fn user_id(&self) -> Uuid {
+        match self {
+            Self::Tokens(tokens) => tokens.user_id,
+            Self::TwoFactorRequired(challenge) => challenge.user_id,
+        }
+    }
+}
+
+/// Secreto a cargar en la app autenticadora; se muestra sólo al activar.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TwoFactorEnrollment {
+    /// Secreto en base32, para cargarlo a mano.
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecondFactor {
+    Totp,
+    RecoveryCode,
+    Enrollment,
+}
+
+/// Login completado con el segundo factor. `recovery_codes` sólo viene al
+/// activar 2FA desde el desafío de enrolamiento.
+#[derive(Debug, Clone)]
+pub struct TwoFactorLogin {
+    pub tokens: TokenPair,
+    pub method: SecondFactor,
+    pub recovery_codes: Option<Vec<String>>,
+}
+
+/// Códigos de un solo uso para entrar sin la app; sólo se guardan sus hashes.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecoveryCodes {
+    pub codes: Vec<String>,
+}
@@ -0,0 +1,299 @@
+use crate::domain::error::DomainError;
+use crate::domain::events::{DomainEventType, OutboxEvent};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use url::{Host, Url};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const WEBHOOK_SECRET_PREFIX: &str = "whsec_";
+pub const MIN_WEBHOOK_SECRET_LENGTH: usize = 16;
+pub const MAX_WEBHOOK_URL_LENGTH: usize = 2000;
+/// Entregas tomadas por ciclo del worker.
+pub const WEBHOOK_BATCH_SIZE: i64 = 50;
+pub const WEBHOOK_CLAIM_LEASE_SECONDS: i64 = 60;
+/// Envíos simultáneos por lote: con el timeout del cliente, un lote lleno de
+/// destinos lentos termina antes de que venza el lease.
+pub const WEBHOOK_CONCURRENCY: usize = 10;
+/// Intentos tras los que la entrega pasa a `DESCARTADA` (dead-letter).
+pub const WEBHOOK_MAX_ATTEMPTS: i32 = 8;
+pub const DEFAULT_WEBHOOK_DELIVERIES_LIMIT: i64 = 50;
+pub const MAX_WEBHOOK_DELIVERIES_LIMIT: i64 = 200;
+const WEBHOOK_RETRY_BASE_SECONDS: i64 = 30;
+const WEBHOOK_RETRY_MAX_SECONDS: i64 = 6 * 3600;
+
+/// Destino externo de eventos de dominio. El secreto con el que se firman
+/// los envíos no forma parte de la vista.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookSubscription {
+    pub id: Uuid,
+    pub url: String,
+    pub event_types: Vec<DomainEventType>,
+    pub active: bool,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewWebhookSubscription {
+    pub url: String,
+    pub event_types: Vec<DomainEventType>,
+    pub secret: String,
+    pub created_by: Uuid,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WebhookSubscriptionUpdate {
+    pub url: Option<String>,
+    pub event_types: Option<Vec<DomainEventType>>,
+    pub secret: Option<String>,
+    pub active: Option<bool>,
+}
+
+/// Única respuesta que contiene el secreto de firma.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookSubscriptionCreated {
+    pub secret: String,
+    pub subscription: WebhookSubscription,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WebhookDeliveryStatus {
+    Pendiente,
+    Entregada,
+    /// Dead-letter: agotó los reintentos; sólo vuelve a salir con un
+    /// reenvío manual.
+    Descartada,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pendiente => "PENDIENTE",
+            Self::Entregada => "ENTREGADA",
+            Self::Descartada => "DESCARTADA",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "PENDIENTE" => Some(Self::Pendiente),
+            "ENTREGADA" => Some(Self::Entregada),
+            "DESCARTADA" => Some(Self::Descartada),
+            _ => None,
+        }
+    }
+}
+
+/// Registro de envío de un evento a una suscripción, con el resultado del
+/// último intento.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub subscription_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: DomainEventType,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub last_status_code: Option<i32>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WebhookDeliveryFilter {
+    pub status: Option<WebhookDeliveryStatus>,
+    pub limit: i64,
+}
+
+/// Entrega reservada por el worker, con lo necesario para enviarla.
+#[derive(Debug, Clone)]
+pub struct PendingWebhookDelivery {
+    pub delivery_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    /// Intentos contando el actual.
+    pub attempts: i32,
+    pub event: OutboxEvent,
+}
+
+/// Resultado de un intento de envío.
+#[derive(Debug, Clone)]
+pub struct WebhookAttempt {
+    pub status: WebhookDeliveryStatus,
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub attempted_at: DateTime<Utc>,
+}
+
+/// Destinos aceptados para los webhooks. Por defecto sólo `https` hacia
+/// direcciones públicas; `allow_insecure` (desarrollo) habilita `http` y la
+/// red local.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WebhookTargetPolicy {
+    pub allow_insecure: bool,
+}
+
+impl WebhookTargetPolicy {
+    /// Validación al dar de alta la URL. Un host con nombre se vuelve a
+    /// verificar en cada envío, ya resuelto (`allows_ip`).
+    pub fn validate_url(&self, url: &str) -> Result<(), DomainError> {
+        let url = url.trim();
+        let parsed = Url::parse(url).ok().filter(|parsed| {
+            url.len() <= MAX_WEBHOOK_URL_LENGTH
+                && !url.contains(char::is_whitespace)
+                && matches!(parsed.scheme(), "https" | "http")
+                && parsed.host().is_some()
+        });
+        let Some(parsed) = parsed else {
+            return Err(DomainError::Validation(
+                "url debe ser una URL http(s) válida".to_string(),
+            ));
+        };
+
+        if !self.allow_insecure {
+            if parsed.scheme() != "https" {
+                return Err(DomainError::Validation("url debe usar https".to_string()));
+            }
+            let internal = match parsed.host() {
+                Some(Host::Ipv4(ip)) => !self.allows_ip(IpAddr::V4(ip)),
+                Some(Host::Ipv6(ip)) => !self.allows_ip(IpAddr::V6(ip)),
+                Some(Host::Domain(domain)) => {
+                    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+                    domain == "localhost" || domain.ends_with(".localhost")
+                }
+                None => true,
+            };
+            if internal {
+                return Err(DomainError::Validation(
+                    "url no puede apuntar a una dirección local o privada".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn allows_ip(&self, ip: IpAddr) -> bool {
+        self.allow_insecure || is_public_ip(ip)
+    }
+}
+
+/// Falso para loopback, redes privadas, link-local y demás rangos que no
+/// son destinos de Internet.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                // 100.64.0.0/10: NAT de proveedores.
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(mapped));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // fc00::/7: direcciones locales únicas.
+                || (first & 0xfe00) == 0xfc00
+                // fe80::/10: link-local.
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+pub fn validate_event_types(event_types: &[DomainEventType]) -> Result<(), DomainError> {
+    if event_types.is_empty() {
+        return Err(DomainError::Validation(
+            "event_types no puede estar vacío".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_webhook_secret(secret: &str) -> Result<(), DomainError> {
+    if secret.chars().count() < MIN_WEBHOOK_SECRET_LENGTH {
+        return Err(DomainError::Validation(format!(
+            "secret debe tener al menos {} caracteres",
+            MIN_WEBHOOK_SECRET_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// Espera antes del próximo intento; `None` manda la entrega a dead-letter.
+pub fn webhook_retry_delay(attempts: i32) -> Option<Duration> {
+    if attempts >= WEBHOOK_MAX_ATTEMPTS {
+        return None;
+    }
+    let exponent = attempts.clamp(1, 20) - 1;
+    let seconds = WEBHOOK_RETRY_BASE_SECONDS.saturating_mul(1 << exponent);
+    Some(Duration::seconds(seconds.min(WEBHOOK_RETRY_MAX_SECONDS)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_must_be_http() {
+        let dev = WebhookTargetPolicy {
+            allow_insecure: true,
+        };
+        assert!(dev
+            .validate_url("https://hooks.example.com/gasflow")
+            .is_ok());
+        assert!(dev.validate_url("http://127.0.0.1:9000/hook").is_ok());
+        assert!(dev.validate_url("ftp://example.com").is_err());
+        assert!(dev.validate_url("https://").is_err());
+        assert!(dev.validate_url("https://example.com/a b").is_err());
+    }
+
+    #[test]
+    fn production_urls_must_be_https_and_public() {
+        let policy = WebhookTargetPolicy::default();
+        assert!(policy
+            .validate_url("https://hooks.example.com/gasflow")
+            .is_ok());
+        assert!(policy.validate_url("http://hooks.example.com").is_err());
+        assert!(policy.validate_url("https://127.0.0.1/hook").is_err());
+        assert!(policy.validate_url("https://localhost:8443").is_err());
+        assert!(policy.validate_url("https://10.1.2.3").is_err());
+        assert!(policy
+            .validate_url("https://169.254.169.254/latest")
+            .is_err());
+        assert!(policy.validate_url("https://[::1]/hook").is_err());
+        assert!(policy.validate_url("https://[fd00::1]/hook").is_err());
+        assert!(policy.validate_url("https://[::ffff:192.168.0.1]").is_err());
+        assert!(policy.allows_ip("8.8.8.8".parse().unwrap()));
+        assert!(!policy.allows_ip("100.64.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn retries_back_off_until_dead_letter() {
+        assert_eq!(webhook_retry_delay(1), Some(Duration::seconds(30)));
+        assert_eq!(webhook_retry_delay(3), Some(Duration::seconds(120)));
+        assert_eq!(
+            webhook_retry_delay(WEBHOOK_MAX_ATTEMPTS - 1),
+            Some(Duration::seconds(1920))
+        );
+        assert_eq!(webhook_retry_delay(WEBHOOK_MAX_ATTEMPTS), None);
+    }
+}
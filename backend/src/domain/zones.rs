@@ -0,0 +1,217 @@
+use crate::domain::error::DomainError;
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const MIN_POLYGON_POINTS: usize = 3;
+
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, ToSchema,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DayOfWeek {
+    Lunes,
+    Martes,
+    Miercoles,
+    Jueves,
+    Viernes,
+    Sabado,
+    Domingo,
+}
+
+impl DayOfWeek {
+    pub const ALL: [DayOfWeek; 7] = [
+        Self::Lunes,
+        Self::Martes,
+        Self::Miercoles,
+        Self::Jueves,
+        Self::Viernes,
+        Self::Sabado,
+        Self::Domingo,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lunes => "LUNES",
+            Self::Martes => "MARTES",
+            Self::Miercoles => "MIERCOLES",
+            Self::Jueves => "JUEVES",
+            Self::Viernes => "VIERNES",
+            Self::Sabado => "SABADO",
+            Self::Domingo => "DOMINGO",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "LUNES" => Some(Self::Lunes),
+            "MARTES" => Some(Self::Martes),
+            "MIERCOLES" => Some(Self::Miercoles),
+            "JUEVES" => Some(Self::Jueves),
+            "VIERNES" => Some(Self::Viernes),
+            "SABADO" => Some(Self::Sabado),
+            "DOMINGO" => Some(Self::Domingo),
+            _ => None,
+        }
+    }
+
+    pub fn of(date: NaiveDate) -> Self {
+        match date.weekday() {
+            Weekday::Mon => Self::Lunes,
+            Weekday::Tue => Self::Martes,
+            Weekday::Wed => Self::Miercoles,
+            Weekday::Thu => Self::Jueves,
+            Weekday::Fri => Self::Viernes,
+            Weekday::Sat => Self::Sabado,
+            Weekday::Sun => Self::Domingo,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Zone {
+    pub id: Uuid,
+    pub name: String,
+    pub polygon: Option<Vec<GeoPoint>>,
+    pub service_days: Vec<DayOfWeek>,
+    pub default_slots: Vec<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Zone {
+    pub fn serves(&self, date: NaiveDate) -> bool {
+        self.service_days.contains(&DayOfWeek::of(date))
+    }
+
+    /// Sin franjas por defecto la zona acepta todas las del catálogo.
+    pub fn offers_slot(&self, slot_name: &str) -> bool {
+        let slot = normalize_catalog_name(slot_name);
+        self.default_slots.is_empty()
+            || self
+                .default_slots
+                .iter()
+                .any(|default| normalize_catalog_name(default) == slot)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewZone {
+    pub name: String,
+    pub polygon: Option<Vec<GeoPoint>>,
+    pub service_days: Vec<DayOfWeek>,
+    pub default_slots: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ZoneUpdate {
+    pub name: String,
+    pub polygon: Option<Vec<GeoPoint>>,
+    pub service_days: Vec<DayOfWeek>,
+    pub default_slots: Vec<String>,
+    pub active: bool,
+}
+
+/// Clave de comparación para nombres de catálogo: "Centro", "centro" y
+/// "CENTRO " deben resolver al mismo registro.
+pub fn normalize_catalog_name(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .map(|c| match c {
+            'á' | 'Á' => 'A',
+            'é' | 'É' => 'E',
+            'í' | 'Í' => 'I',
+            'ó' | 'Ó' => 'O',
+            'ú' | 'Ú' | 'ü' | 'Ü' => 'U',
+            'ñ' | 'Ñ' => 'N',
+            other => other,
+        })
+        .collect::<String>()
+        .to_uppercase()
+}
+
+pub fn validate_zone_fields(
+    name: &str,
+    polygon: Option<&[GeoPoint]>,
+    service_days: &[DayOfWeek],
+) -> Result<(), DomainError> {
+    if name.trim().is_empty() {
+        return Err(DomainError::Validation(
+            "name de zona es obligatorio".to_string(),
+        ));
+    }
+    if service_days.is_empty() {
+        return Err(DomainError::Validation(
+            "service_days debe incluir al menos un día".to_string(),
+        ));
+    }
+    if let Some(points) = polygon {
+        if points.len() < MIN_POLYGON_POINTS {
+            return Err(DomainError::Validation(format!(
+                "polygon requiere al menos {} puntos",
+                MIN_POLYGON_POINTS
+            )));
+        }
+        let out_of_range = points.iter().any(|p| {
+            !p.lat.is_finite()
+                || !p.lng.is_finite()
+                || !(-90.0..=90.0).contains(&p.lat)
+                || !(-180.0..=180.0).contains(&p.lng)
+        });
+        if out_of_range {
+            return Err(DomainError::Validation(
+                "polygon contiene coordenadas inválidas".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_collapses_case_spacing_and_accents() {
+        assert_eq!(normalize_catalog_name("Centro"), "CENTRO");
+        assert_eq!(normalize_catalog_name("  centro "), "CENTRO");
+        assert_eq!(normalize_catalog_name("Barrio   Norte"), "BARRIO NORTE");
+        assert_eq!(normalize_catalog_name("Mañana"), "MANANA");
+    }
+
+    #[test]
+    fn day_of_week_from_date() {
+        let monday = NaiveDate::from_ymd_opt(2026, 2, 16).unwrap();
+        let sunday = NaiveDate::from_ymd_opt(2026, 2, 22).unwrap();
+        assert_eq!(DayOfWeek::of(monday), DayOfWeek::Lunes);
+        assert_eq!(DayOfWeek::of(sunday), DayOfWeek::Domingo);
+    }
+
+    #[test]
+    fn reject_degenerate_polygon() {
+        let points = [
+            GeoPoint { lat: 1.0, lng: 1.0 },
+            GeoPoint { lat: 2.0, lng: 2.0 },
+        ];
+        let result = validate_zone_fields("Centro", Some(&points), &[DayOfWeek::Lunes]);
+        assert!(matches!(result, Err(DomainError::Validation(_))));
+    }
+
+    #[test]
+    fn reject_zone_without_service_days() {
+        let result = validate_zone_fields("Centro", None, &[]);
+        assert!(matches!(result, Err(DomainError::Validation(_))));
+    }
+}
@@ -7,6 +7,13 @@ pub mod ports;
 use adapters::auth::jwt::JwtService;
 use adapters::db::repository::PgRepository;
 use adapters::observability::metrics::MetricsRegistry;
+use application::events::live::LiveEvents;
+use application::jobs::JobRegistry;
+use domain::auth::LoginPolicy;
+use domain::calendar::CalendarPolicy;
+use domain::delivery::DeliveryPolicy;
+use domain::webhooks::WebhookTargetPolicy;
+use ports::blob_store_port::BlobStore;
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -14,4 +21,15 @@ pub struct AppState {
     pub repo: PgRepository,
     pub jwt: JwtService,
     pub metrics: Arc<MetricsRegistry>,
+    pub jobs: Arc<JobRegistry>,
+    pub login_policy: LoginPolicy,
+    pub delivery_policy: DeliveryPolicy,
+    pub webhook_policy: WebhookTargetPolicy,
+    pub calendar_policy: CalendarPolicy,
+    /// Toma la IP del cliente de `X-Forwarded-For` (sólo detrás de un proxy).
+    pub trust_proxy_headers: bool,
+    /// Eventos de dominio para `GET /events/stream`.
+    pub live_events: LiveEvents,
+    /// Archivos de fotos y firmas de entregas.
+    pub blobs: Arc<dyn BlobStore>,
 }
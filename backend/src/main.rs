@@ -1,15 +1,37 @@
 use anyhow::Result;
 use axum::Router;
-use gasflow_backend::adapters::auth::jwt::JwtService;
+use gasflow_backend::adapters::auth::jwt::{load_signing_keys, JwtService};
 use gasflow_backend::adapters::db::repository::PgRepository;
+use gasflow_backend::adapters::events::pg_listener;
+use gasflow_backend::adapters::events::webhook::{HttpWebhookClient, WebhookSink};
 use gasflow_backend::adapters::http::router::build_router;
 use gasflow_backend::adapters::observability::metrics::MetricsRegistry;
+use gasflow_backend::adapters::storage::local_fs::LocalBlobStore;
+use gasflow_backend::application::events::dispatch_events;
+use gasflow_backend::application::events::in_process::InProcessSink;
+use gasflow_backend::application::events::live::LiveEvents;
+use gasflow_backend::application::jobs::{register_jobs, run_job, JobRegistry, JobsConfig};
+use gasflow_backend::application::webhooks::deliver_webhooks;
+use gasflow_backend::application::webhooks::fanout::WebhookFanoutSink;
 use gasflow_backend::config::Settings;
+use gasflow_backend::domain::audit::AuditContext;
+use gasflow_backend::domain::auth::LoginPolicy;
+use gasflow_backend::domain::calendar::CalendarPolicy;
+use gasflow_backend::domain::delivery::DeliveryPolicy;
+use gasflow_backend::domain::events::OUTBOX_BATCH_SIZE;
+use gasflow_backend::domain::jobs::JobTrigger;
+use gasflow_backend::domain::webhooks::{WebhookTargetPolicy, WEBHOOK_BATCH_SIZE};
+use gasflow_backend::ports::event_sink_port::EventSink;
 use gasflow_backend::AppState;
 use sqlx::postgres::PgPoolOptions;
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
-use tracing::info;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+const SCHEDULER_TICK_SECONDS: u64 = 30;
+const OUTBOX_TICK_SECONDS: u64 = 2;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -19,6 +41,9 @@ async fn main() -> Result<()> {
         .init();
 
     let settings = Settings::from_env()?;
+    let calendar_policy = CalendarPolicy {
+        timezone: settings.business_tz,
+    };
 
     let pool = PgPoolOptions::new()
         .max_connections(settings.database_max_connections)
@@ -27,18 +52,197 @@ async fn main() -> Result<()> {
 
     sqlx::migrate!("./migrations").run(&pool).await?;
 
+    let jobs = Arc::new(JobRegistry::build(&JobsConfig {
+        recurring_orders_cron: settings.recurring_orders_cron.clone(),
+        recurring_days_ahead: settings.recurring_days_ahead,
+        purge_job_runs_cron: settings.purge_job_runs_cron.clone(),
+        job_runs_retention_days: settings.job_runs_retention_days,
+        purge_refresh_tokens_cron: settings.purge_refresh_tokens_cron.clone(),
+        purge_driver_positions_cron: settings.purge_driver_positions_cron.clone(),
+        driver_positions_retention_days: settings.driver_positions_retention_days,
+        calendar: calendar_policy,
+    })?);
+
+    let jwt = match (&settings.jwt_keys_dir, &settings.jwt_active_kid) {
+        (Some(dir), Some(active_kid)) => {
+            let keys = load_signing_keys(Path::new(dir))?;
+            info!(active_kid = %active_kid, keys = keys.len(), "jwt asymmetric signing enabled");
+            JwtService::with_keys(
+                keys,
+                active_kid,
+                settings.jwt_secret.clone(),
+                settings.access_token_minutes,
+                settings.refresh_token_days,
+            )?
+        }
+        _ => JwtService::new(
+            settings.jwt_secret.clone().unwrap_or_default(),
+            settings.access_token_minutes,
+            settings.refresh_token_days,
+        ),
+    };
+
+    let metrics = Arc::new(MetricsRegistry::default());
+    let live_events = LiveEvents::new();
+    let webhook_policy = WebhookTargetPolicy {
+        allow_insecure: settings.webhooks_allow_insecure,
+    };
+
     let state = AppState {
-        repo: PgRepository::new(pool),
-        jwt: JwtService::new(settings.jwt_secret, settings.jwt_expiration_hours),
-        metrics: Arc::new(MetricsRegistry::default()),
+        repo: PgRepository::new(pool.clone()),
+        jwt,
+        metrics: metrics.clone(),
+        jobs: jobs.clone(),
+        login_policy: LoginPolicy {
+            free_attempts: settings.login_free_attempts,
+            ip_free_attempts: settings.login_ip_free_attempts,
+            max_delay_seconds: settings.login_max_backoff_seconds,
+            lockout_threshold: settings.login_lockout_threshold,
+            failure_window_minutes: settings.login_failure_window_minutes,
+            allow_plaintext_passwords: settings.allow_plaintext_passwords,
+            reset_code_minutes: settings.password_reset_code_minutes,
+            require_admin_two_factor: settings.require_admin_2fa,
+            ..LoginPolicy::default()
+        },
+        delivery_policy: DeliveryPolicy {
+            over_tolerance: settings.delivery_over_tolerance,
+        },
+        webhook_policy,
+        calendar_policy,
+        trust_proxy_headers: settings.trust_proxy_headers,
+        live_events: live_events.clone(),
+        blobs: Arc::new(LocalBlobStore::new(&settings.attachments_dir)),
     };
 
+    // El stream en vivo no depende del dispatcher: cada réplica escucha los
+    // eventos de todas.
+    tokio::spawn(pg_listener::forward_notifications(
+        pool,
+        state.repo.clone(),
+        live_events,
+    ));
+
+    if settings.jobs_enabled {
+        register_jobs::execute(&state.repo, &jobs, chrono::Utc::now()).await?;
+        spawn_scheduler(state.repo.clone(), jobs);
+    }
+
+    if settings.events_dispatch_enabled {
+        let mut sinks: Vec<Arc<dyn EventSink>> = vec![
+            Arc::new(InProcessSink::new().subscribe(metrics)),
+            Arc::new(WebhookFanoutSink::new(state.repo.clone())),
+        ];
+        if let Some(url) = &settings.events_webhook_url {
+            sinks.push(Arc::new(WebhookSink::new(url.clone(), webhook_policy)?));
+            info!(url = %url, "domain events webhook enabled");
+        }
+        spawn_event_dispatcher(state.repo.clone(), sinks);
+        spawn_webhook_dispatcher(state.repo.clone(), HttpWebhookClient::new(webhook_policy)?);
+    }
+
     let app: Router = build_router(state);
     let addr = SocketAddr::from(([0, 0, 0, 0], settings.port));
 
     info!(%addr, "gasflow backend listening");
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
     Ok(())
 }
+
+// Todas las réplicas corren el loop; el advisory lock de cada job decide
+// cuál lo ejecuta.
+fn spawn_scheduler(repo: PgRepository, jobs: Arc<JobRegistry>) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(SCHEDULER_TICK_SECONDS));
+        loop {
+            ticker.tick().await;
+            for job in jobs.jobs() {
+                match run_job::execute(
+                    &repo,
+                    job,
+                    JobTrigger::Programada,
+                    chrono::Utc::now(),
+                    &AuditContext::system(),
+                )
+                .await
+                {
+                    Ok(Some(run)) => info!(
+                        job = job.name(),
+                        run_id = %run.id,
+                        status = run.status.as_str(),
+                        "job finished"
+                    ),
+                    Ok(None) => {}
+                    Err(err) => error!(job = job.name(), error = %err, "job could not run"),
+                }
+            }
+        }
+    });
+}
+
+// Igual que el scheduler, corre en todas las réplicas: el claim del outbox
+// reparte los eventos sin duplicarlos entre ellas.
+fn spawn_event_dispatcher(repo: PgRepository, sinks: Vec<Arc<dyn EventSink>>) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(OUTBOX_TICK_SECONDS));
+        loop {
+            ticker.tick().await;
+            // Un lote lleno indica que quedan más pendientes: se sigue sin esperar.
+            loop {
+                match dispatch_events::execute(&repo, &sinks, chrono::Utc::now()).await {
+                    Ok(report) => {
+                        if report.failed > 0 {
+                            warn!(
+                                claimed = report.claimed,
+                                failed = report.failed,
+                                "some domain events could not be delivered"
+                            );
+                        }
+                        if (report.claimed as i64) < OUTBOX_BATCH_SIZE {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        error!(error = %err, "outbox dispatch failed");
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+// Las suscripciones se envían en su propio loop, cada una con su backoff: un
+// destino lento no demora el despacho de los eventos de dominio.
+fn spawn_webhook_dispatcher(repo: PgRepository, webhooks: HttpWebhookClient) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(OUTBOX_TICK_SECONDS));
+        loop {
+            ticker.tick().await;
+            loop {
+                match deliver_webhooks::execute(&repo, &webhooks, chrono::Utc::now()).await {
+                    Ok(report) => {
+                        if report.dead_lettered > 0 {
+                            warn!(
+                                dead_lettered = report.dead_lettered,
+                                "webhook deliveries moved to dead-letter"
+                            );
+                        }
+                        if (report.claimed as i64) < WEBHOOK_BATCH_SIZE {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        error!(error = %err, "webhook delivery failed");
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,18 @@
+use crate::domain::api_keys::{ApiKey, NewApiKey};
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[async_trait]
+pub trait ApiKeysPort: Send + Sync {
+    async fn create_api_key(&self, input: NewApiKey) -> Result<ApiKey, DomainError>;
+    async fn list_api_keys(&self) -> Result<Vec<ApiKey>, DomainError>;
+    async fn find_api_key_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, DomainError>;
+    async fn get_api_key_by_id(&self, api_key_id: Uuid) -> Result<Option<ApiKey>, DomainError>;
+    /// Devuelve `None` si la key no existe; revocar dos veces no cambia la
+    /// fecha original.
+    async fn revoke_api_key(&self, api_key_id: Uuid) -> Result<Option<ApiKey>, DomainError>;
+    /// Registra el uso; a lo sumo una escritura por minuto y key.
+    async fn touch_api_key(&self, api_key_id: Uuid, now: DateTime<Utc>) -> Result<(), DomainError>;
+}
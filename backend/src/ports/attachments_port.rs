@@ -0,0 +1,26 @@
+use crate::domain::attachments::{Attachment, AttachmentTarget, NewAttachment, StoredAttachment};
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// Metadatos de los adjuntos; los archivos viven en el `BlobStore`.
+#[async_trait]
+pub trait AttachmentsPort: Send + Sync {
+    /// Cuenta los adjuntos del registro con un lock y rechaza con
+    /// `Conflict` si ya tiene `MAX_ATTACHMENTS_PER_RECORD`.
+    async fn create_attachment(&self, input: NewAttachment) -> Result<Attachment, DomainError>;
+    async fn list_attachments(
+        &self,
+        target: AttachmentTarget,
+        record_id: Uuid,
+    ) -> Result<Vec<Attachment>, DomainError>;
+    async fn count_attachments(
+        &self,
+        target: AttachmentTarget,
+        record_id: Uuid,
+    ) -> Result<i64, DomainError>;
+    async fn find_attachment(
+        &self,
+        attachment_id: Uuid,
+    ) -> Result<Option<StoredAttachment>, DomainError>;
+}
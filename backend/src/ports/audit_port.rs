@@ -1,8 +1,22 @@
-use crate::domain::audit::NewAuditEvent;
+use crate::domain::audit::{AuditEvent, AuditFilter, ChainedAuditEvent, NewAuditEvent};
 use crate::domain::error::DomainError;
 use async_trait::async_trait;
 
 #[async_trait]
 pub trait AuditPort: Send + Sync {
+    /// Agrega el evento al final de la cadena de hashes.
     async fn record_audit_event(&self, event: NewAuditEvent) -> Result<(), DomainError>;
+    /// Eventos que cumplen el filtro, del más reciente al más viejo y a
+    /// partir del cursor; devuelve a lo sumo `limit`.
+    async fn list_audit_events(
+        &self,
+        filter: &AuditFilter,
+        limit: i64,
+    ) -> Result<Vec<AuditEvent>, DomainError>;
+    /// Eslabones con `seq` mayor a `after_seq`, en orden de cadena.
+    async fn list_audit_chain(
+        &self,
+        after_seq: i64,
+        limit: i64,
+    ) -> Result<Vec<ChainedAuditEvent>, DomainError>;
 }
@@ -1,10 +1,71 @@
-use crate::domain::auth::User;
+use crate::domain::auth::{
+    LoginFailures, LoginScope, NewPasswordReset, NewRefreshToken, PasswordReset, RefreshToken, User,
+};
 use crate::domain::error::DomainError;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 #[async_trait]
 pub trait AuthPort: Send + Sync {
     async fn find_user_by_username(&self, username: &str) -> Result<Option<User>, DomainError>;
     async fn find_user_by_id(&self, user_id: Uuid) -> Result<Option<User>, DomainError>;
+    async fn store_refresh_token(&self, input: NewRefreshToken) -> Result<(), DomainError>;
+    async fn find_refresh_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<RefreshToken>, DomainError>;
+    /// Marca el token como usado; devuelve `false` si ya estaba revocado, así
+    /// dos refresh concurrentes con el mismo token no rotan ambos.
+    async fn consume_refresh_token(&self, token_id: Uuid) -> Result<bool, DomainError>;
+    async fn revoke_refresh_family(&self, family_id: Uuid) -> Result<(), DomainError>;
+    /// Incrementa `token_generation` y revoca todos los refresh tokens del
+    /// usuario: cierra todas sus sesiones.
+    async fn revoke_user_tokens(&self, user_id: Uuid) -> Result<(), DomainError>;
+    async fn purge_refresh_tokens(&self, before: DateTime<Utc>) -> Result<u64, DomainError>;
+    async fn find_login_failures(
+        &self,
+        scope: LoginScope,
+        key: &str,
+    ) -> Result<Option<LoginFailures>, DomainError>;
+    /// Suma un fallo; si el último es anterior a `window_start` el contador
+    /// arranca de nuevo.
+    async fn record_login_failure(
+        &self,
+        scope: LoginScope,
+        key: &str,
+        now: DateTime<Utc>,
+        window_start: DateTime<Utc>,
+    ) -> Result<LoginFailures, DomainError>;
+    async fn clear_login_failures(&self, scope: LoginScope, key: &str) -> Result<(), DomainError>;
+    /// Bloquea la cuenta e invalida sus access tokens vigentes.
+    async fn lock_user(&self, user_id: Uuid) -> Result<(), DomainError>;
+    /// Reemplaza el hash sólo si sigue siendo `current`, para no pisar un
+    /// cambio de contraseña concurrente.
+    async fn update_password_hash(
+        &self,
+        user_id: Uuid,
+        current: &str,
+        password_hash: &str,
+    ) -> Result<(), DomainError>;
+    /// Guarda la contraseña nueva, baja `must_change_password` y cierra todas
+    /// las sesiones previas.
+    async fn change_password(
+        &self,
+        user_id: Uuid,
+        password_hash: &str,
+    ) -> Result<User, DomainError>;
+    /// Reemplaza cualquier código pendiente del usuario y lo obliga a cambiar
+    /// la contraseña.
+    async fn create_password_reset(
+        &self,
+        input: NewPasswordReset,
+    ) -> Result<PasswordReset, DomainError>;
+    async fn find_password_reset(
+        &self,
+        user_id: Uuid,
+        code_hash: &str,
+    ) -> Result<Option<PasswordReset>, DomainError>;
+    /// Marca el código como usado; `false` si otro pedido ya lo usó.
+    async fn consume_password_reset(&self, reset_id: Uuid) -> Result<bool, DomainError>;
 }
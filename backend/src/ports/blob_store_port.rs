@@ -0,0 +1,13 @@
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+
+/// Almacenamiento de archivos por clave. Las claves son rutas relativas con
+/// `/` que arma la aplicación; el adaptador decide dónde quedan los bytes.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Reemplaza el contenido si la clave ya existía.
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<(), DomainError>;
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, DomainError>;
+    /// Borrar una clave inexistente no es error.
+    async fn delete(&self, key: &str) -> Result<(), DomainError>;
+}
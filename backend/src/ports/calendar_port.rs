@@ -0,0 +1,18 @@
+use crate::domain::calendar::{Holiday, NewHoliday};
+use crate::domain::error::DomainError;
+use crate::domain::zones::DayOfWeek;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+#[async_trait]
+pub trait CalendarPort: Send + Sync {
+    async fn list_holidays(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<Holiday>, DomainError>;
+    async fn add_holiday(&self, input: NewHoliday) -> Result<Holiday, DomainError>;
+    async fn remove_holiday(&self, date: NaiveDate) -> Result<(), DomainError>;
+    async fn closed_weekdays(&self) -> Result<Vec<DayOfWeek>, DomainError>;
+    async fn set_closed_weekdays(&self, days: &[DayOfWeek]) -> Result<(), DomainError>;
+}
@@ -0,0 +1,29 @@
+use crate::domain::customers::{CustomerAddress, NewCustomerAddress};
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait CustomersPort: Send + Sync {
+    async fn create_customer_address(
+        &self,
+        input: NewCustomerAddress,
+    ) -> Result<CustomerAddress, DomainError>;
+    async fn list_customer_addresses(
+        &self,
+        customer_id: Uuid,
+    ) -> Result<Vec<CustomerAddress>, DomainError>;
+    /// Sólo encuentra direcciones del cliente indicado.
+    async fn find_customer_address(
+        &self,
+        customer_id: Uuid,
+        address_id: Uuid,
+    ) -> Result<Option<CustomerAddress>, DomainError>;
+    /// Devuelve `false` si la dirección no existe, es de otro cliente o ya
+    /// estaba desactivada.
+    async fn deactivate_customer_address(
+        &self,
+        customer_id: Uuid,
+        address_id: Uuid,
+    ) -> Result<bool, DomainError>;
+}
@@ -1,12 +1,53 @@
-use crate::domain::delivery::{Delivery, FailedDelivery, NewDelivery, NewFailedDelivery};
+use crate::domain::delivery::{
+    Delivery, DeliveryCorrection, FailedDelivery, NewDelivery, NewDeliveryCorrection,
+    NewFailedDelivery, RegisteredDelivery, RegisteredFailure,
+};
 use crate::domain::error::DomainError;
+use crate::domain::orders::{NewOrder, OrderStatus};
+use crate::domain::slots::SlotReservation;
 use async_trait::async_trait;
+use chrono::NaiveDate;
+use uuid::Uuid;
 
 #[async_trait]
 pub trait DeliveriesPort: Send + Sync {
-    async fn create_delivery(&self, input: NewDelivery) -> Result<Delivery, DomainError>;
+    /// Crea el pedido de seguimiento si lo hay, registra la entrega apuntando
+    /// a él y deja el pedido en `status`, todo en una transacción: si la
+    /// franja del seguimiento no tiene lugar no queda nada escrito. Encola
+    /// `order.created`, `delivery.registered` y `order.status_changed`.
+    async fn create_delivery(
+        &self,
+        input: NewDelivery,
+        status: OrderStatus,
+        follow_up: Option<(NewOrder, SlotReservation)>,
+    ) -> Result<RegisteredDelivery, DomainError>;
+    /// Registra la falla y devuelve el pedido a `ASIGNADO` en
+    /// `scheduled_date`/`time_slot` en una transacción: si la franja se llenó
+    /// no queda la falla escrita. Encola `delivery.failed` y
+    /// `order.status_changed`.
     async fn create_failed_delivery(
         &self,
         input: NewFailedDelivery,
-    ) -> Result<FailedDelivery, DomainError>;
+        scheduled_date: NaiveDate,
+        time_slot: String,
+        reservation: Option<SlotReservation>,
+    ) -> Result<RegisteredFailure, DomainError>;
+    async fn get_delivery(&self, delivery_id: Uuid) -> Result<Option<Delivery>, DomainError>;
+    async fn get_failed_delivery(
+        &self,
+        failure_id: Uuid,
+    ) -> Result<Option<FailedDelivery>, DomainError>;
+    /// Asienta la corrección sobre las cantidades vigentes con la entrega
+    /// bloqueada, deja el pedido en `order_status` y aplica el cambio al
+    /// seguimiento si todavía no salió a reparto (si salió, `Conflict`). Una
+    /// anulación además marca `voided_at`. Encola `delivery.corrected` y los
+    /// `order.status_changed` que correspondan en la misma transacción.
+    async fn correct_delivery(
+        &self,
+        input: NewDeliveryCorrection,
+    ) -> Result<DeliveryCorrection, DomainError>;
+    async fn list_delivery_corrections(
+        &self,
+        delivery_id: Uuid,
+    ) -> Result<Vec<DeliveryCorrection>, DomainError>;
 }
@@ -0,0 +1,24 @@
+use crate::domain::error::DomainError;
+use crate::domain::events::{DomainEventType, OutboxEvent};
+use async_trait::async_trait;
+
+/// Destino al que el dispatcher entrega los eventos del outbox. La entrega es
+/// "al menos una vez": un sink puede recibir un evento repetido si otro falló.
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    fn name(&self) -> &str;
+    fn accepts(&self, _event_type: DomainEventType) -> bool {
+        true
+    }
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), DomainError>;
+}
+
+/// Consumidor dentro del mismo proceso; se registra en un `InProcessSink`.
+#[async_trait]
+pub trait EventSubscriber: Send + Sync {
+    fn name(&self) -> &str;
+    fn handles(&self, _event_type: DomainEventType) -> bool {
+        true
+    }
+    async fn handle(&self, event: &OutboxEvent) -> Result<(), DomainError>;
+}
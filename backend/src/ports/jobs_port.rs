@@ -0,0 +1,51 @@
+use crate::domain::error::DomainError;
+use crate::domain::jobs::{JobRun, JobRunStatus, JobTrigger};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Exclusión de una corrida: mientras el lease exista ninguna otra réplica
+/// puede tomar el mismo job. Si se descarta sin `release` (error o pánico) la
+/// conexión que lo sostiene se cierra y Postgres lo libera igual.
+#[async_trait]
+pub trait JobLease: Send {
+    async fn release(self: Box<Self>) -> Result<(), DomainError>;
+}
+
+pub struct ClaimedJobRun {
+    pub run: JobRun,
+    pub lease: Box<dyn JobLease>,
+}
+
+#[async_trait]
+pub trait JobsPort: Send + Sync {
+    /// Da de alta el job o actualiza su cron; `next_run_at` sólo se recalcula
+    /// cuando el cron cambió.
+    async fn register_job(
+        &self,
+        name: &str,
+        schedule: &str,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<(), DomainError>;
+    async fn job_next_run_at(&self, name: &str) -> Result<Option<DateTime<Utc>>, DomainError>;
+    /// Toma la ejecución bajo un advisory lock de Postgres que se sostiene
+    /// hasta liberar el lease. Devuelve `None` si otra réplica tiene el lock o,
+    /// para las programadas, si el job todavía no venció. Las programadas
+    /// dejan `next_run_at` apuntando a la próxima ocurrencia.
+    async fn claim_job_run(
+        &self,
+        name: &str,
+        trigger: JobTrigger,
+        now: DateTime<Utc>,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<Option<ClaimedJobRun>, DomainError>;
+    async fn finish_job_run(
+        &self,
+        run_id: Uuid,
+        status: JobRunStatus,
+        output: Option<serde_json::Value>,
+        error: Option<String>,
+    ) -> Result<JobRun, DomainError>;
+    async fn list_job_runs(&self, name: &str, limit: i64) -> Result<Vec<JobRun>, DomainError>;
+    async fn purge_job_runs(&self, before: DateTime<Utc>) -> Result<u64, DomainError>;
+}
@@ -1,5 +1,20 @@
+pub mod api_keys_port;
+pub mod attachments_port;
 pub mod audit_port;
 pub mod auth_port;
+pub mod blob_store_port;
+pub mod calendar_port;
+pub mod customers_port;
 pub mod deliveries_port;
+pub mod event_sink_port;
+pub mod jobs_port;
 pub mod orders_port;
+pub mod outbox_port;
+pub mod positions_port;
+pub mod recurring_port;
+pub mod slots_port;
 pub mod stock_port;
+pub mod two_factor_port;
+pub mod users_port;
+pub mod webhooks_port;
+pub mod zones_port;
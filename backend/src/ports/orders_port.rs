@@ -1,12 +1,18 @@
 use crate::domain::error::DomainError;
 use crate::domain::orders::{NewOrder, Order, OrderFilter, OrderStatus, PaginatedOrders};
+use crate::domain::slots::SlotReservation;
 use async_trait::async_trait;
-use chrono::NaiveDate;
 use uuid::Uuid;
 
 #[async_trait]
 pub trait OrdersPort: Send + Sync {
-    async fn create_order(&self, input: NewOrder) -> Result<Order, DomainError>;
+    /// Verifica el cupo de `reservation` y escribe el pedido en la misma
+    /// transacción; encola `order.created` en el outbox.
+    async fn create_order(
+        &self,
+        input: NewOrder,
+        reservation: SlotReservation,
+    ) -> Result<Order, DomainError>;
     async fn list_orders(&self, filter: OrderFilter) -> Result<PaginatedOrders, DomainError>;
     async fn get_order_by_id(&self, order_id: Uuid) -> Result<Option<Order>, DomainError>;
     async fn update_order_status(
@@ -14,11 +20,6 @@ pub trait OrdersPort: Send + Sync {
         order_id: Uuid,
         status: OrderStatus,
     ) -> Result<Order, DomainError>;
-    async fn reprogram_order(
-        &self,
-        order_id: Uuid,
-        scheduled_date: NaiveDate,
-        time_slot: String,
-    ) -> Result<Order, DomainError>;
+    /// Todo o nada; encola un `order.assigned` por pedido.
     async fn assign_orders(&self, order_ids: &[Uuid], driver_id: Uuid) -> Result<(), DomainError>;
 }
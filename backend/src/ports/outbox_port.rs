@@ -0,0 +1,39 @@
+use crate::domain::error::DomainError;
+use crate::domain::events::OutboxEvent;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Lado de lectura del outbox. La escritura no tiene método propio: la hace
+/// cada operación de los demás puertos en su misma transacción.
+#[async_trait]
+pub trait OutboxPort: Send + Sync {
+    /// Toma hasta `limit` eventos vencidos y los reserva por
+    /// `OUTBOX_CLAIM_LEASE_SECONDS` para que otra réplica no los repita.
+    async fn claim_outbox_events(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<OutboxEvent>, DomainError>;
+    /// Corre hasta `until` la reserva de los eventos reclamados que siguen
+    /// sin publicar.
+    async fn extend_outbox_claims(
+        &self,
+        event_ids: &[Uuid],
+        until: DateTime<Utc>,
+    ) -> Result<(), DomainError>;
+    /// Lectura puntual, para los avisos de `NOTIFY` que sólo traen el id.
+    async fn find_outbox_event(&self, event_id: Uuid) -> Result<Option<OutboxEvent>, DomainError>;
+    async fn mark_event_published(
+        &self,
+        event_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<(), DomainError>;
+    /// `retry_at` en `None` descarta el evento.
+    async fn mark_event_failed(
+        &self,
+        event_id: Uuid,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), DomainError>;
+}
@@ -0,0 +1,34 @@
+use crate::domain::error::DomainError;
+use crate::domain::positions::{DriverPosition, LatestDriverPosition, PositionFix};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait PositionsPort: Send + Sync {
+    /// Guarda los fixes ignorando los que ya existen para el mismo
+    /// `recorded_at`; devuelve cuántos se insertaron.
+    async fn record_positions(
+        &self,
+        driver_id: Uuid,
+        fixes: &[PositionFix],
+    ) -> Result<usize, DomainError>;
+    /// Último fix del repartidor registrado desde `since`.
+    async fn latest_driver_position(
+        &self,
+        driver_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> Result<Option<PositionFix>, DomainError>;
+    /// Último fix de cada repartidor activo que haya informado alguno.
+    async fn latest_driver_positions(&self) -> Result<Vec<LatestDriverPosition>, DomainError>;
+    /// Recorrido de un día del calendario del negocio (de medianoche a
+    /// medianoche en `tz`) en orden cronológico.
+    async fn driver_positions_on(
+        &self,
+        driver_id: Uuid,
+        date: NaiveDate,
+        tz: Tz,
+    ) -> Result<Vec<DriverPosition>, DomainError>;
+    async fn purge_driver_positions(&self, before: DateTime<Utc>) -> Result<u64, DomainError>;
+}
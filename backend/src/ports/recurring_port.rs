@@ -0,0 +1,75 @@
+use crate::domain::error::DomainError;
+use crate::domain::orders::{NewOrder, Order};
+use crate::domain::recurring::{
+    NewOccurrence, NewRecurringOrder, RecurringOccurrence, RecurringOrder, RecurringStatus,
+};
+use crate::domain::slots::SlotReservation;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait RecurringOrdersPort: Send + Sync {
+    async fn create_recurring_order(
+        &self,
+        input: NewRecurringOrder,
+    ) -> Result<RecurringOrder, DomainError>;
+    async fn list_recurring_orders(
+        &self,
+        status: Option<RecurringStatus>,
+    ) -> Result<Vec<RecurringOrder>, DomainError>;
+    async fn get_recurring_order_by_id(
+        &self,
+        recurring_order_id: Uuid,
+    ) -> Result<Option<RecurringOrder>, DomainError>;
+    /// Plantillas activas con una fecha pendiente hasta `until` inclusive.
+    async fn due_recurring_orders(
+        &self,
+        until: NaiveDate,
+    ) -> Result<Vec<RecurringOrder>, DomainError>;
+    async fn update_recurring_status(
+        &self,
+        recurring_order_id: Uuid,
+        status: RecurringStatus,
+        next_date: NaiveDate,
+    ) -> Result<RecurringOrder, DomainError>;
+    /// Mueve `next_date` sólo si sigue valiendo `current`; devuelve `false` si
+    /// otra ejecución ya tomó esa fecha.
+    async fn advance_recurring_order(
+        &self,
+        recurring_order_id: Uuid,
+        current: NaiveDate,
+        next: NaiveDate,
+    ) -> Result<bool, DomainError>;
+    /// Avanza `next_date` y registra la ocurrencia en una misma transacción;
+    /// `None` si otra ejecución ya tomó la fecha.
+    async fn advance_with_occurrence(
+        &self,
+        current: NaiveDate,
+        next: NaiveDate,
+        input: NewOccurrence,
+    ) -> Result<Option<RecurringOccurrence>, DomainError>;
+    /// Avanza `next_date` desde la fecha del pedido, crea el pedido (con su
+    /// reserva de franja) y la ocurrencia `CREADA`, todo o nada. `None` si otra
+    /// ejecución ya tomó la fecha.
+    async fn materialize_occurrence(
+        &self,
+        recurring_order_id: Uuid,
+        next: NaiveDate,
+        input: NewOrder,
+        reservation: SlotReservation,
+    ) -> Result<Option<Order>, DomainError>;
+    async fn find_occurrence(
+        &self,
+        recurring_order_id: Uuid,
+        date: NaiveDate,
+    ) -> Result<Option<RecurringOccurrence>, DomainError>;
+    async fn record_occurrence(
+        &self,
+        input: NewOccurrence,
+    ) -> Result<RecurringOccurrence, DomainError>;
+    async fn list_occurrences(
+        &self,
+        recurring_order_id: Uuid,
+    ) -> Result<Vec<RecurringOccurrence>, DomainError>;
+}
@@ -0,0 +1,35 @@
+use crate::domain::error::DomainError;
+use crate::domain::slots::{NewTimeSlot, SlotLoad, SlotZoneCapacity, TimeSlot, TimeSlotUpdate};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait SlotsPort: Send + Sync {
+    async fn create_slot(&self, input: NewTimeSlot) -> Result<TimeSlot, DomainError>;
+    async fn list_slots(&self, include_inactive: bool) -> Result<Vec<TimeSlot>, DomainError>;
+    async fn find_slot_by_name(
+        &self,
+        normalized_name: &str,
+    ) -> Result<Option<TimeSlot>, DomainError>;
+    async fn update_slot(
+        &self,
+        slot_id: Uuid,
+        input: TimeSlotUpdate,
+    ) -> Result<TimeSlot, DomainError>;
+    async fn set_zone_capacity(
+        &self,
+        capacity: SlotZoneCapacity,
+    ) -> Result<SlotZoneCapacity, DomainError>;
+    async fn remove_zone_capacity(&self, slot_id: Uuid, zone_id: Uuid) -> Result<(), DomainError>;
+    async fn zone_capacities(&self, zone_id: Uuid) -> Result<Vec<SlotZoneCapacity>, DomainError>;
+    /// Carga reservada de una franja para una fecha y zona, opcionalmente
+    /// excluyendo un pedido (al reprogramarlo no compite consigo mismo).
+    async fn slot_load(
+        &self,
+        date: NaiveDate,
+        zone_id: Uuid,
+        slot_name: &str,
+        exclude_order_id: Option<Uuid>,
+    ) -> Result<SlotLoad, DomainError>;
+}
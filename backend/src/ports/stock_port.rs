@@ -2,6 +2,7 @@ use crate::domain::error::DomainError;
 use crate::domain::stock::Inbound;
 use async_trait::async_trait;
 use chrono::NaiveDate;
+use chrono_tz::Tz;
 
 #[derive(Debug, Clone)]
 pub struct StockTotals {
@@ -19,7 +20,19 @@ pub struct DailyReportTotals {
 
 #[async_trait]
 pub trait StockPort: Send + Sync {
+    /// Encola `stock.inbound_registered` en el outbox dentro de la misma
+    /// transacción.
     async fn register_inbound(&self, input: Inbound) -> Result<(), DomainError>;
-    async fn stock_totals(&self, date: Option<NaiveDate>) -> Result<StockTotals, DomainError>;
-    async fn daily_report_totals(&self, date: NaiveDate) -> Result<DailyReportTotals, DomainError>;
+    /// Las entregas y correcciones cuentan en el día de `tz` en que se
+    /// registraron.
+    async fn stock_totals(
+        &self,
+        date: Option<NaiveDate>,
+        tz: Tz,
+    ) -> Result<StockTotals, DomainError>;
+    async fn daily_report_totals(
+        &self,
+        date: NaiveDate,
+        tz: Tz,
+    ) -> Result<DailyReportTotals, DomainError>;
 }
@@ -0,0 +1,54 @@
+use crate::domain::error::DomainError;
+use crate::domain::two_factor::{LoginChallenge, NewLoginChallenge};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait TwoFactorPort: Send + Sync {
+    /// Guarda un secreto pendiente de confirmar; `false` si el usuario ya
+    /// tiene 2FA activo.
+    async fn set_pending_totp_secret(
+        &self,
+        user_id: Uuid,
+        secret: &str,
+    ) -> Result<bool, DomainError>;
+    /// Activa el secreto pendiente, registra `step` como usado y reemplaza
+    /// los códigos de recuperación; `false` si no había activación pendiente.
+    async fn enable_totp(
+        &self,
+        user_id: Uuid,
+        step: i64,
+        recovery_code_hashes: Vec<String>,
+    ) -> Result<bool, DomainError>;
+    /// Avanza el último paso aceptado; `false` si `step` ya se usó.
+    async fn record_totp_step(&self, user_id: Uuid, step: i64) -> Result<bool, DomainError>;
+    /// Borra secreto y códigos de recuperación.
+    async fn disable_totp(&self, user_id: Uuid) -> Result<(), DomainError>;
+    async fn replace_recovery_codes(
+        &self,
+        user_id: Uuid,
+        code_hashes: Vec<String>,
+    ) -> Result<(), DomainError>;
+    /// Marca el código como usado; `false` si no existe o ya se usó.
+    async fn consume_recovery_code(
+        &self,
+        user_id: Uuid,
+        code_hash: &str,
+    ) -> Result<bool, DomainError>;
+    async fn create_login_challenge(
+        &self,
+        input: NewLoginChallenge,
+    ) -> Result<LoginChallenge, DomainError>;
+    async fn find_login_challenge(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<LoginChallenge>, DomainError>;
+    /// Suma un intento fallido y descarta el desafío al llegar a `max_attempts`.
+    async fn record_challenge_failure(
+        &self,
+        challenge_id: Uuid,
+        max_attempts: i32,
+    ) -> Result<(), DomainError>;
+    /// Borra el desafío; `false` si otro pedido ya lo usó.
+    async fn consume_login_challenge(&self, challenge_id: Uuid) -> Result<bool, DomainError>;
+}
@@ -0,0 +1,16 @@
+use crate::domain::auth::{NewUser, Role, User, UserFilter};
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait UsersPort: Send + Sync {
+    async fn create_user(&self, input: NewUser) -> Result<User, DomainError>;
+    async fn list_users(&self, filter: UserFilter) -> Result<Vec<User>, DomainError>;
+    async fn update_username(&self, user_id: Uuid, username: &str) -> Result<User, DomainError>;
+    /// Rechaza con `Conflict` quitarle el rol al último ADMIN activo.
+    async fn set_user_role(&self, user_id: Uuid, role: Role) -> Result<User, DomainError>;
+    /// Rechaza con `Conflict` desactivar al último ADMIN activo.
+    async fn set_user_active(&self, user_id: Uuid, active: bool) -> Result<User, DomainError>;
+    async fn unlock_user(&self, user_id: Uuid) -> Result<User, DomainError>;
+}
@@ -0,0 +1,72 @@
+use crate::domain::error::DomainError;
+use crate::domain::events::OutboxEvent;
+use crate::domain::webhooks::{
+    NewWebhookSubscription, PendingWebhookDelivery, WebhookAttempt, WebhookDelivery,
+    WebhookDeliveryFilter, WebhookSubscription, WebhookSubscriptionUpdate,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[async_trait]
+pub trait WebhooksPort: Send + Sync {
+    async fn create_webhook_subscription(
+        &self,
+        input: NewWebhookSubscription,
+    ) -> Result<WebhookSubscription, DomainError>;
+    async fn list_webhook_subscriptions(&self) -> Result<Vec<WebhookSubscription>, DomainError>;
+    async fn get_webhook_subscription(
+        &self,
+        subscription_id: Uuid,
+    ) -> Result<Option<WebhookSubscription>, DomainError>;
+    async fn update_webhook_subscription(
+        &self,
+        subscription_id: Uuid,
+        update: WebhookSubscriptionUpdate,
+    ) -> Result<WebhookSubscription, DomainError>;
+    /// Borra también su registro de entregas.
+    async fn delete_webhook_subscription(&self, subscription_id: Uuid) -> Result<(), DomainError>;
+    /// Crea una entrega pendiente por cada suscripción activa que espera ese
+    /// tipo de evento; repetir el mismo evento no duplica entregas.
+    async fn enqueue_webhook_deliveries(&self, event: &OutboxEvent) -> Result<u64, DomainError>;
+    /// Toma hasta `limit` entregas vencidas de suscripciones activas y las
+    /// reserva por `WEBHOOK_CLAIM_LEASE_SECONDS`.
+    async fn claim_webhook_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<PendingWebhookDelivery>, DomainError>;
+    async fn record_webhook_attempt(
+        &self,
+        delivery_id: Uuid,
+        attempt: WebhookAttempt,
+    ) -> Result<(), DomainError>;
+    /// De la más reciente a la más vieja.
+    async fn list_webhook_deliveries(
+        &self,
+        subscription_id: Uuid,
+        filter: WebhookDeliveryFilter,
+    ) -> Result<Vec<WebhookDelivery>, DomainError>;
+    async fn get_webhook_delivery(
+        &self,
+        delivery_id: Uuid,
+    ) -> Result<Option<WebhookDelivery>, DomainError>;
+    /// Vuelve la entrega a `PENDIENTE` con los intentos en cero.
+    async fn requeue_webhook_delivery(
+        &self,
+        delivery_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<WebhookDelivery, DomainError>;
+}
+
+/// Cliente HTTP con el que el worker envía los webhooks.
+#[async_trait]
+pub trait WebhookClient: Send + Sync {
+    /// Devuelve el código HTTP; el error es sólo para fallas de transporte.
+    async fn post_json(
+        &self,
+        url: &str,
+        headers: &[(&'static str, String)],
+        body: String,
+    ) -> Result<u16, DomainError>;
+}
@@ -0,0 +1,13 @@
+use crate::domain::error::DomainError;
+use crate::domain::zones::{NewZone, Zone, ZoneUpdate};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait ZonesPort: Send + Sync {
+    async fn create_zone(&self, input: NewZone) -> Result<Zone, DomainError>;
+    async fn list_zones(&self, include_inactive: bool) -> Result<Vec<Zone>, DomainError>;
+    async fn get_zone_by_id(&self, zone_id: Uuid) -> Result<Option<Zone>, DomainError>;
+    async fn find_zone_by_name(&self, normalized_name: &str) -> Result<Option<Zone>, DomainError>;
+    async fn update_zone(&self, zone_id: Uuid, input: ZoneUpdate) -> Result<Zone, DomainError>;
+}